@@ -395,7 +395,7 @@ impl Store for AuthStore {
 /// This allows the AuthStore to:
 /// - Serialize its state on the server
 /// - Deserialize and restore state on the client during hydration
-#[cfg(feature = "hydrate")]
+#[cfg(any(feature = "ssr", feature = "hydrate"))]
 impl leptos_store::hydration::HydratableStore for AuthStore {
     fn serialize_state(&self) -> Result<String, leptos_store::hydration::StoreHydrationError> {
         // Use get_untracked() since we're intentionally reading outside reactive context
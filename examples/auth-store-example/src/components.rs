@@ -10,9 +10,9 @@
 //!
 //! # Hydration Support
 //!
-//! When the `hydrate` feature is enabled, this example demonstrates:
+//! When the `ssr` or `hydrate` feature is enabled, this example demonstrates:
 //! - Server-side state serialization with `provide_hydrated_store`
-//! - Client-side state restoration with `use_hydrated_store`
+//! - Client-side state restoration with `HydrationBuilder`
 //!
 //! The store state is automatically transferred from server to client,
 //! ensuring no hydration mismatches.
@@ -33,7 +33,7 @@ use crate::auth_store::{AuthStore, LoginCredentials};
 ///
 /// # Hydration
 ///
-/// When built with the `hydrate` feature:
+/// When built with the `ssr` or `hydrate` feature:
 /// - On the server: Creates store and renders hydration script
 /// - On the client: Restores store state from serialized data
 #[component]
@@ -42,26 +42,27 @@ pub fn App() -> impl IntoView {
     provide_meta_context();
 
     // Create and provide the auth store
-    // With hydration: uses `provide_hydrated_store` which:
-    //   1. On server: Creates store and embeds serialized state
-    //   2. On client: Reads embedded state and restores the store
-    // Without hydration: just provides the store to context
-    let store = AuthStore::new();
-
-    #[cfg(feature = "hydrate")]
-    {
-        // Hydration-aware store provision
-        // This will attempt to restore state from server-rendered HTML
-        provide_hydrated_store(store);
-    }
-
-    #[cfg(not(feature = "hydrate"))]
-    {
-        // Standard store provision (CSR or non-hydration SSR)
-        provide_store(store);
-    }
+    // With SSR/hydration, `provide_hydrated_store` provides the store and
+    // renders a script tag with its serialized state:
+    //   1. On server: No hydration data exists, so a fresh store is used
+    //   2. On client: `HydrationBuilder` restores the state the server embedded
+    // Both sides render the same script tag, so hydration stays consistent.
+    #[cfg(any(feature = "ssr", feature = "hydrate"))]
+    let hydration_script = provide_hydrated_store(
+        leptos_store::hydration::HydrationBuilder::new()
+            .with_fallback(AuthStore::new())
+            .build(),
+    );
+
+    // Without hydration: just provide the store to context
+    #[cfg(not(any(feature = "ssr", feature = "hydrate")))]
+    let hydration_script = {
+        provide_store(AuthStore::new());
+        ().into_any()
+    };
 
     view! {
+        {hydration_script}
         <Stylesheet id="leptos" href="/pkg/auth-store-example.css"/>
         <Title text="Auth Store Example"/>
         <Meta name="description" content="SSR Example for leptos-store"/>
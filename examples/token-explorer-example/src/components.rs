@@ -20,7 +20,7 @@ use leptos_router::{
 };
 use leptos_store::prelude::*;
 
-use crate::token_store::{SortField, Token, TokenStore, fetch_tokens};
+use crate::token_store::{LoadTokens, SortField, Token, TokenStore, refresh_tokens, store_env};
 
 // ============================================================================
 // URL Query Parameter Handling
@@ -107,21 +107,7 @@ fn build_query_string(search: &str, sort: &SortField, desc: bool) -> String {
 
 /// Polling interval in milliseconds (30 seconds)
 #[cfg(feature = "hydrate")]
-const POLL_INTERVAL_MS: u32 = 30_000;
-
-/// Read hydration data from a script tag in the DOM
-#[cfg(feature = "hydrate")]
-fn read_hydration_script(store_key: &str) -> Option<String> {
-    use wasm_bindgen::JsCast;
-
-    let window = web_sys::window()?;
-    let document = window.document()?;
-    let script_id = format!("__leptos_store_{}", store_key);
-    let element = document.get_element_by_id(&script_id)?;
-    let script = element.dyn_into::<web_sys::HtmlScriptElement>().ok()?;
-    let text = script.text().ok()?;
-    Some(text)
-}
+const POLL_INTERVAL_MS: u64 = 30_000;
 
 /// Format number with thousands separator
 fn format_with_commas(n: u64) -> String {
@@ -139,27 +125,26 @@ fn format_with_commas(n: u64) -> String {
 pub fn App() -> impl IntoView {
     provide_meta_context();
 
-    // On server: Store is already provided by main.rs
-    // On client (hydrate): Read serialized state and create store
-    #[cfg(feature = "hydrate")]
-    {
-        use leptos_store::hydration::HydratableStore;
-
-        // Try to hydrate from serialized data, fallback to empty store
-        let store = if let Some(data) = read_hydration_script("token_store") {
-            TokenStore::from_hydrated_state(&data).unwrap_or_else(|_| TokenStore::new())
-        } else {
-            TokenStore::new()
-        };
-        provide_store(store);
+    // On the server `main.rs` already provided the environment
+    if use_context::<StoreEnv>().is_none() {
+        provide_store_env(store_env());
     }
 
-    // On CSR (no SSR): just create empty store
-    #[cfg(all(not(feature = "hydrate"), not(feature = "ssr")))]
+    // With SSR/hydration: restore the store from the page or create it,
+    // provide it, and register its state for the `<StoreHydrationScripts/>`
+    // in the shell. On the server no hydration data exists yet, so the
+    // factory's empty store is used (the resource in TokenExplorer
+    // populates it); on the client the embedded state is restored.
+    #[cfg(any(feature = "ssr", feature = "hydrate"))]
     {
-        provide_store(TokenStore::new());
+        register_hydratable(TokenStore::new);
+        hydrate_all();
     }
 
+    // On CSR (no SSR): just create empty store
+    #[cfg(not(any(feature = "ssr", feature = "hydrate")))]
+    provide_store(TokenStore::new());
+
     view! {
         <Stylesheet id="leptos" href="/pkg/token-explorer-example.css"/>
         <Title text="Token Explorer - Solana Tokens"/>
@@ -214,77 +199,40 @@ fn TokenExplorer() -> impl IntoView {
         initial_desc,
     ));
 
-    // Create a resource that fetches tokens on mount (works for SSR and CSR)
-    let tokens_resource = Resource::new(
+    // Fetch tokens on mount (works for SSR and CSR) and commit them into
+    // the store
+    let tokens_resource = store_resource(
+        &store,
         || (), // No reactive dependencies - fetch once on mount
-        move |_| async move { fetch_tokens().await },
+        |_| LoadTokens,
+        move |store, response| {
+            store.set_tokens(response.tokens.clone());
+            set_last_updated.set(response.fetched_at.clone());
+            set_is_refreshing.set(false);
+        },
     );
 
-    // Effect to update store when resource loads
-    {
-        let store = store.clone();
-        Effect::new(move |_| {
-            if let Some(Ok(response)) = tokens_resource.get() {
-                store.set_tokens(response.tokens);
-                set_last_updated.set(response.fetched_at);
-                set_is_refreshing.set(false);
-            }
-        });
-    }
-
-    // Client-side polling every 30 seconds
+    // Client-side polling every 30 seconds, paused while the tab is hidden
     #[cfg(feature = "hydrate")]
     {
-        use wasm_bindgen::JsCast;
-
-        let store = store.clone();
-        let (interval_id, set_interval_id) = signal::<Option<i32>>(None);
-
-        Effect::new(move |_| {
-            let store = store.clone();
-
-            // Set up the polling interval using web_sys
-            let window = web_sys::window().expect("no global window");
-
-            let callback = wasm_bindgen::closure::Closure::wrap(Box::new(move || {
-                let store = store.clone();
-                set_is_refreshing.set(true);
-
-                // Spawn the async fetch
-                leptos::task::spawn_local(async move {
-                    match fetch_tokens().await {
-                        Ok(response) => {
-                            store.set_tokens(response.tokens);
-                            set_last_updated.set(response.fetched_at);
-                        }
-                        Err(e) => {
-                            store.set_error(Some(format!("Refresh failed: {}", e)));
-                        }
-                    }
-                    set_is_refreshing.set(false);
-                });
-            }) as Box<dyn Fn()>);
-
-            let id = window
-                .set_interval_with_callback_and_timeout_and_arguments_0(
-                    callback.as_ref().unchecked_ref(),
-                    POLL_INTERVAL_MS as i32,
-                )
-                .expect("failed to set interval");
-
-            set_interval_id.set(Some(id));
-
-            // Prevent the closure from being dropped
-            callback.forget();
-        });
-
-        // Clean up interval on unmount
-        on_cleanup(move || {
-            if let Some(id) = interval_id.get_untracked() {
-                if let Some(window) = web_sys::window() {
-                    window.clear_interval_with_handle(id);
+        use std::time::Duration;
+
+        let refresh = store
+            .poll(Duration::from_millis(POLL_INTERVAL_MS), refresh_tokens())
+            .revalidate_on_focus()
+            .revalidate_on_reconnect()
+            .handle();
+        Effect::new(move |_| match refresh.state() {
+            ActionState::Pending => set_is_refreshing.set(true),
+            ActionState::Success => {
+                if let Some(response) = refresh.value() {
+                    set_last_updated.set(response.fetched_at);
                 }
+                set_is_refreshing.set(false);
             }
+            // The error was recorded in the store by `refresh_tokens`
+            ActionState::Error => set_is_refreshing.set(false),
+            ActionState::Idle => {}
         });
     }
 
@@ -293,14 +241,8 @@ fn TokenExplorer() -> impl IntoView {
         let store = store.clone();
         async move {
             set_is_refreshing.set(true);
-            match fetch_tokens().await {
-                Ok(response) => {
-                    store.set_tokens(response.tokens);
-                    set_last_updated.set(response.fetched_at);
-                }
-                Err(e) => {
-                    store.set_error(Some(format!("Refresh failed: {e}")));
-                }
+            if let Ok(response) = refresh_tokens().execute(&store).await {
+                set_last_updated.set(response.fetched_at);
             }
             set_is_refreshing.set(false);
         }
@@ -337,7 +279,10 @@ fn TokenExplorer() -> impl IntoView {
             />
             <SearchAndFilter update_url=update_url.clone() initial_search=initial_search />
             <Suspense fallback=move || view! { <LoadingState /> }>
-                <TokenGrid />
+                {move || Suspend::new(async move {
+                    let _ = tokens_resource.await;
+                    view! { <TokenGrid /> }
+                })}
             </Suspense>
             <TokenDetail />
         </div>
@@ -427,8 +372,7 @@ fn format_time(iso: &str) -> String {
 }
 
 /// Debounce delay for search input (milliseconds)
-#[cfg(feature = "hydrate")]
-const SEARCH_DEBOUNCE_MS: u32 = 300;
+const SEARCH_DEBOUNCE_MS: u64 = 300;
 
 /// Search and filter controls with debounced URL sync
 ///
@@ -449,36 +393,16 @@ where
     // Track the last committed search (for distinct_until_changed behavior)
     let (last_committed, set_last_committed) = signal(initial_search);
 
-    // Track debounce timer handle for cleanup
-    #[cfg(feature = "hydrate")]
-    let (timer_handle, set_timer_handle) = signal::<Option<i32>>(None);
-
     // Clone for different closures
-    #[cfg(feature = "hydrate")]
-    let store_for_debounce = store.clone();
-    #[cfg(feature = "hydrate")]
-    let update_url_for_debounce = update_url.clone();
     let store_clear = store.clone();
     let update_url_clear = update_url.clone();
 
-    // Debounced search handler (client-side)
-    #[cfg(feature = "hydrate")]
-    let trigger_debounced_search = move |value: String| {
-        use wasm_bindgen::JsCast;
-        use wasm_bindgen::prelude::*;
-
-        // Cancel any pending timer
-        if let Some(handle) = timer_handle.get_untracked() {
-            if let Some(window) = web_sys::window() {
-                window.clear_timeout_with_handle(handle);
-            }
-        }
-
-        // Set up new debounce timer
-        let store = store_for_debounce.clone();
-        let update_url = update_url_for_debounce.clone();
-
-        let callback = Closure::once(Box::new(move || {
+    // Debounced store/URL update; the pending timer is cleared when the
+    // component is disposed.
+    let trigger_debounced_search = {
+        let store = store.clone();
+        let update_url = update_url.clone();
+        debounced_action(SEARCH_DEBOUNCE_MS, move |value: String| {
             // distinct_until_changed: only update if value changed
             let last = last_committed.get_untracked();
             if value != last {
@@ -492,37 +416,7 @@ where
                 let desc = store.is_sort_desc_untracked();
                 update_url(value, sort, desc);
             }
-        }) as Box<dyn FnOnce()>);
-
-        if let Some(window) = web_sys::window() {
-            if let Ok(handle) = window.set_timeout_with_callback_and_timeout_and_arguments_0(
-                callback.as_ref().unchecked_ref(),
-                SEARCH_DEBOUNCE_MS as i32,
-            ) {
-                set_timer_handle.set(Some(handle));
-            }
-        }
-
-        // Prevent closure from being dropped
-        callback.forget();
-    };
-
-    // SSR fallback - no debounce
-    #[cfg(not(feature = "hydrate"))]
-    let trigger_debounced_search = {
-        let store = store.clone();
-        let update_url = update_url.clone();
-        move |value: String| {
-            // distinct_until_changed
-            let last = last_committed.get_untracked();
-            if value != last {
-                set_last_committed.set(value.clone());
-                store.set_search_query(value.clone());
-                let sort = store.sort_by_untracked();
-                let desc = store.is_sort_desc_untracked();
-                update_url(value, sort, desc);
-            }
-        }
+        })
     };
 
     view! {
@@ -539,7 +433,7 @@ where
                             // Immediate UI update
                             set_search_input.set(value.clone());
                             // Debounced store/URL update
-                            trigger(value);
+                            trigger.call(value);
                         }
                     }
                 />
@@ -549,7 +443,10 @@ where
                     on:click={
                         let store_for_btn = store_clear.clone();
                         let update_url_for_btn = update_url_clear.clone();
+                        let trigger = trigger_debounced_search.clone();
                         move |_| {
+                            // Drop a pending debounced update
+                            trigger.cancel();
                             set_search_input.set(String::new());
                             set_last_committed.set(String::new());
                             store_for_btn.set_search_query(String::new());
@@ -13,7 +13,12 @@ async fn main() -> std::io::Result<()> {
     use actix_web::*;
     use leptos::prelude::*;
     use leptos_actix::{LeptosRoutes, generate_route_list};
-    use token_explorer_example::{components::App, token_store::*};
+    use leptos_store::hydration::register_rehydration_source;
+    use leptos_store::prelude::{StoreHydrationScripts, provide_store_env};
+    use token_explorer_example::components::App;
+    use token_explorer_example::token_store::{
+        JupiterApi, TokenStore, fetch_tokens_server, store_env,
+    };
 
     // Set defaults for manual mode
     if std::env::var("LEPTOS_OUTPUT_NAME").is_err() {
@@ -25,11 +30,29 @@ async fn main() -> std::io::Result<()> {
         }
     }
 
+    // API configuration for the profile in `LEPTOS_STORE_PROFILE`
+    let env = store_env();
+    let api = env
+        .require::<JupiterApi>()
+        .expect("store environment has a Jupiter API config")
+        .clone();
+
+    // Fresh token state for clients calling `rehydrate(&store, rehydrate_state)`
+    register_rehydration_source(move || {
+        let api = api.clone();
+        async move {
+            let store = TokenStore::new();
+            store.set_tokens(fetch_tokens_server(&api).await?);
+            Ok::<_, String>(store)
+        }
+    });
+
     let conf = get_configuration(None).expect("Failed to load Leptos configuration");
     let addr = conf.leptos_options.site_addr;
 
     println!("🪙 Token Explorer Example (SSR Mode)");
     println!("   Listening on http://{}", addr);
+    println!("   Profile: {}", env.profile());
     println!("   Tokens are fetched fresh on each request");
     println!("   Client polls every 30 seconds for updates");
 
@@ -38,33 +61,14 @@ async fn main() -> std::io::Result<()> {
         let site_root = leptos_options.site_root.clone();
 
         let routes = generate_route_list(App);
+        let env = env.clone();
 
         actix_web::App::new()
             .service(Files::new("/pkg", format!("{site_root}/pkg")))
-            .leptos_routes(routes, {
+            // Provides the environment to server functions as well as pages
+            .leptos_routes_with_context(routes, move || provide_store_env(env.clone()), {
                 let leptos_options = leptos_options.clone();
                 move || {
-                    // Note: We use a resource to fetch tokens per-request
-                    // The actual fetching happens in the App component via create_resource
-
-                    // Create an empty store - it will be populated by the resource
-                    let store = TokenStore::new();
-
-                    // Serialize empty store state for hydration
-                    // The client will immediately fetch fresh data
-                    let hydration_data = {
-                        let json = serde_json::to_string(&store.state.get_untracked())
-                            .unwrap_or_default();
-                        let escaped = json.replace("</script>", "<\\/script>");
-                        format!(
-                            r#"<script id="__leptos_store_token_store" type="application/json">{}</script>"#,
-                            escaped
-                        )
-                    };
-
-                    // Provide store to context
-                    leptos_store::context::provide_store(store);
-
                     view! {
                         <!DOCTYPE html>
                         <html lang="en">
@@ -73,11 +77,11 @@ async fn main() -> std::io::Result<()> {
                                 <meta name="viewport" content="width=device-width, initial-scale=1"/>
                                 <AutoReload options=leptos_options.clone() />
                                 <HydrationScripts options=leptos_options.clone() />
+                                <StoreHydrationScripts />
                                 <leptos_meta::MetaTags/>
                             </head>
                             <body>
                                 <App/>
-                                <div inner_html=hydration_data />
                             </body>
                         </html>
                     }
@@ -463,7 +463,7 @@ impl Store for TokenStore {
 // Hydration Support
 // ============================================================================
 
-#[cfg(feature = "hydrate")]
+#[cfg(any(feature = "ssr", feature = "hydrate"))]
 impl leptos_store::hydration::HydratableStore for TokenStore {
     fn serialize_state(&self) -> Result<String, leptos_store::hydration::StoreHydrationError> {
         let state = self.state.get_untracked();
@@ -480,6 +480,21 @@ impl leptos_store::hydration::HydratableStore for TokenStore {
         Ok(Self::with_state(state))
     }
 
+    fn hydrate_into(&self, data: &str) -> Result<(), leptos_store::hydration::StoreHydrationError> {
+        let state: TokenState = serde_json::from_str(data).map_err(|e| {
+            leptos_store::hydration::StoreHydrationError::Deserialization(e.to_string())
+        })?;
+        self.state.set(state);
+        Ok(())
+    }
+
+    /// Nothing to send until tokens have been fetched; the client creates
+    /// an empty store itself.
+    fn skip_hydration(&self) -> bool {
+        self.state
+            .with_untracked(|state| state.tokens.is_empty() && state.last_fetched.is_none())
+    }
+
     fn store_key() -> &'static str {
         "token_store"
     }
@@ -489,8 +504,32 @@ impl leptos_store::hydration::HydratableStore for TokenStore {
 // API Client
 // ============================================================================
 
-/// Jupiter API base URL
-pub const JUPITER_API_BASE: &str = "https://jupiter.ghostnn.ai/data/v1/assets/search";
+/// Jupiter API configuration, read from the [`StoreEnv`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct JupiterApi {
+    /// URL of the asset search endpoint.
+    pub search_url: String,
+}
+
+/// Build the store environment for the active profile.
+///
+/// The server resolves the profile from `LEPTOS_STORE_PROFILE` when it
+/// starts, and the client from the same variable when it is built; there
+/// is no runtime configuration on the client. That is enough here, since
+/// the client only reaches the API through the `fetch_tokens` server
+/// function.
+pub fn store_env() -> StoreEnv {
+    StoreEnv::builder()
+        .config(JupiterApi {
+            search_url: "https://jupiter.ghostnn.ai/data/v1/assets/search".to_string(),
+        })
+        .profile(Profile::Test, |p| {
+            p.config(JupiterApi {
+                search_url: "http://127.0.0.1:3010/assets/search".to_string(),
+            })
+        })
+        .build_current()
+}
 
 /// Default token addresses to fetch
 pub const DEFAULT_TOKEN_IDS: &[&str] = &[
@@ -507,15 +546,15 @@ pub const DEFAULT_TOKEN_IDS: &[&str] = &[
 ];
 
 /// Build the API URL for fetching tokens
-pub fn build_api_url(token_ids: &[&str], limit: usize) -> String {
+pub fn build_api_url(api: &JupiterApi, token_ids: &[&str], limit: usize) -> String {
     let query = token_ids.join(",");
-    format!("{JUPITER_API_BASE}?query={query}&limit={limit}")
+    format!("{}?query={query}&limit={limit}", api.search_url)
 }
 
 /// Fetch tokens from the Jupiter API (server-side internal function)
 #[cfg(feature = "ssr")]
-pub async fn fetch_tokens_server() -> Result<Vec<Token>, String> {
-    let url = build_api_url(DEFAULT_TOKEN_IDS, 10);
+pub async fn fetch_tokens_server(api: &JupiterApi) -> Result<Vec<Token>, String> {
+    let url = build_api_url(api, DEFAULT_TOKEN_IDS, 10);
 
     let response = reqwest::get(&url)
         .await
@@ -547,7 +586,14 @@ pub struct FetchTokensResponse {
 /// Server function to fetch tokens - can be called from client via HTTP
 #[leptos::prelude::server(FetchTokens, "/api")]
 pub async fn fetch_tokens() -> Result<FetchTokensResponse, leptos::prelude::ServerFnError> {
-    let tokens = fetch_tokens_server()
+    // Provided to server functions by `leptos_routes_with_context` in `main.rs`
+    let env =
+        leptos_store::env::try_use_store_env().map_err(leptos::prelude::ServerFnError::new)?;
+    let api = env
+        .require::<JupiterApi>()
+        .map_err(leptos::prelude::ServerFnError::new)?
+        .clone();
+    let tokens = fetch_tokens_server(&api)
         .await
         .map_err(|e| leptos::prelude::ServerFnError::new(e))?;
 
@@ -557,6 +603,45 @@ pub async fn fetch_tokens() -> Result<FetchTokensResponse, leptos::prelude::Serv
     })
 }
 
+// Serves fresh store state for `leptos_store::hydration::rehydrate`; the
+// token store's source is registered in `main.rs`.
+#[cfg(any(feature = "ssr", feature = "hydrate"))]
+leptos_store::rehydrate_endpoint!(pub fn rehydrate_state);
+
+/// Error loading tokens through the server function.
+///
+/// Serializable, so a failed load can be sent to the client with the
+/// page's resource.
+#[derive(Clone, Debug, thiserror::Error, Serialize, Deserialize)]
+#[error("{0}")]
+pub struct LoadTokensError(pub String);
+
+/// Async action loading the token list through the server function.
+#[derive(Clone, Debug)]
+pub struct LoadTokens;
+
+impl AsyncAction<TokenStore> for LoadTokens {
+    type Output = FetchTokensResponse;
+    type Error = LoadTokensError;
+
+    async fn execute(&self, _store: &TokenStore) -> ActionResult<Self::Output, Self::Error> {
+        fetch_tokens()
+            .await
+            .map_err(|e| LoadTokensError(e.to_string()))
+    }
+}
+
+/// Async action refreshing the token list through the server function.
+///
+/// Commits the fetched tokens into the store, or records the error.
+pub fn refresh_tokens() -> ServerFnAction<TokenStore, FetchTokensResponse> {
+    ServerFnAction::new(fetch_tokens)
+        .on_success(|store: &TokenStore, response: &FetchTokensResponse| {
+            store.set_tokens(response.tokens.clone());
+        })
+        .on_error(|store, e| store.set_error(Some(format!("Refresh failed: {e}"))))
+}
+
 /// Get current timestamp as ISO 8601 string
 #[cfg(feature = "ssr")]
 fn current_timestamp() -> String {
@@ -730,5 +815,11 @@ mod tests {
             assert_eq!(tokens[0].id, "hydrate_test");
             assert_eq!(tokens[0].usd_price, 0.5);
         }
+
+        #[test]
+        fn test_empty_store_skips_hydration() {
+            assert!(TokenStore::new().skip_hydration());
+            assert!(!TokenStore::with_tokens(Vec::new()).skip_hydration());
+        }
     }
 }
@@ -0,0 +1,251 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2026 web-mech
+
+//! Implementation of `#[derive(HydratableStore)]`.
+
+use crate::store::signal_state_type;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::{Data, DeriveInput, Field, Fields, Ident, LitStr, Path, Type};
+
+/// Options parsed from the struct-level `#[hydrate(...)]` attribute.
+#[derive(Default)]
+struct HydrateOptions {
+    key: Option<LitStr>,
+    state: Option<Ident>,
+    versioned: bool,
+    binary: bool,
+    masked: bool,
+    validated: bool,
+    codec: Option<Type>,
+    skip_default: bool,
+    skip_if: Option<Path>,
+    sanitize: Option<Path>,
+}
+
+impl HydrateOptions {
+    fn parse(input: &DeriveInput) -> syn::Result<Self> {
+        let mut options = Self::default();
+        for attr in input.attrs.iter().filter(|a| a.path().is_ident("hydrate")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("key") {
+                    options.key = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("state") {
+                    options.state = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("versioned") {
+                    options.versioned = true;
+                } else if meta.path.is_ident("binary") {
+                    options.binary = true;
+                } else if meta.path.is_ident("masked") {
+                    options.masked = true;
+                } else if meta.path.is_ident("validated") {
+                    options.validated = true;
+                } else if meta.path.is_ident("codec") {
+                    options.codec = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("skip_default") {
+                    options.skip_default = true;
+                } else if meta.path.is_ident("skip_if") {
+                    options.skip_if = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("sanitize") {
+                    options.sanitize = Some(meta.value()?.parse()?);
+                } else {
+                    return Err(meta.error(
+                        "unknown hydrate attribute; expected `key = \"...\"`, `state = field`, `versioned`, `binary`, `masked`, `validated`, `codec = Type`, `skip_default`, `skip_if = path`, or `sanitize = path`",
+                    ));
+                }
+                if options.skip_default && options.skip_if.is_some() {
+                    return Err(meta.error("`skip_default` and `skip_if` can't be combined"));
+                }
+                if [
+                    options.versioned,
+                    options.binary,
+                    options.masked,
+                    options.validated,
+                    options.codec.is_some(),
+                ]
+                    .iter()
+                    .filter(|set| **set)
+                    .count()
+                    > 1
+                {
+                    return Err(meta.error(
+                        "`versioned`, `binary`, `masked`, `validated` and `codec` can't be combined",
+                    ));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(options)
+    }
+}
+
+/// Locate the state signal field.
+///
+/// Resolution order: the field named by `#[hydrate(state = ...)]`, a field
+/// named `state`, or the only `RwSignal<_>` field.
+fn find_state_field<'a>(
+    input: &DeriveInput,
+    options: &HydrateOptions,
+    fields: &'a [Field],
+) -> syn::Result<&'a Field> {
+    if let Some(state) = &options.state {
+        return fields
+            .iter()
+            .find(|f| f.ident.as_ref() == Some(state))
+            .ok_or_else(|| syn::Error::new(state.span(), format!("no field named `{state}`")));
+    }
+
+    if let Some(field) = fields
+        .iter()
+        .find(|f| f.ident.as_ref().is_some_and(|i| i == "state"))
+    {
+        return Ok(field);
+    }
+
+    let signals: Vec<&Field> = fields
+        .iter()
+        .filter(|f| signal_state_type(&f.ty).is_some())
+        .collect();
+    match signals.as_slice() {
+        [field] => Ok(field),
+        [] => Err(syn::Error::new(
+            input.ident.span(),
+            "`#[derive(HydratableStore)]` requires a field of type `RwSignal<State>`",
+        )),
+        _ => Err(syn::Error::new(
+            input.ident.span(),
+            "multiple `RwSignal` fields found; name the state field with `#[hydrate(state = field)]`",
+        )),
+    }
+}
+
+pub(crate) fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let options = HydrateOptions::parse(&input)?;
+    let key = options.key.as_ref().ok_or_else(|| {
+        syn::Error::new(
+            input.ident.span(),
+            "`#[derive(HydratableStore)]` requires `#[hydrate(key = \"...\")]`",
+        )
+    })?;
+
+    let fields: Vec<Field> = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(named) => named.named.iter().cloned().collect(),
+            _ => {
+                return Err(syn::Error::new(
+                    input.ident.span(),
+                    "`#[derive(HydratableStore)]` only supports structs with named fields",
+                ));
+            }
+        },
+        _ => {
+            return Err(syn::Error::new(
+                input.ident.span(),
+                "`#[derive(HydratableStore)]` only supports structs",
+            ));
+        }
+    };
+
+    let state_field = find_state_field(&input, &options, &fields)?;
+    let state_ident = state_field.ident.as_ref().expect("named field");
+    let state_ty = signal_state_type(&state_field.ty).ok_or_else(|| {
+        syn::Error::new(
+            state_field.ty.span(),
+            "the store state field must have type `RwSignal<State>`",
+        )
+    })?;
+
+    let other_fields: Vec<&Ident> = fields
+        .iter()
+        .filter_map(|f| f.ident.as_ref())
+        .filter(|i| *i != state_ident)
+        .collect();
+
+    let codec = if options.versioned {
+        quote!(::leptos_store::hydration::VersionedCodec)
+    } else if options.binary {
+        quote!(::leptos_store::hydration::BinaryCodec)
+    } else if options.validated {
+        quote!(::leptos_store::hydration::ValidatedCodec)
+    } else if let Some(codec) = &options.codec {
+        quote!(#codec)
+    } else {
+        quote!(::leptos_store::hydration::JsonCodec)
+    };
+    let (serialize, deserialize) = if options.masked {
+        (
+            quote!((|state| ::leptos_store::hydration::serialize_masked(#key, state))),
+            quote!(::leptos_store::hydration::deserialize_masked),
+        )
+    } else {
+        (
+            quote!(<#codec as ::leptos_store::hydration::StateCodec<#state_ty>>::encode),
+            quote!(<#codec as ::leptos_store::hydration::StateCodec<#state_ty>>::decode),
+        )
+    };
+
+    let skip = if options.skip_default {
+        Some(
+            quote!((|state: &#state_ty| *state == <#state_ty as ::core::default::Default>::default())),
+        )
+    } else {
+        options.skip_if.as_ref().map(|path| quote!(#path))
+    };
+    let skip_hydration = skip.map(|skip| {
+        quote! {
+            fn skip_hydration(&self) -> bool {
+                ::leptos::prelude::WithUntracked::with_untracked(&self.#state_ident, |state| #skip(state))
+            }
+        }
+    });
+
+    let sanitize = options.sanitize.as_ref().map(|path| {
+        quote! {
+            fn sanitize(state: #state_ty) -> #state_ty {
+                #path(state)
+            }
+        }
+    });
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::leptos_store::hydration::HydratableStore for #name #ty_generics #where_clause {
+            fn serialize_state(
+                &self,
+            ) -> ::core::result::Result<String, ::leptos_store::hydration::StoreHydrationError> {
+                let state = ::leptos::prelude::GetUntracked::get_untracked(&self.#state_ident);
+                #serialize(&state)
+            }
+
+            fn from_hydrated_state(
+                data: &str,
+            ) -> ::core::result::Result<Self, ::leptos_store::hydration::StoreHydrationError> {
+                let state: #state_ty = <Self as ::leptos_store::hydration::HydratableStore>::sanitize(#deserialize(data)?);
+                Ok(Self {
+                    #state_ident: ::leptos::prelude::RwSignal::new(state),
+                    #( #other_fields: ::core::default::Default::default(), )*
+                })
+            }
+
+            fn hydrate_into(
+                &self,
+                data: &str,
+            ) -> ::core::result::Result<(), ::leptos_store::hydration::StoreHydrationError> {
+                let state: #state_ty = <Self as ::leptos_store::hydration::HydratableStore>::sanitize(#deserialize(data)?);
+                ::leptos::prelude::Set::set(&self.#state_ident, state);
+                Ok(())
+            }
+
+            #skip_hydration
+
+            #sanitize
+
+            fn store_key() -> &'static str {
+                #key
+            }
+        }
+    })
+}
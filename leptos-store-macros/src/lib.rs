@@ -0,0 +1,88 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2026 web-mech
+
+//! Procedural macros for `leptos-store`.
+//!
+//! This crate is an implementation detail of `leptos-store`; use the
+//! re-exports from `leptos_store` instead of depending on it directly.
+
+use proc_macro::TokenStream;
+use syn::{DeriveInput, ExprStruct, ItemEnum, ItemImpl, ItemStruct, parse_macro_input};
+
+mod hydrate;
+mod mutation;
+mod state;
+mod store;
+mod store_impl;
+
+/// Derive the `Store` trait for a struct holding an `RwSignal<State>`.
+///
+/// See `leptos_store::store::Store` for the full documentation.
+#[proc_macro_derive(Store, attributes(store))]
+pub fn derive_store(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    store::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derive the `HydratableStore` trait for a store holding an `RwSignal<State>`.
+///
+/// See `leptos_store::hydration::HydratableStore` for the full documentation.
+#[proc_macro_derive(HydratableStore, attributes(hydrate))]
+pub fn derive_hydratable_store(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    hydrate::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Classify the methods of a store impl block.
+///
+/// See `leptos_store::store::store` for the full documentation.
+#[proc_macro_attribute]
+pub fn store(args: TokenStream, input: TokenStream) -> TokenStream {
+    let mut options = store_impl::ImplOptions::default();
+    let parser = syn::meta::parser(|meta| options.parse(meta));
+    parse_macro_input!(args with parser);
+    let item = parse_macro_input!(input as ItemImpl);
+    store_impl::expand(options, item)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Generate `with_<field>` builder setters for a `define_state!` struct.
+///
+/// Used by `leptos_store::define_state!`; not meant to be called directly.
+#[doc(hidden)]
+#[proc_macro]
+pub fn state_builders(input: TokenStream) -> TokenStream {
+    let item = parse_macro_input!(input as ItemStruct);
+    state::expand(item)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Emit a `store!` mutation enum with CamelCase variants.
+///
+/// Used by `leptos_store::store!`; not meant to be called directly.
+#[doc(hidden)]
+#[proc_macro]
+pub fn mutation_enum(input: TokenStream) -> TokenStream {
+    let item = parse_macro_input!(input as ItemEnum);
+    mutation::expand_enum(item)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Build a `store!` mutation enum variant named after its mutator.
+///
+/// Used by `leptos_store::store!`; not meant to be called directly.
+#[doc(hidden)]
+#[proc_macro]
+pub fn mutation_variant(input: TokenStream) -> TokenStream {
+    let expr = parse_macro_input!(input as ExprStruct);
+    mutation::expand_variant(expr)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
@@ -0,0 +1,79 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2026 web-mech
+
+//! Mutation enums for `store!` stores.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{ExprStruct, Ident, ItemEnum};
+
+/// Convert a mutator name such as `set_tokens` into a variant name such as
+/// `SetTokens`.
+fn variant_ident(mutator: &Ident) -> Ident {
+    let name = mutator.to_string();
+    let name = name.strip_prefix("r#").unwrap_or(&name);
+    let mut variant = String::with_capacity(name.len());
+    let mut upper = true;
+    for c in name.chars() {
+        if c == '_' {
+            upper = true;
+        } else if upper {
+            variant.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            variant.push(c);
+        }
+    }
+    Ident::new(&variant, mutator.span())
+}
+
+/// Emit the mutation enum declared by `store!`, with its variants renamed
+/// from the mutator names to CamelCase, and a `name()` method returning
+/// the mutator name of a variant.
+///
+/// `#[cfg]` attributes on a variant are copied to its `name()` arm.
+pub(crate) fn expand_enum(mut item: ItemEnum) -> syn::Result<TokenStream> {
+    let mut arms = Vec::with_capacity(item.variants.len());
+    for variant in &mut item.variants {
+        let mutator = variant.ident.to_string();
+        let mutator = mutator.strip_prefix("r#").unwrap_or(&mutator).to_string();
+        variant.ident = variant_ident(&variant.ident);
+        let ident = &variant.ident;
+        let cfgs = variant
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("cfg"));
+        arms.push(quote! {
+            #(#cfgs)*
+            Self::#ident { .. } => #mutator,
+        });
+    }
+
+    let name = &item.ident;
+    Ok(quote! {
+        #item
+
+        impl #name {
+            /// Returns the name of the mutator.
+            #[allow(dead_code)]
+            pub fn name(&self) -> &'static str {
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+    })
+}
+
+/// Rename the variant of a mutation enum struct expression, such as
+/// `CartMutation::add_items { .. }`, to its CamelCase name.
+pub(crate) fn expand_variant(mut expr: ExprStruct) -> syn::Result<TokenStream> {
+    let Some(segment) = expr.path.segments.last_mut() else {
+        return Err(syn::Error::new_spanned(
+            &expr.path,
+            "expected a variant path",
+        ));
+    };
+    segment.ident = variant_ident(&segment.ident);
+    Ok(quote!(#expr))
+}
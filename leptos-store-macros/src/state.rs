@@ -0,0 +1,52 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2026 web-mech
+
+//! Builder setters for `define_state!` structs.
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Fields, ItemStruct};
+
+/// Generate a `with_<field>` setter for every named field of `item`.
+///
+/// The struct itself is emitted by `define_state!`; only the setters are
+/// generated here. `#[cfg]` attributes on a field are copied to its setter.
+pub(crate) fn expand(item: ItemStruct) -> syn::Result<TokenStream> {
+    let Fields::Named(fields) = &item.fields else {
+        return Err(syn::Error::new_spanned(
+            &item.ident,
+            "state builders require named fields",
+        ));
+    };
+
+    let vis = &item.vis;
+    let name = &item.ident;
+    let (impl_generics, ty_generics, where_clause) = item.generics.split_for_impl();
+
+    let setters = fields.named.iter().map(|field| {
+        let field_name = field.ident.as_ref().expect("named field");
+        let ty = &field.ty;
+        let setter = format_ident!("with_{}", field_name);
+        let doc = format!("Returns the state with `{field_name}` set to the given value.");
+        let cfgs = field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("cfg"));
+        quote! {
+            #(#cfgs)*
+            #[doc = #doc]
+            #[allow(dead_code)]
+            #[must_use]
+            #vis fn #setter(mut self, #field_name: #ty) -> Self {
+                self.#field_name = #field_name;
+                self
+            }
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            #(#setters)*
+        }
+    })
+}
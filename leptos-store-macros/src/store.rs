@@ -0,0 +1,218 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2026 web-mech
+
+//! Implementation of `#[derive(Store)]`.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::{Data, DeriveInput, Field, Fields, GenericArgument, Ident, LitStr, PathArguments, Type};
+
+/// Options parsed from the struct-level `#[store(...)]` attribute.
+#[derive(Default)]
+struct StoreOptions {
+    key: Option<LitStr>,
+    default: bool,
+    constructors: bool,
+}
+
+impl StoreOptions {
+    fn parse(input: &DeriveInput) -> syn::Result<Self> {
+        let mut options = Self::default();
+        for attr in input.attrs.iter().filter(|a| a.path().is_ident("store")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("key") {
+                    options.key = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("default") {
+                    options.default = true;
+                } else if meta.path.is_ident("constructors") {
+                    options.constructors = true;
+                } else {
+                    return Err(meta.error(
+                        "unknown store attribute; expected `key = \"...\"`, `default`, or `constructors`",
+                    ));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(options)
+    }
+}
+
+/// Check if a field is marked with `#[store(state)]`.
+fn is_marked_state(field: &Field) -> syn::Result<bool> {
+    let mut marked = false;
+    for attr in field.attrs.iter().filter(|a| a.path().is_ident("store")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("state") {
+                marked = true;
+                Ok(())
+            } else {
+                Err(meta.error("unknown store field attribute; expected `state`"))
+            }
+        })?;
+    }
+    Ok(marked)
+}
+
+/// Extract `State` from a field of type `RwSignal<State>`.
+pub(crate) fn signal_state_type(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != "RwSignal" {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    args.args.iter().find_map(|arg| match arg {
+        GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    })
+}
+
+/// Locate the state signal field.
+///
+/// Resolution order: a field marked `#[store(state)]`, a field named
+/// `state`, or the only `RwSignal<_>` field.
+fn find_state_field<'a>(input: &DeriveInput, fields: &'a [Field]) -> syn::Result<&'a Field> {
+    let mut marked = Vec::new();
+    for field in fields {
+        if is_marked_state(field)? {
+            marked.push(field);
+        }
+    }
+    if marked.len() > 1 {
+        return Err(syn::Error::new(
+            marked[1].span(),
+            "only one field may be marked `#[store(state)]`",
+        ));
+    }
+    if let Some(field) = marked.pop() {
+        return Ok(field);
+    }
+
+    if let Some(field) = fields
+        .iter()
+        .find(|f| f.ident.as_ref().is_some_and(|i| i == "state"))
+    {
+        return Ok(field);
+    }
+
+    let signals: Vec<&Field> = fields
+        .iter()
+        .filter(|f| signal_state_type(&f.ty).is_some())
+        .collect();
+    match signals.as_slice() {
+        [field] => Ok(field),
+        [] => Err(syn::Error::new(
+            input.ident.span(),
+            "`#[derive(Store)]` requires a field of type `RwSignal<State>`",
+        )),
+        _ => Err(syn::Error::new(
+            input.ident.span(),
+            "multiple `RwSignal` fields found; mark the state field with `#[store(state)]`",
+        )),
+    }
+}
+
+pub(crate) fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let options = StoreOptions::parse(&input)?;
+
+    let fields: Vec<Field> = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(named) => named.named.iter().cloned().collect(),
+            _ => {
+                return Err(syn::Error::new(
+                    input.ident.span(),
+                    "`#[derive(Store)]` only supports structs with named fields",
+                ));
+            }
+        },
+        _ => {
+            return Err(syn::Error::new(
+                input.ident.span(),
+                "`#[derive(Store)]` only supports structs",
+            ));
+        }
+    };
+
+    let state_field = find_state_field(&input, &fields)?;
+    let state_ident = state_field.ident.as_ref().expect("named field");
+    let state_ty = signal_state_type(&state_field.ty).ok_or_else(|| {
+        syn::Error::new(
+            state_field.ty.span(),
+            "the store state field must have type `RwSignal<State>`",
+        )
+    })?;
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let name_fn = options.key.as_ref().map(|key| {
+        quote! {
+            fn name(&self) -> &'static str {
+                #key
+            }
+        }
+    });
+
+    let other_fields: Vec<&Ident> = fields
+        .iter()
+        .filter_map(|f| f.ident.as_ref())
+        .filter(|i| *i != state_ident)
+        .collect();
+
+    let constructors = options.constructors.then(|| {
+        quote! {
+            impl #impl_generics #name #ty_generics #where_clause {
+                /// Create a new store with default state.
+                #[allow(dead_code)]
+                pub fn new() -> Self {
+                    Self::with_state(::core::default::Default::default())
+                }
+
+                /// Create a new store with custom initial state.
+                #[allow(dead_code)]
+                pub fn with_state(state: #state_ty) -> Self {
+                    Self {
+                        #state_ident: ::leptos::prelude::RwSignal::new(state),
+                        #( #other_fields: ::core::default::Default::default(), )*
+                    }
+                }
+            }
+        }
+    });
+
+    let default_impl = options.default.then(|| {
+        quote! {
+            impl #impl_generics ::core::default::Default for #name #ty_generics #where_clause {
+                fn default() -> Self {
+                    Self {
+                        #state_ident: ::leptos::prelude::RwSignal::new(
+                            ::core::default::Default::default(),
+                        ),
+                        #( #other_fields: ::core::default::Default::default(), )*
+                    }
+                }
+            }
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics ::leptos_store::store::Store for #name #ty_generics #where_clause {
+            type State = #state_ty;
+
+            fn state(&self) -> ::leptos::prelude::ReadSignal<Self::State> {
+                self.#state_ident.read_only()
+            }
+
+            #name_fn
+        }
+
+        #constructors
+        #default_impl
+    })
+}
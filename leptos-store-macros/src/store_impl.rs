@@ -0,0 +1,189 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2026 web-mech
+
+//! Implementation of the `#[store]` attribute on impl blocks.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::meta::ParseNestedMeta;
+use syn::spanned::Spanned;
+use syn::{Ident, ImplItem, ImplItemFn, ItemImpl, Visibility, parse_quote};
+
+/// Method categories recognized inside a `#[store]` impl block.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum MethodKind {
+    Getter,
+    Mutator,
+    Action,
+}
+
+impl MethodKind {
+    fn from_ident(ident: &Ident) -> Option<Self> {
+        if ident == "getter" {
+            Some(Self::Getter)
+        } else if ident == "mutator" {
+            Some(Self::Mutator)
+        } else if ident == "action" {
+            Some(Self::Action)
+        } else {
+            None
+        }
+    }
+}
+
+/// Options parsed from `#[store(...)]`.
+pub(crate) struct ImplOptions {
+    state: Ident,
+}
+
+impl Default for ImplOptions {
+    fn default() -> Self {
+        Self {
+            state: Ident::new("state", proc_macro2::Span::call_site()),
+        }
+    }
+}
+
+impl ImplOptions {
+    pub(crate) fn parse(&mut self, meta: ParseNestedMeta) -> syn::Result<()> {
+        if meta.path.is_ident("state") {
+            self.state = meta.value()?.parse()?;
+            Ok(())
+        } else {
+            Err(meta.error("unknown store attribute; expected `state = field`"))
+        }
+    }
+}
+
+/// Remove the classification attribute from a method, returning its kind.
+fn take_kind(method: &mut ImplItemFn) -> syn::Result<Option<MethodKind>> {
+    let mut kind = None;
+    let mut error: Option<syn::Error> = None;
+    method.attrs.retain(|attr| {
+        let Some(found) = attr.path().get_ident().and_then(MethodKind::from_ident) else {
+            return true;
+        };
+        if kind.is_some() {
+            error.get_or_insert_with(|| {
+                syn::Error::new(
+                    attr.span(),
+                    "a method can only be one of `#[getter]`, `#[mutator]`, or `#[action]`",
+                )
+            });
+        }
+        kind = Some(found);
+        false
+    });
+    match error {
+        Some(err) => Err(err),
+        None => Ok(kind),
+    }
+}
+
+fn check_receiver(method: &ImplItemFn, what: &str) -> syn::Result<()> {
+    match method.sig.receiver() {
+        Some(receiver) if receiver.reference.is_some() && receiver.mutability.is_none() => Ok(()),
+        _ => Err(syn::Error::new(
+            method.sig.span(),
+            format!("{what} must take `&self`"),
+        )),
+    }
+}
+
+pub(crate) fn expand(options: ImplOptions, mut item: ItemImpl) -> syn::Result<TokenStream> {
+    if item.trait_.is_some() {
+        return Err(syn::Error::new(
+            item.span(),
+            "`#[store]` can only be applied to inherent impl blocks",
+        ));
+    }
+
+    let mut getters = Vec::new();
+    let mut mutators = Vec::new();
+    let mut actions = Vec::new();
+
+    for impl_item in &mut item.items {
+        let ImplItem::Fn(method) = impl_item else {
+            continue;
+        };
+        let Some(kind) = take_kind(method)? else {
+            continue;
+        };
+        let name = method.sig.ident.to_string();
+
+        match kind {
+            MethodKind::Getter => {
+                check_receiver(method, "getters")?;
+                if method.sig.asyncness.is_some() {
+                    return Err(syn::Error::new(
+                        method.sig.asyncness.span(),
+                        "getters must be synchronous",
+                    ));
+                }
+                getters.push(name);
+            }
+            MethodKind::Mutator => {
+                check_receiver(method, "mutators")?;
+                if method.sig.asyncness.is_some() {
+                    return Err(syn::Error::new(
+                        method.sig.asyncness.span(),
+                        "mutators must be synchronous",
+                    ));
+                }
+                if !matches!(method.vis, Visibility::Inherited) {
+                    return Err(syn::Error::new(
+                        method.vis.span(),
+                        "mutators must be private; expose state changes through an `#[action]`",
+                    ));
+                }
+                mutators.push(name);
+            }
+            MethodKind::Action => {
+                if matches!(method.vis, Visibility::Inherited) {
+                    method.vis = parse_quote!(pub);
+                }
+                actions.push(name);
+            }
+        }
+    }
+
+    let state = &options.state;
+    let self_ty = &item.self_ty;
+    let (impl_generics, _, where_clause) = item.generics.split_for_impl();
+
+    Ok(quote! {
+        #item
+
+        impl #impl_generics #self_ty #where_clause {
+            /// Read state with a closure (for getters).
+            #[allow(dead_code)]
+            #[inline]
+            fn read<R>(
+                &self,
+                f: impl FnOnce(&<Self as ::leptos_store::store::Store>::State) -> R,
+            ) -> R {
+                ::leptos::prelude::With::with(&self.#state, f)
+            }
+
+            /// Update state with a closure (for mutators).
+            #[allow(dead_code)]
+            #[inline]
+            fn mutate<R>(
+                &self,
+                f: impl FnOnce(&mut <Self as ::leptos_store::store::Store>::State) -> R,
+            ) -> R {
+                ::leptos::prelude::Update::try_update(&self.#state, f).expect("signal disposed")
+            }
+        }
+
+        impl #impl_generics ::leptos_store::store::DescribeStore for #self_ty #where_clause {
+            fn methods() -> ::leptos_store::store::StoreMethods {
+                ::leptos_store::store::StoreMethods {
+                    getters: &[#(#getters),*],
+                    mutators: &[#(#mutators),*],
+                    actions: &[#(#actions),*],
+                }
+            }
+        }
+    })
+}
@@ -62,6 +62,7 @@ echo ""
 echo "=== Publishing to crates.io ==="
 
 if [ -n "$DRY_RUN" ]; then
+    cargo publish -p leptos-store-macros --dry-run
     cargo publish --dry-run
     echo ""
     echo "Dry run complete. Run without --dry-run to actually publish."
@@ -74,7 +75,8 @@ else
     echo "Pushing tag to origin..."
     git push origin "$TAG"
     
-    # Publish to crates.io
+    # Publish to crates.io (the macros crate must go first)
+    cargo publish -p leptos-store-macros
     cargo publish
     echo ""
     echo "✅ Successfully published leptos-store v$VERSION to crates.io!"
@@ -0,0 +1,266 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2026 web-mech
+
+//! Per-request stores for Actix servers rendering with `leptos_actix`.
+//!
+//! `leptos_actix` renders each request under its own reactive owner and
+//! provides the [`HttpRequest`] in context. [`ActixStores`] lists the
+//! stores an app needs and how to build them from the request, with its
+//! app data, headers, cookies, and extensions (where session middleware
+//! keeps the session). Pass its [`context`](ActixStores::context) closure
+//! to `leptos_routes_with_context`, and every request gets fresh stores
+//! provided to the app, with hydratable stores registered for the
+//! `<StoreHydrationScripts/>` in the shell.
+//!
+//! This is the Actix counterpart of [`crate::axum`]. The module is only
+//! available off `wasm32`. It doesn't depend on `leptos_actix`, which
+//! would turn on Leptos' `ssr` feature for everything built with this
+//! crate; name its request type when creating the context instead.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use leptos_store::actix::ActixStores;
+//!
+//! let stores = ActixStores::new()
+//!     .hydrated_store(|req| {
+//!         let user = req.cookie("user").map(|c| c.value().to_string());
+//!         AuthStore::with_user(user)
+//!     })
+//!     .store(|req| {
+//!         let db = req.app_data::<web::Data<Pool>>().cloned();
+//!         CartStore::new(db)
+//!     });
+//!
+//! HttpServer::new(move || {
+//!     let context = stores.clone().context::<leptos_actix::Request>();
+//!     App::new().leptos_routes_with_context(routes.clone(), context, shell)
+//! })
+//! ```
+
+use crate::context::provide_store;
+use crate::request::{RequestData, StoreFromRequest};
+use crate::store::Store;
+use actix_web::{HttpMessage, HttpRequest};
+use leptos::prelude::*;
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// Builds one store from a request and provides it.
+type StoreInit = Arc<dyn Fn(&HttpRequest) + Send + Sync>;
+
+/// The stores to create for every request, and how.
+#[derive(Clone, Default)]
+pub struct ActixStores {
+    stores: Vec<StoreInit>,
+}
+
+impl ActixStores {
+    /// Create an empty list of request stores.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build a store from each request with `factory` and provide it.
+    pub fn store<S, F>(mut self, factory: F) -> Self
+    where
+        S: Store + Clone + Send + Sync + 'static,
+        F: Fn(&HttpRequest) -> S + Send + Sync + 'static,
+    {
+        self.stores
+            .push(Arc::new(move |req| provide_store(factory(req))));
+        self
+    }
+
+    /// Build a hydratable store from each request with `factory`, provide
+    /// it, and register it for `<StoreHydrationScripts/>`.
+    ///
+    /// See [`register_hydrated_store`](crate::context::register_hydrated_store).
+    #[cfg(feature = "hydrate")]
+    pub fn hydrated_store<S, F>(mut self, factory: F) -> Self
+    where
+        S: crate::hydration::HydratableStore + Clone + Send + Sync + 'static,
+        F: Fn(&HttpRequest) -> S + Send + Sync + 'static,
+    {
+        self.stores.push(Arc::new(move |req| {
+            crate::context::register_hydrated_store(factory(req))
+        }));
+        self
+    }
+
+    /// Build a store from each request with its [`StoreFromRequest`] impl
+    /// and provide it.
+    pub fn from_request<S>(self) -> Self
+    where
+        S: StoreFromRequest + Clone + Send + Sync + 'static,
+    {
+        self.store(|req| S::from_request(req))
+    }
+
+    /// Build a hydratable store from each request with its
+    /// [`StoreFromRequest`] impl, provide it, and register it for
+    /// `<StoreHydrationScripts/>`.
+    #[cfg(feature = "hydrate")]
+    pub fn hydrated_from_request<S>(self) -> Self
+    where
+        S: StoreFromRequest + crate::hydration::HydratableStore + Clone + Send + Sync + 'static,
+    {
+        self.hydrated_store(|req| S::from_request(req))
+    }
+
+    /// Create and provide every store for the current request.
+    ///
+    /// Reads the request from context as an `R`, the request wrapper
+    /// `leptos_actix` provides (`leptos_actix::Request`); outside a request,
+    /// nothing is provided.
+    pub fn provide<R>(&self)
+    where
+        R: Deref<Target = HttpRequest> + Clone + Send + Sync + 'static,
+    {
+        let Some(req) = use_context::<R>() else {
+            leptos::logging::error!("No Actix request in context; request stores not provided");
+            return;
+        };
+        for init in &self.stores {
+            init(&req);
+        }
+    }
+
+    /// Returns a closure providing the stores, for the additional context
+    /// of `leptos_routes_with_context`.
+    ///
+    /// `R` is the request wrapper in context, `leptos_actix::Request`.
+    pub fn context<R>(self) -> impl Fn() + Clone + Send + Sync + 'static
+    where
+        R: Deref<Target = HttpRequest> + Clone + Send + Sync + 'static,
+    {
+        move || self.provide::<R>()
+    }
+}
+
+impl RequestData for HttpRequest {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers().get(name)?.to_str().ok()
+    }
+
+    fn cookie(&self, name: &str) -> Option<String> {
+        HttpRequest::cookie(self, name).map(|cookie| cookie.value().to_string())
+    }
+
+    fn extension<T: Clone + Send + Sync + 'static>(&self) -> Option<T> {
+        self.extensions().get::<T>().cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::{try_use_store, use_store};
+    use actix_web::test::TestRequest;
+    use send_wrapper::SendWrapper;
+
+    /// Stands in for `leptos_actix::Request`.
+    #[derive(Clone)]
+    struct Request(Arc<SendWrapper<HttpRequest>>);
+
+    impl Deref for Request {
+        type Target = HttpRequest;
+
+        fn deref(&self) -> &HttpRequest {
+            &self.0
+        }
+    }
+
+    #[derive(Clone, Debug, Default, PartialEq)]
+    struct TestState {
+        user: String,
+    }
+
+    #[derive(Clone)]
+    struct TestStore {
+        state: RwSignal<TestState>,
+    }
+
+    impl Store for TestStore {
+        type State = TestState;
+
+        fn state(&self) -> ReadSignal<Self::State> {
+            self.state.read_only()
+        }
+    }
+
+    fn user_store_named(user: &str) -> TestStore {
+        TestStore {
+            state: RwSignal::new(TestState {
+                user: user.to_string(),
+            }),
+        }
+    }
+
+    fn user_store(req: &HttpRequest) -> TestStore {
+        let user = req.headers().get("x-user").and_then(|v| v.to_str().ok());
+        user_store_named(user.unwrap_or("anonymous"))
+    }
+
+    /// A session inserted by middleware.
+    #[derive(Clone)]
+    struct Session(String);
+
+    impl StoreFromRequest for TestStore {
+        fn from_request(request: &impl RequestData) -> Self {
+            let session = request.extension::<Session>();
+            let user = session.map(|s| s.0).or_else(|| request.cookie("user"));
+            user_store_named(user.as_deref().unwrap_or("anonymous"))
+        }
+    }
+
+    #[test]
+    fn test_stores_built_from_request() {
+        let context = ActixStores::new().store(user_store).context::<Request>();
+
+        for user in ["alice", "bob"] {
+            let owner = Owner::new();
+            owner.with(|| {
+                let req = TestRequest::default()
+                    .insert_header(("x-user", user))
+                    .to_http_request();
+                provide_context(Request(Arc::new(SendWrapper::new(req))));
+
+                context();
+                assert_eq!(use_store::<TestStore>().state.get_untracked().user, user);
+            });
+        }
+    }
+
+    #[test]
+    fn test_stores_from_request() {
+        let context = ActixStores::new()
+            .from_request::<TestStore>()
+            .context::<Request>();
+        for (session, user) in [(None, "alice"), (Some("bob"), "bob")] {
+            let owner = Owner::new();
+            owner.with(|| {
+                let req = TestRequest::default()
+                    .insert_header(("cookie", "theme=dark; user=alice"))
+                    .to_http_request();
+                assert_eq!(req.header("cookie"), Some("theme=dark; user=alice"));
+                if let Some(session) = session {
+                    req.extensions_mut().insert(Session(session.to_string()));
+                }
+                provide_context(Request(Arc::new(SendWrapper::new(req))));
+
+                context();
+                assert_eq!(use_store::<TestStore>().state.get_untracked().user, user);
+            });
+        }
+    }
+
+    #[test]
+    fn test_stores_without_request() {
+        let owner = Owner::new();
+        owner.with(|| {
+            ActixStores::new().store(user_store).provide::<Request>();
+            assert!(try_use_store::<TestStore>().is_err());
+        });
+    }
+}
@@ -33,19 +33,49 @@
 //! assert!(state.is_finished());
 //! ```
 
-use futures::future::BoxFuture;
+use futures::channel::oneshot;
+use futures::future::{BoxFuture, Shared, WeakShared};
+use futures::{FutureExt, Stream, StreamExt};
 use leptos::prelude::*;
+use leptos::server::codee::string::JsonSerdeCodec;
+use leptos::server::codee::{Decoder, Encoder};
 use pin_project_lite::pin_project;
+use std::any::{Any, TypeId};
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
 use std::future::Future;
+use std::hash::{BuildHasher, RandomState};
 use std::marker::PhantomData;
 use std::pin::Pin;
-use std::task::{Context, Poll};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError, Weak};
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
 use thiserror::Error;
 
-use crate::store::Store;
+use crate::deps::{self, ActionDeps};
+use crate::middleware;
+use crate::store::{DryRunStore, MutationPlan, Store, record_dry_run};
 
 /// Errors that can occur during action execution.
-#[derive(Debug, Error)]
+///
+/// Use [`ActionError::from_error`] to keep the original error instead of
+/// flattening it into a message, so callers can still react to it:
+///
+/// ```rust
+/// use leptos_store::prelude::*;
+/// use std::error::Error;
+///
+/// #[derive(Debug, thiserror::Error)]
+/// #[error("HTTP {0}")]
+/// struct HttpError(u16);
+///
+/// let err = ActionError::from_error(HttpError(401));
+/// assert_eq!(err.to_string(), "Action failed: HTTP 401");
+/// assert!(matches!(err.downcast_ref::<HttpError>(), Some(HttpError(401))));
+/// assert!(err.source().is_some_and(|source| source.is::<HttpError>()));
+/// ```
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
 pub enum ActionError {
     /// The action was cancelled.
     #[error("Action cancelled")]
@@ -66,6 +96,15 @@ pub enum ActionError {
     /// Validation error before action execution.
     #[error("Validation error: {0}")]
     Validation(String),
+
+    /// The action was rejected by a [`RateLimiter`].
+    #[error("Rate limit exceeded")]
+    RateLimited,
+
+    /// The action failed with an underlying error, available as the
+    /// [`source`](std::error::Error::source).
+    #[error("Action failed: {0}")]
+    Source(#[source] SourceError),
 }
 
 impl ActionError {
@@ -83,6 +122,85 @@ impl ActionError {
     pub fn validation(msg: impl Into<String>) -> Self {
         Self::Validation(msg.into())
     }
+
+    /// Create an error keeping `err` as its source.
+    pub fn from_error(err: impl std::error::Error + Send + Sync + 'static) -> Self {
+        Self::Source(SourceError::new(err))
+    }
+
+    /// Returns the source error if it's a `T`.
+    pub fn downcast_ref<T: std::error::Error + 'static>(&self) -> Option<&T> {
+        match self {
+            Self::Source(source) => source.downcast_ref(),
+            _ => None,
+        }
+    }
+}
+
+/// The underlying error of [`ActionError::Source`].
+///
+/// Clones share the same error, and two `SourceError`s are equal only if
+/// they share it.
+#[derive(Clone)]
+pub struct SourceError(Arc<dyn std::error::Error + Send + Sync>);
+
+impl SourceError {
+    /// Wrap `err`.
+    pub fn new(err: impl std::error::Error + Send + Sync + 'static) -> Self {
+        Self(Arc::new(err))
+    }
+
+    /// Returns the error if it's a `T`.
+    pub fn downcast_ref<T: std::error::Error + 'static>(&self) -> Option<&T> {
+        self.0.downcast_ref()
+    }
+}
+
+impl From<Box<dyn std::error::Error + Send + Sync>> for SourceError {
+    fn from(err: Box<dyn std::error::Error + Send + Sync>) -> Self {
+        Self(Arc::from(err))
+    }
+}
+
+// Deliberately not `Error` itself, so `ActionError::source` returns the
+// wrapped error rather than this wrapper.
+impl std::ops::Deref for SourceError {
+    type Target = dyn std::error::Error + Send + Sync;
+
+    fn deref(&self) -> &Self::Target {
+        &*self.0
+    }
+}
+
+impl PartialEq for SourceError {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for SourceError {}
+
+impl fmt::Debug for SourceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&*self.0, f)
+    }
+}
+
+impl fmt::Display for SourceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&*self.0, f)
+    }
+}
+
+impl From<ServerFnError> for ActionError {
+    /// Keeps request failures as [`ActionError::Network`]; other server
+    /// function errors become [`ActionError::Failed`].
+    fn from(err: ServerFnError) -> Self {
+        match err {
+            ServerFnError::Request(msg) => Self::Network(msg),
+            err => Self::Failed(err.to_string()),
+        }
+    }
 }
 
 /// Result type for actions.
@@ -220,222 +338,3374 @@ pub trait AsyncAction<S: Store>: Send + Sync {
     ) -> impl Future<Output = ActionResult<Self::Output, Self::Error>> + Send;
 }
 
-/// A boxed async action for type erasure.
-pub type BoxedAsyncAction<S, O, E> =
-    Box<dyn Fn(&S) -> BoxFuture<'static, ActionResult<O, E>> + Send + Sync>;
-
-/// Builder for constructing async actions with fluent API.
+/// Trait for async actions producing a stream of results.
+///
+/// Use this for paginated fetches, server-sent events, and other sources
+/// that deliver data incrementally. With
+/// [`StoreActionExt::dispatch_stream`], each item is passed to a mutator as
+/// it arrives instead of once at the end.
 ///
 /// # Example
 ///
 /// ```rust
+/// use futures::stream::{self, Stream, StreamExt};
 /// use leptos::prelude::*;
 /// use leptos_store::prelude::*;
 ///
 /// #[derive(Clone, Default)]
-/// struct MyState { value: i32 }
+/// struct FeedState { posts: Vec<String> }
 ///
 /// #[derive(Clone)]
-/// struct MyStore { state: RwSignal<MyState> }
+/// struct FeedStore { state: RwSignal<FeedState> }
 ///
-/// impl Store for MyStore {
-///     type State = MyState;
+/// impl Store for FeedStore {
+///     type State = FeedState;
 ///     fn state(&self) -> ReadSignal<Self::State> { self.state.read_only() }
 /// }
 ///
-/// let builder: AsyncActionBuilder<MyStore, (), ActionError> = AsyncActionBuilder::new()
-///     .with_timeout(5000)
-///     .with_retry(3);
+/// struct LoadPages { pages: u32 }
 ///
-/// assert_eq!(builder.timeout_ms(), Some(5000));
-/// assert_eq!(builder.retry_count(), 3);
+/// impl StreamAction<FeedStore> for LoadPages {
+///     type Item = Vec<String>;
+///     type Error = ActionError;
+///
+///     fn stream(
+///         &self,
+///         _store: &FeedStore,
+///     ) -> impl Stream<Item = ActionResult<Vec<String>>> + Send {
+///         stream::iter(0..self.pages).then(|page| async move {
+///             // Fetch one page
+///             Ok(vec![format!("post {page}")])
+///         })
+///     }
+/// }
+///
+/// let store = FeedStore { state: RwSignal::new(FeedState::default()) };
+/// let pages: Vec<_> = futures::executor::block_on(LoadPages { pages: 2 }.stream(&store).collect());
+/// assert_eq!(pages.len(), 2);
 /// ```
-pub struct AsyncActionBuilder<S: Store, O, E> {
-    timeout_ms: Option<u64>,
-    retry_count: u32,
-    _marker: PhantomData<(S, O, E)>,
+pub trait StreamAction<S: Store>: Send + Sync {
+    /// The type of each item produced by this action.
+    type Item: Send;
+
+    /// The error type that can end the stream.
+    type Error: Send + std::error::Error;
+
+    /// Start the action, returning its stream of results.
+    ///
+    /// An `Err` item ends the dispatch.
+    fn stream(&self, store: &S)
+    -> impl Stream<Item = ActionResult<Self::Item, Self::Error>> + Send;
 }
 
-impl<S: Store, O, E> Default for AsyncActionBuilder<S, O, E> {
-    fn default() -> Self {
-        Self::new()
-    }
+/// An async action identified by a key, so that identical dispatches can
+/// share one execution.
+///
+/// Two actions of the same type with the same key are considered the same
+/// request, e.g. fetches of the same URL. The output and error must be
+/// `Clone` so the result can be shared. See
+/// [`StoreActionExt::dispatch_deduped`].
+pub trait KeyedAction<S: Store>:
+    AsyncAction<S, Output: Clone + Sync, Error: Clone + Sync> + 'static
+{
+    /// Returns the key identifying the request this action makes.
+    fn action_key(&self) -> String;
 }
 
-impl<S: Store, O, E> AsyncActionBuilder<S, O, E> {
-    /// Create a new async action builder.
-    pub fn new() -> Self {
-        Self {
-            timeout_ms: None,
-            retry_count: 0,
-            _marker: PhantomData,
-        }
-    }
+type SharedResult<O, E> = Shared<BoxFuture<'static, ActionResult<O, E>>>;
 
-    /// Set a timeout for the action in milliseconds.
-    pub fn with_timeout(mut self, timeout_ms: u64) -> Self {
-        self.timeout_ms = Some(timeout_ms);
-        self
-    }
+/// An in-flight dispatch of a [`KeyedAction`].
+struct InFlightEntry<S: Store, O, E> {
+    /// Identifies the store instance. Compared by pointer; its `Hash` impl
+    /// isn't stable across handles, so entries are searched linearly.
+    state: ArcReadSignal<S::State>,
+    key: String,
+    result: WeakShared<BoxFuture<'static, ActionResult<O, E>>>,
+}
 
-    /// Set the number of retry attempts.
-    pub fn with_retry(mut self, count: u32) -> Self {
-        self.retry_count = count;
-        self
-    }
+/// In-flight dispatches of one action type.
+type InFlight<S, O, E> = Vec<InFlightEntry<S, O, E>>;
 
-    /// Get the configured timeout.
-    pub fn timeout_ms(&self) -> Option<u64> {
-        self.timeout_ms
-    }
+/// In-flight dispatches of every [`KeyedAction`] type.
+static IN_FLIGHT: Mutex<BTreeMap<TypeId, Box<dyn Any + Send>>> = Mutex::new(BTreeMap::new());
 
-    /// Get the configured retry count.
-    pub fn retry_count(&self) -> u32 {
-        self.retry_count
-    }
+/// Run `f` with the in-flight dispatches of action type `A`.
+fn with_in_flight<S, A, R>(f: impl FnOnce(&mut InFlight<S, A::Output, A::Error>) -> R) -> R
+where
+    S: Store,
+    A: KeyedAction<S>,
+{
+    let mut registry = IN_FLIGHT.lock().unwrap_or_else(PoisonError::into_inner);
+    let in_flight = registry
+        .entry(TypeId::of::<A>())
+        .or_insert_with(|| Box::new(InFlight::<S, A::Output, A::Error>::new()))
+        .downcast_mut::<InFlight<S, A::Output, A::Error>>()
+        .expect("in-flight dispatches are keyed by action type");
+    f(in_flight)
 }
 
-pin_project! {
-    /// A future that wraps an async action execution.
-    pub struct ActionFuture<F> {
-        #[pin]
-        inner: F,
-        state: ActionState,
-    }
+/// Keys of the keyed dispatches in flight on the stores of one type.
+struct PendingKeys<S: Store> {
+    /// Notified when a key of any store of this type starts or stops being
+    /// pending. Kept per type, so it outlives the stores it reports on.
+    changed: ArcTrigger,
+    /// In-flight dispatch count per store instance and key.
+    pending: Vec<(ArcReadSignal<S::State>, String, usize)>,
 }
 
-impl<F> ActionFuture<F> {
-    /// Create a new action future.
-    pub fn new(inner: F) -> Self {
-        Self {
-            inner,
-            state: ActionState::Pending,
-        }
-    }
+/// Pending keys of every store type with keyed dispatches.
+static PENDING_KEYS: Mutex<BTreeMap<TypeId, Box<dyn Any + Send>>> = Mutex::new(BTreeMap::new());
 
-    /// Get the current state of the action.
-    pub fn state(&self) -> &ActionState {
-        &self.state
-    }
+/// Call `f` with the pending keys of stores of type `S`.
+fn with_pending_keys<S: Store, R>(f: impl FnOnce(&mut PendingKeys<S>) -> R) -> R {
+    let mut registry = PENDING_KEYS.lock().unwrap_or_else(PoisonError::into_inner);
+    let keys = registry
+        .entry(TypeId::of::<S>())
+        .or_insert_with(|| {
+            Box::new(PendingKeys::<S> {
+                changed: ArcTrigger::new(),
+                pending: Vec::new(),
+            })
+        })
+        .downcast_mut::<PendingKeys<S>>()
+        .expect("pending keys are keyed by store type");
+    f(keys)
 }
 
-impl<F, T, E> Future for ActionFuture<F>
-where
-    F: Future<Output = ActionResult<T, E>>,
-{
-    type Output = ActionResult<T, E>;
-
-    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        let this = self.project();
+/// Marks `key` as pending on a store instance until dropped.
+struct PendingKey<S: Store> {
+    state: ArcReadSignal<S::State>,
+    key: String,
+}
 
-        match this.inner.poll(cx) {
-            Poll::Ready(Ok(value)) => {
-                *this.state = ActionState::Success;
-                Poll::Ready(Ok(value))
+impl<S: Store> PendingKey<S> {
+    fn new(state: ArcReadSignal<S::State>, key: String) -> Self {
+        let changed = with_pending_keys::<S, _>(|keys| {
+            match keys
+                .pending
+                .iter_mut()
+                .find(|(pending, pending_key, _)| *pending == state && *pending_key == key)
+            {
+                Some((_, _, count)) => *count += 1,
+                None => keys.pending.push((state.clone(), key.clone(), 1)),
             }
-            Poll::Ready(Err(err)) => {
-                *this.state = ActionState::Error;
-                Poll::Ready(Err(err))
+            keys.changed.clone()
+        });
+        changed.notify();
+        Self { state, key }
+    }
+}
+
+impl<S: Store> Drop for PendingKey<S> {
+    fn drop(&mut self) {
+        let changed = with_pending_keys::<S, _>(|keys| {
+            for (state, key, count) in &mut keys.pending {
+                if *state == self.state && *key == self.key {
+                    *count -= 1;
+                }
             }
-            Poll::Pending => Poll::Pending,
-        }
+            keys.pending.retain(|(_, _, count)| *count > 0);
+            keys.changed.clone()
+        });
+        changed.notify();
     }
 }
 
-/// Reactive action handle for use in components.
+/// An async action whose successful results are cached for a while.
 ///
-/// This provides a way to track action state reactively and
-/// dispatch actions from event handlers.
-#[derive(Clone)]
-pub struct ReactiveAction<I, O>
-where
-    I: Clone + Send + Sync + 'static,
-    O: Clone + Send + Sync + 'static,
-{
-    input: RwSignal<Option<I>>,
-    value: RwSignal<Option<O>>,
-    pending: RwSignal<bool>,
-    version: RwSignal<usize>,
+/// Dispatching the action with [`StoreActionExt::dispatch_cached`] within
+/// `ttl_ms` of a successful dispatch with the same key on the same store
+/// instance returns the cached output instead of executing again. Errors
+/// aren't cached. Use [`StoreActionExt::invalidate`] to drop a cached
+/// result early, e.g. after a mutation made it stale.
+pub trait CachedAction<S: Store>: AsyncAction<S, Output: Clone + Sync> + 'static {
+    /// Returns the key identifying the cached result.
+    fn cache_key(&self) -> String;
+
+    /// Returns how long a result stays cached, in milliseconds.
+    fn ttl_ms(&self) -> u64;
 }
 
-impl<I, O> Default for ReactiveAction<I, O>
-where
-    I: Clone + Send + Sync + 'static,
-    O: Clone + Send + Sync + 'static,
-{
-    fn default() -> Self {
-        Self::new()
+/// A cached [`CachedAction`] output.
+struct CacheEntry {
+    id: u64,
+    /// The store instance, as an `ArcReadSignal` of its state.
+    state: Box<dyn Any + Send>,
+    action: TypeId,
+    key: String,
+    output: Box<dyn Any + Send>,
+}
+
+impl CacheEntry {
+    /// Check if the entry was cached by `state`'s store.
+    fn is_for<T: 'static>(&self, state: &ArcReadSignal<T>) -> bool {
+        self.state.downcast_ref::<ArcReadSignal<T>>() == Some(state)
     }
 }
 
-impl<I, O> ReactiveAction<I, O>
-where
-    I: Clone + Send + Sync + 'static,
-    O: Clone + Send + Sync + 'static,
-{
-    /// Create a new reactive action.
-    pub fn new() -> Self {
-        Self {
-            input: RwSignal::new(None),
-            value: RwSignal::new(None),
-            pending: RwSignal::new(false),
-            version: RwSignal::new(0),
+static ACTION_CACHE: Mutex<Vec<CacheEntry>> = Mutex::new(Vec::new());
+static NEXT_CACHE_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Runs the queued dispatches of one store instance one at a time.
+#[derive(Default)]
+struct StoreQueue {
+    state: Mutex<StoreQueueState>,
+}
+
+#[derive(Default)]
+struct StoreQueueState {
+    running: bool,
+    waiting: VecDeque<oneshot::Sender<QueuePermit>>,
+}
+
+/// Queues of every store instance with queued dispatches, by store type.
+type StoreQueues<S> = Vec<(ArcReadSignal<<S as Store>::State>, Weak<StoreQueue>)>;
+
+static STORE_QUEUES: Mutex<BTreeMap<TypeId, Box<dyn Any + Send>>> = Mutex::new(BTreeMap::new());
+
+impl StoreQueue {
+    /// Returns the queue of `store`.
+    ///
+    /// Queues are only kept alive by their dispatches, so an idle store
+    /// holds none.
+    fn of<S: Store>(store: &S) -> Arc<Self> {
+        let state = ArcReadSignal::from(store.state());
+        let mut registry = STORE_QUEUES.lock().unwrap_or_else(PoisonError::into_inner);
+        let queues = registry
+            .entry(TypeId::of::<S>())
+            .or_insert_with(|| Box::new(StoreQueues::<S>::new()))
+            .downcast_mut::<StoreQueues<S>>()
+            .expect("store queues are keyed by store type");
+        queues.retain(|(_, queue)| queue.strong_count() > 0);
+        if let Some(queue) = queues
+            .iter()
+            .find(|(queued, _)| *queued == state)
+            .and_then(|(_, queue)| queue.upgrade())
+        {
+            return queue;
         }
+        let queue = Arc::new(Self::default());
+        queues.push((state, Arc::downgrade(&queue)));
+        queue
     }
 
-    /// Get the current input value.
-    pub fn input(&self) -> Option<I> {
-        self.input.get()
+    /// Take a place in the queue: the permit to run now, or a receiver for
+    /// it once the dispatches ahead have finished.
+    fn enqueue(self: &Arc<Self>) -> Result<QueuePermit, oneshot::Receiver<QueuePermit>> {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        if !state.running {
+            state.running = true;
+            return Ok(QueuePermit(Some(Arc::clone(self))));
+        }
+        let (sender, receiver) = oneshot::channel();
+        state.waiting.push_back(sender);
+        Err(receiver)
     }
 
-    /// Get the current output value.
-    pub fn value(&self) -> Option<O> {
-        self.value.get()
+    /// Hand the permit to the next waiting dispatch, skipping abandoned ones.
+    fn release(self: &Arc<Self>) {
+        loop {
+            let next = {
+                let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+                match state.waiting.pop_front() {
+                    Some(next) => next,
+                    None => {
+                        state.running = false;
+                        return;
+                    }
+                }
+            };
+            match next.send(QueuePermit(Some(Arc::clone(self)))) {
+                Ok(()) => return,
+                Err(mut permit) => {
+                    permit.0 = None;
+                }
+            }
+        }
     }
+}
 
-    /// Check if the action is pending.
-    pub fn pending(&self) -> bool {
-        self.pending.get()
-    }
+/// The right to run on a [`StoreQueue`], passed on when dropped.
+struct QueuePermit(Option<Arc<StoreQueue>>);
 
-    /// Get the version number (incremented on each dispatch).
-    pub fn version(&self) -> usize {
-        self.version.get()
+impl Drop for QueuePermit {
+    fn drop(&mut self) {
+        if let Some(queue) = self.0.take() {
+            queue.release();
+        }
     }
+}
 
-    /// Set the input value.
-    pub fn set_input(&self, input: I) {
-        self.input.set(Some(input));
-    }
+/// A boxed async action for type erasure.
+pub type BoxedAsyncAction<S, O, E> =
+    Box<dyn Fn(&S) -> BoxFuture<'static, ActionResult<O, E>> + Send + Sync>;
 
-    /// Set the output value and mark as not pending.
-    pub fn set_value(&self, value: O) {
-        self.value.set(Some(value));
-        self.pending.set(false);
-    }
+type ServerCall<O> = Arc<dyn Fn() -> BoxFuture<'static, ActionResult<O>> + Send + Sync>;
+type Rollback<S> = Box<dyn FnOnce(&S) + Send>;
+type OptimisticUpdate<S> = Arc<dyn Fn(&S) -> Rollback<S> + Send + Sync>;
+type SuccessHandler<S, O> = Arc<dyn Fn(&S, &O) + Send + Sync>;
 
-    /// Mark the action as pending.
-    pub fn set_pending(&self) {
-        self.pending.set(true);
-        self.version.update(|v| *v += 1);
-    }
+/// An async action calling a Leptos `#[server]` function.
+///
+/// Wraps the call with the usual bookkeeping, so a server function can be
+/// dispatched like any other action without a hand-written
+/// [`AsyncAction`] impl:
+///
+/// - [`on_success`](Self::on_success) commits the output into the store.
+/// - [`on_error`](Self::on_error) records the failure, usually with an
+///   error mutator.
+/// - [`optimistic`](Self::optimistic) applies an update before the call
+///   returns, and rolls it back if the call fails.
+///
+/// Server function errors are converted into [`ActionError`]. Clones share
+/// the same call and hooks, so the action can be polled or dispatched
+/// repeatedly.
+///
+/// # Example
+///
+/// ```rust
+/// use leptos::prelude::*;
+/// use leptos_store::prelude::{Action, *};
+///
+/// #[derive(Clone, Default)]
+/// struct TodoState { todos: Vec<String>, error: Option<String> }
+///
+/// #[derive(Clone)]
+/// struct TodoStore { state: RwSignal<TodoState> }
+///
+/// impl Store for TodoStore {
+///     type State = TodoState;
+///     fn state(&self) -> ReadSignal<Self::State> { self.state.read_only() }
+/// }
+///
+/// // Stands in for a `#[server]` function.
+/// async fn add_todo(title: String) -> Result<usize, ServerFnError> {
+///     Err(ServerFnError::new(format!("{title} already exists")))
+/// }
+///
+/// fn add(title: &str) -> ServerFnAction<TodoStore, usize> {
+///     let title = title.to_string();
+///     ServerFnAction::new({
+///         let title = title.clone();
+///         move || add_todo(title.clone())
+///     })
+///     .optimistic(move |store: &TodoStore| {
+///         store.state.update(|s| s.todos.push(title.clone()));
+///         |store: &TodoStore| store.state.update(|s| { s.todos.pop(); })
+///     })
+///     .on_error(|store, err| store.state.update(|s| s.error = Some(err.to_string())))
+/// }
+///
+/// let store = TodoStore { state: RwSignal::new(TodoState::default()) };
+/// let result = futures::executor::block_on(add("milk").execute(&store));
+///
+/// assert!(result.is_err());
+/// // The optimistic update was rolled back and the error recorded.
+/// assert!(store.state.get_untracked().todos.is_empty());
+/// assert!(store.state.get_untracked().error.is_some());
+/// ```
+pub struct ServerFnAction<S, O> {
+    call: ServerCall<O>,
+    optimistic: Option<OptimisticUpdate<S>>,
+    on_success: Option<SuccessHandler<S, O>>,
+    on_error: Option<ErrorHandler<S, ActionError>>,
+}
 
-    /// Clear the action state.
-    pub fn clear(&self) {
-        self.input.set(None);
-        self.value.set(None);
-        self.pending.set(false);
+impl<S, O> Clone for ServerFnAction<S, O> {
+    fn clone(&self) -> Self {
+        Self {
+            call: Arc::clone(&self.call),
+            optimistic: self.optimistic.clone(),
+            on_success: self.on_success.clone(),
+            on_error: self.on_error.clone(),
+        }
     }
 }
 
-/// Extension trait for stores to execute actions.
-pub trait StoreActionExt: Store + Sized {
-    /// Execute a synchronous action.
-    fn dispatch<A>(&self, action: A) -> A::Output
+impl<S: Store, O: Send + 'static> ServerFnAction<S, O> {
+    /// Create an action running `call`, such as `move || fetch_tokens()`.
+    pub fn new<F, Fut, E>(call: F) -> Self
     where
-        A: Action<Self>,
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<O, E>> + Send + 'static,
+        E: Into<ActionError>,
     {
-        action.execute(self)
+        Self {
+            call: Arc::new(move || call().map(|result| result.map_err(Into::into)).boxed()),
+            optimistic: None,
+            on_success: None,
+            on_error: None,
+        }
+    }
+
+    /// Apply `update` before calling the server function.
+    ///
+    /// `update` returns the function undoing it, which runs if the call
+    /// fails.
+    pub fn optimistic<F, R>(mut self, update: F) -> Self
+    where
+        F: Fn(&S) -> R + Send + Sync + 'static,
+        R: FnOnce(&S) + Send + 'static,
+    {
+        self.optimistic = Some(Arc::new(move |store| Box::new(update(store))));
+        self
+    }
+
+    /// Pass the output of a successful call to `commit`, usually a mutator.
+    pub fn on_success<F>(mut self, commit: F) -> Self
+    where
+        F: Fn(&S, &O) + Send + Sync + 'static,
+    {
+        self.on_success = Some(Arc::new(commit));
+        self
+    }
+
+    /// Pass the error of a failed call to `on_error`, usually an error
+    /// mutator.
+    pub fn on_error<F>(mut self, on_error: F) -> Self
+    where
+        F: Fn(&S, &ActionError) + Send + Sync + 'static,
+    {
+        self.on_error = Some(Arc::new(on_error));
+        self
+    }
+}
+
+impl<S: Store, O: Send + 'static> AsyncAction<S> for ServerFnAction<S, O> {
+    type Output = O;
+    type Error = ActionError;
+
+    async fn execute(&self, store: &S) -> ActionResult<O> {
+        let rollback = self.optimistic.as_ref().map(|update| update(store));
+        let result = (self.call)().await;
+        match &result {
+            Ok(output) => {
+                if let Some(on_success) = &self.on_success {
+                    on_success(store, output);
+                }
+            }
+            Err(err) => {
+                if let Some(rollback) = rollback {
+                    rollback(store);
+                }
+                if let Some(on_error) = &self.on_error {
+                    on_error(store, err);
+                }
+            }
+        }
+        result
+    }
+}
+
+/// Exponential backoff between retry attempts.
+///
+/// The delay before retry `n` (starting at `0`) is
+/// `initial_ms * factor^n`, capped at `max_ms`. With jitter, each delay is
+/// shortened by a random fraction of up to `jitter`, so clients that failed
+/// together don't retry in lockstep.
+///
+/// # Example
+///
+/// ```rust
+/// use leptos_store::r#async::Backoff;
+///
+/// let backoff = Backoff::exponential(100).with_max_ms(1000).with_jitter(0.5);
+///
+/// assert_eq!(backoff.base_delay_ms(0), 100);
+/// assert_eq!(backoff.base_delay_ms(2), 400);
+/// assert_eq!(backoff.base_delay_ms(10), 1000);
+///
+/// let delay = backoff.delay_ms(2);
+/// assert!((200..=400).contains(&delay));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Backoff {
+    initial_ms: u64,
+    factor: f64,
+    max_ms: u64,
+    jitter: f64,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self::exponential(100)
+    }
+}
+
+impl Backoff {
+    /// Create a backoff starting at `initial_ms` and doubling on each retry.
+    ///
+    /// Delays are capped at 30 seconds, without jitter.
+    pub fn exponential(initial_ms: u64) -> Self {
+        Self {
+            initial_ms,
+            factor: 2.0,
+            max_ms: 30_000,
+            jitter: 0.0,
+        }
+    }
+
+    /// Set the multiplier applied to the delay after each retry.
+    pub fn with_factor(mut self, factor: f64) -> Self {
+        self.factor = factor.max(1.0);
+        self
+    }
+
+    /// Set the maximum delay in milliseconds.
+    pub fn with_max_ms(mut self, max_ms: u64) -> Self {
+        self.max_ms = max_ms;
+        self
+    }
+
+    /// Set the jitter, the largest fraction (`0.0..=1.0`) a delay may be
+    /// shortened by.
+    pub fn with_jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Get the delay before the first retry.
+    pub fn initial_ms(&self) -> u64 {
+        self.initial_ms
+    }
+
+    /// Get the delay multiplier.
+    pub fn factor(&self) -> f64 {
+        self.factor
+    }
+
+    /// Get the maximum delay.
+    pub fn max_ms(&self) -> u64 {
+        self.max_ms
+    }
+
+    /// Get the jitter fraction.
+    pub fn jitter(&self) -> f64 {
+        self.jitter
+    }
+
+    /// Returns the delay before retry `retry`, without jitter.
+    pub fn base_delay_ms(&self, retry: u32) -> u64 {
+        let exponent = i32::try_from(retry).unwrap_or(i32::MAX);
+        let delay = self.initial_ms as f64 * self.factor.powi(exponent);
+        delay.min(self.max_ms as f64) as u64
+    }
+
+    /// Returns the delay before retry `retry`, with jitter applied.
+    pub fn delay_ms(&self, retry: u32) -> u64 {
+        self.jittered(self.base_delay_ms(retry), random_unit())
+    }
+
+    /// Shorten `delay_ms` by `sample * jitter` of itself.
+    fn jittered(&self, delay_ms: u64, sample: f64) -> u64 {
+        delay_ms - (delay_ms as f64 * self.jitter * sample) as u64
+    }
+}
+
+/// Decides whether and when a failed attempt is retried.
+///
+/// Set with [`AsyncActionBuilder::with_retry_policy`] to replace the fixed
+/// retry count and [`Backoff`]. The policy sees the retry number (starting
+/// at `0`) and the error of the failed attempt, so it can give up on errors
+/// that won't go away or honor a delay the server asked for. Closures
+/// taking `(retry, &error)` are policies too.
+///
+/// # Example
+///
+/// ```rust
+/// use leptos_store::r#async::{Backoff, RetryPolicy};
+///
+/// enum ApiError {
+///     Status { code: u16, retry_after_ms: Option<u64> },
+///     Offline,
+/// }
+///
+/// /// Retries server errors up to 3 times, honoring `Retry-After`.
+/// struct ServerErrors(Backoff);
+///
+/// impl RetryPolicy<ApiError> for ServerErrors {
+///     fn retry_delay_ms(&self, retry: u32, error: &ApiError) -> Option<u64> {
+///         match error {
+///             ApiError::Status { code, retry_after_ms } if *code >= 500 && retry < 3 => {
+///                 Some(retry_after_ms.unwrap_or_else(|| self.0.delay_ms(retry)))
+///             }
+///             _ => None,
+///         }
+///     }
+/// }
+///
+/// let policy = ServerErrors(Backoff::exponential(100));
+/// let unavailable = ApiError::Status { code: 503, retry_after_ms: Some(2000) };
+/// assert_eq!(policy.retry_delay_ms(0, &unavailable), Some(2000));
+/// assert_eq!(policy.retry_delay_ms(3, &unavailable), None);
+/// let not_found = ApiError::Status { code: 404, retry_after_ms: None };
+/// assert_eq!(policy.retry_delay_ms(0, &not_found), None);
+/// assert_eq!(policy.retry_delay_ms(0, &ApiError::Offline), None);
+/// ```
+pub trait RetryPolicy<E>: Send + Sync {
+    /// Returns the delay in milliseconds before retry `retry` after `error`,
+    /// or `None` to give up and return `error`.
+    fn retry_delay_ms(&self, retry: u32, error: &E) -> Option<u64>;
+}
+
+impl<E, F> RetryPolicy<E> for F
+where
+    F: Fn(u32, &E) -> Option<u64> + Send + Sync,
+{
+    fn retry_delay_ms(&self, retry: u32, error: &E) -> Option<u64> {
+        self(retry, error)
+    }
+}
+
+/// Returns a pseudo-random number in `0.0..1.0`, good enough for jitter.
+fn random_unit() -> f64 {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let bits = RandomState::new().hash_one(COUNTER.fetch_add(1, Ordering::Relaxed));
+    (bits >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// What a [`RateLimiter`] does with a dispatch over the limit.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Overflow {
+    /// Fail with [`ActionError::RateLimited`].
+    #[default]
+    Reject,
+    /// Wait until the window has room again.
+    Delay,
+}
+
+#[derive(Default)]
+struct RateLimitState {
+    used: u32,
+    waiting: VecDeque<oneshot::Sender<()>>,
+}
+
+/// Limits dispatches to `limit` per sliding window.
+///
+/// Each dispatch takes a slot for the length of the window; a dispatch
+/// finding no free slot is rejected or delayed, depending on the
+/// [`Overflow`] policy. Use it with
+/// [`AsyncActionBuilder::with_rate_limit`] or
+/// [`AsyncDispatcher::with_rate_limit`] so spammy UI events can't hammer an
+/// API. Clones share the same slots, so one limiter can cover several
+/// actions.
+///
+/// # Example
+///
+/// ```rust
+/// use leptos_store::prelude::*;
+///
+/// let limiter = RateLimiter::new(2, 1000);
+/// assert!(limiter.try_acquire());
+/// assert!(limiter.try_acquire());
+/// assert!(!limiter.try_acquire());
+/// assert_eq!(limiter.available(), 0);
+/// ```
+#[derive(Clone)]
+pub struct RateLimiter {
+    limit: u32,
+    window_ms: u64,
+    overflow: Overflow,
+    state: Arc<Mutex<RateLimitState>>,
+}
+
+impl RateLimiter {
+    /// Allow `limit` dispatches per `window_ms` milliseconds.
+    pub fn new(limit: u32, window_ms: u64) -> Self {
+        Self {
+            limit,
+            window_ms,
+            overflow: Overflow::default(),
+            state: Arc::default(),
+        }
+    }
+
+    /// Set what happens to dispatches over the limit.
+    pub fn with_overflow(mut self, overflow: Overflow) -> Self {
+        self.overflow = overflow;
+        self
+    }
+
+    /// Get the number of dispatches allowed per window.
+    pub fn limit(&self) -> u32 {
+        self.limit
+    }
+
+    /// Get the window length in milliseconds.
+    pub fn window_ms(&self) -> u64 {
+        self.window_ms
+    }
+
+    /// Get the overflow policy.
+    pub fn overflow(&self) -> Overflow {
+        self.overflow
+    }
+
+    /// Returns the number of free slots in the current window.
+    pub fn available(&self) -> u32 {
+        let state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        self.limit.saturating_sub(state.used)
+    }
+
+    /// Take a slot if one is free, regardless of the overflow policy.
+    pub fn try_acquire(&self) -> bool {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        if state.used >= self.limit {
+            return false;
+        }
+        state.used += 1;
+        drop(state);
+        self.hold_slot();
+        true
+    }
+
+    /// Take a slot, applying the overflow policy if none is free.
+    ///
+    /// Fails with [`ActionError::RateLimited`] if the dispatch is rejected.
+    pub fn acquire(&self) -> impl Future<Output = ActionResult<()>> + Send + 'static {
+        let waiting = {
+            let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+            if state.used < self.limit {
+                state.used += 1;
+                None
+            } else if self.overflow == Overflow::Delay {
+                let (sender, receiver) = oneshot::channel();
+                state.waiting.push_back(sender);
+                Some(Ok(receiver))
+            } else {
+                Some(Err(ActionError::RateLimited))
+            }
+        };
+        if waiting.is_none() {
+            self.hold_slot();
+        }
+        async move {
+            match waiting {
+                None => Ok(()),
+                Some(Ok(receiver)) => receiver.await.map_err(|_| ActionError::Cancelled),
+                Some(Err(err)) => Err(err),
+            }
+        }
+    }
+
+    /// Free a taken slot once the window has passed.
+    fn hold_slot(&self) {
+        let limiter = self.clone();
+        // Dropping the handle doesn't cancel the timer.
+        let _ = set_timer(Duration::from_millis(self.window_ms), move || {
+            limiter.release_slot();
+        });
+    }
+
+    /// Hand a freed slot to the next waiting dispatch, or free it.
+    fn release_slot(&self) {
+        loop {
+            let next = {
+                let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+                match state.waiting.pop_front() {
+                    Some(next) => next,
+                    None => {
+                        state.used = state.used.saturating_sub(1);
+                        return;
+                    }
+                }
+            };
+            if next.send(()).is_ok() {
+                self.hold_slot();
+                return;
+            }
+        }
+    }
+}
+
+impl fmt::Debug for RateLimiter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RateLimiter")
+            .field("limit", &self.limit)
+            .field("window_ms", &self.window_ms)
+            .field("overflow", &self.overflow)
+            .field("available", &self.available())
+            .finish()
+    }
+}
+
+/// Builder for constructing async actions with fluent API.
+///
+/// # Example
+///
+/// ```rust
+/// use leptos::prelude::*;
+/// use leptos_store::prelude::*;
+///
+/// #[derive(Clone, Default)]
+/// struct MyState { value: i32 }
+///
+/// #[derive(Clone)]
+/// struct MyStore { state: RwSignal<MyState> }
+///
+/// impl Store for MyStore {
+///     type State = MyState;
+///     fn state(&self) -> ReadSignal<Self::State> { self.state.read_only() }
+/// }
+///
+/// let builder: AsyncActionBuilder<MyStore, (), ActionError> = AsyncActionBuilder::new()
+///     .with_timeout(5000)
+///     .with_retry(3)
+///     .with_backoff(Backoff::exponential(200).with_jitter(0.25));
+///
+/// assert_eq!(builder.timeout_ms(), Some(5000));
+/// assert_eq!(builder.retry_count(), 3);
+/// assert_eq!(builder.backoff().initial_ms(), 200);
+/// ```
+///
+/// # Retrying
+///
+/// [`execute`](Self::execute) runs an [`AsyncAction`] and, if it fails,
+/// retries it up to [`retry_count`](Self::retry_count) times, waiting for
+/// the configured [`Backoff`] between attempts, or as long as its
+/// [`RetryPolicy`] asks for. The result of the last
+/// attempt is returned. Each attempt is limited to the configured timeout,
+/// so the action's error type must implement `From<ActionError>`.
+///
+/// ```rust
+/// # use leptos::prelude::*;
+/// # use leptos_store::prelude::*;
+/// # use std::sync::atomic::{AtomicU32, Ordering};
+/// # #[derive(Clone, Default)]
+/// # struct MyState { value: i32 }
+/// # #[derive(Clone)]
+/// # struct MyStore { state: RwSignal<MyState> }
+/// # impl Store for MyStore {
+/// #     type State = MyState;
+/// #     fn state(&self) -> ReadSignal<Self::State> { self.state.read_only() }
+/// # }
+/// struct Flaky {
+///     attempts: AtomicU32,
+/// }
+///
+/// impl AsyncAction<MyStore> for Flaky {
+///     type Output = u32;
+///     type Error = ActionError;
+///
+///     async fn execute(&self, _store: &MyStore) -> ActionResult<u32> {
+///         match self.attempts.fetch_add(1, Ordering::SeqCst) {
+///             0 => Err(ActionError::network("connection reset")),
+///             attempt => Ok(attempt),
+///         }
+///     }
+/// }
+///
+/// let store = MyStore { state: RwSignal::new(MyState::default()) };
+/// let action = Flaky { attempts: AtomicU32::new(0) };
+/// let builder = AsyncActionBuilder::new()
+///     .with_retry(2)
+///     .with_backoff(Backoff::exponential(1));
+///
+/// let result = futures::executor::block_on(builder.execute(&action, &store));
+/// assert_eq!(result.unwrap(), 1);
+/// ```
+pub struct AsyncActionBuilder<S: Store, O, E> {
+    timeout_ms: Option<u64>,
+    retry_count: u32,
+    backoff: Backoff,
+    retry_policy: Option<Arc<dyn RetryPolicy<E>>>,
+    cancel_token: Option<CancellationToken>,
+    rate_limit: Option<RateLimiter>,
+    _marker: PhantomData<(S, O, E)>,
+}
+
+impl<S: Store, O, E> Default for AsyncActionBuilder<S, O, E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: Store, O, E> AsyncActionBuilder<S, O, E> {
+    /// Create a new async action builder.
+    pub fn new() -> Self {
+        Self {
+            timeout_ms: None,
+            retry_count: 0,
+            backoff: Backoff::default(),
+            retry_policy: None,
+            cancel_token: None,
+            rate_limit: None,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Set a timeout for each attempt in milliseconds.
+    pub fn with_timeout(mut self, timeout_ms: u64) -> Self {
+        self.timeout_ms = Some(timeout_ms);
+        self
+    }
+
+    /// Set the number of retry attempts.
+    pub fn with_retry(mut self, count: u32) -> Self {
+        self.retry_count = count;
+        self
+    }
+
+    /// Get the configured timeout.
+    pub fn timeout_ms(&self) -> Option<u64> {
+        self.timeout_ms
+    }
+
+    /// Set the backoff between retry attempts.
+    pub fn with_backoff(mut self, backoff: Backoff) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Decide retries with `policy` instead of the retry count and backoff.
+    pub fn with_retry_policy(mut self, policy: impl RetryPolicy<E> + 'static) -> Self {
+        self.retry_policy = Some(Arc::new(policy));
+        self
+    }
+
+    /// Abort execution, including pending retries, when `token` is cancelled.
+    pub fn with_cancel_token(mut self, token: CancellationToken) -> Self {
+        self.cancel_token = Some(token);
+        self
+    }
+
+    /// Limit how often the action is executed.
+    pub fn with_rate_limit(mut self, limiter: RateLimiter) -> Self {
+        self.rate_limit = Some(limiter);
+        self
+    }
+
+    /// Get the configured retry count.
+    pub fn retry_count(&self) -> u32 {
+        self.retry_count
+    }
+
+    /// Get the configured backoff.
+    pub fn backoff(&self) -> Backoff {
+        self.backoff
+    }
+
+    /// Get the configured retry policy.
+    pub fn retry_policy(&self) -> Option<&dyn RetryPolicy<E>> {
+        self.retry_policy.as_deref()
+    }
+
+    /// Get the configured cancellation token.
+    pub fn cancel_token(&self) -> Option<&CancellationToken> {
+        self.cancel_token.as_ref()
+    }
+
+    /// Get the configured rate limiter.
+    pub fn rate_limit(&self) -> Option<&RateLimiter> {
+        self.rate_limit.as_ref()
+    }
+
+    /// Execute `action`, retrying failed attempts with backoff.
+    ///
+    /// An attempt that runs longer than the configured timeout is abandoned
+    /// and fails with [`ActionError::Timeout`], converted into the action's
+    /// error type; timed out attempts are retried like other failures.
+    ///
+    /// Returns the first successful result, or the error of the last
+    /// attempt once the retries are exhausted or the retry policy gives up.
+    /// If the cancellation token is cancelled, the current attempt is
+    /// abandoned and [`ActionError::Cancelled`] is returned.
+    ///
+    /// With a rate limiter, execution first takes a slot from it; retries
+    /// don't take another. A rejected execution fails with
+    /// [`ActionError::RateLimited`].
+    ///
+    /// Inside an action dispatched with a [`ReactiveAction`] handle, the
+    /// attempts are reported to the handle's
+    /// [`attempt`](ReactiveAction::attempt) and
+    /// [`max_attempts`](ReactiveAction::max_attempts).
+    pub async fn execute<A>(&self, action: &A, store: &S) -> ActionResult<O, E>
+    where
+        A: AsyncAction<S, Output = O, Error = E>,
+        E: From<ActionError>,
+    {
+        match &self.cancel_token {
+            Some(token) => token
+                .run(self.execute_with_retry(action, store))
+                .await
+                .unwrap_or_else(|err| Err(err.into())),
+            None => self.execute_with_retry(action, store).await,
+        }
+    }
+
+    async fn execute_with_retry<A>(&self, action: &A, store: &S) -> ActionResult<O, E>
+    where
+        A: AsyncAction<S, Output = O, Error = E>,
+        E: From<ActionError>,
+    {
+        if let Some(limiter) = &self.rate_limit {
+            limiter.acquire().await?;
+        }
+        // Report attempts to the handle of the dispatch running us, if any.
+        let progress = deps::action_dep::<DispatchProgress>();
+        if let Some(progress) = &progress {
+            let max_attempts = self.retry_policy.is_none().then(|| self.retry_count + 1);
+            progress.max_attempts.set(max_attempts);
+        }
+        let mut retry = 0;
+        loop {
+            if let Some(progress) = &progress {
+                progress.attempt.set(retry + 1);
+            }
+            let attempt = action.execute(store);
+            let result = match self.timeout_ms {
+                Some(timeout_ms) => timeout(timeout_ms, attempt)
+                    .await
+                    .unwrap_or_else(|err| Err(err.into())),
+                None => attempt.await,
+            };
+            let err = match result {
+                Ok(output) => return Ok(output),
+                Err(err) => err,
+            };
+            let delay_ms = match &self.retry_policy {
+                Some(policy) => policy.retry_delay_ms(retry, &err),
+                None => (retry < self.retry_count).then(|| self.backoff.delay_ms(retry)),
+            };
+            let Some(delay_ms) = delay_ms else {
+                return Err(err);
+            };
+            delay(Duration::from_millis(delay_ms)).await;
+            retry += 1;
+        }
+    }
+}
+
+/// Shared state of a [`CancellationToken`].
+#[derive(Default)]
+struct CancellationState {
+    cancelled: AtomicBool,
+    wakers: Mutex<Vec<Waker>>,
+}
+
+/// A token for cancelling in-flight async actions.
+///
+/// Clones share the same cancellation state. Actions can poll
+/// [`is_cancelled`](Self::is_cancelled) or [`check`](Self::check) between
+/// steps, await [`cancelled`](Self::cancelled), or wrap a future with
+/// [`run`](Self::run) to abandon it at its next await point.
+///
+/// # Example
+///
+/// ```rust
+/// use leptos_store::prelude::*;
+///
+/// let token = CancellationToken::new();
+/// let search = token.run(futures::future::pending::<Vec<String>>());
+///
+/// token.cancel();
+/// let result = futures::executor::block_on(search);
+/// assert_eq!(result, Err(ActionError::Cancelled));
+/// assert!(token.check().is_err());
+/// ```
+#[derive(Clone, Default)]
+pub struct CancellationToken {
+    state: Arc<CancellationState>,
+}
+
+impl CancellationToken {
+    /// Create a token that is not cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel the token, waking every task waiting on it.
+    pub fn cancel(&self) {
+        self.state.cancelled.store(true, Ordering::SeqCst);
+        let wakers = self
+            .state
+            .wakers
+            .lock()
+            .map(|mut wakers| std::mem::take(&mut *wakers))
+            .unwrap_or_default();
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+
+    /// Check if the token has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.state.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Returns [`ActionError::Cancelled`] if the token has been cancelled.
+    pub fn check(&self) -> ActionResult<()> {
+        if self.is_cancelled() {
+            Err(ActionError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Wait until the token is cancelled.
+    pub async fn cancelled(&self) {
+        let _ = self.run(std::future::pending::<()>()).await;
+    }
+
+    /// Run `future`, abandoning it with [`ActionError::Cancelled`] if the
+    /// token is cancelled first.
+    pub fn run<F: Future>(&self, future: F) -> Cancellable<F> {
+        Cancellable {
+            inner: future,
+            token: self.clone(),
+        }
+    }
+
+    /// Register `waker` to be woken on cancellation.
+    fn register(&self, waker: &Waker) {
+        if let Ok(mut wakers) = self.state.wakers.lock()
+            && !wakers.iter().any(|w| w.will_wake(waker))
+        {
+            wakers.push(waker.clone());
+        }
+    }
+}
+
+impl fmt::Debug for CancellationToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CancellationToken")
+            .field("cancelled", &self.is_cancelled())
+            .finish()
+    }
+}
+
+pin_project! {
+    /// A future that fails with [`ActionError::Cancelled`] once its
+    /// [`CancellationToken`] is cancelled.
+    ///
+    /// Created by [`CancellationToken::run`].
+    pub struct Cancellable<F> {
+        #[pin]
+        inner: F,
+        token: CancellationToken,
+    }
+}
+
+impl<F: Future> Future for Cancellable<F> {
+    type Output = ActionResult<F::Output>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        if this.token.is_cancelled() {
+            return Poll::Ready(Err(ActionError::Cancelled));
+        }
+        if let Poll::Ready(output) = this.inner.poll(cx) {
+            return Poll::Ready(Ok(output));
+        }
+        this.token.register(cx.waker());
+        // Cancelled between the first check and registering the waker.
+        if this.token.is_cancelled() {
+            return Poll::Ready(Err(ActionError::Cancelled));
+        }
+        Poll::Pending
+    }
+}
+
+/// A scheduled timer callback.
+pub(crate) struct TimerHandle {
+    #[cfg(target_arch = "wasm32")]
+    handle: Option<TimeoutHandle>,
+    #[cfg(not(target_arch = "wasm32"))]
+    cancelled: Arc<AtomicBool>,
+}
+
+impl TimerHandle {
+    /// Cancel the callback if it hasn't run yet.
+    pub(crate) fn cancel(&self) {
+        #[cfg(target_arch = "wasm32")]
+        if let Some(handle) = self.handle {
+            handle.clear();
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Run `callback` after `duration`.
+///
+/// Uses `setTimeout` in the browser. Elsewhere all timers share one thread,
+/// which also runs the callbacks, so they should be short.
+pub(crate) fn set_timer(
+    duration: Duration,
+    callback: impl FnOnce() + Send + 'static,
+) -> TimerHandle {
+    #[cfg(target_arch = "wasm32")]
+    {
+        TimerHandle {
+            handle: set_timeout_with_handle(callback, duration).ok(),
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let cancelled = Arc::new(AtomicBool::new(false));
+        timer::schedule(
+            std::time::Instant::now() + duration,
+            Arc::clone(&cancelled),
+            Box::new(callback),
+        );
+        TimerHandle { cancelled }
+    }
+}
+
+/// Returns the wall-clock time in milliseconds since the Unix epoch.
+pub(crate) fn now_ms() -> u64 {
+    #[cfg(target_arch = "wasm32")]
+    {
+        leptos::web_sys::js_sys::Date::now() as u64
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |since| since.as_millis() as u64)
+    }
+}
+
+/// The timer thread behind [`set_timer`] outside the browser.
+#[cfg(not(target_arch = "wasm32"))]
+mod timer {
+    use std::cmp::Ordering;
+    use std::collections::BinaryHeap;
+    use std::panic::{AssertUnwindSafe, catch_unwind};
+    use std::sync::atomic::{self, AtomicBool};
+    use std::sync::{Arc, Condvar, Mutex, Once};
+    use std::time::Instant;
+
+    /// A pending callback, ordered so the earliest deadline is popped first.
+    struct Entry {
+        deadline: Instant,
+        cancelled: Arc<AtomicBool>,
+        callback: Box<dyn FnOnce() + Send>,
+    }
+
+    impl PartialEq for Entry {
+        fn eq(&self, other: &Self) -> bool {
+            self.deadline == other.deadline
+        }
+    }
+
+    impl Eq for Entry {}
+
+    impl PartialOrd for Entry {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    impl Ord for Entry {
+        fn cmp(&self, other: &Self) -> Ordering {
+            other.deadline.cmp(&self.deadline)
+        }
+    }
+
+    static QUEUE: Mutex<BinaryHeap<Entry>> = Mutex::new(BinaryHeap::new());
+    static CHANGED: Condvar = Condvar::new();
+    static START: Once = Once::new();
+
+    /// Run `callback` at `deadline` unless `cancelled` is set by then.
+    pub(super) fn schedule(
+        deadline: Instant,
+        cancelled: Arc<AtomicBool>,
+        callback: Box<dyn FnOnce() + Send>,
+    ) {
+        START.call_once(|| {
+            std::thread::Builder::new()
+                .name("leptos-store-timer".to_string())
+                .spawn(run)
+                .expect("failed to spawn the leptos-store timer thread");
+        });
+        if let Ok(mut queue) = QUEUE.lock() {
+            queue.push(Entry {
+                deadline,
+                cancelled,
+                callback,
+            });
+            CHANGED.notify_one();
+        }
+    }
+
+    fn run() {
+        loop {
+            let Some(due) = next_due() else {
+                return;
+            };
+            // Run callbacks unlocked, since they may schedule more timers.
+            for entry in due {
+                if !entry.cancelled.load(atomic::Ordering::SeqCst) {
+                    let _ = catch_unwind(AssertUnwindSafe(entry.callback));
+                }
+            }
+        }
+    }
+
+    /// Wait until at least one entry is due, and remove the due entries.
+    fn next_due() -> Option<Vec<Entry>> {
+        let mut queue = QUEUE.lock().ok()?;
+        loop {
+            let now = Instant::now();
+            let mut due = Vec::new();
+            while queue.peek().is_some_and(|entry| entry.deadline <= now) {
+                due.extend(queue.pop());
+            }
+            if !due.is_empty() {
+                return Some(due);
+            }
+            queue = match queue.peek().map(|entry| entry.deadline - now) {
+                Some(wait) => CHANGED.wait_timeout(queue, wait).ok()?.0,
+                None => CHANGED.wait(queue).ok()?,
+            };
+        }
+    }
+}
+
+/// Shared state between a [`Delay`] and its timer.
+#[derive(Default)]
+struct DelayState {
+    done: bool,
+    waker: Option<Waker>,
+}
+
+impl DelayState {
+    /// Mark the delay as elapsed and wake its task.
+    fn fire(state: &Mutex<DelayState>) {
+        if let Ok(mut state) = state.lock() {
+            state.done = true;
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+        }
+    }
+}
+
+/// A future that completes after a duration.
+///
+/// The timer is cancelled when the delay is dropped.
+pub(crate) struct Delay {
+    state: Arc<Mutex<DelayState>>,
+    timer: Option<TimerHandle>,
+}
+
+/// Returns a future that completes after `duration`.
+pub(crate) fn delay(duration: Duration) -> Delay {
+    let state = Arc::new(Mutex::new(DelayState {
+        done: duration.is_zero(),
+        waker: None,
+    }));
+    let timer = (!duration.is_zero()).then(|| {
+        let state = Arc::clone(&state);
+        set_timer(duration, move || DelayState::fire(&state))
+    });
+    Delay { state, timer }
+}
+
+impl Future for Delay {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let Ok(mut state) = self.state.lock() else {
+            return Poll::Ready(());
+        };
+        if state.done {
+            Poll::Ready(())
+        } else {
+            state.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+impl Drop for Delay {
+    fn drop(&mut self) {
+        if let Some(timer) = &self.timer {
+            timer.cancel();
+        }
+    }
+}
+
+pin_project! {
+    /// A future that fails with [`ActionError::Timeout`] if the inner
+    /// future doesn't complete in time.
+    ///
+    /// Created by [`timeout`]. Dropping the inner future on timeout cancels
+    /// it at its next await point.
+    pub struct Timeout<F> {
+        #[pin]
+        inner: F,
+        delay: Delay,
+        timeout_ms: u64,
+    }
+}
+
+/// Run `future` with a time limit of `timeout_ms` milliseconds.
+///
+/// Works in the browser and on the server, without an async runtime.
+///
+/// # Example
+///
+/// ```rust
+/// use leptos_store::r#async::{ActionError, timeout};
+///
+/// let result = futures::executor::block_on(timeout(1000, async { 42 }));
+/// assert_eq!(result.unwrap(), 42);
+///
+/// let never = futures::future::pending::<()>();
+/// let result = futures::executor::block_on(timeout(10, never));
+/// assert!(matches!(result, Err(ActionError::Timeout(10))));
+/// ```
+pub fn timeout<F: Future>(timeout_ms: u64, future: F) -> Timeout<F> {
+    Timeout {
+        inner: future,
+        delay: delay(Duration::from_millis(timeout_ms)),
+        timeout_ms,
+    }
+}
+
+impl<F: Future> Future for Timeout<F> {
+    type Output = Result<F::Output, ActionError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        if let Poll::Ready(output) = this.inner.poll(cx) {
+            return Poll::Ready(Ok(output));
+        }
+        match Pin::new(this.delay).poll(cx) {
+            Poll::Ready(()) => Poll::Ready(Err(ActionError::Timeout(*this.timeout_ms))),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+pin_project! {
+    /// A future that wraps an async action execution.
+    pub struct ActionFuture<F> {
+        #[pin]
+        inner: F,
+        state: ActionState,
+    }
+}
+
+impl<F> ActionFuture<F> {
+    /// Create a new action future.
+    pub fn new(inner: F) -> Self {
+        Self {
+            inner,
+            state: ActionState::Pending,
+        }
+    }
+
+    /// Get the current state of the action.
+    pub fn state(&self) -> &ActionState {
+        &self.state
+    }
+}
+
+impl<F, T, E> Future for ActionFuture<F>
+where
+    F: Future<Output = ActionResult<T, E>>,
+{
+    type Output = ActionResult<T, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+
+        match this.inner.poll(cx) {
+            Poll::Ready(Ok(value)) => {
+                *this.state = ActionState::Success;
+                Poll::Ready(Ok(value))
+            }
+            Poll::Ready(Err(err)) => {
+                *this.state = ActionState::Error;
+                Poll::Ready(Err(err))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+struct DebounceInner<T> {
+    delay: Duration,
+    action: Box<dyn Fn(T) + Send + Sync>,
+    timer: Mutex<Option<TimerHandle>>,
+}
+
+/// An action that runs once calls stop for a while.
+///
+/// Created by [`debounced_action`]. Clones share the same pending call.
+pub struct Debounced<T> {
+    inner: Arc<DebounceInner<T>>,
+}
+
+impl<T> Clone for Debounced<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<T: Send + 'static> Debounced<T> {
+    /// Schedule the action with `value`, replacing the pending call.
+    pub fn call(&self, value: T) {
+        let inner = Arc::downgrade(&self.inner);
+        let timer = set_timer(self.inner.delay, move || {
+            if let Some(inner) = inner.upgrade() {
+                (inner.action)(value);
+            }
+        });
+        if let Ok(mut pending) = self.inner.timer.lock()
+            && let Some(previous) = pending.replace(timer)
+        {
+            previous.cancel();
+        }
+    }
+
+    /// Cancel the pending call, if any.
+    pub fn cancel(&self) {
+        if let Ok(mut pending) = self.inner.timer.lock()
+            && let Some(timer) = pending.take()
+        {
+            timer.cancel();
+        }
+    }
+}
+
+/// Create an action that runs `delay_ms` after the last of a burst of
+/// calls, with the latest value.
+///
+/// Works in the browser and on the server. When created inside a reactive
+/// owner (such as a component), the pending call is cancelled when the
+/// owner is disposed.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let search = debounced_action(300, move |query: String| {
+///     store.set_search_query(query);
+/// });
+///
+/// view! { <input on:input:target=move |ev| search.call(ev.target().value()) /> }
+/// ```
+pub fn debounced_action<T, F>(delay_ms: u64, action: F) -> Debounced<T>
+where
+    T: Send + 'static,
+    F: Fn(T) + Send + Sync + 'static,
+{
+    let debounced = Debounced {
+        inner: Arc::new(DebounceInner {
+            delay: Duration::from_millis(delay_ms),
+            action: Box::new(action),
+            timer: Mutex::new(None),
+        }),
+    };
+    let inner = Arc::downgrade(&debounced.inner);
+    on_cleanup(move || {
+        if let Some(inner) = inner.upgrade() {
+            Debounced { inner }.cancel();
+        }
+    });
+    debounced
+}
+
+/// Cooldown state of a [`Throttled`] action.
+struct ThrottleState<T> {
+    cooling: bool,
+    trailing: Option<T>,
+    timer: Option<TimerHandle>,
+}
+
+struct ThrottleInner<T> {
+    interval: Duration,
+    action: Box<dyn Fn(T) + Send + Sync>,
+    state: Mutex<ThrottleState<T>>,
+}
+
+impl<T: Send + 'static> ThrottleInner<T> {
+    fn start_cooldown(inner: &Arc<Self>) -> TimerHandle {
+        let weak = Arc::downgrade(inner);
+        set_timer(inner.interval, move || {
+            if let Some(inner) = weak.upgrade() {
+                Self::end_cooldown(&inner);
+            }
+        })
+    }
+
+    /// Run the trailing call, if any, or end the cooldown.
+    fn end_cooldown(inner: &Arc<Self>) {
+        let trailing = {
+            let Ok(mut state) = inner.state.lock() else {
+                return;
+            };
+            let trailing = state.trailing.take();
+            if trailing.is_some() {
+                state.timer = Some(Self::start_cooldown(inner));
+            } else {
+                state.cooling = false;
+                state.timer = None;
+            }
+            trailing
+        };
+        if let Some(value) = trailing {
+            (inner.action)(value);
+        }
+    }
+}
+
+/// An action that runs at most once per interval.
+///
+/// Created by [`throttled_action`]. Clones share the same cooldown.
+pub struct Throttled<T> {
+    inner: Arc<ThrottleInner<T>>,
+}
+
+impl<T> Clone for Throttled<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<T: Send + 'static> Throttled<T> {
+    /// Run the action with `value` now, or at the end of the cooldown if it
+    /// ran recently. Only the latest value is kept for the end of the
+    /// cooldown.
+    pub fn call(&self, value: T) {
+        {
+            let Ok(mut state) = self.inner.state.lock() else {
+                return;
+            };
+            if state.cooling {
+                state.trailing = Some(value);
+                return;
+            }
+            state.cooling = true;
+            state.timer = Some(ThrottleInner::start_cooldown(&self.inner));
+        }
+        (self.inner.action)(value);
+    }
+
+    /// Drop the pending trailing call and end the cooldown.
+    pub fn cancel(&self) {
+        if let Ok(mut state) = self.inner.state.lock() {
+            state.trailing = None;
+            state.cooling = false;
+            if let Some(timer) = state.timer.take() {
+                timer.cancel();
+            }
+        }
+    }
+}
+
+/// Create an action that runs at most once every `interval_ms`.
+///
+/// The first call runs immediately. Calls during the following interval
+/// are collapsed into one trailing call with the latest value, run when the
+/// interval ends. Works in the browser and on the server, and cancels its
+/// timer when the reactive owner it was created in is disposed.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let save_scroll = throttled_action(100, move |offset: f64| {
+///     store.set_scroll_offset(offset);
+/// });
+/// ```
+pub fn throttled_action<T, F>(interval_ms: u64, action: F) -> Throttled<T>
+where
+    T: Send + 'static,
+    F: Fn(T) + Send + Sync + 'static,
+{
+    let throttled = Throttled {
+        inner: Arc::new(ThrottleInner {
+            interval: Duration::from_millis(interval_ms),
+            action: Box::new(action),
+            state: Mutex::new(ThrottleState {
+                cooling: false,
+                trailing: None,
+                timer: None,
+            }),
+        }),
+    };
+    let inner = Arc::downgrade(&throttled.inner);
+    on_cleanup(move || {
+        if let Some(inner) = inner.upgrade() {
+            Throttled { inner }.cancel();
+        }
+    });
+    throttled
+}
+
+/// Create a [`Resource`] that runs an async action and commits its output
+/// into the store.
+///
+/// `source` is tracked like the source of a [`Resource`]: whenever it
+/// changes, `action` builds an action from it, which runs against `store`
+/// through the registered middleware. The output of each successful run is
+/// passed to `commit`, usually a mutator, so components reading the store
+/// see the data while `<Suspense>` waits on the resource.
+///
+/// On the server, the output is committed as soon as the action finishes.
+/// In the browser, it's committed when the resource's value changes,
+/// including the value hydrated from the server, so the action isn't run
+/// again on hydration. The value is serialized as JSON, so the action's
+/// output and error must implement `Serialize` and `Deserialize`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let tokens = store_resource(
+///     &store,
+///     move || page.get(),
+///     |page| FetchTokens { page },
+///     |store, response| store.set_tokens(response.tokens.clone()),
+/// );
+///
+/// view! {
+///     <Suspense fallback=|| "Loading...">
+///         {move || Suspend::new(async move { tokens.await.map(|_| view! { <TokenGrid /> }) })}
+///     </Suspense>
+/// }
+/// ```
+pub fn store_resource<S, A, Src>(
+    store: &S,
+    source: impl Fn() -> Src + Send + Sync + 'static,
+    action: impl Fn(Src) -> A + Send + Sync + 'static,
+    commit: impl Fn(&S, &A::Output) + Send + Sync + 'static,
+) -> Resource<ActionResult<A::Output, A::Error>>
+where
+    S: Store,
+    A: AsyncAction<S> + 'static,
+    A::Output: Sync + 'static,
+    A::Error: Sync + 'static,
+    Src: PartialEq + Clone + Send + Sync + 'static,
+    JsonSerdeCodec: Encoder<ActionResult<A::Output, A::Error>, Encoded = String>
+        + Decoder<ActionResult<A::Output, A::Error>, Encoded = str>,
+    <JsonSerdeCodec as Encoder<ActionResult<A::Output, A::Error>>>::Error: fmt::Debug,
+    <JsonSerdeCodec as Decoder<ActionResult<A::Output, A::Error>>>::Error: fmt::Debug,
+{
+    let commit = Arc::new(commit);
+    // The server commits as soon as the action finishes, the browser once
+    // the value (possibly hydrated) reaches the resource.
+    let commit_on_fetch = (!cfg!(target_arch = "wasm32")).then(|| Arc::clone(&commit));
+    let deps = ActionDeps::current();
+    let resource = Resource::new(source, {
+        let store = store.clone();
+        move |source| {
+            let action = action(source);
+            let store = store.clone();
+            let deps = deps.clone();
+            let commit = commit_on_fetch.clone();
+            async move {
+                let result = middleware::run_async_action::<S, A, _, _, _>(&store, || {
+                    deps.scope(action.execute(&store))
+                })
+                .await;
+                if let (Ok(output), Some(commit)) = (&result, commit) {
+                    commit(&store, output);
+                }
+                result
+            }
+        }
+    });
+
+    if cfg!(target_arch = "wasm32") {
+        let store = store.clone();
+        Effect::new(move |_| {
+            resource.with(|result| {
+                if let Some(Ok(output)) = result {
+                    commit(&store, output);
+                }
+            });
+        });
+    }
+
+    resource
+}
+
+/// The async function run by [`ReactiveAction::dispatch`].
+type DispatchFn<I, O, E> = Arc<dyn Fn(&I) -> BoxFuture<'static, Result<O, E>> + Send + Sync>;
+
+/// The attempt counters of a [`ReactiveAction`], passed to the running
+/// dispatch as an action dependency so [`AsyncActionBuilder`] can update
+/// them.
+#[derive(Clone, Copy)]
+struct DispatchProgress {
+    attempt: RwSignal<u32>,
+    max_attempts: RwSignal<Option<u32>>,
+}
+
+/// Reactive action handle for use in components.
+///
+/// This provides a way to track action state reactively and
+/// dispatch actions from event handlers.
+///
+/// # Dispatching
+///
+/// An action created with [`from_async`](Self::from_async) runs its async
+/// function on each [`dispatch`](Self::dispatch) and updates the signals
+/// itself. The latest dispatch wins, which suits searches and other inputs
+/// where only the newest request matters:
+///
+/// ```rust,ignore
+/// let search = ReactiveAction::from_async(|query: &String| {
+///     let query = query.clone();
+///     async move { api::search(&query).await }
+/// });
+///
+/// view! {
+///     <input on:input:target=move |ev| search.dispatch(ev.target().value()) />
+///     <Show when=move || search.pending()>"Searching..."</Show>
+/// }
+/// ```
+///
+/// # Errors and State
+///
+/// Failures are recorded with [`set_error`](Self::set_error) and read with
+/// [`error`](Self::error). The error type `E` defaults to [`ActionError`].
+/// [`state`](Self::state) combines the pending flag, error, and value into
+/// an [`ActionState`], so a component can match on one value instead of
+/// checking several signals:
+///
+/// ```rust
+/// use leptos_store::prelude::*;
+///
+/// let save: ReactiveAction<String, u32, String> = ReactiveAction::new();
+/// let status = move || match save.state() {
+///     ActionState::Idle => "Not saved".to_string(),
+///     ActionState::Pending => "Saving...".to_string(),
+///     ActionState::Success => format!("Saved revision {}", save.value().unwrap_or_default()),
+///     ActionState::Error => format!("Failed: {}", save.error().unwrap_or_default()),
+/// };
+///
+/// save.set_pending();
+/// save.set_error("disk full".to_string());
+/// assert_eq!(status(), "Failed: disk full");
+///
+/// save.set_pending();
+/// save.set_value(2);
+/// assert_eq!(status(), "Saved revision 2");
+/// ```
+///
+/// # Cancellation
+///
+/// Each dispatch started with [`set_pending`](Self::set_pending) gets a
+/// fresh [`CancellationToken`], available from [`token`](Self::token).
+/// [`cancel`](Self::cancel) cancels it, marks the action as no longer
+/// pending, and records [`ActionError::Cancelled`], so
+/// [`state`](Self::state) becomes [`ActionState::Error`].
+///
+/// ```rust
+/// use leptos_store::prelude::*;
+///
+/// let search: ReactiveAction<String, Vec<String>> = ReactiveAction::new();
+/// search.set_input("lep".to_string());
+/// search.set_pending();
+/// let request = search.token().run(futures::future::pending::<Vec<String>>());
+///
+/// // The user kept typing; abandon the stale request.
+/// search.cancel();
+/// assert_eq!(search.state(), ActionState::Error);
+/// assert_eq!(search.error(), Some(ActionError::Cancelled));
+/// assert!(futures::executor::block_on(request).is_err());
+/// ```
+///
+/// # Instrumentation
+///
+/// The handle records when the last dispatch started and finished, and
+/// how many attempts an [`AsyncActionBuilder`] made at it, for progress
+/// messages and latency metrics:
+///
+/// ```rust,ignore
+/// let status = move || match (fetch.attempt(), fetch.max_attempts()) {
+///     (attempt, Some(max)) if fetch.pending() && attempt > 1 => {
+///         format!("Retrying ({}/{})...", attempt - 1, max - 1)
+///     }
+///     _ if fetch.pending() => "Loading...".to_string(),
+///     _ => format!("Took {}ms", fetch.elapsed_ms().unwrap_or_default()),
+/// };
+/// ```
+pub struct ReactiveAction<I, O, E = ActionError>
+where
+    I: Clone + Send + Sync + 'static,
+    O: Clone + Send + Sync + 'static,
+    E: Clone + Send + Sync + 'static,
+{
+    input: RwSignal<Option<I>>,
+    value: RwSignal<Option<O>>,
+    error: RwSignal<Option<E>>,
+    pending: RwSignal<bool>,
+    version: RwSignal<usize>,
+    started_at: RwSignal<Option<u64>>,
+    finished_at: RwSignal<Option<u64>>,
+    attempt: RwSignal<u32>,
+    max_attempts: RwSignal<Option<u32>>,
+    token: StoredValue<CancellationToken>,
+    run: StoredValue<Option<DispatchFn<I, O, E>>>,
+}
+
+impl<I, O, E> Clone for ReactiveAction<I, O, E>
+where
+    I: Clone + Send + Sync + 'static,
+    O: Clone + Send + Sync + 'static,
+    E: Clone + Send + Sync + 'static,
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+// Every field is an arena handle, so the handle can be copied into
+// closures like other Leptos reactive primitives.
+impl<I, O, E> Copy for ReactiveAction<I, O, E>
+where
+    I: Clone + Send + Sync + 'static,
+    O: Clone + Send + Sync + 'static,
+    E: Clone + Send + Sync + 'static,
+{
+}
+
+impl<I, O, E> Default for ReactiveAction<I, O, E>
+where
+    I: Clone + Send + Sync + 'static,
+    O: Clone + Send + Sync + 'static,
+    E: Clone + Send + Sync + 'static,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<I, O, E> ReactiveAction<I, O, E>
+where
+    I: Clone + Send + Sync + 'static,
+    O: Clone + Send + Sync + 'static,
+    E: Clone + Send + Sync + 'static,
+{
+    /// Create a new reactive action.
+    pub fn new() -> Self {
+        Self {
+            input: RwSignal::new(None),
+            value: RwSignal::new(None),
+            error: RwSignal::new(None),
+            pending: RwSignal::new(false),
+            version: RwSignal::new(0),
+            started_at: RwSignal::new(None),
+            finished_at: RwSignal::new(None),
+            attempt: RwSignal::new(0),
+            max_attempts: RwSignal::new(None),
+            token: StoredValue::new(CancellationToken::new()),
+            run: StoredValue::new(None),
+        }
+    }
+
+    /// Create a reactive action that runs `run` on each
+    /// [`dispatch`](Self::dispatch).
+    pub fn from_async<F, Fut>(run: F) -> Self
+    where
+        F: Fn(&I) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<O, E>> + Send + 'static,
+    {
+        let action = Self::new();
+        let run: DispatchFn<I, O, E> = Arc::new(move |input| Box::pin(run(input)));
+        action.run.set_value(Some(run));
+        action
+    }
+
+    /// Run the action's async function with `input` in the background.
+    ///
+    /// The latest dispatch wins: a dispatch still in flight is cancelled,
+    /// and its result is discarded. The handle is pending until the
+    /// function finishes, then holds its output or error.
+    ///
+    /// Does nothing, apart from logging a warning, if the action wasn't
+    /// created with [`from_async`](Self::from_async).
+    pub fn dispatch(&self, input: I) {
+        let Some(run) = self.run.get_value() else {
+            leptos::logging::warn!("ReactiveAction::dispatch called without an async function");
+            return;
+        };
+
+        self.token.with_value(CancellationToken::cancel);
+        self.set_input(input.clone());
+        self.set_pending();
+
+        let token = self.token();
+        let future = ActionDeps::current()
+            .with(self.progress())
+            .scope(run(&input));
+        let action = *self;
+        leptos::task::spawn(async move {
+            match token.run(future).await {
+                Ok(Ok(output)) => action.set_value(output),
+                Ok(Err(err)) => action.set_error(err),
+                // Superseded by a newer dispatch, or cancelled
+                Err(_) => {}
+            }
+        });
+    }
+
+    /// Get the current input value.
+    pub fn input(&self) -> Option<I> {
+        self.input.get()
+    }
+
+    /// Get the current output value.
+    pub fn value(&self) -> Option<O> {
+        self.value.get()
+    }
+
+    /// Get the error of the last dispatch, if it failed.
+    pub fn error(&self) -> Option<E> {
+        self.error.get()
+    }
+
+    /// Check if the action is pending.
+    pub fn pending(&self) -> bool {
+        self.pending.get()
+    }
+
+    /// Get the state of the last dispatch.
+    pub fn state(&self) -> ActionState {
+        if self.pending.get() {
+            ActionState::Pending
+        } else if self.error.with(Option::is_some) {
+            ActionState::Error
+        } else if self.value.with(Option::is_some) {
+            ActionState::Success
+        } else {
+            ActionState::Idle
+        }
+    }
+
+    /// Get the cancellation token of the current dispatch.
+    pub fn token(&self) -> CancellationToken {
+        self.token.get_value()
+    }
+
+    /// Get the version number (incremented on each dispatch).
+    pub fn version(&self) -> usize {
+        self.version.get()
+    }
+
+    /// Get when the last dispatch started, in milliseconds since the Unix
+    /// epoch.
+    pub fn started_at_ms(&self) -> Option<u64> {
+        self.started_at.get()
+    }
+
+    /// Get when the last dispatch finished, in milliseconds since the Unix
+    /// epoch, or `None` while it's running.
+    pub fn finished_at_ms(&self) -> Option<u64> {
+        self.finished_at.get()
+    }
+
+    /// Get how long the last dispatch ran, or has been running so far.
+    ///
+    /// While the dispatch is running, the value only changes when the
+    /// dispatch does; read it again on an interval for a live timer.
+    pub fn elapsed_ms(&self) -> Option<u64> {
+        let started = self.started_at.get()?;
+        let finished = self.finished_at.get().unwrap_or_else(now_ms);
+        Some(finished.saturating_sub(started))
+    }
+
+    /// Get the attempt the last dispatch is on, starting at `1`, or `0`
+    /// before the first dispatch.
+    ///
+    /// Only retries made with an [`AsyncActionBuilder`] are counted.
+    pub fn attempt(&self) -> u32 {
+        self.attempt.get()
+    }
+
+    /// Get how many attempts the last dispatch may make, if an
+    /// [`AsyncActionBuilder`] with a fixed retry count is retrying it.
+    pub fn max_attempts(&self) -> Option<u32> {
+        self.max_attempts.get()
+    }
+
+    /// Set the input value.
+    pub fn set_input(&self, input: I) {
+        self.input.set(Some(input));
+    }
+
+    /// Set the output value and mark as not pending.
+    pub fn set_value(&self, value: O) {
+        self.value.set(Some(value));
+        self.error.set(None);
+        self.pending.set(false);
+        self.finish_dispatch();
+    }
+
+    /// Set the error and mark as not pending.
+    pub fn set_error(&self, error: E) {
+        self.error.set(Some(error));
+        self.pending.set(false);
+        self.finish_dispatch();
+    }
+
+    /// Mark the action as pending, starting a dispatch with a new
+    /// cancellation token.
+    pub fn set_pending(&self) {
+        self.token.set_value(CancellationToken::new());
+        // Set pending before clearing the error, so `state` goes straight
+        // from `Error` to `Pending`.
+        self.pending.set(true);
+        self.error.set(None);
+        self.version.update(|v| *v += 1);
+        self.start_dispatch();
+    }
+
+    /// Clear the action state.
+    pub fn clear(&self) {
+        self.input.set(None);
+        self.value.set(None);
+        self.error.set(None);
+        self.pending.set(false);
+        self.started_at.set(None);
+        self.finished_at.set(None);
+        self.attempt.set(0);
+        self.max_attempts.set(None);
+    }
+
+    /// Reset the instrumentation for a new dispatch.
+    fn start_dispatch(&self) {
+        self.started_at.set(Some(now_ms()));
+        self.finished_at.set(None);
+        self.attempt.set(1);
+        self.max_attempts.set(None);
+    }
+
+    /// Record that the dispatch finished.
+    fn finish_dispatch(&self) {
+        self.finished_at.set(Some(now_ms()));
+    }
+
+    /// Get the attempt counters for the running dispatch.
+    fn progress(&self) -> DispatchProgress {
+        DispatchProgress {
+            attempt: self.attempt,
+            max_attempts: self.max_attempts,
+        }
+    }
+}
+
+impl<I, O, E> ReactiveAction<I, O, E>
+where
+    I: Clone + Send + Sync + 'static,
+    O: Clone + Send + Sync + 'static,
+    E: Clone + Send + Sync + From<ActionError> + 'static,
+{
+    /// Cancel the in-flight dispatch.
+    ///
+    /// Does nothing if the action isn't pending.
+    pub fn cancel(&self) {
+        if !self.pending.get_untracked() {
+            return;
+        }
+        self.token.with_value(CancellationToken::cancel);
+        self.set_error(ActionError::Cancelled.into());
+    }
+}
+
+/// How an [`AsyncDispatcher`] handles a dispatch while another is running.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Concurrency {
+    /// Run every dispatch concurrently.
+    #[default]
+    TakeEvery,
+    /// Cancel the running dispatches and start the new one.
+    TakeLatest,
+    /// Run dispatches one at a time, in order.
+    Queue,
+    /// Ignore new dispatches while one is running.
+    Drop,
+}
+
+/// Running and queued dispatches of an [`AsyncDispatcher`].
+struct DispatchQueue<A> {
+    running: Vec<(u64, CancellationToken)>,
+    queued: VecDeque<A>,
+    next_id: u64,
+}
+
+impl<A> DispatchQueue<A> {
+    /// Register a new running dispatch.
+    fn register(&mut self) -> (u64, CancellationToken) {
+        let id = self.next_id;
+        self.next_id += 1;
+        let token = CancellationToken::new();
+        self.running.push((id, token.clone()));
+        (id, token)
+    }
+}
+
+type ErrorHandler<S, E> = Arc<dyn Fn(&S, &E) + Send + Sync>;
+
+/// Dispatches async actions of one type with a [`Concurrency`] policy.
+///
+/// All dispatches share one [`ReactiveAction`] handle, which is pending
+/// while any dispatch is running or queued and holds the result of the
+/// last one to finish. Cancelled dispatches don't change the value or
+/// error.
+///
+/// Created with [`StoreActionExt::dispatcher`]. Clones share the same
+/// dispatches.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// // Search as you type: only the latest query matters.
+/// let search = store.dispatcher::<SearchAction>(Concurrency::TakeLatest);
+///
+/// let on_input = move |ev| {
+///     search.dispatch(SearchAction { query: event_target_value(&ev) });
+/// };
+/// let results = move || search.handle().value();
+/// ```
+pub struct AsyncDispatcher<S, A>
+where
+    S: Store,
+    A: AsyncAction<S> + 'static,
+    A::Output: Clone + Sync + 'static,
+    A::Error: Clone + Sync + 'static,
+{
+    store: S,
+    mode: Concurrency,
+    handle: ReactiveAction<(), A::Output, A::Error>,
+    queue: Arc<Mutex<DispatchQueue<A>>>,
+    on_error: Option<ErrorHandler<S, A::Error>>,
+    rate_limit: Option<RateLimiter>,
+    deps: ActionDeps,
+}
+
+impl<S, A> Clone for AsyncDispatcher<S, A>
+where
+    S: Store,
+    A: AsyncAction<S> + 'static,
+    A::Output: Clone + Sync + 'static,
+    A::Error: Clone + Sync + 'static,
+{
+    fn clone(&self) -> Self {
+        Self {
+            store: self.store.clone(),
+            mode: self.mode,
+            handle: self.handle,
+            queue: Arc::clone(&self.queue),
+            on_error: self.on_error.clone(),
+            rate_limit: self.rate_limit.clone(),
+            deps: self.deps.clone(),
+        }
+    }
+}
+
+impl<S, A> AsyncDispatcher<S, A>
+where
+    S: Store,
+    A: AsyncAction<S> + 'static,
+    A::Output: Clone + Sync + 'static,
+    A::Error: Clone + Sync + 'static,
+{
+    /// Create a dispatcher running actions against `store`.
+    ///
+    /// Dispatched actions get the [`ActionDeps`] in context when the
+    /// dispatcher is created.
+    pub fn new(store: S, mode: Concurrency) -> Self {
+        Self {
+            store,
+            mode,
+            handle: ReactiveAction::new(),
+            queue: Arc::new(Mutex::new(DispatchQueue {
+                running: Vec::new(),
+                queued: VecDeque::new(),
+                next_id: 0,
+            })),
+            on_error: None,
+            rate_limit: None,
+            deps: ActionDeps::current(),
+        }
+    }
+
+    /// Pass the errors of failed dispatches to `on_error`, such as an error
+    /// mutator on the store.
+    pub fn with_error_handler<F>(mut self, on_error: F) -> Self
+    where
+        F: Fn(&S, &A::Error) + Send + Sync + 'static,
+    {
+        self.on_error = Some(Arc::new(on_error));
+        self
+    }
+
+    /// Limit how often actions are dispatched.
+    ///
+    /// With [`Overflow::Reject`], dispatches over the limit are dropped
+    /// and [`dispatch`](Self::dispatch) returns `false`. With
+    /// [`Overflow::Delay`], they start running once the limiter has room.
+    pub fn with_rate_limit(mut self, limiter: RateLimiter) -> Self {
+        self.rate_limit = Some(limiter);
+        self
+    }
+
+    /// Get the concurrency mode.
+    pub fn mode(&self) -> Concurrency {
+        self.mode
+    }
+
+    /// Get the reactive handle tracking the dispatches.
+    pub fn handle(&self) -> ReactiveAction<(), A::Output, A::Error> {
+        self.handle
+    }
+
+    /// Returns the number of running dispatches.
+    pub fn in_flight(&self) -> usize {
+        self.queue
+            .lock()
+            .map(|queue| queue.running.len())
+            .unwrap_or_default()
+    }
+
+    /// Returns the number of queued dispatches.
+    pub fn queued(&self) -> usize {
+        self.queue
+            .lock()
+            .map(|queue| queue.queued.len())
+            .unwrap_or_default()
+    }
+
+    /// Dispatch `action` according to the concurrency mode.
+    ///
+    /// Returns `false` if the action was dropped because another dispatch
+    /// is running in [`Concurrency::Drop`] mode, or by the rate limiter.
+    pub fn dispatch(&self, action: A) -> bool {
+        let Ok(mut queue) = self.queue.lock() else {
+            return false;
+        };
+        let busy = !queue.running.is_empty();
+        if self.mode == Concurrency::Drop && busy {
+            return false;
+        }
+        if let Some(limiter) = &self.rate_limit
+            && limiter.overflow() == Overflow::Reject
+            && !limiter.try_acquire()
+        {
+            return false;
+        }
+        match self.mode {
+            Concurrency::Queue if busy => {
+                queue.queued.push_back(action);
+                return true;
+            }
+            Concurrency::TakeLatest => {
+                for (_, token) in queue.running.drain(..) {
+                    token.cancel();
+                }
+            }
+            _ => {}
+        }
+        let (id, token) = queue.register();
+        drop(queue);
+
+        self.spawn(id, token, action);
+        true
+    }
+
+    /// Cancel every running dispatch and forget the queued ones.
+    pub fn cancel_all(&self) {
+        if let Ok(mut queue) = self.queue.lock() {
+            queue.queued.clear();
+            for (_, token) in queue.running.drain(..) {
+                token.cancel();
+            }
+        }
+        if self.handle.pending.get_untracked() {
+            self.handle.finish_dispatch();
+        }
+        self.handle.pending.set(false);
+    }
+
+    /// Start a registered dispatch.
+    ///
+    /// Called without the queue locked, since updating the handle may run
+    /// effects that dispatch again.
+    fn spawn(&self, id: u64, token: CancellationToken, action: A) {
+        self.handle.token.set_value(token.clone());
+        self.handle.pending.set(true);
+        self.handle.version.update(|v| *v += 1);
+        self.handle.start_dispatch();
+
+        // Rejected dispatches never get here, so only delays are awaited.
+        let slot = self
+            .rate_limit
+            .as_ref()
+            .filter(|limiter| limiter.overflow() == Overflow::Delay)
+            .map(RateLimiter::acquire);
+        let dispatcher = self.clone();
+        leptos::task::spawn(async move {
+            let store = &dispatcher.store;
+            let deps = dispatcher.deps.clone().with(dispatcher.handle.progress());
+            let run = async {
+                if let Some(slot) = slot {
+                    slot.await?;
+                }
+                Ok(middleware::run_async_action::<S, A, _, _, _>(store, || {
+                    deps.scope(action.execute(store))
+                })
+                .await)
+            };
+            let result = token.run(run).await.and_then(|result| result);
+            dispatcher.finish(id, result);
+        });
+    }
+
+    /// Record the result of dispatch `id` and start the next queued one.
+    fn finish(&self, id: u64, result: ActionResult<ActionResult<A::Output, A::Error>>) {
+        let (next, busy) = match self.queue.lock() {
+            Ok(mut queue) => {
+                queue.running.retain(|(running, _)| *running != id);
+                let next = if queue.running.is_empty() {
+                    queue
+                        .queued
+                        .pop_front()
+                        .map(|action| (queue.register(), action))
+                } else {
+                    None
+                };
+                (next, !queue.running.is_empty())
+            }
+            Err(_) => (None, false),
+        };
+
+        match result {
+            Ok(Ok(output)) => {
+                self.handle.value.set(Some(output));
+                self.handle.error.set(None);
+            }
+            Ok(Err(err)) => {
+                if let Some(on_error) = &self.on_error {
+                    on_error(&self.store, &err);
+                }
+                self.handle.error.set(Some(err));
+            }
+            // Cancelled or superseded
+            Err(_) => {}
+        }
+        if !busy {
+            self.handle.finish_dispatch();
+        }
+        self.handle.pending.set(busy);
+
+        if let Some(((id, token), action)) = next {
+            self.spawn(id, token, action);
+        }
+    }
+}
+
+type Teardown = Box<dyn FnOnce() + Send + Sync>;
+
+/// Dispatches an async action at a fixed interval.
+///
+/// Created with [`StoreActionExt::poll`]. Dispatches run with
+/// [`Concurrency::Drop`], so a tick is skipped while the previous dispatch
+/// is still running, and share one [`ReactiveAction`] handle.
+///
+/// While the page is hidden (`document.visibilityState` is `"hidden"`),
+/// ticks are skipped; if any was skipped, the action is dispatched as soon
+/// as the page becomes visible again.
+///
+/// [`revalidate_on_focus`](Poller::revalidate_on_focus),
+/// [`revalidate_on_reconnect`](Poller::revalidate_on_reconnect) and
+/// [`revalidate_on_restore`](Poller::revalidate_on_restore) opt in to
+/// dispatching immediately when the window regains focus, the browser
+/// comes back online, or the page is restored from the back/forward
+/// cache.
+///
+/// Clones control the same polling.
+pub struct Poller<S, A>
+where
+    S: Store,
+    A: AsyncAction<S> + Clone + 'static,
+    A::Output: Clone + Sync + 'static,
+    A::Error: Clone + Sync + 'static,
+{
+    dispatcher: AsyncDispatcher<S, A>,
+    action: A,
+    stopped: Arc<AtomicBool>,
+    missed: Arc<AtomicBool>,
+    teardown: Arc<Mutex<Vec<Teardown>>>,
+}
+
+impl<S, A> Clone for Poller<S, A>
+where
+    S: Store,
+    A: AsyncAction<S> + Clone + 'static,
+    A::Output: Clone + Sync + 'static,
+    A::Error: Clone + Sync + 'static,
+{
+    fn clone(&self) -> Self {
+        Self {
+            dispatcher: self.dispatcher.clone(),
+            action: self.action.clone(),
+            stopped: Arc::clone(&self.stopped),
+            missed: Arc::clone(&self.missed),
+            teardown: Arc::clone(&self.teardown),
+        }
+    }
+}
+
+impl<S, A> Poller<S, A>
+where
+    S: Store,
+    A: AsyncAction<S> + Clone + 'static,
+    A::Output: Clone + Sync + 'static,
+    A::Error: Clone + Sync + 'static,
+{
+    /// Create a poller that hasn't been started.
+    fn new(store: S, action: A) -> Self {
+        Self {
+            dispatcher: AsyncDispatcher::new(store, Concurrency::Drop),
+            action,
+            stopped: Arc::new(AtomicBool::new(false)),
+            missed: Arc::new(AtomicBool::new(false)),
+            teardown: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Get the reactive handle tracking the dispatches.
+    pub fn handle(&self) -> ReactiveAction<(), A::Output, A::Error> {
+        self.dispatcher.handle()
+    }
+
+    /// Check if polling has stopped.
+    pub fn is_stopped(&self) -> bool {
+        self.stopped.load(Ordering::SeqCst)
+    }
+
+    /// Stop polling and cancel the running dispatch.
+    pub fn stop(&self) {
+        if self.stopped.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let teardown =
+            std::mem::take(&mut *self.teardown.lock().unwrap_or_else(PoisonError::into_inner));
+        for teardown in teardown {
+            teardown();
+        }
+        self.dispatcher.cancel_all();
+    }
+
+    /// Dispatch the action whenever the window regains focus.
+    ///
+    /// Does nothing outside the browser.
+    pub fn revalidate_on_focus(self) -> Self {
+        self.revalidate_on("focus")
+    }
+
+    /// Dispatch the action whenever the browser comes back online.
+    ///
+    /// Does nothing outside the browser.
+    pub fn revalidate_on_reconnect(self) -> Self {
+        self.revalidate_on("online")
+    }
+
+    /// Dispatch the action whenever the page is restored from the
+    /// back/forward cache.
+    ///
+    /// A restored page resumes with its timers and data as they were when
+    /// the user navigated away, possibly long ago. Does nothing outside the
+    /// browser.
+    pub fn revalidate_on_restore(self) -> Self {
+        #[cfg(target_arch = "wasm32")]
+        if !self.is_stopped() {
+            let listener = page_restore_listener({
+                let poller = self.clone();
+                move || poller.revalidate()
+            });
+            self.add_teardown(Box::new(move || listener.remove()));
+        }
+        self
+    }
+
+    /// Dispatch the action whenever the window fires `event`.
+    fn revalidate_on(self, event: &'static str) -> Self {
+        #[cfg(target_arch = "wasm32")]
+        if !self.is_stopped() {
+            let listener = window_event_listener_untyped(event, {
+                let poller = self.clone();
+                move |_| poller.revalidate()
+            });
+            self.add_teardown(Box::new(move || listener.remove()));
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        let _ = event;
+        self
+    }
+
+    /// Dispatch the action now, unless polling has stopped.
+    ///
+    /// Counts as catching up on any tick skipped while the page was hidden.
+    #[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+    fn revalidate(&self) {
+        if self.is_stopped() {
+            return;
+        }
+        self.missed.store(false, Ordering::SeqCst);
+        self.dispatcher.dispatch(self.action.clone());
+    }
+
+    /// Run `teardown` when polling stops, or now if it already has.
+    #[cfg(target_arch = "wasm32")]
+    fn add_teardown(&self, teardown: Teardown) {
+        let mut pending = self.teardown.lock().unwrap_or_else(PoisonError::into_inner);
+        if self.is_stopped() {
+            drop(pending);
+            teardown();
+        } else {
+            pending.push(teardown);
+        }
+    }
+
+    /// Handle an interval tick.
+    #[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+    fn tick(&self, hidden: bool) {
+        if self.is_stopped() {
+            return;
+        }
+        if hidden {
+            self.missed.store(true, Ordering::SeqCst);
+        } else {
+            self.dispatcher.dispatch(self.action.clone());
+        }
+    }
+
+    /// Catch up on ticks skipped while the page was hidden.
+    #[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+    fn visibility_changed(&self, hidden: bool) {
+        if !hidden && !self.is_stopped() && self.missed.swap(false, Ordering::SeqCst) {
+            self.dispatcher.dispatch(self.action.clone());
+        }
+    }
+
+    /// Start the interval timer and the visibility listener.
+    #[cfg(target_arch = "wasm32")]
+    fn start(&self, interval: Duration) {
+        let timer = set_interval_with_handle(
+            {
+                let poller = self.clone();
+                move || poller.tick(document().hidden())
+            },
+            interval,
+        )
+        .ok();
+        let listener = window_event_listener_untyped("visibilitychange", {
+            let poller = self.clone();
+            move |_| poller.visibility_changed(document().hidden())
+        });
+        self.add_teardown(Box::new(move || {
+            if let Some(timer) = timer {
+                timer.clear();
+            }
+            listener.remove();
+        }));
+    }
+}
+
+/// Run `callback` whenever the page is restored from the back/forward
+/// cache (`pageshow` with `persisted` set).
+///
+/// The browser resumes a restored page as it was left, so transient state
+/// such as "last refreshed" timestamps or data fetched minutes ago is
+/// stale, while nothing in the app is re-created. Use it to refresh such
+/// state; [`Poller::revalidate_on_restore`] and
+/// [`rehydrate_on_restore`](crate::hydration::rehydrate_on_restore) are
+/// built on it. The listener is removed when the current owner is cleaned
+/// up. Does nothing outside the browser.
+///
+/// ```rust
+/// use leptos::prelude::*;
+/// use leptos_store::r#async::on_page_restore;
+///
+/// # let owner = Owner::new();
+/// # owner.with(|| {
+/// let restored_at = RwSignal::new(None::<f64>);
+/// on_page_restore(move || restored_at.set(Some(0.0)));
+/// # });
+/// ```
+pub fn on_page_restore(callback: impl Fn() + 'static) {
+    #[cfg(target_arch = "wasm32")]
+    {
+        let listener = page_restore_listener(callback);
+        on_cleanup(move || listener.remove());
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    let _ = callback;
+}
+
+/// Listen for the page being restored from the back/forward cache.
+#[cfg(target_arch = "wasm32")]
+fn page_restore_listener(callback: impl Fn() + 'static) -> WindowListenerHandle {
+    use leptos::wasm_bindgen::JsCast;
+
+    window_event_listener_untyped("pageshow", move |event| {
+        if event
+            .dyn_ref::<leptos::web_sys::PageTransitionEvent>()
+            .is_some_and(|event| event.persisted())
+        {
+            callback();
+        }
+    })
+}
+
+/// Whether [`StoreActionExt::spawn`] runs tasks on the server.
+///
+/// Set it in the [`StoreEnv`](crate::env::StoreEnv) provided to the app;
+/// without one, server tasks are skipped. In the browser tasks always run.
+///
+/// ```rust
+/// use leptos_store::env::{Profile, StoreEnv};
+/// use leptos_store::prelude::*;
+///
+/// let env = StoreEnv::builder()
+///     .config(ServerTasks::Spawn)
+///     .build(Profile::Production);
+/// assert_eq!(env.get::<ServerTasks>(), Some(&ServerTasks::Spawn));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ServerTasks {
+    /// Don't run tasks during SSR.
+    #[default]
+    Skip,
+    /// Run tasks on the server's async executor, such as tokio.
+    Spawn,
+}
+
+impl ServerTasks {
+    /// Returns the mode configured in the store environment in context.
+    pub fn current() -> Self {
+        crate::env::try_use_store_env()
+            .ok()
+            .and_then(|env| env.get::<Self>().copied())
+            .unwrap_or_default()
+    }
+}
+
+/// Async actions dispatched together with [`StoreActionExt::dispatch_all`].
+///
+/// Implemented for tuples of two to six [`AsyncAction`]s sharing an error
+/// type. The output is the tuple of their outputs, in order.
+pub trait ActionSet<S: Store>: Send + Sync {
+    /// The outputs of the actions, in order.
+    type Output: Send;
+
+    /// The error type shared by the actions.
+    type Error: Send + std::error::Error;
+
+    /// Execute the actions concurrently, each through the middleware chain.
+    ///
+    /// Fails with the first error, dropping the actions still running.
+    fn execute_all(
+        &self,
+        store: &S,
+    ) -> impl Future<Output = ActionResult<Self::Output, Self::Error>> + Send;
+}
+
+macro_rules! impl_action_set {
+    ($($action:ident $value:ident),+) => {
+        impl<S, E, $($action),+> ActionSet<S> for ($($action,)+)
+        where
+            S: Store,
+            E: Send + std::error::Error + 'static,
+            $($action: AsyncAction<S, Error = E>,)+
+        {
+            type Output = ($($action::Output,)+);
+            type Error = E;
+
+            async fn execute_all(&self, store: &S) -> ActionResult<Self::Output, E> {
+                let ($($value,)+) = self;
+                futures::try_join!($(
+                    middleware::run_async_action::<S, $action, _, _, _>(store, || $value.execute(store))
+                ),+)
+            }
+        }
+    };
+}
+
+impl_action_set!(A a, B b);
+impl_action_set!(A a, B b, C c);
+impl_action_set!(A a, B b, C c, D d);
+impl_action_set!(A a, B b, C c, D d, F f);
+impl_action_set!(A a, B b, C c, D d, F f, G g);
+
+/// Extension trait for stores to execute actions.
+pub trait StoreActionExt: Store + Sized {
+    /// Execute a synchronous action.
+    fn dispatch<A>(&self, action: A) -> A::Output
+    where
+        A: Action<Self>,
+    {
+        middleware::run_action::<Self, A, _>(|| action.execute(self))
+    }
+
+    /// Dispatch an async action in the background.
+    ///
+    /// The action is spawned on the Leptos executor, and the returned
+    /// handle tracks it reactively: it is pending until the action
+    /// finishes, then holds its output or error. Cancelling the handle
+    /// abandons the action.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let login = store.dispatch_async(LoginAction { email, password });
+    ///
+    /// view! {
+    ///     <Show when=move || login.pending()>"Signing in..."</Show>
+    ///     {move || login.error().map(|err| err.to_string())}
+    /// }
+    /// ```
+    fn dispatch_async<A>(&self, action: A) -> ReactiveAction<(), A::Output, A::Error>
+    where
+        A: AsyncAction<Self> + 'static,
+        A::Output: Clone + Sync + 'static,
+        A::Error: Clone + Sync + 'static,
+    {
+        self.dispatch_async_with(action, |_, _| {})
+    }
+
+    /// Dispatch an async action in the background, passing its error to
+    /// `on_error`.
+    ///
+    /// Use this to route failures into an error mutator on the store, in
+    /// addition to the returned handle.
+    ///
+    /// ```rust,ignore
+    /// store.dispatch_async_with(LoginAction { email, password }, |store, err| {
+    ///     store.set_error(err.to_string());
+    /// });
+    /// ```
+    fn dispatch_async_with<A, F>(
+        &self,
+        action: A,
+        on_error: F,
+    ) -> ReactiveAction<(), A::Output, A::Error>
+    where
+        A: AsyncAction<Self> + 'static,
+        A::Output: Clone + Sync + 'static,
+        A::Error: Clone + Sync + 'static,
+        F: Fn(&Self, &A::Error) + Send + Sync + 'static,
+    {
+        let dispatcher =
+            AsyncDispatcher::new(self.clone(), Concurrency::TakeEvery).with_error_handler(on_error);
+        dispatcher.dispatch(action);
+        dispatcher.handle()
+    }
+
+    /// Execute a keyed action, sharing one execution between identical
+    /// dispatches.
+    ///
+    /// If an action of the same type and [`action_key`] is already running
+    /// on this store instance, the returned future waits for it and
+    /// resolves to a clone of its result instead of executing again. Only
+    /// in-flight dispatches are shared; once the action finishes, the next
+    /// dispatch executes it again.
+    ///
+    /// Dispatches are matched by store instance, so separate stores (such
+    /// as those created for different SSR requests) never share results.
+    /// While the action runs, its key is reported by
+    /// [`is_pending`](Self::is_pending).
+    ///
+    /// ```rust,ignore
+    /// // Both components mount at once, but the token list is fetched once.
+    /// let tokens = store.dispatch_deduped(FetchTokens { page: 1 }).await?;
+    /// ```
+    ///
+    /// [`action_key`]: KeyedAction::action_key
+    fn dispatch_deduped<A>(
+        &self,
+        action: A,
+    ) -> impl Future<Output = ActionResult<A::Output, A::Error>> + Send + 'static
+    where
+        A: KeyedAction<Self>,
+    {
+        let state = ArcReadSignal::from(self.state());
+        let key = action.action_key();
+        with_in_flight::<Self, A, _>(|in_flight| {
+            // Forget abandoned dispatches
+            in_flight.retain(|entry| entry.result.upgrade().is_some());
+            if let Some(shared) = in_flight
+                .iter()
+                .find(|entry| entry.state == state && entry.key == key)
+                .and_then(|entry| entry.result.upgrade())
+            {
+                return shared;
+            }
+
+            let store = self.clone();
+            let deps = ActionDeps::current();
+            let finished = (state.clone(), key.clone());
+            let pending = PendingKey::<Self>::new(state.clone(), key.clone());
+            let shared: SharedResult<A::Output, A::Error> = async move {
+                let _pending = pending;
+                let result = middleware::run_async_action::<Self, A, _, _, _>(&store, || {
+                    deps.scope(action.execute(&store))
+                })
+                .await;
+                with_in_flight::<Self, A, _>(|in_flight| {
+                    in_flight.retain(|entry| entry.state != finished.0 || entry.key != finished.1);
+                });
+                result
+            }
+            .boxed()
+            .shared();
+            // Held weakly, so an abandoned dispatch is never resumed.
+            if let Some(result) = shared.downgrade() {
+                in_flight.push(InFlightEntry { state, key, result });
+            }
+            shared
+        })
+    }
+
+    /// Check if a keyed action with `key` is running on this store.
+    ///
+    /// Reports the actions dispatched with
+    /// [`dispatch_deduped`](Self::dispatch_deduped), whatever their type,
+    /// so give row-level actions keys naming their row. Reading it in a
+    /// reactive context tracks it.
+    ///
+    /// ```rust,ignore
+    /// impl KeyedAction<TokenStore> for DeleteToken {
+    ///     fn action_key(&self) -> String {
+    ///         format!("token:{}", self.id)
+    ///     }
+    /// }
+    ///
+    /// let deleting = move || store.is_pending(&format!("token:{id}"));
+    /// ```
+    fn is_pending(&self, key: &str) -> bool {
+        let state = ArcReadSignal::from(self.state());
+        with_pending_keys::<Self, _>(|keys| {
+            keys.changed.track();
+            keys.pending
+                .iter()
+                .any(|(pending, pending_key, _)| *pending == state && pending_key == key)
+        })
+    }
+
+    /// Returns the keys of the keyed actions running on this store, in the
+    /// order they were first dispatched.
+    ///
+    /// See [`is_pending`](Self::is_pending).
+    fn pending_keys(&self) -> Vec<String> {
+        let state = ArcReadSignal::from(self.state());
+        with_pending_keys::<Self, _>(|keys| {
+            keys.changed.track();
+            keys.pending
+                .iter()
+                .filter(|(pending, _, _)| *pending == state)
+                .map(|(_, key, _)| key.clone())
+                .collect()
+        })
+    }
+
+    /// Execute a cached action, or return its cached output.
+    ///
+    /// See [`CachedAction`]. Results are cached per store instance, so
+    /// separate stores (such as those of different SSR requests) never
+    /// share them.
+    ///
+    /// ```rust,ignore
+    /// // Switching back to a tab within a minute doesn't refetch.
+    /// let profile = store.dispatch_cached(FetchProfile { id }).await?;
+    /// ```
+    fn dispatch_cached<A>(
+        &self,
+        action: A,
+    ) -> impl Future<Output = ActionResult<A::Output, A::Error>> + Send + 'static
+    where
+        A: CachedAction<Self>,
+    {
+        let state = ArcReadSignal::from(self.state());
+        let key = action.cache_key();
+        let cached = ACTION_CACHE
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .filter(|entry| entry.action == TypeId::of::<A>() && entry.key == key)
+            .find(|entry| entry.is_for(&state))
+            .and_then(|entry| entry.output.downcast_ref::<A::Output>().cloned());
+        let store = self.clone();
+        let deps = ActionDeps::current();
+
+        async move {
+            if let Some(output) = cached {
+                return Ok(output);
+            }
+            let output = middleware::run_async_action::<Self, A, _, _, _>(&store, || {
+                deps.scope(action.execute(&store))
+            })
+            .await?;
+
+            let id = NEXT_CACHE_ID.fetch_add(1, Ordering::Relaxed);
+            let mut cache = ACTION_CACHE.lock().unwrap_or_else(PoisonError::into_inner);
+            cache.retain(|entry| {
+                entry.action != TypeId::of::<A>() || entry.key != key || !entry.is_for(&state)
+            });
+            cache.push(CacheEntry {
+                id,
+                state: Box::new(state),
+                action: TypeId::of::<A>(),
+                key,
+                output: Box::new(output.clone()),
+            });
+            drop(cache);
+            // Expired entries are removed by id, so a newer result is kept.
+            let _ = set_timer(Duration::from_millis(action.ttl_ms()), move || {
+                ACTION_CACHE
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .retain(|entry| entry.id != id);
+            });
+            Ok(output)
+        }
+    }
+
+    /// Drop the results cached under `key` on this store instance, for
+    /// every [`CachedAction`] type.
+    fn invalidate(&self, key: &str) {
+        let state = ArcReadSignal::from(self.state());
+        ACTION_CACHE
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .retain(|entry| entry.key != key || !entry.is_for(&state));
+    }
+
+    /// Execute an async action after the actions queued before it on this
+    /// store instance have finished.
+    ///
+    /// Queued dispatches run strictly one at a time, in the order this
+    /// method was called, whatever their action types. Use it for write
+    /// APIs where requests must arrive in order, such as collaborative
+    /// editing operations. Dropping the returned future gives up its place
+    /// in the queue, or cancels the action if it's running.
+    ///
+    /// Dispatches made with other methods don't wait for the queue.
+    ///
+    /// ```rust,ignore
+    /// // Each insert is sent only after the previous one was acknowledged.
+    /// for op in ops {
+    ///     let insert = store.dispatch_queued(InsertText { op });
+    ///     store.spawn(async move {
+    ///         let _ = insert.await;
+    ///     });
+    /// }
+    /// ```
+    fn dispatch_queued<A>(
+        &self,
+        action: A,
+    ) -> impl Future<Output = ActionResult<A::Output, A::Error>> + Send + 'static
+    where
+        A: AsyncAction<Self> + 'static,
+    {
+        let ticket = StoreQueue::of(self).enqueue();
+        let store = self.clone();
+        let deps = ActionDeps::current();
+        async move {
+            let _permit = match ticket {
+                Ok(permit) => Some(permit),
+                Err(receiver) => receiver.await.ok(),
+            };
+            middleware::run_async_action::<Self, A, _, _, _>(&store, || {
+                deps.scope(action.execute(&store))
+            })
+            .await
+        }
+    }
+
+    /// Execute several async actions concurrently, resolving to all their
+    /// outputs once every one has succeeded.
+    ///
+    /// Resolves to the first error as soon as an action fails, dropping the
+    /// others. Have the actions return their data instead of committing it,
+    /// and commit the outputs in one mutator call, so the state is only
+    /// updated when all of them succeed. See [`ActionSet`].
+    ///
+    /// ```rust,ignore
+    /// let (user, settings) = store.dispatch_all((FetchUser { id }, FetchSettings)).await?;
+    /// store.load_dashboard(user, settings);
+    /// ```
+    fn dispatch_all<T>(
+        &self,
+        actions: T,
+    ) -> impl Future<Output = ActionResult<T::Output, T::Error>> + Send + 'static
+    where
+        T: ActionSet<Self> + 'static,
+    {
+        let store = self.clone();
+        let deps = ActionDeps::current();
+        async move { deps.scope(actions.execute_all(&store)).await }
+    }
+
+    /// Dispatch a streaming action in the background, passing each item to
+    /// `on_item` as it arrives.
+    ///
+    /// `on_item` is usually a mutator that appends the item to the state.
+    /// The returned handle is pending until the stream ends, and its value
+    /// is the number of items received so far. An error item stops the
+    /// stream and is recorded as the handle's error; items received before
+    /// it stay applied.
+    ///
+    /// ```rust,ignore
+    /// let progress = store.dispatch_stream(LoadPages { pages: 5 }, |store, page| {
+    ///     store.append_posts(page);
+    /// });
+    /// let loaded = move || progress.value().unwrap_or_default();
+    /// ```
+    fn dispatch_stream<A, F>(&self, action: A, on_item: F) -> ReactiveAction<(), usize, A::Error>
+    where
+        A: StreamAction<Self> + 'static,
+        A::Error: Clone + Sync + 'static,
+        F: Fn(&Self, A::Item) + Send + Sync + 'static,
+    {
+        let handle = ReactiveAction::new();
+        handle.set_pending();
+        let token = handle.token();
+        let store = self.clone();
+        let deps = ActionDeps::current().with(handle.progress());
+
+        leptos::task::spawn(async move {
+            let consume = deps.scope(async {
+                let mut stream = std::pin::pin!(action.stream(&store));
+                let mut received = 0;
+                while let Some(item) = stream.next().await {
+                    on_item(&store, item?);
+                    received += 1;
+                    handle.value.set(Some(received));
+                }
+                Ok(received)
+            });
+            let run = middleware::run_async_action::<Self, A, _, _, _>(&store, || consume);
+            match token.run(run).await {
+                Ok(Ok(received)) => handle.set_value(received),
+                Ok(Err(err)) => handle.set_error(err),
+                // `cancel` already recorded the cancellation, if it can.
+                Err(_) => {
+                    handle.finish_dispatch();
+                    handle.pending.set(false);
+                }
+            }
+        });
+
+        handle
+    }
+
+    /// Create a dispatcher for repeated dispatches of one action type.
+    ///
+    /// `mode` decides what happens when an action is dispatched while an
+    /// earlier one is still running. See [`AsyncDispatcher`].
+    fn dispatcher<A>(&self, mode: Concurrency) -> AsyncDispatcher<Self, A>
+    where
+        A: AsyncAction<Self> + 'static,
+        A::Output: Clone + Sync + 'static,
+        A::Error: Clone + Sync + 'static,
+    {
+        AsyncDispatcher::new(self.clone(), mode)
+    }
+
+    /// Call `handler` whenever one of this store's async actions fails.
+    ///
+    /// Use it to apply the same recovery to every action, such as recording
+    /// the error or clearing stale data, instead of repeating it in each
+    /// one. The handler gets the store, the failed action, and its error
+    /// after [`Middleware::on_error`](crate::middleware::Middleware::on_error)
+    /// translated it. It runs for the actions dispatched through this trait
+    /// and [`StorePrefetchExt`](crate::prefetch::StorePrefetchExt), until the
+    /// current reactive owner is disposed, so register it where the store
+    /// is created.
+    ///
+    /// ```rust,ignore
+    /// store.on_action_error(|store, _cx, error| {
+    ///     if let Some(ActionError::Network(_)) = error.downcast_ref::<ActionError>() {
+    ///         store.mark_offline();
+    ///     }
+    ///     store.set_error(error.to_string());
+    /// });
+    /// ```
+    fn on_action_error<F>(&self, handler: F)
+    where
+        F: Fn(&Self, &middleware::ActionContext, &(dyn std::error::Error + 'static))
+            + Send
+            + Sync
+            + 'static,
+    {
+        middleware::register_recovery_handler(self, Arc::new(handler));
+    }
+
+    /// Spawn a background task tied to the current reactive owner.
+    ///
+    /// In the browser the task runs on the local executor. On the server it
+    /// runs only if the store environment sets [`ServerTasks::Spawn`], so
+    /// client-only work such as refreshing or syncing doesn't run during
+    /// SSR. The task is cancelled at its next await point when the owner is
+    /// disposed, or when the returned token is cancelled; a skipped task's
+    /// token is already cancelled.
+    ///
+    /// ```rust,ignore
+    /// let this = store.clone();
+    /// store.spawn(async move {
+    ///     if let Ok(profile) = fetch_profile().await {
+    ///         this.set_profile(profile);
+    ///     }
+    /// });
+    /// ```
+    fn spawn<F>(&self, task: F) -> CancellationToken
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let token = CancellationToken::new();
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if ServerTasks::current() == ServerTasks::Skip {
+            token.cancel();
+            return token;
+        }
+
+        on_cleanup({
+            let token = token.clone();
+            move || token.cancel()
+        });
+        let task = token.run(task);
+        leptos::task::spawn(async move {
+            let _ = task.await;
+        });
+        token
+    }
+
+    /// Returns the supervisor of this store's background tasks.
+    ///
+    /// Unlike [`spawn`](Self::spawn), tasks spawned through it are named,
+    /// can be listed while they run, and are aborted when the owner current
+    /// at the first call is disposed, so call it where the store is
+    /// created. See [`StoreTasks`](crate::tasks::StoreTasks).
+    ///
+    /// ```rust,ignore
+    /// let this = store.clone();
+    /// store.tasks().spawn("sync", async move { this.sync_forever().await });
+    /// assert_eq!(store.tasks().running()[0].name(), "sync");
+    /// ```
+    fn tasks(&self) -> crate::tasks::StoreTasks {
+        crate::tasks::StoreTasks::of(self)
+    }
+
+    /// Dispatch `action` every `interval` in the browser.
+    ///
+    /// The first dispatch happens after one interval. Polling pauses while
+    /// the page is hidden and stops when the current reactive owner is
+    /// disposed, or when [`Poller::stop`] is called. During SSR and outside
+    /// the browser nothing is scheduled, and the returned poller is already
+    /// stopped.
+    ///
+    /// ```rust,ignore
+    /// let poller = store.poll(Duration::from_secs(30), RefreshTokens);
+    /// let refreshing = move || poller.handle().pending();
+    /// ```
+    fn poll<A>(&self, interval: Duration, action: A) -> Poller<Self, A>
+    where
+        A: AsyncAction<Self> + Clone + 'static,
+        A::Output: Clone + Sync + 'static,
+        A::Error: Clone + Sync + 'static,
+    {
+        let poller = Poller::new(self.clone(), action);
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            poller.start(interval);
+            on_cleanup({
+                let poller = poller.clone();
+                move || poller.stop()
+            });
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let _ = interval;
+            poller.stop();
+        }
+
+        poller
+    }
+
+    /// Run an action in dry-run mode, returning the mutations it would perform.
+    ///
+    /// The action runs unchanged against a [shadow](DryRunStore::shadow)
+    /// copy of the store, and each mutator it calls is recorded into the
+    /// returned [`MutationPlan`] instead of touching the store's state.
+    /// Other side effects of the action still happen. Commit the plan with
+    /// [`MutationPlan::commit_to`], which refuses plans made stale by later
+    /// changes.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use leptos::prelude::*;
+    /// use leptos_store::prelude::{Action, *};
+    /// use leptos_store::store;
+    ///
+    /// store! {
+    ///     pub TodoStore {
+    ///         #[derive(PartialEq)]
+    ///         state TodoState {
+    ///             items: Vec<String> = vec!["a".into(), "b".into()],
+    ///         }
+    ///
+    ///         mutators {
+    ///             clear_items(this) {
+    ///                 this.mutate(|s| s.items.clear());
+    ///             }
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// struct DeleteAll;
+    ///
+    /// impl Action<TodoStore> for DeleteAll {
+    ///     type Output = ();
+    ///
+    ///     fn execute(&self, store: &TodoStore) {
+    ///         store.clear_items();
+    ///     }
+    /// }
+    ///
+    /// let store = TodoStore::new();
+    ///
+    /// let plan = store.dry_run(DeleteAll);
+    /// assert_eq!(plan.names(), vec!["clear_items"]);
+    /// assert_eq!(store.state().get().items.len(), 2); // not committed
+    ///
+    /// assert!(plan.commit_to(&store));
+    /// assert!(store.state().get().items.is_empty());
+    /// ```
+    fn dry_run<A>(&self, action: A) -> MutationPlan<Self::State>
+    where
+        Self: DryRunStore,
+        A: Action<Self>,
+    {
+        let initial = self.state().get_untracked();
+        let owner = Owner::new();
+        let plan = owner.with(|| {
+            let shadow = self.shadow(initial.clone());
+            record_dry_run::<Self>(initial, || {
+                action.execute(&shadow);
+                shadow.state().get_untracked()
+            })
+        });
+        owner.cleanup();
+        plan
     }
 }
 
@@ -486,9 +3756,291 @@ mod tests {
         assert_eq!(err.to_string(), "Validation error: Invalid email");
     }
 
-    // Note: AsyncActionBuilder requires a Store type, which makes it
-    // harder to test in isolation. The builder's functionality is
-    // tested through integration tests with real store types.
+    #[test]
+    fn test_action_error_source() {
+        use std::error::Error as _;
+
+        let io = std::io::Error::new(std::io::ErrorKind::PermissionDenied, "401");
+        let err = ActionError::from_error(io);
+        assert_eq!(err.to_string(), "Action failed: 401");
+        assert_eq!(
+            err.downcast_ref::<std::io::Error>()
+                .map(std::io::Error::kind),
+            Some(std::io::ErrorKind::PermissionDenied)
+        );
+        assert!(err.source().unwrap().is::<std::io::Error>());
+        assert!(err.downcast_ref::<std::fmt::Error>().is_none());
+
+        // Clones share the source; separately wrapped errors differ.
+        assert_eq!(err.clone(), err);
+        assert_ne!(
+            ActionError::from_error(std::fmt::Error),
+            ActionError::from_error(std::fmt::Error)
+        );
+        assert!(ActionError::failed("401").source().is_none());
+    }
+
+    #[derive(Clone)]
+    struct TestStore {
+        state: RwSignal<i32>,
+    }
+
+    impl Store for TestStore {
+        type State = i32;
+
+        fn state(&self) -> ReadSignal<Self::State> {
+            self.state.read_only()
+        }
+    }
+
+    /// Fails until `succeed_on` attempts have been made.
+    struct FlakyAction {
+        attempts: std::sync::atomic::AtomicU32,
+        succeed_on: u32,
+    }
+
+    impl AsyncAction<TestStore> for FlakyAction {
+        type Output = u32;
+        type Error = ActionError;
+
+        async fn execute(&self, _store: &TestStore) -> ActionResult<u32> {
+            let attempt = self.attempts.fetch_add(1, Ordering::SeqCst) + 1;
+            if attempt < self.succeed_on {
+                Err(ActionError::network(format!("attempt {attempt}")))
+            } else {
+                Ok(attempt)
+            }
+        }
+    }
+
+    #[test]
+    fn test_backoff_delays() {
+        let backoff = Backoff::exponential(100).with_factor(3.0).with_max_ms(1000);
+        assert_eq!(backoff.base_delay_ms(0), 100);
+        assert_eq!(backoff.base_delay_ms(1), 300);
+        assert_eq!(backoff.base_delay_ms(3), 1000);
+        assert_eq!(backoff.base_delay_ms(u32::MAX), 1000);
+        assert_eq!(backoff.delay_ms(1), 300);
+
+        let backoff = backoff.with_jitter(0.5);
+        assert_eq!(backoff.jittered(400, 0.0), 400);
+        assert_eq!(backoff.jittered(400, 0.5), 300);
+        for retry in 0..5 {
+            let base = backoff.base_delay_ms(retry);
+            assert!((base / 2..=base).contains(&backoff.delay_ms(retry)));
+        }
+    }
+
+    #[test]
+    fn test_builder_execute_retries() {
+        let store = TestStore {
+            state: RwSignal::new(0),
+        };
+        let builder: AsyncActionBuilder<TestStore, u32, ActionError> = AsyncActionBuilder::new()
+            .with_retry(3)
+            .with_backoff(Backoff::exponential(1).with_jitter(1.0));
+
+        let action = FlakyAction {
+            attempts: Default::default(),
+            succeed_on: 3,
+        };
+        let result = futures::executor::block_on(builder.execute(&action, &store));
+        assert_eq!(result.unwrap(), 3);
+
+        let action = FlakyAction {
+            attempts: Default::default(),
+            succeed_on: 10,
+        };
+        let result = futures::executor::block_on(builder.execute(&action, &store));
+        assert_eq!(result.unwrap_err().to_string(), "Network error: attempt 4");
+        assert_eq!(action.attempts.load(Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    fn test_builder_retry_policy() {
+        let store = TestStore {
+            state: RwSignal::new(0),
+        };
+        let retries = Arc::new(Mutex::new(Vec::new()));
+        let builder: AsyncActionBuilder<TestStore, u32, ActionError> =
+            AsyncActionBuilder::new().with_retry(10).with_retry_policy({
+                let retries = Arc::clone(&retries);
+                move |retry, err: &ActionError| {
+                    retries.lock().unwrap().push(retry);
+                    (matches!(err, ActionError::Network(_)) && retry < 2).then_some(1)
+                }
+            });
+        assert!(builder.retry_policy().is_some());
+
+        let action = FlakyAction {
+            attempts: Default::default(),
+            succeed_on: 3,
+        };
+        let result = futures::executor::block_on(builder.execute(&action, &store));
+        assert_eq!(result.unwrap(), 3);
+        assert_eq!(*retries.lock().unwrap(), [0, 1]);
+
+        // The policy gives up instead of the retry count.
+        retries.lock().unwrap().clear();
+        let action = FlakyAction {
+            attempts: Default::default(),
+            succeed_on: 10,
+        };
+        let result = futures::executor::block_on(builder.execute(&action, &store));
+        assert_eq!(result.unwrap_err().to_string(), "Network error: attempt 3");
+        assert_eq!(*retries.lock().unwrap(), [0, 1, 2]);
+    }
+
+    /// Takes `delay_ms` to complete.
+    struct SlowAction {
+        delay_ms: u64,
+    }
+
+    impl AsyncAction<TestStore> for SlowAction {
+        type Output = ();
+        type Error = ActionError;
+
+        async fn execute(&self, _store: &TestStore) -> ActionResult<()> {
+            delay(Duration::from_millis(self.delay_ms)).await;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_delay_and_timeout() {
+        futures::executor::block_on(async {
+            let start = std::time::Instant::now();
+            futures::join!(
+                delay(Duration::from_millis(20)),
+                delay(Duration::from_millis(5)),
+                delay(Duration::ZERO),
+            );
+            assert!(start.elapsed() >= Duration::from_millis(20));
+
+            assert_eq!(timeout(1000, async { 1 }).await.unwrap(), 1);
+            let result = timeout(5, delay(Duration::from_secs(5))).await;
+            assert!(matches!(result, Err(ActionError::Timeout(5))));
+        });
+    }
+
+    #[test]
+    fn test_builder_execute_timeout() {
+        let store = TestStore {
+            state: RwSignal::new(0),
+        };
+        let builder: AsyncActionBuilder<TestStore, (), ActionError> =
+            AsyncActionBuilder::new().with_timeout(10);
+
+        let result =
+            futures::executor::block_on(builder.execute(&SlowAction { delay_ms: 1 }, &store));
+        assert!(result.is_ok());
+
+        let builder = builder.with_retry(1).with_backoff(Backoff::exponential(1));
+        let start = std::time::Instant::now();
+        let result =
+            futures::executor::block_on(builder.execute(&SlowAction { delay_ms: 5000 }, &store));
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Action timed out after 10ms"
+        );
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_rate_limiter() {
+        use futures::executor::block_on;
+
+        let limiter = RateLimiter::new(2, 40);
+        assert!(block_on(limiter.acquire()).is_ok());
+        assert!(limiter.try_acquire());
+        assert_eq!(block_on(limiter.acquire()), Err(ActionError::RateLimited));
+        std::thread::sleep(Duration::from_millis(100));
+        assert_eq!(limiter.available(), 2);
+
+        // Delayed dispatches wait for a slot, skipping abandoned ones.
+        let limiter = RateLimiter::new(1, 100).with_overflow(Overflow::Delay);
+        let start = std::time::Instant::now();
+        let first = limiter.acquire();
+        drop(limiter.acquire());
+        let third = limiter.acquire();
+        assert_eq!(
+            block_on(async { futures::join!(first, third) }),
+            (Ok(()), Ok(()))
+        );
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_millis(100));
+        assert!(elapsed < Duration::from_millis(190), "waited {elapsed:?}");
+
+        let store = TestStore {
+            state: RwSignal::new(0),
+        };
+        let builder: AsyncActionBuilder<TestStore, (), ActionError> =
+            AsyncActionBuilder::new().with_rate_limit(RateLimiter::new(1, 1000));
+        let action = SlowAction { delay_ms: 1 };
+        assert!(block_on(builder.execute(&action, &store)).is_ok());
+        assert_eq!(
+            block_on(builder.execute(&action, &store)),
+            Err(ActionError::RateLimited)
+        );
+    }
+
+    #[test]
+    fn test_cancellation_token() {
+        let token = CancellationToken::new();
+        let clone = token.clone();
+        assert!(token.check().is_ok());
+
+        let waiting = std::thread::spawn(move || {
+            futures::executor::block_on(clone.run(delay(Duration::from_secs(5))))
+        });
+        std::thread::sleep(Duration::from_millis(10));
+        token.cancel();
+
+        assert_eq!(waiting.join().unwrap(), Err(ActionError::Cancelled));
+        assert_eq!(token.check(), Err(ActionError::Cancelled));
+        futures::executor::block_on(token.cancelled());
+    }
+
+    #[test]
+    fn test_builder_execute_cancelled() {
+        let store = TestStore {
+            state: RwSignal::new(0),
+        };
+        let token = CancellationToken::new();
+        let builder: AsyncActionBuilder<TestStore, u32, ActionError> = AsyncActionBuilder::new()
+            .with_retry(5)
+            .with_backoff(Backoff::exponential(1000))
+            .with_cancel_token(token.clone());
+        let action = FlakyAction {
+            attempts: Default::default(),
+            succeed_on: 10,
+        };
+
+        let canceller = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(10));
+            token.cancel();
+        });
+        let result = futures::executor::block_on(builder.execute(&action, &store));
+        canceller.join().unwrap();
+
+        assert_eq!(result, Err(ActionError::Cancelled));
+        assert_eq!(action.attempts.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_builder_without_retry_runs_once() {
+        let store = TestStore {
+            state: RwSignal::new(0),
+        };
+        let action = FlakyAction {
+            attempts: Default::default(),
+            succeed_on: 2,
+        };
+        let builder = AsyncActionBuilder::new();
+        let result = futures::executor::block_on(builder.execute(&action, &store));
+        assert!(result.is_err());
+        assert_eq!(action.attempts.load(Ordering::SeqCst), 1);
+    }
 
     #[test]
     fn test_reactive_action_creation() {
@@ -519,4 +4071,890 @@ mod tests {
         assert!(action.input().is_none());
         assert!(action.value().is_none());
     }
+
+    /// Wait for a spawned dispatch to finish.
+    fn wait_for<I, O, E>(action: &ReactiveAction<I, O, E>)
+    where
+        I: Clone + Send + Sync + 'static,
+        O: Clone + Send + Sync + 'static,
+        E: Clone + Send + Sync + 'static,
+    {
+        let start = std::time::Instant::now();
+        while action.pending.get_untracked() {
+            assert!(
+                start.elapsed() < Duration::from_secs(5),
+                "dispatch timed out"
+            );
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn test_dispatch_async() {
+        let _ = any_spawner::Executor::init_futures_executor();
+        let owner = Owner::new();
+        owner.with(|| {
+            let store = TestStore {
+                state: RwSignal::new(0),
+            };
+
+            let ok = store.dispatch_async(FlakyAction {
+                attempts: Default::default(),
+                succeed_on: 1,
+            });
+            wait_for(&ok);
+            assert_eq!(ok.state(), ActionState::Success);
+            assert_eq!(ok.value(), Some(1));
+
+            let failed = store.dispatch_async_with(
+                FlakyAction {
+                    attempts: Default::default(),
+                    succeed_on: 2,
+                },
+                |store, err| {
+                    assert_eq!(err, &ActionError::network("attempt 1"));
+                    store.state.set(-1);
+                },
+            );
+            wait_for(&failed);
+            assert_eq!(failed.state(), ActionState::Error);
+            assert_eq!(failed.error(), Some(ActionError::network("attempt 1")));
+            assert_eq!(store.state.get_untracked(), -1);
+
+            let slow = store.dispatch_async(SlowAction { delay_ms: 5000 });
+            assert!(slow.pending());
+            slow.cancel();
+            assert_eq!(slow.error(), Some(ActionError::Cancelled));
+        });
+    }
+
+    /// Records its value when it completes after `delay_ms`.
+    #[derive(Clone)]
+    struct LoggedAction {
+        value: u32,
+        delay_ms: u64,
+        log: Arc<Mutex<Vec<u32>>>,
+    }
+
+    impl AsyncAction<TestStore> for LoggedAction {
+        type Output = u32;
+        type Error = ActionError;
+
+        async fn execute(&self, _store: &TestStore) -> ActionResult<u32> {
+            delay(Duration::from_millis(self.delay_ms)).await;
+            self.log.lock().unwrap().push(self.value);
+            Ok(self.value)
+        }
+    }
+
+    /// Dispatch values 1, 2, 3 with decreasing delays and return the
+    /// completion order, the final value, and which dispatches were accepted.
+    fn run_dispatches(mode: Concurrency) -> (Vec<u32>, Option<u32>, Vec<bool>) {
+        let store = TestStore {
+            state: RwSignal::new(0),
+        };
+        let dispatcher = store.dispatcher::<LoggedAction>(mode);
+        let log = Arc::new(Mutex::new(Vec::new()));
+
+        let accepted = [(1, 150), (2, 75), (3, 1)]
+            .into_iter()
+            .map(|(value, delay_ms)| {
+                dispatcher.dispatch(LoggedAction {
+                    value,
+                    delay_ms,
+                    log: Arc::clone(&log),
+                })
+            })
+            .collect();
+        let handle = dispatcher.handle();
+        assert!(handle.pending());
+        wait_for(&handle);
+        assert_eq!(dispatcher.in_flight(), 0);
+
+        let log = log.lock().unwrap().clone();
+        (log, handle.value(), accepted)
+    }
+
+    #[test]
+    fn test_dispatcher_concurrency_modes() {
+        let _ = any_spawner::Executor::init_futures_executor();
+        let owner = Owner::new();
+        owner.with(|| {
+            let (log, value, accepted) = run_dispatches(Concurrency::TakeEvery);
+            assert_eq!(log, [3, 2, 1]);
+            assert_eq!(value, Some(1));
+            assert_eq!(accepted, [true, true, true]);
+
+            let (log, value, _) = run_dispatches(Concurrency::TakeLatest);
+            assert_eq!(log, [3]);
+            assert_eq!(value, Some(3));
+
+            let (log, value, _) = run_dispatches(Concurrency::Queue);
+            assert_eq!(log, [1, 2, 3]);
+            assert_eq!(value, Some(3));
+
+            let (log, value, accepted) = run_dispatches(Concurrency::Drop);
+            assert_eq!(log, [1]);
+            assert_eq!(value, Some(1));
+            assert_eq!(accepted, [true, false, false]);
+        });
+    }
+
+    #[test]
+    fn test_spawn() {
+        let _ = any_spawner::Executor::init_futures_executor();
+        let store = TestStore {
+            state: RwSignal::new(0),
+        };
+        let (log, record) = recorder();
+        let record = Arc::new(record);
+
+        // Skipped during SSR unless configured.
+        let owner = Owner::new();
+        owner.with(|| {
+            let record = Arc::clone(&record);
+            let token = store.spawn(async move { record(1) });
+            assert!(token.is_cancelled());
+        });
+
+        owner.with(|| {
+            crate::env::provide_store_env(
+                crate::env::StoreEnv::builder()
+                    .config(ServerTasks::Spawn)
+                    .build(crate::env::Profile::Development),
+            );
+            let done = store.spawn({
+                let record = Arc::clone(&record);
+                async move { record(2) }
+            });
+            let pending = store.spawn({
+                let record = Arc::clone(&record);
+                async move {
+                    delay(Duration::from_millis(50)).await;
+                    record(3);
+                }
+            });
+            std::thread::sleep(Duration::from_millis(10));
+            assert!(!done.is_cancelled() && !pending.is_cancelled());
+        });
+
+        // Disposing the owner cancels the unfinished task.
+        owner.cleanup();
+        std::thread::sleep(Duration::from_millis(80));
+        assert_eq!(*log.lock().unwrap(), [2]);
+    }
+
+    #[test]
+    fn test_poller_ticks() {
+        let _ = any_spawner::Executor::init_futures_executor();
+        let owner = Owner::new();
+        owner.with(|| {
+            let store = TestStore {
+                state: RwSignal::new(0),
+            };
+            let log = Arc::new(Mutex::new(Vec::new()));
+            let action = LoggedAction {
+                value: 1,
+                delay_ms: 1,
+                log: Arc::clone(&log),
+            };
+            // Nothing is scheduled outside the browser.
+            let poller = store.poll(Duration::from_millis(10), action.clone());
+            assert!(poller.is_stopped());
+
+            let poller = Poller::new(store, action);
+            poller.tick(true);
+            poller.tick(true);
+            assert!(!poller.handle().pending());
+
+            // Skipped ticks are caught up on once.
+            poller.visibility_changed(false);
+            poller.visibility_changed(false);
+            wait_for(&poller.handle());
+            assert_eq!(*log.lock().unwrap(), [1]);
+
+            poller.tick(false);
+            wait_for(&poller.handle());
+            assert_eq!(*log.lock().unwrap(), [1, 1]);
+
+            poller.stop();
+            poller.tick(false);
+            assert!(!poller.handle().pending());
+            assert_eq!(log.lock().unwrap().len(), 2);
+        });
+    }
+
+    #[cfg(feature = "hydrate")]
+    #[test]
+    fn test_store_resource() {
+        #[derive(Clone, Debug, Error, serde::Serialize, serde::Deserialize)]
+        #[error("no page {0}")]
+        struct NoPage(u32);
+
+        struct FetchPage(u32);
+
+        impl AsyncAction<TestStore> for FetchPage {
+            type Output = i32;
+            type Error = NoPage;
+
+            async fn execute(&self, _store: &TestStore) -> Result<i32, NoPage> {
+                match self.0 {
+                    0 => Err(NoPage(0)),
+                    page => Ok(page as i32 * 10),
+                }
+            }
+        }
+
+        let _ = any_spawner::Executor::init_futures_executor();
+        let owner = Owner::new();
+        owner.with(|| {
+            let store = TestStore {
+                state: RwSignal::new(0),
+            };
+            let page = RwSignal::new(1);
+            let resource = store_resource(
+                &store,
+                move || page.get(),
+                FetchPage,
+                |store, value| store.state.set(*value),
+            );
+
+            let result = futures::executor::block_on(resource.into_future());
+            assert_eq!(result.unwrap(), 10);
+            assert_eq!(store.state.get_untracked(), 10);
+
+            // Failures are left in the resource.
+            page.set(0);
+            let start = std::time::Instant::now();
+            while !matches!(resource.get_untracked(), Some(Err(_))) {
+                assert!(
+                    start.elapsed() < Duration::from_secs(5),
+                    "refetch timed out"
+                );
+                std::thread::sleep(Duration::from_millis(1));
+            }
+            assert_eq!(store.state.get_untracked(), 10);
+        });
+    }
+
+    #[test]
+    fn test_server_fn_action() {
+        let store = TestStore {
+            state: RwSignal::new(0),
+        };
+        let errors = Arc::new(Mutex::new(Vec::new()));
+        let action = |fail: bool| {
+            let errors = Arc::clone(&errors);
+            ServerFnAction::new(move || async move {
+                match fail {
+                    true => Err(ServerFnError::Request("offline".to_string())),
+                    false => Ok(5),
+                }
+            })
+            .optimistic(|store: &TestStore| {
+                store.state.update(|n| *n += 100);
+                |store: &TestStore| store.state.update(|n| *n -= 100)
+            })
+            .on_success(|store: &TestStore, n: &i32| store.state.update(|total| *total += n))
+            .on_error(move |_, err| errors.lock().unwrap().push(err.clone()))
+        };
+
+        let result = futures::executor::block_on(action(false).execute(&store));
+        assert_eq!(result, Ok(5));
+        assert_eq!(store.state.get_untracked(), 105);
+
+        let result = futures::executor::block_on(action(true).clone().execute(&store));
+        assert_eq!(result, Err(ActionError::network("offline")));
+        assert_eq!(store.state.get_untracked(), 105);
+        assert_eq!(*errors.lock().unwrap(), [ActionError::network("offline")]);
+
+        assert_eq!(
+            ActionError::from(ServerFnError::new("boom")),
+            ActionError::failed(ServerFnError::new("boom").to_string())
+        );
+    }
+
+    /// Retries a [`FlakyAction`] with an [`AsyncActionBuilder`].
+    struct RetryingAction {
+        succeed_on: u32,
+    }
+
+    impl AsyncAction<TestStore> for RetryingAction {
+        type Output = u32;
+        type Error = ActionError;
+
+        async fn execute(&self, store: &TestStore) -> ActionResult<u32> {
+            let flaky = FlakyAction {
+                attempts: Default::default(),
+                succeed_on: self.succeed_on,
+            };
+            AsyncActionBuilder::new()
+                .with_retry(3)
+                .with_backoff(Backoff::exponential(5))
+                .execute(&flaky, store)
+                .await
+        }
+    }
+
+    #[test]
+    fn test_dispatch_instrumentation() {
+        let _ = any_spawner::Executor::init_futures_executor();
+        let owner = Owner::new();
+        owner.with(|| {
+            let store = TestStore {
+                state: RwSignal::new(0),
+            };
+            let idle: ReactiveAction<(), u32> = ReactiveAction::new();
+            assert_eq!(idle.attempt(), 0);
+            assert_eq!(idle.elapsed_ms(), None);
+
+            let before = now_ms();
+            let handle = store.dispatch_async(RetryingAction { succeed_on: 3 });
+            assert!(handle.started_at_ms().unwrap() >= before);
+            assert_eq!(handle.finished_at_ms(), None);
+            wait_for(&handle);
+
+            assert_eq!(handle.value(), Some(3));
+            assert_eq!(handle.attempt(), 3);
+            assert_eq!(handle.max_attempts(), Some(4));
+            let finished = handle.finished_at_ms().unwrap();
+            assert!(finished >= handle.started_at_ms().unwrap());
+            // Two backoff delays of at least 5ms and 10ms, minus jitter.
+            assert!(handle.elapsed_ms().unwrap() >= 10);
+
+            // Actions without a builder make a single attempt.
+            let handle = store.dispatch_async(SlowAction { delay_ms: 1 });
+            wait_for(&handle);
+            assert_eq!(handle.attempt(), 1);
+            assert_eq!(handle.max_attempts(), None);
+            assert!(handle.finished_at_ms().is_some());
+        });
+    }
+
+    #[test]
+    fn test_poller_revalidate() {
+        let _ = any_spawner::Executor::init_futures_executor();
+        let owner = Owner::new();
+        owner.with(|| {
+            let store = TestStore {
+                state: RwSignal::new(0),
+            };
+            let log = Arc::new(Mutex::new(Vec::new()));
+            let action = LoggedAction {
+                value: 1,
+                delay_ms: 1,
+                log: Arc::clone(&log),
+            };
+            let poller = Poller::new(store, action)
+                .revalidate_on_focus()
+                .revalidate_on_reconnect()
+                .revalidate_on_restore();
+
+            // Revalidating catches up on skipped ticks.
+            poller.tick(true);
+            poller.revalidate();
+            wait_for(&poller.handle());
+            assert_eq!(*log.lock().unwrap(), [1]);
+            poller.visibility_changed(false);
+            assert!(!poller.handle().pending());
+
+            poller.stop();
+            poller.revalidate();
+            assert!(!poller.handle().pending());
+            assert_eq!(log.lock().unwrap().len(), 1);
+        });
+    }
+
+    fn recorder() -> (Arc<Mutex<Vec<u32>>>, impl Fn(u32) + Send + Sync + 'static) {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&log);
+        (log, move |value| sink.lock().unwrap().push(value))
+    }
+
+    #[test]
+    fn test_debounced_action() {
+        let (log, record) = recorder();
+        let debounced = debounced_action(20, record);
+        for value in 1..=3 {
+            debounced.call(value);
+        }
+        std::thread::sleep(Duration::from_millis(80));
+        assert_eq!(*log.lock().unwrap(), [3]);
+
+        debounced.call(4);
+        debounced.cancel();
+        std::thread::sleep(Duration::from_millis(40));
+        assert_eq!(*log.lock().unwrap(), [3]);
+
+        // Disposing the owner cancels the pending call.
+        let (log, record) = recorder();
+        let owner = Owner::new();
+        let debounced = owner.with(|| debounced_action(20, record));
+        debounced.call(1);
+        owner.cleanup();
+        std::thread::sleep(Duration::from_millis(40));
+        assert!(log.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_throttled_action() {
+        let (log, record) = recorder();
+        let throttled = throttled_action(30, record);
+        for value in 1..=3 {
+            throttled.call(value);
+        }
+        assert_eq!(*log.lock().unwrap(), [1]);
+        std::thread::sleep(Duration::from_millis(120));
+        assert_eq!(*log.lock().unwrap(), [1, 3]);
+
+        throttled.call(4);
+        throttled.call(5);
+        throttled.cancel();
+        std::thread::sleep(Duration::from_millis(60));
+        assert_eq!(*log.lock().unwrap(), [1, 3, 4]);
+    }
+
+    #[test]
+    fn test_reactive_action_error_state() {
+        let action: ReactiveAction<(), i32, String> = ReactiveAction::new();
+        let states = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&states);
+        let _effect = ImmediateEffect::new(move || {
+            let state = action.state();
+            let mut seen = seen.lock().unwrap();
+            if seen.last() != Some(&state) {
+                seen.push(state);
+            }
+        });
+
+        action.set_pending();
+        action.set_error("offline".to_string());
+        assert_eq!(action.error(), Some("offline".to_string()));
+
+        // A new dispatch clears the previous error
+        action.set_pending();
+        assert!(action.error().is_none());
+        action.set_value(7);
+        action.clear();
+
+        assert_eq!(
+            states.lock().unwrap().clone(),
+            [
+                ActionState::Idle,
+                ActionState::Pending,
+                ActionState::Error,
+                ActionState::Pending,
+                ActionState::Success,
+                ActionState::Idle,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reactive_action_dispatch() {
+        let _ = any_spawner::Executor::init_futures_executor();
+        let owner = Owner::new();
+        owner.with(|| {
+            let (log, record) = recorder();
+            let record = Arc::new(record);
+            let action = ReactiveAction::from_async(move |&(value, delay_ms): &(u32, u64)| {
+                let record = Arc::clone(&record);
+                async move {
+                    delay(Duration::from_millis(delay_ms)).await;
+                    record(value);
+                    if value == 0 {
+                        Err(ActionError::validation("zero"))
+                    } else {
+                        Ok(value * 10)
+                    }
+                }
+            });
+
+            action.dispatch((1, 100));
+            action.dispatch((2, 1));
+            assert_eq!(action.input(), Some((2, 1)));
+            wait_for(&action);
+            std::thread::sleep(Duration::from_millis(150));
+
+            // The first dispatch was superseded before it finished
+            assert_eq!(*log.lock().unwrap(), [2]);
+            assert_eq!(action.value(), Some(20));
+            assert_eq!(action.version(), 2);
+
+            action.dispatch((0, 1));
+            wait_for(&action);
+            assert_eq!(action.state(), ActionState::Error);
+            assert_eq!(action.error(), Some(ActionError::validation("zero")));
+
+            // Without an async function, dispatch is a no-op
+            let manual: ReactiveAction<u32, u32> = ReactiveAction::new();
+            manual.dispatch(1);
+            assert_eq!(manual.state(), ActionState::Idle);
+        });
+    }
+
+    /// Streams `items`, failing on `0`.
+    struct NumberStream {
+        items: Vec<i32>,
+    }
+
+    impl StreamAction<TestStore> for NumberStream {
+        type Item = i32;
+        type Error = ActionError;
+
+        fn stream(&self, _store: &TestStore) -> impl Stream<Item = ActionResult<i32>> + Send {
+            let items = self.items.clone();
+            futures::stream::iter(items).then(|item| async move {
+                delay(Duration::from_millis(1)).await;
+                if item == 0 {
+                    Err(ActionError::failed("zero"))
+                } else {
+                    Ok(item)
+                }
+            })
+        }
+    }
+
+    #[test]
+    fn test_dispatch_stream() {
+        let _ = any_spawner::Executor::init_futures_executor();
+        let owner = Owner::new();
+        owner.with(|| {
+            let store = TestStore {
+                state: RwSignal::new(0),
+            };
+            let add = |store: &TestStore, item: i32| store.state.update(|total| *total += item);
+
+            let progress = store.dispatch_stream(
+                NumberStream {
+                    items: vec![1, 2, 3],
+                },
+                add,
+            );
+            assert!(progress.pending());
+            wait_for(&progress);
+            assert_eq!(progress.state(), ActionState::Success);
+            assert_eq!(progress.value(), Some(3));
+            assert_eq!(store.state.get_untracked(), 6);
+
+            let progress = store.dispatch_stream(
+                NumberStream {
+                    items: vec![10, 0, 100],
+                },
+                add,
+            );
+            wait_for(&progress);
+            assert_eq!(progress.error(), Some(ActionError::failed("zero")));
+            assert_eq!(progress.value(), Some(1));
+            assert_eq!(store.state.get_untracked(), 16);
+        });
+    }
+
+    /// Counts its executions, keyed by `key`.
+    struct CountedFetch {
+        key: &'static str,
+        executions: Arc<std::sync::atomic::AtomicU32>,
+    }
+
+    impl AsyncAction<TestStore> for CountedFetch {
+        type Output = u32;
+        type Error = ActionError;
+
+        async fn execute(&self, _store: &TestStore) -> ActionResult<u32> {
+            let execution = self.executions.fetch_add(1, Ordering::SeqCst) + 1;
+            delay(Duration::from_millis(10)).await;
+            Ok(execution)
+        }
+    }
+
+    impl KeyedAction<TestStore> for CountedFetch {
+        fn action_key(&self) -> String {
+            self.key.to_string()
+        }
+    }
+
+    #[test]
+    fn test_dispatch_deduped() {
+        let store = TestStore {
+            state: RwSignal::new(0),
+        };
+        let other = TestStore {
+            state: RwSignal::new(0),
+        };
+        let executions = Arc::new(std::sync::atomic::AtomicU32::new(0));
+        let fetch = |key| CountedFetch {
+            key,
+            executions: Arc::clone(&executions),
+        };
+
+        let (a, b, c, d) = futures::executor::block_on(async {
+            futures::join!(
+                store.dispatch_deduped(fetch("tokens")),
+                store.dispatch_deduped(fetch("tokens")),
+                store.dispatch_deduped(fetch("prices")),
+                other.dispatch_deduped(fetch("tokens")),
+            )
+        });
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert_ne!(a, d);
+        assert_eq!(executions.load(Ordering::SeqCst), 3);
+
+        // Finished dispatches aren't cached
+        futures::executor::block_on(store.dispatch_deduped(fetch("tokens"))).unwrap();
+        assert_eq!(executions.load(Ordering::SeqCst), 4);
+
+        // An abandoned dispatch isn't shared
+        drop(store.dispatch_deduped(fetch("tokens")));
+        futures::executor::block_on(store.dispatch_deduped(fetch("tokens"))).unwrap();
+        assert_eq!(executions.load(Ordering::SeqCst), 5);
+    }
+
+    #[test]
+    fn test_pending_keys() {
+        let owner = Owner::new();
+        owner.with(|| {
+            let store = TestStore {
+                state: RwSignal::new(0),
+            };
+            let other = TestStore {
+                state: RwSignal::new(0),
+            };
+            let executions = Arc::new(std::sync::atomic::AtomicU32::new(0));
+            let fetch = |key| CountedFetch {
+                key,
+                executions: Arc::clone(&executions),
+            };
+            let deleting = Memo::new({
+                let store = store.clone();
+                move |_| store.is_pending("row:1")
+            });
+            assert!(!deleting.get());
+
+            let first = store.dispatch_deduped(fetch("row:1"));
+            let shared = store.dispatch_deduped(fetch("row:1"));
+            let second = store.dispatch_deduped(fetch("row:2"));
+            let abandoned = other.dispatch_deduped(fetch("row:1"));
+            assert!(deleting.get());
+            assert_eq!(store.pending_keys(), ["row:1", "row:2"]);
+            assert_eq!(other.pending_keys(), ["row:1"]);
+
+            let _ = futures::executor::block_on(async { futures::join!(first, shared, second) });
+            assert!(!deleting.get());
+            assert!(store.pending_keys().is_empty());
+            assert!(other.is_pending("row:1"));
+
+            drop(abandoned);
+            assert!(!other.is_pending("row:1"));
+        });
+    }
+
+    /// Counts its executions, failing when `fail` is set.
+    struct CachedFetch {
+        key: &'static str,
+        fail: bool,
+        executions: Arc<std::sync::atomic::AtomicU32>,
+    }
+
+    impl AsyncAction<TestStore> for CachedFetch {
+        type Output = u32;
+        type Error = ActionError;
+
+        async fn execute(&self, _store: &TestStore) -> ActionResult<u32> {
+            let execution = self.executions.fetch_add(1, Ordering::SeqCst) + 1;
+            if self.fail {
+                return Err(ActionError::network("offline"));
+            }
+            Ok(execution)
+        }
+    }
+
+    impl CachedAction<TestStore> for CachedFetch {
+        fn cache_key(&self) -> String {
+            self.key.to_string()
+        }
+
+        fn ttl_ms(&self) -> u64 {
+            50
+        }
+    }
+
+    #[test]
+    fn test_dispatch_cached() {
+        use futures::executor::block_on;
+
+        let store = TestStore {
+            state: RwSignal::new(0),
+        };
+        let other = TestStore {
+            state: RwSignal::new(0),
+        };
+        let executions = Arc::new(std::sync::atomic::AtomicU32::new(0));
+        let fetch = |key, fail| CachedFetch {
+            key,
+            fail,
+            executions: Arc::clone(&executions),
+        };
+
+        // Errors aren't cached.
+        assert!(block_on(store.dispatch_cached(fetch("tokens", true))).is_err());
+        assert_eq!(
+            block_on(store.dispatch_cached(fetch("tokens", false))),
+            Ok(2)
+        );
+        assert_eq!(
+            block_on(store.dispatch_cached(fetch("tokens", false))),
+            Ok(2)
+        );
+        assert_eq!(
+            block_on(store.dispatch_cached(fetch("prices", false))),
+            Ok(3)
+        );
+        assert_eq!(
+            block_on(other.dispatch_cached(fetch("tokens", false))),
+            Ok(4)
+        );
+
+        store.invalidate("tokens");
+        assert_eq!(
+            block_on(store.dispatch_cached(fetch("tokens", false))),
+            Ok(5)
+        );
+        assert_eq!(
+            block_on(store.dispatch_cached(fetch("prices", false))),
+            Ok(3)
+        );
+
+        std::thread::sleep(Duration::from_millis(120));
+        assert_eq!(
+            block_on(store.dispatch_cached(fetch("tokens", false))),
+            Ok(6)
+        );
+    }
+
+    #[test]
+    fn test_dispatch_queued() {
+        let store = TestStore {
+            state: RwSignal::new(0),
+        };
+        let other = TestStore {
+            state: RwSignal::new(0),
+        };
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let logged = |value, delay_ms| LoggedAction {
+            value,
+            delay_ms,
+            log: Arc::clone(&log),
+        };
+
+        let abandoned = store.dispatch_queued(logged(9, 1));
+        let results = futures::executor::block_on(async {
+            futures::join!(
+                store.dispatch_queued(logged(1, 60)),
+                store.dispatch_queued(logged(2, 30)),
+                store.dispatch_queued(logged(3, 1)),
+                other.dispatch_queued(logged(4, 1)),
+                // Giving up a place doesn't hold up the queue.
+                async { drop(abandoned) },
+            )
+        });
+        assert_eq!(results.0, Ok(1));
+        assert_eq!(results.2, Ok(3));
+        // Other stores don't wait.
+        assert_eq!(*log.lock().unwrap(), [4, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_dispatch_all() {
+        let store = TestStore {
+            state: RwSignal::new(0),
+        };
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let logged = |value, delay_ms| LoggedAction {
+            value,
+            delay_ms,
+            log: Arc::clone(&log),
+        };
+
+        // Actions run concurrently, and outputs keep their order.
+        let outputs = futures::executor::block_on(store.dispatch_all((
+            logged(1, 40),
+            logged(2, 1),
+            SlowAction { delay_ms: 1 },
+        )));
+        assert_eq!(outputs, Ok((1, 2, ())));
+        assert_eq!(*log.lock().unwrap(), [2, 1]);
+
+        // The first error wins without waiting for the slower actions.
+        log.lock().unwrap().clear();
+        let failed = futures::executor::block_on(store.dispatch_all((
+            logged(3, 200),
+            FlakyAction {
+                attempts: Default::default(),
+                succeed_on: 2,
+            },
+        )));
+        assert_eq!(failed, Err(ActionError::network("attempt 1")));
+        assert!(log.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_on_action_error() {
+        let store = TestStore {
+            state: RwSignal::new(0),
+        };
+        let other = TestStore {
+            state: RwSignal::new(0),
+        };
+        let owner = Owner::new();
+        owner.with(|| {
+            store.on_action_error(|store, cx, error| {
+                assert_eq!(cx.action(), "FlakyAction");
+                assert!(error.downcast_ref::<ActionError>().is_some());
+                store.state.update(|failures| *failures += 1);
+            });
+        });
+        let flaky = || FlakyAction {
+            attempts: Default::default(),
+            succeed_on: 2,
+        };
+
+        let _ = futures::executor::block_on(store.dispatch_queued(flaky()));
+        let _ = futures::executor::block_on(store.dispatch_all((flaky(), flaky())));
+        assert_eq!(store.state.get_untracked(), 2);
+
+        // Other instances and successes aren't reported.
+        let _ = futures::executor::block_on(other.dispatch_queued(flaky()));
+        let _ = futures::executor::block_on(store.dispatch_queued(SlowAction { delay_ms: 1 }));
+        assert_eq!(other.state.get_untracked(), 0);
+        assert_eq!(store.state.get_untracked(), 2);
+
+        // The handler is removed with its owner.
+        owner.cleanup();
+        let _ = futures::executor::block_on(store.dispatch_queued(flaky()));
+        assert_eq!(store.state.get_untracked(), 2);
+    }
+
+    #[test]
+    fn test_reactive_action_cancel() {
+        let action: ReactiveAction<String, i32> = ReactiveAction::new();
+        assert_eq!(action.state(), ActionState::Idle);
+
+        action.cancel();
+        assert!(action.error().is_none());
+
+        action.set_pending();
+        let first = action.token();
+        assert_eq!(action.state(), ActionState::Pending);
+        action.cancel();
+        assert!(first.is_cancelled());
+        assert_eq!(action.state(), ActionState::Error);
+        assert_eq!(action.error(), Some(ActionError::Cancelled));
+
+        action.set_pending();
+        assert!(!action.token().is_cancelled());
+        action.set_value(1);
+        assert_eq!(action.state(), ActionState::Success);
+        assert!(action.error().is_none());
+    }
 }
@@ -0,0 +1,261 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2026 web-mech
+
+//! Per-request stores for Axum servers rendering with `leptos_axum`.
+//!
+//! `leptos_axum` renders each request under its own reactive owner and
+//! provides the request's [`Parts`] in context. [`AxumStores`] lists the
+//! stores an app needs and how to build them from those parts; its
+//! [`context`](AxumStores::context) closure is passed to
+//! `leptos_routes_with_context`, so every request gets fresh stores
+//! provided to the app, and hydratable stores registered for the
+//! `<StoreHydrationScripts/>` in the shell.
+//!
+//! The module only depends on the `http` crate, whose request types Axum
+//! uses, so enabling the `axum` feature doesn't pin an Axum version.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use leptos_store::axum::AxumStores;
+//!
+//! let stores = AxumStores::new()
+//!     .hydrated_store(|parts| {
+//!         let theme = parts.headers.get("x-theme").and_then(|v| v.to_str().ok());
+//!         SettingsStore::with_theme(theme.unwrap_or("light"))
+//!     })
+//!     .store(|_| CartStore::new());
+//!
+//! let app = Router::new()
+//!     .leptos_routes_with_context(&leptos_options, routes, stores.context(), {
+//!         let leptos_options = leptos_options.clone();
+//!         move || shell(leptos_options.clone())
+//!     })
+//!     .with_state(leptos_options);
+//!
+//! fn shell(options: LeptosOptions) -> impl IntoView {
+//!     view! {
+//!         <!DOCTYPE html>
+//!         <html>
+//!             <head>
+//!                 <HydrationScripts options/>
+//!                 <StoreHydrationScripts/>
+//!             </head>
+//!             <body><App/></body>
+//!         </html>
+//!     }
+//! }
+//! ```
+
+use crate::context::provide_store;
+use crate::request::{RequestData, StoreFromRequest, find_cookie};
+use crate::store::Store;
+use http::request::Parts;
+use leptos::prelude::*;
+use std::sync::Arc;
+
+/// Builds one store from a request and provides it.
+type StoreInit = Arc<dyn Fn(&Parts) + Send + Sync>;
+
+/// The stores to create for every request, and how.
+#[derive(Clone, Default)]
+pub struct AxumStores {
+    stores: Vec<StoreInit>,
+}
+
+impl AxumStores {
+    /// Create an empty list of request stores.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build a store from each request with `factory` and provide it.
+    pub fn store<S, F>(mut self, factory: F) -> Self
+    where
+        S: Store + Clone + Send + Sync + 'static,
+        F: Fn(&Parts) -> S + Send + Sync + 'static,
+    {
+        self.stores
+            .push(Arc::new(move |parts| provide_store(factory(parts))));
+        self
+    }
+
+    /// Build a hydratable store from each request with `factory`, provide
+    /// it, and register it for `<StoreHydrationScripts/>`.
+    ///
+    /// See [`register_hydrated_store`](crate::context::register_hydrated_store).
+    #[cfg(feature = "hydrate")]
+    pub fn hydrated_store<S, F>(mut self, factory: F) -> Self
+    where
+        S: crate::hydration::HydratableStore + Clone + Send + Sync + 'static,
+        F: Fn(&Parts) -> S + Send + Sync + 'static,
+    {
+        self.stores.push(Arc::new(move |parts| {
+            crate::context::register_hydrated_store(factory(parts))
+        }));
+        self
+    }
+
+    /// Build a store from each request with its [`StoreFromRequest`] impl
+    /// and provide it.
+    pub fn from_request<S>(self) -> Self
+    where
+        S: StoreFromRequest + Clone + Send + Sync + 'static,
+    {
+        self.store(|parts| S::from_request(parts))
+    }
+
+    /// Build a hydratable store from each request with its
+    /// [`StoreFromRequest`] impl, provide it, and register it for
+    /// `<StoreHydrationScripts/>`.
+    #[cfg(feature = "hydrate")]
+    pub fn hydrated_from_request<S>(self) -> Self
+    where
+        S: StoreFromRequest + crate::hydration::HydratableStore + Clone + Send + Sync + 'static,
+    {
+        self.hydrated_store(|parts| S::from_request(parts))
+    }
+
+    /// Create and provide every store for the current request.
+    ///
+    /// Reads the request from context; outside a `leptos_axum` request,
+    /// the factories get empty parts.
+    pub fn provide(&self) {
+        let parts = request_parts().unwrap_or_else(|| {
+            leptos::logging::warn!("No request in context; building stores from an empty request");
+            http::Request::new(()).into_parts().0
+        });
+        for init in &self.stores {
+            init(&parts);
+        }
+    }
+
+    /// Returns a closure providing the stores, for the additional context
+    /// of `leptos_routes_with_context` or `render_app_to_stream_with_context`.
+    pub fn context(self) -> impl Fn() + Clone + Send + Sync + 'static {
+        move || self.provide()
+    }
+}
+
+/// Returns the parts of the request being rendered, as provided by
+/// `leptos_axum`.
+pub fn request_parts() -> Option<Parts> {
+    use_context::<Parts>()
+}
+
+impl RequestData for Parts {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name)?.to_str().ok()
+    }
+
+    fn cookie(&self, name: &str) -> Option<String> {
+        let headers = self.headers.get_all(http::header::COOKIE);
+        find_cookie(headers.iter().filter_map(|v| v.to_str().ok()), name)
+    }
+
+    fn extension<T: Clone + Send + Sync + 'static>(&self) -> Option<T> {
+        self.extensions.get::<T>().cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::use_store;
+
+    #[derive(Clone, Debug, Default, PartialEq)]
+    struct TestState {
+        user: String,
+    }
+
+    #[derive(Clone)]
+    struct TestStore {
+        state: RwSignal<TestState>,
+    }
+
+    impl TestStore {
+        fn new(user: &str) -> Self {
+            Self {
+                state: RwSignal::new(TestState {
+                    user: user.to_string(),
+                }),
+            }
+        }
+    }
+
+    impl Store for TestStore {
+        type State = TestState;
+
+        fn state(&self) -> ReadSignal<Self::State> {
+            self.state.read_only()
+        }
+    }
+
+    fn user_store(parts: &Parts) -> TestStore {
+        let user = parts.headers.get("x-user").and_then(|v| v.to_str().ok());
+        TestStore::new(user.unwrap_or("anonymous"))
+    }
+
+    /// A session inserted by middleware.
+    #[derive(Clone)]
+    struct Session(String);
+
+    impl StoreFromRequest for TestStore {
+        fn from_request(request: &impl RequestData) -> Self {
+            let session = request.extension::<Session>();
+            let user = session.map(|s| s.0).or_else(|| request.cookie("user"));
+            Self::new(user.as_deref().unwrap_or("anonymous"))
+        }
+    }
+
+    #[test]
+    fn test_stores_built_from_request() {
+        let context = AxumStores::new().store(user_store).context();
+
+        for user in ["alice", "bob"] {
+            let owner = Owner::new();
+            owner.with(|| {
+                let (parts, ()) = http::Request::builder()
+                    .header("x-user", user)
+                    .body(())
+                    .unwrap()
+                    .into_parts();
+                provide_context(parts);
+
+                context();
+                assert_eq!(use_store::<TestStore>().state.get_untracked().user, user);
+            });
+        }
+    }
+
+    #[test]
+    fn test_stores_from_request() {
+        let context = AxumStores::new().from_request::<TestStore>().context();
+        let owner = Owner::new();
+        owner.with(|| {
+            let (mut parts, ()) = http::Request::builder()
+                .header("cookie", "theme=dark; user=alice")
+                .body(())
+                .unwrap()
+                .into_parts();
+            assert_eq!(parts.cookie("theme").as_deref(), Some("dark"));
+            parts.extensions.insert(Session("bob".to_string()));
+            provide_context(parts);
+
+            context();
+            assert_eq!(use_store::<TestStore>().state.get_untracked().user, "bob");
+        });
+    }
+
+    #[test]
+    fn test_stores_without_request() {
+        let owner = Owner::new();
+        owner.with(|| {
+            AxumStores::new().store(user_store).provide();
+            assert_eq!(
+                use_store::<TestStore>().state.get_untracked().user,
+                "anonymous"
+            );
+        });
+    }
+}
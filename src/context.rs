@@ -44,7 +44,13 @@
 
 use crate::store::{Store, StoreError};
 use leptos::prelude::*;
+use std::collections::HashMap;
 use std::marker::PhantomData;
+#[cfg(feature = "hydrate")]
+use std::sync::OnceLock;
+#[cfg(feature = "hydrate")]
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
 
 #[cfg(feature = "hydrate")]
 use crate::hydration::{HydratableStore, StoreHydrationError, has_hydration_data, hydrate_store};
@@ -129,9 +135,13 @@ pub fn provide_store<S: Store + Clone + Send + Sync + 'static>(store: S) {
 /// }
 /// ```
 pub fn use_store<S: Store + Clone + Send + Sync + 'static>() -> S {
-    use_context::<StoreProvider<S>>()
-        .expect("Store not found in context. Did you forget to call provide_store?")
-        .get()
+    let store = use_context::<StoreProvider<S>>()
+        .map(|p| p.get())
+        .or_else(use_lazy_store::<S>)
+        .expect("Store not found in context. Did you forget to call provide_store?");
+    #[cfg(feature = "hydrate")]
+    crate::hydration::mark_store_used::<S>();
+    store
 }
 
 /// Try to access a store from the Leptos context.
@@ -170,8 +180,11 @@ pub fn use_store<S: Store + Clone + Send + Sync + 'static>() -> S {
 /// }
 /// ```
 pub fn try_use_store<S: Store + Clone + Send + Sync + 'static>() -> Result<S, StoreError> {
+    #[cfg(feature = "hydrate")]
+    crate::hydration::mark_store_used::<S>();
     use_context::<StoreProvider<S>>()
         .map(|p| p.get())
+        .or_else(use_lazy_store::<S>)
         .ok_or_else(|| {
             StoreError::ContextNotAvailable(format!(
                 "Store {} not found in context",
@@ -180,6 +193,117 @@ pub fn try_use_store<S: Store + Clone + Send + Sync + 'static>() -> Result<S, St
         })
 }
 
+/// Access a store from the Leptos context, or create and provide one if
+/// there is none.
+///
+/// `factory` only runs when no store of type `S` is in context. The new
+/// store is provided at the current owner, so descendants share it, while
+/// a sibling calling this creates its own. Use it for components that
+/// should also work outside the tree providing their store, such as in
+/// tests and component previews.
+///
+/// ```rust,no_run
+/// use leptos::prelude::*;
+/// use leptos_store::prelude::*;
+///
+/// #[derive(Clone, Default)]
+/// struct CounterState { count: i32 }
+///
+/// #[derive(Clone)]
+/// struct CounterStore { state: RwSignal<CounterState> }
+///
+/// impl CounterStore {
+///     fn new() -> Self { Self { state: RwSignal::new(CounterState::default()) } }
+/// }
+///
+/// impl Store for CounterStore {
+///     type State = CounterState;
+///     fn state(&self) -> ReadSignal<Self::State> { self.state.read_only() }
+/// }
+///
+/// #[component]
+/// fn Counter() -> impl IntoView {
+///     let store = use_store_or_provide(CounterStore::new);
+///     view! { <span>{move || store.state().get().count}</span> }
+/// }
+/// ```
+pub fn use_store_or_provide<S: Store + Clone + Send + Sync + 'static>(
+    factory: impl FnOnce() -> S,
+) -> S {
+    try_use_store::<S>().unwrap_or_else(|_| {
+        let store = factory();
+        provide_store(store.clone());
+        store
+    })
+}
+
+/// Create a store owned by the current reactive owner and provide it.
+///
+/// `factory` runs under a child of the current owner, so the signals,
+/// effects and memos the store creates, and any `on_cleanup` callbacks it
+/// registers (e.g. to drop an
+/// [`ActionSubscription`](crate::middleware::ActionSubscription)), belong
+/// to the store. Its [background tasks](crate::tasks) are bound to the
+/// same owner, wherever they're spawned from. When the current owner is
+/// cleaned up, as when a routed page unmounts, all of it is disposed and
+/// aborted instead of outliving the navigation.
+///
+/// Call it inside a component; outside any owner, there's nothing to tie
+/// the store to and it is disposed right away.
+///
+/// ```rust,ignore
+/// #[component]
+/// fn TokenPage() -> impl IntoView {
+///     let store = provide_owned_store(|| {
+///         let store = TokenStore::new();
+///         let subscription = on_store_action_event::<TokenStore>(log_event);
+///         on_cleanup(move || drop(subscription));
+///         store
+///     });
+///     view! { <TokenList/> }
+/// }
+/// ```
+pub fn provide_owned_store<S: Store + Clone + Send + Sync + 'static>(
+    factory: impl FnOnce() -> S,
+) -> S {
+    let store = owned_store(factory);
+    provide_store(store.clone());
+    store
+}
+
+/// Create a store local to the current component.
+///
+/// Like [`provide_owned_store`], the store and everything it owns are
+/// disposed when the component is, but the store is never put in context:
+/// [`use_store`] in descendants doesn't see it, and it doesn't shadow a
+/// store of the same type provided above. Each instance of a widget, such
+/// as a self-contained editor, gets its own store without colliding with
+/// the others; pass it down as a prop if children need it.
+///
+/// ```rust,ignore
+/// #[component]
+/// fn Editor(initial: String) -> impl IntoView {
+///     let editor = use_local_store(|| EditorStore::with_text(initial));
+///     view! { <textarea on:input:target=move |ev| editor.set_text(ev.target().value())/> }
+/// }
+/// ```
+pub fn use_local_store<S: Store + Clone + Send + Sync + 'static>(factory: impl FnOnce() -> S) -> S {
+    owned_store(factory)
+}
+
+/// Create a store under a child of the current owner, which lives until
+/// the current owner is cleaned up.
+fn owned_store<S: Store>(factory: impl FnOnce() -> S) -> S {
+    let owner = Owner::current().unwrap_or_default().child();
+    let store = owner.with(|| {
+        let store = factory();
+        crate::tasks::StoreTasks::of(&store);
+        store
+    });
+    on_cleanup(move || owner.cleanup());
+    store
+}
+
 /// Wrapper for stores in Leptos context.
 ///
 /// This struct wraps a store for use in Leptos' context system.
@@ -271,6 +395,297 @@ pub fn provide_scoped_store<S: Store + Clone + Send + Sync + 'static, const ID:
     provide_context(ScopedStoreProvider::<S, ID>::new(store));
 }
 
+/// The scoped stores of type `S` provided with runtime keys, by key.
+#[derive(Clone)]
+struct KeyedScopedStores<S> {
+    stores: Arc<HashMap<String, S>>,
+}
+
+/// Provide a scoped store under a key chosen at runtime.
+///
+/// Like [`provide_scoped_store`], but scopes created dynamically, such as
+/// tabs or list items, can use their own ID as the key. The store is
+/// visible to descendants of the current owner, along with the stores
+/// provided under other keys above it; providing a key again replaces the
+/// store for this owner's descendants only.
+///
+/// ```rust,ignore
+/// for tab in tabs {
+///     provide_scoped_store_keyed(EditorStore::new(), format!("tab-{}", tab.id));
+/// }
+///
+/// let editor = use_scoped_store_keyed::<EditorStore>("tab-3");
+/// ```
+pub fn provide_scoped_store_keyed<S: Store + Clone + Send + Sync + 'static>(
+    store: S,
+    key: impl Into<String>,
+) {
+    let mut stores = use_context::<KeyedScopedStores<S>>()
+        .map(|inherited| (*inherited.stores).clone())
+        .unwrap_or_default();
+    stores.insert(key.into(), store);
+    provide_context(KeyedScopedStores {
+        stores: Arc::new(stores),
+    });
+}
+
+/// Access a scoped store provided under `key` with
+/// [`provide_scoped_store_keyed`].
+///
+/// # Panics
+///
+/// Panics if no store of type `S` was provided under `key`.
+pub fn use_scoped_store_keyed<S: Store + Clone + Send + Sync + 'static>(key: &str) -> S {
+    use_context::<KeyedScopedStores<S>>()
+        .and_then(|scoped| scoped.stores.get(key).cloned())
+        .unwrap_or_else(|| panic!("Scoped store `{key}` not found in context"))
+}
+
+/// A store with one instance per key, like a Recoil atom family.
+///
+/// Implement it for stores such as the todo list of a given list ID, then
+/// get the instance for a key with [`use_store_keyed`].
+pub trait StoreFamily: Store {
+    /// The key identifying an instance.
+    type Key: Eq + std::hash::Hash + Clone + Send + Sync + 'static;
+
+    /// Create the instance for `key`.
+    fn create(key: &Self::Key) -> Self;
+}
+
+/// The instances of a store family by key, with the owner of each.
+type FamilyInstances<S> = HashMap<<S as StoreFamily>::Key, (Owner, S)>;
+
+/// The cached instances of a store family, each created under its own
+/// child of `owner`.
+struct StoreFamilyCache<S: StoreFamily> {
+    owner: Owner,
+    stores: Arc<Mutex<FamilyInstances<S>>>,
+}
+
+impl<S: StoreFamily> Clone for StoreFamilyCache<S> {
+    fn clone(&self) -> Self {
+        Self {
+            owner: self.owner.clone(),
+            stores: Arc::clone(&self.stores),
+        }
+    }
+}
+
+/// Provide a cache for the instances of the store family `S`.
+///
+/// Instances created by [`use_store_keyed`] below the current owner are
+/// cached here, and disposed when the current owner is cleaned up.
+pub fn provide_store_family<S: StoreFamily>() -> StoreFamilyHandle<S> {
+    let owner = Owner::current().unwrap_or_default();
+    let cache = StoreFamilyCache::<S> {
+        owner: owner.child(),
+        stores: Arc::new(Mutex::new(HashMap::new())),
+    };
+    provide_context(cache.clone());
+    StoreFamilyHandle { cache }
+}
+
+/// Get the instance of the store family `S` for `key`, creating and
+/// caching it on first use.
+///
+/// Later calls with the same key, from any component below the family's
+/// cache, return the same instance. Instances are created under the
+/// cache's owner rather than the caller's, so they outlive the component
+/// that happened to create them, and are disposed along with the cache;
+/// see [`provide_store_family`].
+///
+/// # Panics
+///
+/// Panics if no [`provide_store_family::<S>`](provide_store_family) was
+/// called above the current owner.
+///
+/// ```rust,ignore
+/// impl StoreFamily for TodoListStore {
+///     type Key = u32;
+///
+///     fn create(list_id: &u32) -> Self {
+///         TodoListStore::for_list(*list_id)
+///     }
+/// }
+///
+/// #[component]
+/// fn TodoList(list_id: u32) -> impl IntoView {
+///     let todos = use_store_keyed::<TodoListStore>(list_id);
+///     // ...
+/// }
+/// ```
+pub fn use_store_keyed<S: StoreFamily>(key: S::Key) -> S {
+    let cache = use_context::<StoreFamilyCache<S>>().unwrap_or_else(|| {
+        panic!(
+            "Store family `{}` not found in context; call provide_store_family first",
+            std::any::type_name::<S>()
+        )
+    });
+    if let Some((_, store)) = cache
+        .stores
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(&key)
+    {
+        return store.clone();
+    }
+    // Create outside the lock, so the factory can use other instances
+    let owner = cache.owner.child();
+    let store = owner.with(|| S::create(&key));
+    cache
+        .stores
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .entry(key)
+        .or_insert((owner, store))
+        .1
+        .clone()
+}
+
+/// Control over the cached instances of a store family.
+///
+/// Returned by [`provide_store_family`].
+pub struct StoreFamilyHandle<S: StoreFamily> {
+    cache: StoreFamilyCache<S>,
+}
+
+impl<S: StoreFamily> Clone for StoreFamilyHandle<S> {
+    fn clone(&self) -> Self {
+        Self {
+            cache: self.cache.clone(),
+        }
+    }
+}
+
+impl<S: StoreFamily> StoreFamilyHandle<S> {
+    /// Returns the keys of the cached instances.
+    pub fn keys(&self) -> Vec<S::Key> {
+        self.cache
+            .stores
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .keys()
+            .cloned()
+            .collect()
+    }
+
+    /// Dispose the cached instance for `key`, so the next
+    /// [`use_store_keyed`] creates a new one.
+    ///
+    /// Returns `false` if no instance was cached. Components still holding
+    /// the old instance must not use it afterwards.
+    pub fn evict(&self, key: &S::Key) -> bool {
+        let evicted = self
+            .cache
+            .stores
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(key);
+        match evicted {
+            Some((owner, _)) => {
+                owner.cleanup();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+// ============================================================================
+// Interface (trait object) context functions
+// ============================================================================
+
+/// Wrapper for store interfaces in Leptos context.
+///
+/// Holds a store behind a trait object so components can depend on an
+/// interface instead of a concrete store type.
+pub struct StoreInterfaceProvider<T: ?Sized> {
+    store: Arc<T>,
+}
+
+impl<T: ?Sized> Clone for StoreInterfaceProvider<T> {
+    fn clone(&self) -> Self {
+        Self {
+            store: Arc::clone(&self.store),
+        }
+    }
+}
+
+impl<T: ?Sized + Send + Sync + 'static> StoreInterfaceProvider<T> {
+    /// Create a new interface provider.
+    pub fn new(store: Arc<T>) -> Self {
+        Self { store }
+    }
+
+    /// Get a handle to the provided implementation.
+    pub fn get(&self) -> Arc<T> {
+        Arc::clone(&self.store)
+    }
+}
+
+/// Provide a store to the component tree as an interface.
+///
+/// The store is looked up by the interface type rather than its concrete
+/// type, so tests can provide a lightweight fake instead. The interface
+/// trait must have `Send + Sync` as supertraits.
+///
+/// # Example
+///
+/// ```rust
+/// use leptos::prelude::*;
+/// use leptos_store::prelude::*;
+/// use std::sync::Arc;
+///
+/// trait AuthApi: Send + Sync {
+///     fn is_authenticated(&self) -> bool;
+/// }
+///
+/// struct FakeAuth;
+///
+/// impl AuthApi for FakeAuth {
+///     fn is_authenticated(&self) -> bool {
+///         true
+///     }
+/// }
+///
+/// # Owner::new().with(|| {
+/// provide_store_as::<dyn AuthApi>(Arc::new(FakeAuth));
+///
+/// let auth = use_store_trait::<dyn AuthApi>();
+/// assert!(auth.is_authenticated());
+/// # });
+/// ```
+pub fn provide_store_as<T: ?Sized + Send + Sync + 'static>(store: Arc<T>) {
+    provide_context(StoreInterfaceProvider::new(store));
+}
+
+/// Access a store interface from the Leptos context.
+///
+/// # Panics
+///
+/// Panics if no implementation was provided via [`provide_store_as`].
+/// Use [`try_use_store_trait`] for a non-panicking alternative.
+pub fn use_store_trait<T: ?Sized + Send + Sync + 'static>() -> Arc<T> {
+    use_context::<StoreInterfaceProvider<T>>()
+        .expect("Store interface not found in context. Did you forget to call provide_store_as?")
+        .get()
+}
+
+/// Try to access a store interface from the Leptos context.
+///
+/// This is a non-panicking alternative to [`use_store_trait`].
+pub fn try_use_store_trait<T: ?Sized + Send + Sync + 'static>() -> Result<Arc<T>, StoreError> {
+    use_context::<StoreInterfaceProvider<T>>()
+        .map(|p| p.get())
+        .ok_or_else(|| {
+            StoreError::ContextNotAvailable(format!(
+                "Store interface {} not found in context",
+                std::any::type_name::<T>()
+            ))
+        })
+}
+
 // ============================================================================
 // Hydration-aware context functions
 // ============================================================================
@@ -292,6 +707,12 @@ pub fn provide_scoped_store<S: Store + Clone + Send + Sync + 'static, const ID:
 ///
 /// An `impl IntoView` that renders the hydration script tag.
 ///
+/// # Panics
+///
+/// In debug builds, panics if a different store type already used the
+/// same [`store_key`](crate::hydration::HydratableStore::store_key), since
+/// its script would shadow this one.
+///
 /// # Example
 ///
 /// ```rust,ignore
@@ -315,33 +736,665 @@ pub fn provide_scoped_store<S: Store + Clone + Send + Sync + 'static, const ID:
 pub fn provide_hydrated_store<S: HydratableStore + Clone + Send + Sync + 'static>(
     store: S,
 ) -> impl IntoView {
-    use crate::hydration::hydration_script_id;
+    HydratedStoreProvider::new(store).provide()
+}
 
-    // Serialize the state before providing
-    let serialized = store.serialize_state();
+/// Transforms a serialized payload.
+#[cfg(feature = "hydrate")]
+type PayloadHook = Box<dyn FnOnce(&str) -> String>;
 
-    // Provide the store to context
-    provide_store(store);
+/// Decides that a state isn't worth embedding.
+#[cfg(feature = "hydrate")]
+type SkipPredicate<T> = Box<dyn Fn(&T) -> bool>;
 
-    // Return the hydration script
-    match serialized {
-        Ok(data) => {
-            // Escape any script closing tags in the data
-            let escaped_data = data.replace("</script>", r"<\/script>");
-            leptos::html::script()
-                .id(hydration_script_id(S::store_key()))
+/// A builder for [`provide_hydrated_store`] with options for how the state
+/// is embedded.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let script = HydratedStoreProvider::new(CartStore::new())
+///     .key("sidebar_cart")
+///     .pretty(true)
+///     .skip_if_default()
+///     .provide();
+/// ```
+#[cfg(feature = "hydrate")]
+pub struct HydratedStoreProvider<S: HydratableStore> {
+    store: S,
+    key: Option<String>,
+    pretty: bool,
+    skip: Option<SkipPredicate<S::State>>,
+    escape: Option<PayloadHook>,
+    post_process: Option<PayloadHook>,
+}
+
+#[cfg(feature = "hydrate")]
+impl<S: HydratableStore + Clone + Send + Sync + 'static> HydratedStoreProvider<S> {
+    /// Create a builder providing `store`.
+    pub fn new(store: S) -> Self {
+        Self {
+            store,
+            key: None,
+            pretty: false,
+            skip: None,
+            escape: None,
+            post_process: None,
+        }
+    }
+
+    /// Embed the state under `key` instead of
+    /// [`S::store_key()`](HydratableStore::store_key).
+    ///
+    /// This lets several instances of one store type, provided in different
+    /// subtrees, ship their own state. Read it back on the client with
+    /// [`HydrationBuilder::key`](crate::hydration::HydrationBuilder::key).
+    pub fn key(mut self, key: impl Into<String>) -> Self {
+        self.key = Some(key.into());
+        self
+    }
+
+    /// Pretty-print the JSON payload, for reading it in the page source.
+    ///
+    /// Only applies in debug builds; release builds always embed compact
+    /// JSON.
+    pub fn pretty(mut self, pretty: bool) -> Self {
+        self.pretty = pretty;
+        self
+    }
+
+    /// Don't render a script when the state equals its default; the client
+    /// then falls back to creating the store itself.
+    pub fn skip_if_default(self) -> Self
+    where
+        S::State: Default + PartialEq,
+    {
+        self.skip_if(|state| *state == S::State::default())
+    }
+
+    /// Don't render a script when `predicate` says the state is empty.
+    ///
+    /// Applies in addition to the store's own
+    /// [`skip_hydration`](HydratableStore::skip_hydration).
+    pub fn skip_if(mut self, predicate: impl Fn(&S::State) -> bool + 'static) -> Self {
+        self.skip = Some(Box::new(predicate));
+        self
+    }
+
+    /// Escape the payload with `escape` instead of the built-in HTML
+    /// escaping.
+    ///
+    /// The result is written into the script as-is, so it must not contain
+    /// `</script>` or `<!--`.
+    pub fn escape(mut self, escape: impl FnOnce(&str) -> String + 'static) -> Self {
+        self.escape = Some(Box::new(escape));
+        self
+    }
+
+    /// Transform the serialized state before it's compressed and escaped.
+    ///
+    /// The client gets the transformed string, so it must still
+    /// deserialize with [`HydratableStore::from_hydrated_state`].
+    pub fn post_process(mut self, hook: impl FnOnce(&str) -> String + 'static) -> Self {
+        self.post_process = Some(Box::new(hook));
+        self
+    }
+
+    /// Provide the store to the component tree and return its hydration
+    /// script.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, panics if a different store type already used the
+    /// same key: the one set with [`key`](Self::key), or else the
+    /// [`store_key`](crate::hydration::HydratableStore::store_key).
+    pub fn provide(mut self) -> impl IntoView {
+        use crate::hydration::{check_store_key, hydration_script_id};
+
+        let key = self.key.as_deref().unwrap_or(S::store_key());
+        check_store_key::<S>(key);
+        let id = hydration_script_id(key);
+
+        // Serialize the state before providing
+        let payload = self.payload();
+
+        // Provide the store to context
+        provide_store(self.store);
+
+        // Return the hydration script
+        match payload {
+            Some(Ok(payload)) => leptos::html::script()
+                .id(id)
                 .attr("type", "application/json")
-                .inner_html(escaped_data)
-                .into_any()
+                .inner_html(payload)
+                .into_any(),
+            Some(Err(e)) => {
+                // Log error but don't fail rendering
+                leptos::logging::error!("Failed to serialize store for hydration: {}", e);
+                ().into_any()
+            }
+            None => ().into_any(),
+        }
+    }
+
+    /// The escaped script content, or `None` if the state is skipped.
+    pub(crate) fn payload(&mut self) -> Option<Result<String, StoreHydrationError>> {
+        use crate::hydration::{encode_payload, escape_script_data, report_serialize};
+
+        if self.store.skip_hydration() {
+            return None;
+        }
+        if let Some(skip) = &self.skip
+            && self.store.state().with_untracked(|state| skip(state))
+        {
+            return None;
+        }
+
+        let key = self.key.as_deref().unwrap_or(S::store_key());
+        let mut data = match report_serialize(key, || self.store.serialize_state()) {
+            Ok(data) => data,
+            Err(e) => return Some(Err(e)),
+        };
+        if self.pretty
+            && cfg!(debug_assertions)
+            && let Ok(value) = serde_json::from_str::<serde_json::Value>(&data)
+        {
+            data = serde_json::to_string_pretty(&value).unwrap_or(data);
+        }
+        if let Some(post_process) = self.post_process.take() {
+            data = post_process(&data);
         }
+        let encoded = encode_payload(&data);
+        Some(Ok(match self.escape.take() {
+            Some(escape) => escape(&encoded),
+            None => escape_script_data(&encoded),
+        }))
+    }
+}
+
+/// Provide a store to a Leptos island, with its state keyed by island
+/// instance.
+///
+/// Islands hydrate one by one and don't share context, so each island
+/// creates its own store. On the server, `factory` builds it and the
+/// returned view renders its state in a script inside the island, under
+/// [`island_store_key`]`(S::store_key(), instance)`; only islands that
+/// are actually rendered ship state. On the client, the island restores
+/// the store from that script, falling back to `factory`. Either way the
+/// store is provided, so components inside the island use
+/// [`use_store`].
+///
+/// `instance` must be unique among the islands using `S` on the page,
+/// e.g. an ID from the island's props.
+///
+/// ```rust,ignore
+/// #[island]
+/// fn CartButton(product_id: u32) -> impl IntoView {
+///     let script = provide_island_store(&product_id.to_string(), CartStore::new);
+///
+///     view! {
+///         {script}
+///         <AddToCart/>
+///     }
+/// }
+/// ```
+///
+/// [`island_store_key`]: crate::hydration::island_store_key
+#[cfg(feature = "hydrate")]
+pub fn provide_island_store<S, F>(instance: &str, factory: F) -> impl IntoView
+where
+    S: HydratableStore + Clone + Send + Sync + 'static,
+    F: FnOnce() -> S,
+{
+    use crate::hydration::{
+        check_store_key, hydration_script_id, island_store_key, read_hydration_data,
+        report_serialize, script_payload,
+    };
+
+    let key = island_store_key(S::store_key(), instance);
+    check_store_key::<S>(S::store_key());
+    check_store_key::<S>(&key);
+    let store = read_hydration_data(&key)
+        .and_then(|data| crate::hydration::from_payload::<S>(&key, &data))
+        .unwrap_or_else(|_| factory());
+    provide_store(store.clone());
+
+    let id = hydration_script_id(&key);
+    move || match untrack(|| {
+        (!store.skip_hydration()).then(|| report_serialize(&key, || store.serialize_state()))
+    })? {
+        Ok(data) => Some(
+            leptos::html::script()
+                .id(id.clone())
+                .attr("type", "application/json")
+                .inner_html(script_payload(&data)),
+        ),
         Err(e) => {
-            // Log error but don't fail rendering
             leptos::logging::error!("Failed to serialize store for hydration: {}", e);
-            ().into_any()
+            None
+        }
+    }
+}
+
+/// Provide a store to a Leptos island, with its state carried in
+/// attributes of the island's root element.
+///
+/// Like [`provide_island_store`], but instead of a script, the returned
+/// attributes are spread onto the island's root element: the state goes
+/// into [`island_store_attribute`]`(S::store_key())` (`data-store-cart`)
+/// and `instance` into the same attribute suffixed with `-instance`. On the
+/// client, the island restores the store from the root carrying its
+/// instance, falling back to `factory`, so every instance of the island
+/// gets its own state without a script per instance.
+///
+/// `instance` must be unique among the islands using `S` on the page, and
+/// the store key must be a valid attribute name suffix.
+///
+/// ```rust,ignore
+/// #[island]
+/// fn CartButton(product_id: u32) -> impl IntoView {
+///     let attrs = provide_island_store_attrs(&product_id.to_string(), CartStore::new);
+///
+///     view! {
+///         <div {..attrs}>
+///             <AddToCart/>
+///         </div>
+///     }
+/// }
+/// ```
+///
+/// [`island_store_attribute`]: crate::hydration::island_store_attribute
+#[cfg(feature = "hydrate")]
+pub fn provide_island_store_attrs<S, F>(instance: &str, factory: F) -> impl leptos::attr::Attribute
+where
+    S: HydratableStore + Clone + Send + Sync + 'static,
+    F: FnOnce() -> S,
+{
+    use crate::hydration::{
+        check_store_key, encode_payload, island_store_attribute, island_store_key,
+        read_island_attribute, report_serialize,
+    };
+    use leptos::attr::custom::custom_attribute;
+
+    let key = island_store_key(S::store_key(), instance);
+    check_store_key::<S>(S::store_key());
+    check_store_key::<S>(&key);
+    let store = read_island_attribute(S::store_key(), instance)
+        .and_then(|data| crate::hydration::from_payload::<S>(&key, &data))
+        .unwrap_or_else(|_| factory());
+    provide_store(store.clone());
+
+    let data = untrack(|| {
+        (!store.skip_hydration()).then(|| report_serialize(&key, || store.serialize_state()))
+    })
+    .and_then(|data| {
+        data.map(|data| encode_payload(&data).into_owned())
+            .inspect_err(|e| {
+                leptos::logging::error!("Failed to serialize store for hydration: {}", e);
+            })
+            .ok()
+    });
+    let name = island_store_attribute(S::store_key());
+    (
+        custom_attribute(format!("{name}-instance"), instance.to_string()),
+        custom_attribute(name, data),
+    )
+}
+
+/// Provide a hydratable store whose state travels through Leptos' own
+/// serialization instead of a store script.
+///
+/// The state is written to the reactive owner's `SharedContext` the same
+/// way a `Resource` is, so it ends up in the `__RESOLVED_RESOURCES` that
+/// `<HydrationScripts/>` already emits. It is serialized when the response
+/// is built, so changes made while rendering are included, and it follows
+/// streaming and islands like any resource would (outside an island
+/// nothing is written). On the client, the store is restored from that
+/// data, falling back to `factory`. Without a shared context, as in pure
+/// client-side rendering, `factory` is always used.
+///
+/// Data is matched by the order of calls, like resources: call this
+/// unconditionally and at the same point of the tree on both server and
+/// client.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use leptos::prelude::*;
+/// use leptos_store::prelude::*;
+///
+/// #[component]
+/// pub fn App() -> impl IntoView {
+///     provide_shared_store(MyStore::new);
+///
+///     view! { <MainContent /> }
+/// }
+/// ```
+#[cfg(feature = "hydrate")]
+pub fn provide_shared_store<S, F>(factory: F)
+where
+    S: HydratableStore + Clone + Send + Sync + 'static,
+    F: FnOnce() -> S,
+{
+    use leptos::reactive::owner::Owner;
+
+    let Some(shared_context) = Owner::current_shared_context() else {
+        provide_store(factory());
+        return;
+    };
+
+    let id = shared_context.next_id();
+    let store = shared_context
+        .read_data(&id)
+        .filter(|data| !data.is_empty())
+        .and_then(|data| crate::hydration::from_payload::<S>(S::store_key(), &data).ok())
+        .unwrap_or_else(factory);
+
+    if !shared_context.is_browser() && shared_context.get_is_hydrating() {
+        let payload = store.clone();
+        shared_context.write_async(
+            id,
+            Box::pin(async move {
+                // A skipped store is sent as an empty string, which the
+                // client treats as missing
+                untrack(|| {
+                    (!payload.skip_hydration()).then(|| {
+                        crate::hydration::report_serialize(S::store_key(), || {
+                            payload.serialize_state()
+                        })
+                    })
+                })
+                .unwrap_or(Ok(String::new()))
+                .unwrap_or_else(|e| {
+                    leptos::logging::error!("Failed to serialize store for hydration: {}", e);
+                    String::new()
+                })
+            }),
+        );
+    }
+
+    provide_store(store);
+}
+
+/// A store provided with [`provide_request_store`], and the resource
+/// loading its state.
+#[cfg(feature = "hydrate")]
+#[derive(Clone)]
+pub struct RequestStore<S> {
+    store: S,
+    state: Resource<Option<String>>,
+    committed: Arc<AtomicBool>,
+}
+
+#[cfg(feature = "hydrate")]
+impl<S: HydratableStore + Clone + Send + Sync + 'static> RequestStore<S> {
+    /// Returns the store, which holds its default state until the
+    /// factory's has arrived.
+    pub fn store(&self) -> S {
+        self.store.clone()
+    }
+
+    /// Wait for the factory's state and return the store holding it.
+    ///
+    /// Await it in a `Suspend` inside `<Suspense>` so the components
+    /// reading the state render it on the server and hydrate with it on
+    /// the client.
+    pub async fn ready(&self) -> S {
+        if let Some(data) = self.state.await {
+            commit_request_state(&self.store, &self.committed, &data);
+        }
+        self.store.clone()
+    }
+}
+
+/// Hydrate a request store with `data`, unless it already was.
+#[cfg(feature = "hydrate")]
+fn commit_request_state<S: HydratableStore>(store: &S, committed: &AtomicBool, data: &str) {
+    if !committed.swap(true, Ordering::AcqRel)
+        && let Err(e) = crate::hydration::payload_into(store, S::store_key(), data)
+    {
+        leptos::logging::warn!("Failed to hydrate request store: {}", e);
+    }
+}
+
+/// Provide a store whose state is loaded by an async `factory`, such as a
+/// fetch from an API or a database, and send it to the client.
+///
+/// The store is provided right away with its default state. `factory`
+/// runs in a [`Resource`]: on the server its store's state replaces the
+/// provided one as soon as it resolves, and is serialized with the other
+/// resources of the response, so the client hydrates the store with it
+/// without running `factory` again. Errors are logged and leave the
+/// default state. Await [`RequestStore::ready`] inside `<Suspense>` to
+/// render the loaded state.
+///
+/// Data is matched by the order of calls, like resources: call this
+/// unconditionally and at the same point of the tree on server and client,
+/// usually at the top of the `App` the `leptos_routes` shell renders.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[component]
+/// pub fn App() -> impl IntoView {
+///     let tokens = provide_request_store(|| async {
+///         fetch_tokens().await.map(TokenStore::with_tokens)
+///     });
+///
+///     view! {
+///         <Suspense fallback=|| "Loading...">
+///             {move || Suspend::new(async move {
+///                 tokens.ready().await;
+///                 view! { <TokenGrid/> }
+///             })}
+///         </Suspense>
+///     }
+/// }
+/// ```
+#[cfg(feature = "hydrate")]
+pub fn provide_request_store<S, F, Fut, E>(factory: F) -> RequestStore<S>
+where
+    S: HydratableStore + Default + Clone + Send + Sync + 'static,
+    F: Fn() -> Fut + Send + Sync + 'static,
+    Fut: std::future::Future<Output = Result<S, E>> + Send + 'static,
+    E: std::fmt::Display,
+{
+    let store = S::default();
+    let committed = Arc::new(AtomicBool::new(false));
+    // The server commits as soon as the state is loaded, the browser once
+    // it (possibly hydrated) reaches the resource.
+    let commit_on_load =
+        (!cfg!(target_arch = "wasm32")).then(|| (store.clone(), Arc::clone(&committed)));
+    let state = Resource::new(
+        || (),
+        move |_| {
+            let load = factory();
+            let commit = commit_on_load.clone();
+            async move {
+                let data = match load.await.map(|loaded| {
+                    crate::hydration::report_serialize(S::store_key(), || loaded.serialize_state())
+                }) {
+                    Ok(Ok(data)) => data,
+                    Ok(Err(e)) => {
+                        leptos::logging::error!("Failed to serialize request store: {}", e);
+                        return None;
+                    }
+                    Err(e) => {
+                        leptos::logging::warn!("Request store factory failed: {}", e);
+                        return None;
+                    }
+                };
+                if let Some((store, committed)) = commit {
+                    commit_request_state(&store, &committed, &data);
+                }
+                Some(data)
+            }
+        },
+    );
+
+    let request = RequestStore {
+        store,
+        state,
+        committed,
+    };
+    if cfg!(target_arch = "wasm32") {
+        let request = request.clone();
+        Effect::new(move |_| {
+            if let Some(Some(data)) = request.state.get() {
+                commit_request_state(&request.store, &request.committed, &data);
+            }
+        });
+    }
+    provide_store(request.store());
+    request
+}
+
+/// Provide a hydratable store to the component tree and register its
+/// hydration script with [`StoreHydrationScripts`].
+///
+/// Unlike [`provide_hydrated_store`], nothing is rendered in place: the
+/// script is emitted by the `<StoreHydrationScripts/>` in the application
+/// shell, together with those of every other registered store. On the
+/// client this just provides the store.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use leptos::prelude::*;
+/// use leptos_store::prelude::*;
+///
+/// #[component]
+/// pub fn App() -> impl IntoView {
+///     register_hydrated_store(MyStore::new());
+///
+///     view! { <MainContent /> }
+/// }
+/// ```
+///
+/// [`StoreHydrationScripts`]: crate::hydration::StoreHydrationScripts
+#[cfg(feature = "hydrate")]
+pub fn register_hydrated_store<S: HydratableStore + Clone + Send + Sync + 'static>(store: S) {
+    let payload = store.clone();
+    crate::hydration::register_hydration_payload::<S>(move || payload.clone());
+    provide_store(store);
+}
+
+/// Initializes a lazily hydrated store.
+#[cfg(feature = "hydrate")]
+type LazyInit<S> = Box<dyn FnOnce() -> S + Send>;
+
+/// A store provided with [`register_lazy_hydrated_store`], created on
+/// first use.
+#[cfg(feature = "hydrate")]
+struct LazyStoreProvider<S> {
+    store: Arc<OnceLock<S>>,
+    init: Arc<Mutex<Option<LazyInit<S>>>>,
+}
+
+#[cfg(feature = "hydrate")]
+impl<S> Clone for LazyStoreProvider<S> {
+    fn clone(&self) -> Self {
+        Self {
+            store: Arc::clone(&self.store),
+            init: Arc::clone(&self.init),
         }
     }
 }
 
+#[cfg(feature = "hydrate")]
+impl<S: Clone> LazyStoreProvider<S> {
+    fn get(&self) -> S {
+        self.store
+            .get_or_init(|| {
+                let init = self
+                    .init
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .take()
+                    .expect("lazy store is initialized once");
+                init()
+            })
+            .clone()
+    }
+}
+
+/// Returns the lazily hydrated store of type `S` in context, creating it on
+/// first use.
+#[cfg(feature = "hydrate")]
+fn use_lazy_store<S: Clone + Send + Sync + 'static>() -> Option<S> {
+    let lazy = use_context::<LazyStoreProvider<S>>()?;
+    crate::hydration::mark_store_used::<S>();
+    Some(lazy.get())
+}
+
+/// Without the `hydrate` feature no store is provided lazily.
+#[cfg(not(feature = "hydrate"))]
+fn use_lazy_store<S>() -> Option<S> {
+    None
+}
+
+/// Provide a hydratable store that is only deserialized when first used.
+///
+/// The store's hydration payload is located right away, but parsing it is
+/// deferred until the first [`use_store`] (or [`use_hydrated_store`]), which
+/// keeps the parse time of large states out of the critical hydration
+/// path. If there is no payload or it can't be read, `fallback` creates the
+/// store instead, as it always does on the server. Like
+/// [`register_hydrated_store`], the store's script is rendered by
+/// [`StoreHydrationScripts`].
+///
+/// The store is created under the reactive owner current at this call, not
+/// the one of the component that first uses it.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[component]
+/// pub fn App() -> impl IntoView {
+///     register_lazy_hydrated_store(TokenStore::new);
+///
+///     view! { <MainContent /> }
+/// }
+/// ```
+///
+/// [`StoreHydrationScripts`]: crate::hydration::StoreHydrationScripts
+#[cfg(feature = "hydrate")]
+pub fn register_lazy_hydrated_store<S, F>(fallback: F)
+where
+    S: HydratableStore + Clone + Send + Sync + 'static,
+    F: FnOnce() -> S + Send + 'static,
+{
+    let data = crate::hydration::read_hydration_data(S::store_key());
+    let owner = Owner::current();
+    let init: LazyInit<S> = Box::new(move || {
+        let create = move || match data {
+            Ok(data) => match crate::hydration::from_payload::<S>(S::store_key(), &data) {
+                Ok(store) => {
+                    store.reconcile();
+                    store
+                }
+                Err(e) => {
+                    leptos::logging::warn!("Hydration failed, using fallback: {}", e);
+                    fallback()
+                }
+            },
+            Err(_) => fallback(),
+        };
+        match owner {
+            Some(owner) => owner.with(create),
+            None => create(),
+        }
+    });
+    let lazy = LazyStoreProvider {
+        store: Arc::new(OnceLock::new()),
+        init: Arc::new(Mutex::new(Some(init))),
+    };
+
+    let payload = lazy.clone();
+    crate::hydration::register_hydration_payload::<S>(move || payload.get());
+    provide_context(lazy);
+}
+
 /// Access a hydratable store, hydrating from serialized data if available.
 ///
 /// This function is used on the client during hydration to:
@@ -377,6 +1430,11 @@ pub fn provide_hydrated_store<S: HydratableStore + Clone + Send + Sync + 'static
 /// [`HydratableStore`]: crate::hydration::HydratableStore
 #[cfg(feature = "hydrate")]
 pub fn use_hydrated_store<S: HydratableStore + Clone + Send + Sync + 'static>() -> S {
+    // A lazily hydrated store parses its payload once, on first use
+    if let Some(store) = use_lazy_store::<S>() {
+        return store;
+    }
+
     // First, try to hydrate from DOM
     if has_hydration_data(S::store_key()) {
         match hydrate_store::<S>() {
@@ -423,6 +1481,11 @@ pub fn use_hydrated_store<S: HydratableStore + Clone + Send + Sync + 'static>()
 #[cfg(feature = "hydrate")]
 pub fn try_use_hydrated_store<S: HydratableStore + Clone + Send + Sync + 'static>()
 -> Result<S, StoreHydrationError> {
+    // A lazily hydrated store parses its payload once, on first use
+    if let Some(store) = use_lazy_store::<S>() {
+        return Ok(store);
+    }
+
     // First, try to hydrate from DOM
     if has_hydration_data(S::store_key()) {
         match hydrate_store::<S>() {
@@ -507,6 +1570,188 @@ mod tests {
         assert_eq!(provider.as_ref().state.get().value, 100);
     }
 
+    #[test]
+    fn test_use_store_or_provide() {
+        let owner = Owner::new();
+        owner.with(|| {
+            // Without a store in context, the factory's store is provided
+            let store = use_store_or_provide(|| TestStore::new(1));
+            assert_eq!(store.state.get_untracked().value, 1);
+            assert_eq!(use_store::<TestStore>().state.get_untracked().value, 1);
+
+            // Descendants get the existing store, without calling the factory
+            let child = Owner::current().unwrap().child();
+            child.with(|| {
+                let store = use_store_or_provide::<TestStore>(|| unreachable!());
+                store.state.update(|state| state.value = 2);
+            });
+            assert_eq!(store.state.get_untracked().value, 2);
+        });
+    }
+
+    #[test]
+    fn test_scoped_store_keyed() {
+        let owner = Owner::new();
+        owner.with(|| {
+            provide_scoped_store_keyed(TestStore::new(1), "tab-1");
+            provide_scoped_store_keyed(TestStore::new(2), String::from("tab-2"));
+            assert_eq!(
+                use_scoped_store_keyed::<TestStore>("tab-1")
+                    .state
+                    .get_untracked()
+                    .value,
+                1
+            );
+
+            // A child sees the keys above it, and its own replace them only for itself
+            let child = Owner::current().unwrap().child();
+            child.with(|| {
+                provide_scoped_store_keyed(TestStore::new(3), "tab-2");
+                let value = |key| {
+                    use_scoped_store_keyed::<TestStore>(key)
+                        .state
+                        .get_untracked()
+                        .value
+                };
+                assert_eq!(value("tab-1"), 1);
+                assert_eq!(value("tab-2"), 3);
+            });
+            assert_eq!(
+                use_scoped_store_keyed::<TestStore>("tab-2")
+                    .state
+                    .get_untracked()
+                    .value,
+                2
+            );
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Scoped store `tab-9` not found in context")]
+    fn test_scoped_store_keyed_missing() {
+        let owner = Owner::new();
+        owner.with(|| {
+            provide_scoped_store_keyed(TestStore::new(1), "tab-1");
+            use_scoped_store_keyed::<TestStore>("tab-9");
+        });
+    }
+
+    impl StoreFamily for TestStore {
+        type Key = i32;
+
+        fn create(key: &i32) -> Self {
+            Self::new(*key)
+        }
+    }
+
+    #[test]
+    fn test_use_store_keyed() {
+        let owner = Owner::new();
+        let store = owner.with(|| {
+            let family = provide_store_family::<TestStore>();
+
+            // The first call creates the instance, later calls reuse it
+            let child = Owner::current().unwrap().child();
+            let store = child.with(|| use_store_keyed::<TestStore>(1));
+            store.state.update(|state| state.value = 10);
+            // Instances outlive the component that created them
+            child.cleanup();
+            drop(child);
+            let sibling = Owner::current().unwrap().child();
+            sibling.with(|| {
+                assert_eq!(
+                    use_store_keyed::<TestStore>(1).state.get_untracked().value,
+                    10
+                );
+                assert_eq!(
+                    use_store_keyed::<TestStore>(2).state.get_untracked().value,
+                    2
+                );
+            });
+
+            let mut keys = family.keys();
+            keys.sort();
+            assert_eq!(keys, [1, 2]);
+
+            // An evicted key is disposed and gets a new instance
+            assert!(family.evict(&1));
+            assert!(!family.evict(&1));
+            assert!(store.state.try_get_untracked().is_none());
+            assert_eq!(
+                use_store_keyed::<TestStore>(1).state.get_untracked().value,
+                1
+            );
+            use_store_keyed::<TestStore>(2)
+        });
+
+        // Instances are disposed with the family's owner
+        owner.cleanup();
+        assert!(store.state.try_get_untracked().is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "not found in context; call provide_store_family first")]
+    fn test_use_store_keyed_without_family() {
+        let owner = Owner::new();
+        owner.with(|| {
+            use_store_keyed::<TestStore>(3);
+        });
+    }
+
+    #[test]
+    fn test_provide_owned_store() {
+        let cleaned_up = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let owner = Owner::new();
+        let page = owner.child();
+        let store = page.with(|| {
+            let store = provide_owned_store({
+                let cleaned_up = Arc::clone(&cleaned_up);
+                move || {
+                    on_cleanup(move || {
+                        cleaned_up.store(true, std::sync::atomic::Ordering::SeqCst);
+                    });
+                    TestStore::new(5)
+                }
+            });
+            assert_eq!(use_store::<TestStore>().state.get_untracked().value, 5);
+            store
+        });
+        assert_eq!(store.state.get_untracked().value, 5);
+
+        // Unmounting the page disposes the store and runs its cleanups
+        page.cleanup();
+        assert!(store.state.try_get_untracked().is_none());
+        assert!(cleaned_up.load(std::sync::atomic::Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_use_local_store() {
+        let owner = Owner::new();
+        owner.with(|| {
+            provide_store(TestStore::new(1));
+
+            // Each widget gets its own store, and the shared one stays in context
+            let widgets: Vec<_> = (10..12)
+                .map(|value| {
+                    let widget = Owner::current().unwrap().child();
+                    let store = widget.with(|| {
+                        let store = use_local_store(|| TestStore::new(value));
+                        assert_eq!(use_store::<TestStore>().state.get_untracked().value, 1);
+                        store
+                    });
+                    (widget, store)
+                })
+                .collect();
+            assert_eq!(widgets[0].1.state.get_untracked().value, 10);
+            assert_eq!(widgets[1].1.state.get_untracked().value, 11);
+
+            // Unmounting one widget disposes only its store
+            widgets[0].0.cleanup();
+            assert!(widgets[0].1.state.try_get_untracked().is_none());
+            assert_eq!(widgets[1].1.state.get_untracked().value, 11);
+        });
+    }
+
     #[test]
     fn test_scoped_store_provider() {
         let store = TestStore::new(50);
@@ -516,6 +1761,42 @@ mod tests {
         assert_eq!(retrieved.state.get().value, 50);
     }
 
+    trait ValueApi: Send + Sync {
+        fn value(&self) -> i32;
+    }
+
+    impl ValueApi for TestStore {
+        fn value(&self) -> i32 {
+            self.state.get().value
+        }
+    }
+
+    struct FakeValue;
+
+    impl ValueApi for FakeValue {
+        fn value(&self) -> i32 {
+            -1
+        }
+    }
+
+    #[test]
+    fn test_store_interface_context() {
+        let owner = Owner::new();
+        owner.with(|| {
+            assert!(try_use_store_trait::<dyn ValueApi>().is_err());
+
+            provide_store_as::<dyn ValueApi>(Arc::new(TestStore::new(7)));
+            assert_eq!(use_store_trait::<dyn ValueApi>().value(), 7);
+
+            // A child scope can swap in a fake implementation
+            let child = Owner::current().unwrap().child();
+            child.with(|| {
+                provide_store_as::<dyn ValueApi>(Arc::new(FakeValue));
+                assert_eq!(use_store_trait::<dyn ValueApi>().value(), -1);
+            });
+        });
+    }
+
     #[test]
     fn test_store_error_context_not_available() {
         let err = StoreError::ContextNotAvailable("TestStore not found".to_string());
@@ -0,0 +1,266 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2026 web-mech
+
+//! Dependencies for async actions.
+//!
+//! Async actions only receive the store they run against, and usually run
+//! in a spawned task where Leptos context is no longer available. Shared
+//! services (an HTTP client, configuration, other stores) are instead
+//! collected in an [`ActionDeps`] and provided to the component tree with
+//! [`provide_action_deps`].
+//!
+//! When an action is dispatched through [`StoreActionExt`] or a
+//! [`StorePrefetchExt`] method, the dependencies in context at that point
+//! are captured, and [`action_dep`] returns them while the action runs. The
+//! [`StoreEnv`] in context, if any, is available the same way.
+//!
+//! # Example
+//!
+//! ```rust
+//! use leptos::prelude::*;
+//! use leptos_store::prelude::{Action, *};
+//! use std::sync::Arc;
+//!
+//! struct ApiClient {
+//!     base_url: String,
+//! }
+//!
+//! #[derive(Clone, Default)]
+//! struct TokenState {
+//!     url: String,
+//! }
+//!
+//! #[derive(Clone)]
+//! struct TokenStore {
+//!     state: RwSignal<TokenState>,
+//! }
+//!
+//! impl Store for TokenStore {
+//!     type State = TokenState;
+//!     fn state(&self) -> ReadSignal<Self::State> { self.state.read_only() }
+//! }
+//!
+//! struct FetchTokens;
+//!
+//! impl AsyncAction<TokenStore> for FetchTokens {
+//!     type Output = String;
+//!     type Error = StoreError;
+//!
+//!     async fn execute(&self, _store: &TokenStore) -> Result<String, StoreError> {
+//!         let client: Arc<ApiClient> = require_action_dep()?;
+//!         Ok(format!("{}/tokens", client.base_url))
+//!     }
+//! }
+//!
+//! Owner::new().with(|| {
+//!     provide_action_deps(ActionDeps::new().with(ApiClient {
+//!         base_url: "https://api.example.com".to_string(),
+//!     }));
+//!
+//!     let store = TokenStore { state: RwSignal::new(TokenState::default()) };
+//!     let url = futures::executor::block_on(store.dispatch_queued(FetchTokens));
+//!     assert_eq!(url.unwrap(), "https://api.example.com/tokens");
+//! });
+//! ```
+//!
+//! [`StoreActionExt`]: crate::r#async::StoreActionExt
+//! [`StorePrefetchExt`]: crate::prefetch::StorePrefetchExt
+
+use crate::env::StoreEnv;
+use crate::store::StoreError;
+use leptos::prelude::*;
+use pin_project_lite::pin_project;
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+type DepMap = HashMap<TypeId, Arc<dyn Any + Send + Sync>>;
+
+thread_local! {
+    static CURRENT: RefCell<Option<ActionDeps>> = const { RefCell::new(None) };
+}
+
+/// Dependencies available to async actions, looked up by type.
+///
+/// Cloning is cheap; all clones share the same values.
+#[derive(Clone, Default)]
+pub struct ActionDeps {
+    deps: Arc<DepMap>,
+}
+
+impl ActionDeps {
+    /// Create an empty set of dependencies.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a dependency, replacing any previous one of the same type.
+    pub fn with<T: Send + Sync + 'static>(self, value: T) -> Self {
+        self.with_shared(Arc::new(value))
+    }
+
+    /// Add a dependency that is already shared.
+    pub fn with_shared<T: Send + Sync + 'static>(mut self, value: Arc<T>) -> Self {
+        Arc::make_mut(&mut self.deps).insert(TypeId::of::<T>(), value);
+        self
+    }
+
+    /// Get a dependency by type.
+    pub fn get<T: Send + Sync + 'static>(&self) -> Option<Arc<T>> {
+        let dep = self.deps.get(&TypeId::of::<T>())?;
+        Arc::clone(dep).downcast().ok()
+    }
+
+    /// Check if a dependency of the given type is set.
+    pub fn contains<T: Send + Sync + 'static>(&self) -> bool {
+        self.deps.contains_key(&TypeId::of::<T>())
+    }
+
+    /// Resolve the dependencies from context.
+    ///
+    /// Returns those provided with [`provide_action_deps`], plus the
+    /// [`StoreEnv`] in context unless one was provided explicitly.
+    pub fn current() -> Self {
+        let deps = use_context::<Self>().unwrap_or_default();
+        match use_context::<StoreEnv>() {
+            Some(env) if !deps.contains::<StoreEnv>() => deps.with(env),
+            _ => deps,
+        }
+    }
+
+    /// Make these dependencies available to [`action_dep`] while `future`
+    /// runs.
+    pub fn scope<F: Future>(self, future: F) -> Scoped<F> {
+        Scoped {
+            deps: Some(self),
+            future,
+        }
+    }
+}
+
+impl fmt::Debug for ActionDeps {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ActionDeps")
+            .field("deps", &self.deps.len())
+            .finish()
+    }
+}
+
+pin_project! {
+    /// Future returned by [`ActionDeps::scope`].
+    pub struct Scoped<F> {
+        deps: Option<ActionDeps>,
+        #[pin]
+        future: F,
+    }
+}
+
+impl<F: Future> Future for Scoped<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        // Puts the outer scope back, even if the future panics.
+        struct Restore<'a> {
+            deps: &'a mut Option<ActionDeps>,
+            outer: Option<ActionDeps>,
+        }
+        impl Drop for Restore<'_> {
+            fn drop(&mut self) {
+                *self.deps = CURRENT.with(|current| current.replace(self.outer.take()));
+            }
+        }
+
+        let outer = CURRENT.with(|current| current.replace(this.deps.take()));
+        let _restore = Restore {
+            deps: this.deps,
+            outer,
+        };
+        this.future.poll(cx)
+    }
+}
+
+/// Provide dependencies for the async actions dispatched in the component
+/// tree.
+pub fn provide_action_deps(deps: ActionDeps) {
+    provide_context(deps);
+}
+
+/// Get a dependency of the running action by type.
+///
+/// Returns `None` if it wasn't provided, or if called outside an action.
+pub fn action_dep<T: Send + Sync + 'static>() -> Option<Arc<T>> {
+    CURRENT.with(|current| current.borrow().as_ref()?.get())
+}
+
+/// Get a dependency of the running action by type, or an error naming the
+/// missing type.
+pub fn require_action_dep<T: Send + Sync + 'static>() -> Result<Arc<T>, StoreError> {
+    action_dep().ok_or_else(|| {
+        StoreError::NotFound(format!(
+            "Action dependency {} not provided",
+            std::any::type_name::<T>()
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::Profile;
+
+    struct Client(&'static str);
+
+    #[test]
+    fn test_deps_lookup() {
+        let deps = ActionDeps::new().with(Client("a")).with(1u32);
+        assert_eq!(deps.get::<Client>().unwrap().0, "a");
+        assert!(deps.contains::<u32>());
+        assert!(deps.get::<String>().is_none());
+
+        // Adding to a clone leaves the original untouched.
+        let replaced = deps.clone().with(Client("b"));
+        assert_eq!(replaced.get::<Client>().unwrap().0, "b");
+        assert_eq!(deps.get::<Client>().unwrap().0, "a");
+    }
+
+    #[test]
+    fn test_scope() {
+        assert!(action_dep::<Client>().is_none());
+
+        let outer = ActionDeps::new().with(Client("outer"));
+        let inner = ActionDeps::new().with(Client("inner"));
+        let seen = futures::executor::block_on(outer.scope(async {
+            let before = action_dep::<Client>().unwrap().0;
+            let nested = inner
+                .scope(async { action_dep::<Client>().unwrap().0 })
+                .await;
+            let after = action_dep::<Client>().unwrap().0;
+            (before, nested, after)
+        }));
+        assert_eq!(seen, ("outer", "inner", "outer"));
+
+        assert!(action_dep::<Client>().is_none());
+        let err = require_action_dep::<Client>().err().unwrap();
+        assert!(err.to_string().contains("Client"));
+    }
+
+    #[test]
+    fn test_current_from_context() {
+        let owner = Owner::new();
+        owner.with(|| {
+            assert!(!ActionDeps::current().contains::<Client>());
+
+            provide_action_deps(ActionDeps::new().with(Client("context")));
+            crate::env::provide_store_env(StoreEnv::empty(Profile::Test));
+            let deps = ActionDeps::current();
+            assert_eq!(deps.get::<Client>().unwrap().0, "context");
+            assert_eq!(deps.get::<StoreEnv>().unwrap().profile(), &Profile::Test);
+        });
+    }
+}
@@ -0,0 +1,216 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2026 web-mech
+
+//! Machine-readable store descriptions and diagram export.
+//!
+//! Stores defined with the [`store!`](crate::store!) macro implement
+//! [`StoreSchema`] when the `describe` feature is enabled. The resulting
+//! [`StoreDescription`] lists the store's state fields, getters, mutators,
+//! actions, and declared transitions, and can be rendered as JSON,
+//! Graphviz DOT, or a Mermaid state diagram.
+//!
+//! Descriptions are static data generated at compile time, so a test or
+//! build step can write them into the docs:
+//!
+//! ```rust
+//! use leptos_store::describe::StoreSchema;
+//! use leptos_store::store;
+//!
+//! #[derive(Clone, Debug, Default, PartialEq)]
+//! pub enum Status {
+//!     #[default]
+//!     Idle,
+//!     Loading,
+//!     Ready,
+//! }
+//!
+//! store! {
+//!     pub FeedStore {
+//!         state FeedState {
+//!             status: Status,
+//!         }
+//!
+//!         mutators {
+//!             set_status(this, status: Status) {
+//!                 this.mutate(|s| s.status = status);
+//!             }
+//!         }
+//!
+//!         actions {
+//!             fetch(this) {
+//!                 this.set_status(Status::Loading);
+//!             }
+//!             finish(this) {
+//!                 this.set_status(Status::Ready);
+//!             }
+//!         }
+//!
+//!         transitions(status) {
+//!             Idle => Loading: fetch,
+//!             Loading => Ready: finish,
+//!         }
+//!     }
+//! }
+//!
+//! let description = FeedStore::describe();
+//! assert_eq!(description.actions.len(), 2);
+//! assert!(description.to_mermaid().contains("Idle --> Loading: fetch"));
+//! assert!(description.to_dot().contains("\"Loading\" -> \"Ready\" [label=\"finish\"]"));
+//! ```
+
+use serde::Serialize;
+use std::fmt::Write;
+
+/// A named, typed item: a state field or a method parameter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct FieldDescription {
+    /// The field or parameter name.
+    pub name: &'static str,
+    /// The type, as written in the store definition.
+    pub ty: &'static str,
+}
+
+/// A getter, mutator, or action.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct MethodDescription {
+    /// The method name.
+    pub name: &'static str,
+    /// The method parameters, excluding the receiver.
+    pub params: &'static [FieldDescription],
+    /// The return type, if any.
+    pub returns: Option<&'static str>,
+    /// Whether the method is `async`.
+    pub is_async: bool,
+}
+
+/// A declared transition between two values of a state field.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct TransitionDescription {
+    /// The state field holding the machine's current value.
+    pub field: &'static str,
+    /// The source value.
+    pub from: &'static str,
+    /// The target value.
+    pub to: &'static str,
+    /// The getter, mutator, or action performing the transition.
+    pub action: &'static str,
+}
+
+/// A complete description of a store.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct StoreDescription {
+    /// The store type name.
+    pub name: &'static str,
+    /// The state type name.
+    pub state: &'static str,
+    /// The state fields.
+    pub fields: &'static [FieldDescription],
+    /// The getters.
+    pub getters: &'static [MethodDescription],
+    /// The mutators.
+    pub mutators: &'static [MethodDescription],
+    /// The actions.
+    pub actions: &'static [MethodDescription],
+    /// The declared transitions.
+    pub transitions: &'static [TransitionDescription],
+}
+
+impl StoreDescription {
+    /// Render the description as pretty-printed JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("store descriptions are always serializable")
+    }
+
+    /// Render the declared transitions as a Graphviz DOT digraph.
+    ///
+    /// Each transition becomes an edge labelled with its action. A store
+    /// without transitions renders as a single node.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "digraph \"{}\" {{", self.name);
+        let _ = writeln!(out, "    rankdir=LR;");
+        if self.transitions.is_empty() {
+            let _ = writeln!(out, "    \"{}\" [shape=box];", self.name);
+        }
+        for t in self.transitions {
+            let _ = writeln!(
+                out,
+                "    \"{}\" -> \"{}\" [label=\"{}\"];",
+                t.from, t.to, t.action
+            );
+        }
+        out.push('}');
+        out.push('\n');
+        out
+    }
+
+    /// Render the declared transitions as a Mermaid state diagram.
+    pub fn to_mermaid(&self) -> String {
+        let mut out = String::from("stateDiagram-v2\n");
+        for t in self.transitions {
+            let _ = writeln!(out, "    {} --> {}: {}", t.from, t.to, t.action);
+        }
+        out
+    }
+}
+
+/// Trait for stores that provide a [`StoreDescription`].
+///
+/// Implemented by the [`store!`](crate::store!) macro when the `describe`
+/// feature is enabled.
+pub trait StoreSchema {
+    /// Returns the description of this store.
+    fn describe() -> StoreDescription;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DESCRIPTION: StoreDescription = StoreDescription {
+        name: "DoorStore",
+        state: "DoorState",
+        fields: &[FieldDescription {
+            name: "door",
+            ty: "Door",
+        }],
+        getters: &[],
+        mutators: &[],
+        actions: &[MethodDescription {
+            name: "open",
+            params: &[],
+            returns: None,
+            is_async: false,
+        }],
+        transitions: &[
+            TransitionDescription {
+                field: "door",
+                from: "Closed",
+                to: "Open",
+                action: "open",
+            },
+            TransitionDescription {
+                field: "door",
+                from: "Open",
+                to: "Closed",
+                action: "close",
+            },
+        ],
+    };
+
+    #[test]
+    fn test_diagram_output() {
+        assert_eq!(
+            DESCRIPTION.to_mermaid(),
+            "stateDiagram-v2\n    Closed --> Open: open\n    Open --> Closed: close\n"
+        );
+
+        let dot = DESCRIPTION.to_dot();
+        assert!(dot.starts_with("digraph \"DoorStore\" {"));
+        assert!(dot.contains("\"Open\" -> \"Closed\" [label=\"close\"];"));
+
+        let json: serde_json::Value = serde_json::from_str(&DESCRIPTION.to_json()).unwrap();
+        assert_eq!(json["actions"][0]["name"], "open");
+        assert_eq!(json["transitions"][1]["to"], "Closed");
+    }
+}
@@ -0,0 +1,307 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2026 web-mech
+
+//! Normalized, ID-keyed entity collections.
+//!
+//! List-heavy stores tend to keep entities in a `Vec<T>` and search it on
+//! every lookup. [`EntityState`] instead keeps entities in a map keyed by
+//! ID, plus a separate list of IDs for ordering, so lookups, updates, and
+//! removals don't scan the collection.
+//!
+//! The [`entity_store!`](crate::entity_store!) macro generates a complete
+//! store around an [`EntityState`], with `upsert`, `upsert_many`, `remove`,
+//! `select_by_id`, `ids`, and ordering helpers.
+//!
+//! # Example
+//!
+//! ```rust
+//! use leptos_store::entity::EntityState;
+//!
+//! #[derive(Clone, Debug, PartialEq)]
+//! struct User {
+//!     id: u32,
+//!     name: String,
+//! }
+//!
+//! let mut users = EntityState::new();
+//! users.upsert(2, User { id: 2, name: "Bo".to_string() });
+//! users.upsert(1, User { id: 1, name: "Al".to_string() });
+//!
+//! assert_eq!(users.ids(), [2, 1]);
+//! assert_eq!(users.get(&1).map(|u| u.name.as_str()), Some("Al"));
+//!
+//! users.sort_by_key(|u| u.name.clone());
+//! assert_eq!(users.ids(), [1, 2]);
+//! ```
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A normalized collection of entities keyed by ID.
+///
+/// Entities are stored in a map for constant-time lookups, and their IDs in
+/// a list that defines the collection order. New entities are appended, and
+/// updating an existing entity keeps its position.
+///
+/// Deserializing repairs a map and ID list that disagree, as a stale or
+/// hand-edited payload can: duplicate IDs and IDs without an entity are
+/// dropped, and entities missing from the list are appended.
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    any(feature = "hydrate", feature = "persist"),
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(
+    any(feature = "hydrate", feature = "persist"),
+    serde(
+        bound(
+            serialize = "K: serde::Serialize + Eq + Hash, T: serde::Serialize",
+            deserialize = "K: serde::Deserialize<'de> + Eq + Hash + Clone, T: serde::Deserialize<'de>"
+        ),
+        from = "RawEntityState<K, T>"
+    )
+)]
+pub struct EntityState<K, T> {
+    entities: HashMap<K, T>,
+    ids: Vec<K>,
+}
+
+/// The serialized form of an [`EntityState`], before its map and ID list
+/// are reconciled.
+#[cfg(any(feature = "hydrate", feature = "persist"))]
+#[derive(serde::Deserialize)]
+#[serde(bound(deserialize = "K: serde::Deserialize<'de> + Eq + Hash, T: serde::Deserialize<'de>"))]
+struct RawEntityState<K, T> {
+    entities: HashMap<K, T>,
+    ids: Vec<K>,
+}
+
+#[cfg(any(feature = "hydrate", feature = "persist"))]
+impl<K: Eq + Hash + Clone, T> From<RawEntityState<K, T>> for EntityState<K, T> {
+    fn from(raw: RawEntityState<K, T>) -> Self {
+        let RawEntityState {
+            mut entities,
+            ids: listed,
+        } = raw;
+        let mut state = Self::new();
+        for id in listed {
+            if let Some(entity) = entities.remove(&id) {
+                state.upsert(id, entity);
+            }
+        }
+        state.extend(entities);
+        state
+    }
+}
+
+impl<K, T> Default for EntityState<K, T> {
+    fn default() -> Self {
+        Self {
+            entities: HashMap::new(),
+            ids: Vec::new(),
+        }
+    }
+}
+
+impl<K: Eq + Hash, T: PartialEq> PartialEq for EntityState<K, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ids == other.ids && self.entities == other.entities
+    }
+}
+
+impl<K: Eq + Hash + Clone, T> EntityState<K, T> {
+    /// Create an empty collection.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of entities.
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Check if the collection is empty.
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Check if an entity with `id` exists.
+    pub fn contains(&self, id: &K) -> bool {
+        self.entities.contains_key(id)
+    }
+
+    /// Returns the entity with `id`.
+    pub fn get(&self, id: &K) -> Option<&T> {
+        self.entities.get(id)
+    }
+
+    /// Returns a mutable reference to the entity with `id`.
+    pub fn get_mut(&mut self, id: &K) -> Option<&mut T> {
+        self.entities.get_mut(id)
+    }
+
+    /// Returns the IDs in collection order.
+    pub fn ids(&self) -> &[K] {
+        &self.ids
+    }
+
+    /// Returns the position of `id` in the collection order.
+    pub fn position(&self, id: &K) -> Option<usize> {
+        self.ids.iter().position(|k| k == id)
+    }
+
+    /// Iterate over the entities in collection order.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.ids.iter().filter_map(|id| self.entities.get(id))
+    }
+
+    /// Insert or replace the entity with `id`, returning the previous entity.
+    ///
+    /// Replaced entities keep their position; new ones are appended.
+    pub fn upsert(&mut self, id: K, entity: T) -> Option<T> {
+        let previous = self.entities.insert(id.clone(), entity);
+        if previous.is_none() {
+            self.ids.push(id);
+        }
+        previous
+    }
+
+    /// Remove the entity with `id`.
+    pub fn remove(&mut self, id: &K) -> Option<T> {
+        let removed = self.entities.remove(id)?;
+        self.ids.retain(|k| k != id);
+        Some(removed)
+    }
+
+    /// Keep only the entities for which `keep` returns `true`.
+    pub fn retain(&mut self, mut keep: impl FnMut(&T) -> bool) {
+        self.entities.retain(|_, entity| keep(entity));
+        let entities = &self.entities;
+        self.ids.retain(|id| entities.contains_key(id));
+    }
+
+    /// Remove all entities.
+    pub fn clear(&mut self) {
+        self.entities.clear();
+        self.ids.clear();
+    }
+
+    /// Sort the collection order with a comparator.
+    pub fn sort_by(&mut self, mut compare: impl FnMut(&T, &T) -> Ordering) {
+        let entities = &self.entities;
+        self.ids
+            .sort_by(|a, b| match (entities.get(a), entities.get(b)) {
+                (Some(a), Some(b)) => compare(a, b),
+                (a, b) => a.is_some().cmp(&b.is_some()),
+            });
+    }
+
+    /// Sort the collection order by a key extracted from each entity.
+    pub fn sort_by_key<O: Ord>(&mut self, mut key: impl FnMut(&T) -> O) {
+        let entities = &self.entities;
+        self.ids.sort_by_key(|id| entities.get(id).map(&mut key));
+    }
+
+    /// Move the entity with `id` to `index` in the collection order.
+    ///
+    /// `index` is clamped to the end of the collection. Returns `false` if
+    /// there is no entity with `id`.
+    pub fn move_to(&mut self, id: &K, index: usize) -> bool {
+        let Some(from) = self.position(id) else {
+            return false;
+        };
+        let id = self.ids.remove(from);
+        let index = index.min(self.ids.len());
+        self.ids.insert(index, id);
+        true
+    }
+}
+
+impl<K: Eq + Hash + Clone, T> FromIterator<(K, T)> for EntityState<K, T> {
+    fn from_iter<I: IntoIterator<Item = (K, T)>>(iter: I) -> Self {
+        let mut state = Self::new();
+        state.extend(iter);
+        state
+    }
+}
+
+impl<K: Eq + Hash + Clone, T> Extend<(K, T)> for EntityState<K, T> {
+    fn extend<I: IntoIterator<Item = (K, T)>>(&mut self, iter: I) {
+        for (id, entity) in iter {
+            self.upsert(id, entity);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn letters() -> EntityState<u32, &'static str> {
+        [(3, "c"), (1, "a"), (2, "b")].into_iter().collect()
+    }
+
+    #[test]
+    fn test_upsert_keeps_position() {
+        let mut state = letters();
+        assert_eq!(state.upsert(1, "A"), Some("a"));
+        assert_eq!(state.upsert(4, "d"), None);
+        assert_eq!(state.ids(), [3, 1, 2, 4]);
+        assert_eq!(
+            state.iter().copied().collect::<Vec<_>>(),
+            ["c", "A", "b", "d"]
+        );
+        assert_eq!(state.len(), 4);
+    }
+
+    #[test]
+    fn test_remove_and_retain() {
+        let mut state = letters();
+        assert_eq!(state.remove(&1), Some("a"));
+        assert_eq!(state.remove(&1), None);
+        assert_eq!(state.ids(), [3, 2]);
+
+        state.retain(|letter| *letter != "c");
+        assert_eq!(state.ids(), [2]);
+        assert!(!state.contains(&3));
+
+        state.clear();
+        assert!(state.is_empty());
+    }
+
+    #[test]
+    fn test_ordering_helpers() {
+        let mut state = letters();
+        state.sort_by_key(|letter| *letter);
+        assert_eq!(state.ids(), [1, 2, 3]);
+
+        state.sort_by(|a, b| b.cmp(a));
+        assert_eq!(state.ids(), [3, 2, 1]);
+
+        assert!(state.move_to(&1, 0));
+        assert_eq!(state.ids(), [1, 3, 2]);
+        assert!(state.move_to(&1, 10));
+        assert_eq!(state.ids(), [3, 2, 1]);
+        assert!(!state.move_to(&9, 0));
+        assert_eq!(state.position(&2), Some(1));
+    }
+
+    #[test]
+    #[cfg(any(feature = "hydrate", feature = "persist"))]
+    fn test_deserialize_repairs_inconsistent_payload() {
+        let state = letters();
+        let json = serde_json::to_string(&state).unwrap();
+        let restored: EntityState<u32, String> = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.ids(), [3, 1, 2]);
+
+        // 9 has no entity, 3 is listed twice, and 4 isn't listed at all
+        let broken = r#"{"entities":{"1":"a","3":"c","4":"d"},"ids":[3,9,1,3]}"#;
+        let mut restored: EntityState<u32, String> = serde_json::from_str(broken).unwrap();
+        assert_eq!(restored.ids(), [3, 1, 4]);
+        assert_eq!(restored.len(), restored.iter().count());
+
+        restored.sort_by_key(|letter| letter.clone());
+        assert_eq!(restored.ids(), [1, 3, 4]);
+    }
+}
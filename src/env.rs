@@ -0,0 +1,354 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2026 web-mech
+
+//! Environment-specific configuration for stores.
+//!
+//! This module lets stores read configuration (API base URLs, polling
+//! intervals, feature toggles) from a [`StoreEnv`] provided once at app
+//! startup, instead of scattering `const` URLs across store modules.
+//!
+//! # Profiles
+//!
+//! A [`StoreEnv`] is built from typed config values registered per
+//! [`Profile`]. Values registered without a profile act as defaults and are
+//! overridden by values registered for the active profile.
+//!
+//! The active profile is resolved by [`Profile::current`]:
+//!
+//! - **Server**: the `LEPTOS_STORE_PROFILE` environment variable at runtime
+//! - **Client (WASM)**: the `LEPTOS_STORE_PROFILE` variable at build time.
+//!   There is no runtime source on the client, such as an `env.js` file;
+//!   values only the deployed server knows should reach the client through
+//!   hydrated store state or a server function
+//! - Falls back to [`Profile::Development`] in debug builds and
+//!   [`Profile::Production`] in release builds
+//!
+//! # Example
+//!
+//! ```rust
+//! use leptos_store::env::{Profile, StoreEnv};
+//!
+//! #[derive(Clone, Debug, PartialEq)]
+//! struct ApiConfig {
+//!     base_url: String,
+//! }
+//!
+//! let env = StoreEnv::builder()
+//!     .config(ApiConfig { base_url: "http://localhost:8080".to_string() })
+//!     .profile(Profile::Production, |p| {
+//!         p.config(ApiConfig { base_url: "https://api.example.com".to_string() })
+//!     })
+//!     .build(Profile::Production);
+//!
+//! assert_eq!(env.profile(), &Profile::Production);
+//! assert_eq!(env.require::<ApiConfig>().unwrap().base_url, "https://api.example.com");
+//! ```
+//!
+//! In components and actions, provide the environment once and read it
+//! with type-checked access:
+//!
+//! ```rust,ignore
+//! provide_store_env(env);
+//!
+//! // Later, inside an async action, where context may be gone
+//! let env = require_action_dep::<StoreEnv>()?;
+//! let api = env.require::<ApiConfig>()?;
+//! let url = format!("{}/tokens", api.base_url);
+//! ```
+
+use crate::store::StoreError;
+use leptos::prelude::*;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+/// Name of the variable used to select the active profile.
+pub const PROFILE_ENV_VAR: &str = "LEPTOS_STORE_PROFILE";
+
+/// A named configuration profile.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Profile {
+    /// Local development.
+    Development,
+    /// Automated tests.
+    Test,
+    /// Pre-production environment.
+    Staging,
+    /// Production environment.
+    Production,
+    /// Any other named environment.
+    Custom(String),
+}
+
+impl Profile {
+    /// Parse a profile from its name.
+    ///
+    /// Common aliases (`dev`, `prod`, `stage`) are accepted; unknown names
+    /// become [`Profile::Custom`].
+    pub fn parse(name: &str) -> Self {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "development" | "dev" => Self::Development,
+            "test" => Self::Test,
+            "staging" | "stage" => Self::Staging,
+            "production" | "prod" => Self::Production,
+            other => Self::Custom(other.to_string()),
+        }
+    }
+
+    /// Resolve the active profile for the current target.
+    ///
+    /// See the [module documentation](self) for the resolution order.
+    pub fn current() -> Self {
+        #[cfg(not(target_arch = "wasm32"))]
+        let configured = std::env::var(PROFILE_ENV_VAR).ok();
+        #[cfg(target_arch = "wasm32")]
+        let configured = option_env!("LEPTOS_STORE_PROFILE").map(str::to_string);
+
+        match configured {
+            Some(name) if !name.trim().is_empty() => Self::parse(&name),
+            _ if cfg!(debug_assertions) => Self::Development,
+            _ => Self::Production,
+        }
+    }
+
+    /// Returns the profile name.
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Development => "development",
+            Self::Test => "test",
+            Self::Staging => "staging",
+            Self::Production => "production",
+            Self::Custom(name) => name,
+        }
+    }
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+type ConfigMap = HashMap<TypeId, Arc<dyn Any + Send + Sync>>;
+
+/// Resolved store configuration for the active profile.
+///
+/// Cloning is cheap; all clones share the same configuration values.
+#[derive(Clone)]
+pub struct StoreEnv {
+    profile: Profile,
+    configs: Arc<ConfigMap>,
+}
+
+impl StoreEnv {
+    /// Create a builder for a new environment.
+    pub fn builder() -> StoreEnvBuilder {
+        StoreEnvBuilder::default()
+    }
+
+    /// Create an empty environment for a profile.
+    pub fn empty(profile: Profile) -> Self {
+        Self {
+            profile,
+            configs: Arc::default(),
+        }
+    }
+
+    /// Returns the active profile.
+    pub fn profile(&self) -> &Profile {
+        &self.profile
+    }
+
+    /// Get a config value by type.
+    pub fn get<C: Send + Sync + 'static>(&self) -> Option<&C> {
+        self.configs
+            .get(&TypeId::of::<C>())
+            .and_then(|c| c.downcast_ref::<C>())
+    }
+
+    /// Get a config value by type, or an error naming the missing type.
+    pub fn require<C: Send + Sync + 'static>(&self) -> Result<&C, StoreError> {
+        self.get::<C>().ok_or_else(|| {
+            StoreError::NotFound(format!(
+                "Config {} not set for profile {}",
+                std::any::type_name::<C>(),
+                self.profile
+            ))
+        })
+    }
+
+    /// Check if a config value of the given type is set.
+    pub fn contains<C: Send + Sync + 'static>(&self) -> bool {
+        self.configs.contains_key(&TypeId::of::<C>())
+    }
+}
+
+impl fmt::Debug for StoreEnv {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StoreEnv")
+            .field("profile", &self.profile)
+            .field("configs", &self.configs.len())
+            .finish()
+    }
+}
+
+/// Config values registered for a single profile.
+#[derive(Default)]
+pub struct ProfileConfig {
+    configs: ConfigMap,
+}
+
+impl ProfileConfig {
+    /// Register a config value for this profile.
+    pub fn config<C: Send + Sync + 'static>(mut self, value: C) -> Self {
+        self.configs.insert(TypeId::of::<C>(), Arc::new(value));
+        self
+    }
+}
+
+/// Builder for [`StoreEnv`].
+#[derive(Default)]
+pub struct StoreEnvBuilder {
+    defaults: ConfigMap,
+    profiles: HashMap<Profile, ConfigMap>,
+}
+
+impl StoreEnvBuilder {
+    /// Register a default config value used by every profile.
+    pub fn config<C: Send + Sync + 'static>(mut self, value: C) -> Self {
+        self.defaults.insert(TypeId::of::<C>(), Arc::new(value));
+        self
+    }
+
+    /// Register profile-specific config values.
+    ///
+    /// Values registered here override defaults of the same type when
+    /// `profile` is active.
+    pub fn profile(
+        mut self,
+        profile: Profile,
+        configure: impl FnOnce(ProfileConfig) -> ProfileConfig,
+    ) -> Self {
+        let existing = self.profiles.remove(&profile).unwrap_or_default();
+        let configured = configure(ProfileConfig { configs: existing });
+        self.profiles.insert(profile, configured.configs);
+        self
+    }
+
+    /// Build the environment for the given profile.
+    pub fn build(mut self, profile: Profile) -> StoreEnv {
+        let mut configs = self.defaults;
+        if let Some(overrides) = self.profiles.remove(&profile) {
+            configs.extend(overrides);
+        }
+        StoreEnv {
+            profile,
+            configs: Arc::new(configs),
+        }
+    }
+
+    /// Build the environment for [`Profile::current`].
+    pub fn build_current(self) -> StoreEnv {
+        self.build(Profile::current())
+    }
+}
+
+/// Provide a store environment to the component tree.
+pub fn provide_store_env(env: StoreEnv) {
+    provide_context(env);
+}
+
+/// Access the store environment from context.
+///
+/// # Panics
+///
+/// Panics if no environment was provided. Use [`try_use_store_env`] for a
+/// non-panicking alternative.
+pub fn use_store_env() -> StoreEnv {
+    use_context::<StoreEnv>()
+        .expect("StoreEnv not found in context. Did you forget to call provide_store_env?")
+}
+
+/// Try to access the store environment from context.
+pub fn try_use_store_env() -> Result<StoreEnv, StoreError> {
+    use_context::<StoreEnv>()
+        .ok_or_else(|| StoreError::ContextNotAvailable("StoreEnv not found in context".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct ApiConfig {
+        base_url: String,
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct PollingConfig {
+        interval_ms: u64,
+    }
+
+    fn env_for(profile: Profile) -> StoreEnv {
+        StoreEnv::builder()
+            .config(ApiConfig {
+                base_url: "http://localhost".to_string(),
+            })
+            .config(PollingConfig { interval_ms: 1000 })
+            .profile(Profile::Production, |p| {
+                p.config(ApiConfig {
+                    base_url: "https://api.example.com".to_string(),
+                })
+            })
+            .build(profile)
+    }
+
+    #[test]
+    fn test_profile_parse() {
+        assert_eq!(Profile::parse("prod"), Profile::Production);
+        assert_eq!(Profile::parse(" Development "), Profile::Development);
+        assert_eq!(Profile::parse("stage"), Profile::Staging);
+        assert_eq!(Profile::parse("qa"), Profile::Custom("qa".to_string()));
+        assert_eq!(Profile::Custom("qa".to_string()).to_string(), "qa");
+    }
+
+    #[test]
+    fn test_defaults_and_overrides() {
+        let dev = env_for(Profile::Development);
+        assert_eq!(
+            dev.require::<ApiConfig>().unwrap().base_url,
+            "http://localhost"
+        );
+
+        let prod = env_for(Profile::Production);
+        assert_eq!(
+            prod.require::<ApiConfig>().unwrap().base_url,
+            "https://api.example.com"
+        );
+        // Defaults are kept when not overridden
+        assert_eq!(prod.get::<PollingConfig>().unwrap().interval_ms, 1000);
+    }
+
+    #[test]
+    fn test_missing_config_error() {
+        let env = StoreEnv::empty(Profile::Test);
+        assert!(!env.contains::<ApiConfig>());
+
+        let err = env.require::<ApiConfig>().unwrap_err();
+        assert!(err.to_string().contains("ApiConfig"));
+        assert!(err.to_string().contains("test"));
+    }
+
+    #[test]
+    fn test_env_context() {
+        let owner = Owner::new();
+        owner.with(|| {
+            assert!(try_use_store_env().is_err());
+
+            provide_store_env(env_for(Profile::Staging));
+            let env = use_store_env();
+            assert_eq!(env.profile(), &Profile::Staging);
+        });
+    }
+}
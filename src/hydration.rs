@@ -58,6 +58,16 @@
 
 #[cfg(feature = "hydrate")]
 use crate::store::Store;
+#[cfg(feature = "hydrate")]
+use leptos::prelude::*;
+#[cfg(feature = "hydrate")]
+use std::any::TypeId;
+#[cfg(feature = "hydrate")]
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+#[cfg(feature = "hydrate")]
+use std::sync::{Arc, Mutex, PoisonError};
+#[cfg(feature = "hydrate")]
+use std::time::Duration;
 use thiserror::Error;
 
 /// Errors that can occur during store hydration.
@@ -82,6 +92,31 @@ pub enum StoreHydrationError {
     /// DOM access error (WASM-specific).
     #[error("DOM error: {0}")]
     DomError(String),
+
+    /// The payload was written by a newer state schema than this build supports.
+    #[error("Unsupported state version {found} (this build supports up to {supported})")]
+    UnsupportedVersion {
+        /// The newest version this build can read.
+        supported: u32,
+        /// The version found in the payload.
+        found: u32,
+    },
+
+    /// The payload doesn't match the state's [`HydrationSchema`].
+    #[error("Invalid hydration data at `{path}`: {message}")]
+    Schema {
+        /// Where the mismatch is, e.g. `$.tokens[3].price`.
+        path: String,
+        /// What was expected there.
+        message: String,
+    },
+    /// Fresh state couldn't be fetched from the server.
+    #[error("Rehydration failed: {0}")]
+    Rehydration(String),
+
+    /// An encrypted payload couldn't be sealed or opened.
+    #[error("Payload encryption error: {0}")]
+    Encryption(String),
 }
 
 /// Trait for stores that support SSR hydration.
@@ -127,6 +162,50 @@ pub enum StoreHydrationError {
 ///     }
 /// }
 /// ```
+///
+/// # Deriving
+///
+/// `#[derive(HydratableStore)]` implements this trait for a struct holding
+/// an `RwSignal<State>` field. The state is read with `get_untracked`, so
+/// serializing doesn't subscribe the caller. Struct-level options:
+///
+/// - `#[hydrate(key = "...")]` - the [`store_key`](Self::store_key) (required)
+/// - `#[hydrate(state = field)]` - the state field, if it isn't named
+///   `state` or the only `RwSignal` field
+/// - `#[hydrate(versioned)]` - use [`serialize_versioned`] and
+///   [`deserialize_versioned`] for a [`VersionedState`]
+/// - `#[hydrate(binary)]` - use `serialize_binary` and `deserialize_binary`
+///   (postcard + base64, requires the `hydrate-binary` feature)
+/// - `#[hydrate(masked)]` - use [`serialize_masked`] and
+///   [`deserialize_masked`], so a [`HydrationMask`] can skip fields (the
+///   state must implement `Default`)
+/// - `#[hydrate(validated)]` - use [`deserialize_validated`], so payloads
+///   that don't match the [`HydrationSchema`] of `State::default()` fail
+///   with the path of the offending field
+/// - `#[hydrate(codec = Type)]` - encode the state with a [`StateCodec`]
+/// - `#[hydrate(skip_default)]` - [skip](Self::skip_hydration) the payload
+///   while the state equals `State::default()` (the state must implement
+///   `Default` and `PartialEq`)
+/// - `#[hydrate(skip_if = path)]` - skip the payload while
+///   `path(&state)` returns `true`
+/// - `#[hydrate(sanitize = path)]` - pass hydrated states through
+///   `path(state) -> State` ([`sanitize`](Self::sanitize))
+///
+/// `versioned`, `binary`, `masked`, `validated` and `codec` can't be
+/// combined; without any of them the state is plain JSON ([`JsonCodec`]).
+/// Neither can `skip_default` and `skip_if`.
+///
+/// Non-state fields are initialized with `Default::default()` when
+/// hydrating.
+///
+/// ```rust,ignore
+/// #[derive(Clone, Store, HydratableStore)]
+/// #[hydrate(key = "auth_store", state = auth)]
+/// struct AuthStore {
+///     #[store(state)]
+///     auth: RwSignal<AuthState>,
+/// }
+/// ```
 #[cfg(feature = "hydrate")]
 pub trait HydratableStore: Store + Sized {
     /// Serialize the store's state to a JSON string.
@@ -141,258 +220,2541 @@ pub trait HydratableStore: Store + Sized {
     /// store's state from the server-rendered data.
     fn from_hydrated_state(data: &str) -> Result<Self, StoreHydrationError>;
 
+    /// Replace this store's state with serialized state, in place.
+    ///
+    /// Unlike [`from_hydrated_state`](Self::from_hydrated_state), clones of
+    /// the store already handed out see the new state, and their
+    /// subscribers are notified. Used by [`rehydrate`].
+    ///
+    /// The derive and the store macros implement it; the default returns
+    /// [`StoreHydrationError::InvalidData`].
+    fn hydrate_into(&self, data: &str) -> Result<(), StoreHydrationError> {
+        let _ = data;
+        Err(StoreHydrationError::InvalidData(format!(
+            "{} can't be hydrated in place",
+            std::any::type_name::<Self>()
+        )))
+    }
+
+    /// Clean up a state right after it's deserialized on the client, before
+    /// the store sees it.
+    ///
+    /// Use it to drop fields that shouldn't stay in memory or to clamp
+    /// values coming from the page, per value rather than per type like
+    /// `#[serde(skip)]`. Defaults to returning `state` unchanged. The derive
+    /// runs it when hydrating; hand-written
+    /// [`from_hydrated_state`](Self::from_hydrated_state) and
+    /// [`hydrate_into`](Self::hydrate_into) should call it too.
+    fn sanitize(state: Self::State) -> Self::State {
+        state
+    }
+
+    /// Reconcile the state just hydrated from the server with state kept on
+    /// the client, such as persisted state.
+    ///
+    /// Called on the new store right after it's created from the page's
+    /// payload by [`hydrate_store`] (and so [`use_hydrated_store`],
+    /// [`register_hydratable`] and [`HydrationBuilder`]) or a lazily
+    /// hydrated store, before anything reads it. Defaults to doing nothing,
+    /// so the server's state wins. Stores declared with the
+    /// [`store!`](crate::store!) macro implement it with the `reconcile`
+    /// option of their `persist` section, using a
+    /// [`Reconcile`](crate::persistence::Reconcile) strategy.
+    ///
+    /// [`use_hydrated_store`]: crate::context::use_hydrated_store
+    fn reconcile(&self) {}
+
+    /// Returns `true` if the state isn't worth sending to the client, e.g.
+    /// because it's still the default.
+    ///
+    /// No payload is rendered for a skipped store, so the client creates it
+    /// through its fallback, such as the factory given to
+    /// [`register_hydratable`]. Defaults to `false`.
+    fn skip_hydration(&self) -> bool {
+        false
+    }
+
     /// Returns a unique key for this store type.
     ///
     /// This key is used to identify the store's data in the hydration
-    /// script tag. Must be unique across all stores in the application.
+    /// script tag. Must be unique across all stores in the application;
+    /// in debug builds, providing or registering two store types with the
+    /// same key panics.
     fn store_key() -> &'static str;
 }
 
-/// The ID prefix used for hydration script tags.
-pub const HYDRATION_SCRIPT_PREFIX: &str = "__LEPTOS_STORE_STATE__";
-
-/// Generate the full script element ID for a store.
+/// Derive macro for the [`HydratableStore`] trait.
+///
+/// See the [`HydratableStore`] trait documentation for the supported
+/// attributes.
 #[cfg(feature = "hydrate")]
-pub fn hydration_script_id(store_key: &str) -> String {
-    format!("{HYDRATION_SCRIPT_PREFIX}{store_key}")
-}
+pub use leptos_store_macros::HydratableStore;
 
-/// Serialize a store's state to JSON for embedding in HTML.
-///
-/// # Arguments
-///
-/// * `store` - The store to serialize
+/// Key holding the schema version in versioned state payloads.
+pub const STATE_VERSION_KEY: &str = "__version";
+
+/// Key holding the state in versioned state payloads.
+pub const STATE_KEY: &str = "state";
+
+/// Trait for state types with a schema version.
 ///
-/// # Returns
+/// Versioned state is serialized as `{"__version": N, "state": {...}}`. When
+/// an older payload is read (for example, from a cached client bundle or a
+/// server that hasn't been redeployed yet), [`migrate`](Self::migrate) is
+/// called to upgrade it. Payloads without a version envelope are treated as
+/// version `0`.
 ///
-/// A JSON string representation of the store's state.
+/// Usually implemented with the `version = N;` option of
+/// [`define_hydratable_state!`](crate::define_hydratable_state).
 #[cfg(feature = "hydrate")]
-pub fn serialize_store_state<S: HydratableStore>(store: &S) -> Result<String, StoreHydrationError> {
-    store.serialize_state()
+pub trait VersionedState: serde::Serialize + serde::de::DeserializeOwned {
+    /// The current schema version.
+    const VERSION: u32;
+
+    /// Upgrade state serialized by an older schema version.
+    fn migrate(from_version: u32, value: serde_json::Value) -> Result<Self, StoreHydrationError>;
 }
 
-/// Read hydration data from the DOM.
-///
-/// This function looks for a script tag with the store's hydration ID
-/// and extracts the serialized state data.
-///
-/// # Arguments
-///
-/// * `store_key` - The unique key for the store
-///
-/// # Returns
-///
-/// The serialized state data as a string.
-#[cfg(all(feature = "hydrate", target_arch = "wasm32"))]
-pub fn read_hydration_data(store_key: &str) -> Result<String, StoreHydrationError> {
-    use wasm_bindgen::JsCast;
+/// Serialize state to JSON for a hydration payload.
+#[cfg(feature = "hydrate")]
+pub fn serialize_state<T: serde::Serialize>(state: &T) -> Result<String, StoreHydrationError> {
+    serde_json::to_string(state).map_err(|e| StoreHydrationError::Serialization(e.to_string()))
+}
 
-    let window = web_sys::window()
-        .ok_or_else(|| StoreHydrationError::DomError("No window object".to_string()))?;
+/// Deserialize state from a JSON hydration payload.
+#[cfg(feature = "hydrate")]
+pub fn deserialize_state<T: serde::de::DeserializeOwned>(
+    data: &str,
+) -> Result<T, StoreHydrationError> {
+    serde_json::from_str(data).map_err(|e| StoreHydrationError::Deserialization(e.to_string()))
+}
 
-    let document = window
-        .document()
-        .ok_or_else(|| StoreHydrationError::DomError("No document object".to_string()))?;
+/// Serialize versioned state into a version envelope.
+#[cfg(feature = "hydrate")]
+pub fn serialize_versioned<T: VersionedState>(state: &T) -> Result<String, StoreHydrationError> {
+    let value = serde_json::to_value(state)
+        .map_err(|e| StoreHydrationError::Serialization(e.to_string()))?;
+    let mut envelope = serde_json::Map::new();
+    envelope.insert(STATE_VERSION_KEY.to_string(), T::VERSION.into());
+    envelope.insert(STATE_KEY.to_string(), value);
+    serde_json::to_string(&envelope).map_err(|e| StoreHydrationError::Serialization(e.to_string()))
+}
 
-    let script_id = hydration_script_id(store_key);
-    let element = document
-        .get_element_by_id(&script_id)
-        .ok_or_else(|| StoreHydrationError::NotFound(store_key.to_string()))?;
+/// Deserialize versioned state, migrating older payloads.
+///
+/// # Errors
+///
+/// Returns [`StoreHydrationError::UnsupportedVersion`] if the payload is
+/// newer than [`VersionedState::VERSION`].
+#[cfg(feature = "hydrate")]
+pub fn deserialize_versioned<T: VersionedState>(data: &str) -> Result<T, StoreHydrationError> {
+    let value: serde_json::Value = serde_json::from_str(data)
+        .map_err(|e| StoreHydrationError::Deserialization(e.to_string()))?;
 
-    let script = element
-        .dyn_into::<web_sys::HtmlScriptElement>()
-        .map_err(|_| StoreHydrationError::InvalidData("Element is not a script tag".to_string()))?;
+    let (version, state) = match value {
+        serde_json::Value::Object(mut map)
+            if map.len() == 2
+                && map.contains_key(STATE_KEY)
+                && map.contains_key(STATE_VERSION_KEY) =>
+        {
+            let version = map
+                .get(STATE_VERSION_KEY)
+                .and_then(serde_json::Value::as_u64)
+                .and_then(|v| u32::try_from(v).ok())
+                .ok_or_else(|| {
+                    StoreHydrationError::InvalidData(format!("Invalid {STATE_VERSION_KEY} field"))
+                })?;
+            (version, map.remove(STATE_KEY).unwrap_or_default())
+        }
+        unversioned => (0, unversioned),
+    };
 
-    let content = script.text().map_err(|e| {
-        StoreHydrationError::DomError(format!("Failed to read script content: {:?}", e))
-    })?;
+    if version > T::VERSION {
+        return Err(StoreHydrationError::UnsupportedVersion {
+            supported: T::VERSION,
+            found: version,
+        });
+    }
+    if version < T::VERSION {
+        return T::migrate(version, state);
+    }
+    serde_json::from_value(state).map_err(|e| StoreHydrationError::Deserialization(e.to_string()))
+}
 
-    Ok(content)
+/// Serialize state with postcard into a base64 hydration payload.
+///
+/// Binary payloads are usually much smaller than JSON for numeric-heavy
+/// state. Postcard isn't self-describing, so the state can't use
+/// `#[serde(flatten)]`, untagged enums, or `serde_json::Value`, and the
+/// server and client must agree on the exact state type.
+#[cfg(feature = "hydrate-binary")]
+pub fn serialize_binary<T: serde::Serialize>(state: &T) -> Result<String, StoreHydrationError> {
+    use base64::Engine;
+
+    let bytes = postcard::to_allocvec(state)
+        .map_err(|e| StoreHydrationError::Serialization(e.to_string()))?;
+    Ok(base64::engine::general_purpose::STANDARD.encode(bytes))
 }
 
-/// Stub for non-WASM targets.
-#[cfg(all(feature = "hydrate", not(target_arch = "wasm32")))]
-pub fn read_hydration_data(store_key: &str) -> Result<String, StoreHydrationError> {
-    Err(StoreHydrationError::DomError(format!(
-        "DOM access not available on this platform for key: {store_key}"
-    )))
+/// Deserialize state from a payload written by [`serialize_binary`].
+#[cfg(feature = "hydrate-binary")]
+pub fn deserialize_binary<T: serde::de::DeserializeOwned>(
+    data: &str,
+) -> Result<T, StoreHydrationError> {
+    use base64::Engine;
+
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(data.trim())
+        .map_err(|e| StoreHydrationError::InvalidData(e.to_string()))?;
+    postcard::from_bytes(&bytes).map_err(|e| StoreHydrationError::Deserialization(e.to_string()))
 }
 
-/// Hydrate a store from DOM data.
-///
-/// This function reads the serialized state from the DOM and creates
-/// a new store instance with the hydrated state.
+/// Fields left out of hydration payloads, by store key.
 ///
-/// # Type Parameters
-///
-/// * `S` - The store type to hydrate
-///
-/// # Returns
+/// Set it in the [`StoreEnv`](crate::env::StoreEnv) provided to the app,
+/// per request if needed, to drop heavy fields from the embedded state of
+/// stores that serialize with [`serialize_masked`] (or
+/// `#[hydrate(masked)]`). Skipped fields are top-level fields of the state
+/// and are restored from `State::default()` on the client.
 ///
-/// A new store instance with the hydrated state, or an error if
-/// hydration fails.
+/// ```rust,ignore
+/// let mask = if is_mobile {
+///     HydrationMask::default().skip("token_store", ["tokens"])
+/// } else {
+///     HydrationMask::default()
+/// };
+/// provide_store_env(StoreEnv::builder().config(mask).build(Profile::Production));
+/// ```
 #[cfg(feature = "hydrate")]
-pub fn hydrate_store<S: HydratableStore>() -> Result<S, StoreHydrationError> {
-    let data = read_hydration_data(S::store_key())?;
-    S::from_hydrated_state(&data)
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HydrationMask {
+    skipped: std::collections::BTreeMap<String, Vec<String>>,
 }
 
-/// Check if hydration data is available for a store.
-///
-/// This is useful for conditional hydration logic where you want
-/// to fall back to default state if no hydration data exists.
-#[cfg(all(feature = "hydrate", target_arch = "wasm32"))]
-pub fn has_hydration_data(store_key: &str) -> bool {
-    if let Some(window) = web_sys::window() {
-        if let Some(document) = window.document() {
-            let script_id = hydration_script_id(store_key);
-            return document.get_element_by_id(&script_id).is_some();
+#[cfg(feature = "hydrate")]
+impl HydrationMask {
+    /// Leave `fields` out of the payload of the store keyed `store_key`.
+    pub fn skip<I>(mut self, store_key: &str, fields: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.skipped
+            .entry(store_key.to_string())
+            .or_default()
+            .extend(fields.into_iter().map(Into::into));
+        self
+    }
+
+    /// Returns the fields skipped for the store keyed `store_key`.
+    pub fn skipped(&self, store_key: &str) -> &[String] {
+        self.skipped.get(store_key).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns the mask configured in the store environment in context.
+    pub fn current() -> Self {
+        crate::env::try_use_store_env()
+            .ok()
+            .and_then(|env| env.get::<Self>().cloned())
+            .unwrap_or_default()
+    }
+}
+
+/// Serialize state to JSON, leaving out the fields the current
+/// [`HydrationMask`] skips for `store_key`.
+#[cfg(feature = "hydrate")]
+pub fn serialize_masked<T: serde::Serialize>(
+    store_key: &str,
+    state: &T,
+) -> Result<String, StoreHydrationError> {
+    let mask = HydrationMask::current();
+    let skipped = mask.skipped(store_key);
+    if skipped.is_empty() {
+        return serialize_state(state);
+    }
+    let mut value = serde_json::to_value(state)
+        .map_err(|e| StoreHydrationError::Serialization(e.to_string()))?;
+    if let serde_json::Value::Object(map) = &mut value {
+        for field in skipped {
+            map.remove(field);
         }
     }
-    false
+    serde_json::to_string(&value).map_err(|e| StoreHydrationError::Serialization(e.to_string()))
 }
 
-/// Stub for non-WASM targets.
-#[cfg(all(feature = "hydrate", not(target_arch = "wasm32")))]
-pub fn has_hydration_data(_store_key: &str) -> bool {
-    false
+/// Deserialize state written by [`serialize_masked`], taking skipped fields
+/// from `T::default()`.
+#[cfg(feature = "hydrate")]
+pub fn deserialize_masked<T>(data: &str) -> Result<T, StoreHydrationError>
+where
+    T: serde::Serialize + serde::de::DeserializeOwned + Default,
+{
+    let value: serde_json::Value = serde_json::from_str(data)
+        .map_err(|e| StoreHydrationError::Deserialization(e.to_string()))?;
+    let mut state = serde_json::to_value(T::default())
+        .map_err(|e| StoreHydrationError::Deserialization(e.to_string()))?;
+    match (&mut state, value) {
+        (serde_json::Value::Object(defaults), serde_json::Value::Object(fields)) => {
+            defaults.extend(fields);
+        }
+        (_, value) => state = value,
+    }
+    serde_json::from_value(state).map_err(|e| StoreHydrationError::Deserialization(e.to_string()))
 }
 
-/// Generate the HTML for a hydration script tag.
-///
-/// This is used during SSR to embed the serialized store state
-/// in the HTML document.
+/// The shape of a state's JSON, generated from a sample value.
 ///
-/// # Arguments
-///
-/// * `store_key` - The unique key for the store
-/// * `data` - The serialized state data
+/// Payloads are checked against it before deserializing, so a mismatch is
+/// reported with the path of the offending field
+/// (`$.tokens[3].price: expected number, found string`) instead of a line
+/// and column in a one-line payload. [`deserialize_validated`] (or
+/// `#[hydrate(validated)]`) uses the schema of `State::default()`.
 ///
-/// # Returns
+/// The schema is only as precise as its sample: `null` values (such as
+/// `None` fields) and empty arrays accept anything, and the other fields of
+/// the sample are required. Use [`allow`](Self::allow) for fields whose
+/// shape varies, such as enums with data.
 ///
-/// An HTML string containing the script tag with the embedded data.
+/// ```rust,ignore
+/// let state: TokenState = HydrationSchema::of::<TokenState>()?
+///     .allow("filter")
+///     .deserialize(&data)?;
+/// ```
 #[cfg(feature = "hydrate")]
-pub fn hydration_script_html(store_key: &str, data: &str) -> String {
-    let script_id = hydration_script_id(store_key);
-    // Escape any script closing tags in the data
-    let escaped_data = data.replace("</script>", "<\\/script>");
-    format!(r#"<script id="{script_id}" type="application/json">{escaped_data}</script>"#)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HydrationSchema {
+    root: SchemaNode,
 }
 
-/// A builder for creating hydration-aware stores.
-///
-/// This builder provides a fluent API for creating stores that
-/// automatically handle hydration on the client.
 #[cfg(feature = "hydrate")]
-pub struct HydrationBuilder<S: HydratableStore> {
-    fallback: Option<S>,
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum SchemaNode {
+    Any,
+    Bool,
+    Integer,
+    Number,
+    String,
+    Array(Box<SchemaNode>),
+    Object(std::collections::BTreeMap<String, SchemaNode>),
 }
 
 #[cfg(feature = "hydrate")]
-impl<S: HydratableStore> Default for HydrationBuilder<S> {
-    fn default() -> Self {
-        Self::new()
+impl SchemaNode {
+    fn from_value(value: &serde_json::Value) -> Self {
+        use serde_json::Value;
+        match value {
+            Value::Null => Self::Any,
+            Value::Bool(_) => Self::Bool,
+            Value::Number(n) if n.is_f64() => Self::Number,
+            Value::Number(_) => Self::Integer,
+            Value::String(_) => Self::String,
+            Value::Array(items) => {
+                Self::Array(Box::new(items.first().map_or(Self::Any, Self::from_value)))
+            }
+            Value::Object(fields) => Self::Object(
+                fields
+                    .iter()
+                    .map(|(name, value)| (name.clone(), Self::from_value(value)))
+                    .collect(),
+            ),
+        }
+    }
+
+    fn describe(&self) -> &'static str {
+        match self {
+            Self::Any => "anything",
+            Self::Bool => "a boolean",
+            Self::Integer => "an integer",
+            Self::Number => "a number",
+            Self::String => "a string",
+            Self::Array(_) => "an array",
+            Self::Object(_) => "an object",
+        }
+    }
+
+    fn validate(&self, value: &serde_json::Value, path: &str) -> Result<(), StoreHydrationError> {
+        use serde_json::Value;
+        let matches = match (self, value) {
+            (Self::Any, _) | (Self::Bool, Value::Bool(_)) | (Self::String, Value::String(_)) => {
+                true
+            }
+            (Self::Number, Value::Number(_)) => true,
+            (Self::Integer, Value::Number(n)) => !n.is_f64(),
+            (Self::Array(item), Value::Array(items)) => {
+                for (i, value) in items.iter().enumerate() {
+                    item.validate(value, &format!("{path}[{i}]"))?;
+                }
+                true
+            }
+            (Self::Object(fields), Value::Object(values)) => {
+                for (name, field) in fields {
+                    match values.get(name) {
+                        Some(value) => field.validate(value, &format!("{path}.{name}"))?,
+                        None if *field == Self::Any => {}
+                        None => {
+                            return Err(StoreHydrationError::Schema {
+                                path: path.to_string(),
+                                message: format!("missing field `{name}`"),
+                            });
+                        }
+                    }
+                }
+                true
+            }
+            _ => false,
+        };
+        if matches {
+            return Ok(());
+        }
+        let found = match value {
+            Value::Null => "null",
+            Value::Bool(_) => "a boolean",
+            Value::Number(_) => "a number",
+            Value::String(_) => "a string",
+            Value::Array(_) => "an array",
+            Value::Object(_) => "an object",
+        };
+        Err(StoreHydrationError::Schema {
+            path: path.to_string(),
+            message: format!("expected {}, found {found}", self.describe()),
+        })
     }
 }
 
 #[cfg(feature = "hydrate")]
-impl<S: HydratableStore> HydrationBuilder<S> {
-    /// Create a new hydration builder.
-    pub fn new() -> Self {
-        Self { fallback: None }
+impl HydrationSchema {
+    /// Generate the schema of `sample`'s JSON.
+    pub fn from_sample<T: serde::Serialize>(sample: &T) -> Result<Self, StoreHydrationError> {
+        let value = serde_json::to_value(sample)
+            .map_err(|e| StoreHydrationError::Serialization(e.to_string()))?;
+        Ok(Self {
+            root: SchemaNode::from_value(&value),
+        })
     }
 
-    /// Set a fallback store to use if hydration fails.
-    ///
-    /// If hydration data is not found or deserialization fails,
-    /// this fallback store will be used instead.
-    pub fn with_fallback(mut self, store: S) -> Self {
-        self.fallback = Some(store);
-        self
+    /// Generate the schema of `T::default()`.
+    pub fn of<T: serde::Serialize + Default>() -> Result<Self, StoreHydrationError> {
+        Self::from_sample(&T::default())
     }
 
-    /// Build the store, attempting hydration first.
-    ///
-    /// This will try to hydrate from DOM data. If hydration fails
-    /// and a fallback was provided, the fallback will be used.
-    ///
-    /// # Panics
+    /// Accept anything at `path`, and don't require it.
     ///
-    /// Panics if hydration fails and no fallback was provided.
-    pub fn build(self) -> S {
-        match hydrate_store::<S>() {
-            Ok(store) => store,
-            Err(e) => {
-                if let Some(fallback) = self.fallback {
-                    fallback
-                } else {
-                    panic!("Store hydration failed and no fallback provided: {e}")
+    /// Fields are separated by `.` and array items are written `[]`, e.g.
+    /// `tokens[].metadata`. Paths not in the schema are ignored.
+    pub fn allow(mut self, path: &str) -> Self {
+        let mut node = &mut self.root;
+        for segment in path.split('.').filter(|s| !s.is_empty()) {
+            let name = segment.trim_end_matches("[]");
+            if !name.is_empty() {
+                match node {
+                    SchemaNode::Object(fields) if fields.contains_key(name) => {
+                        node = fields.get_mut(name).expect("field exists");
+                    }
+                    _ => return self,
                 }
             }
-        }
-    }
-
-    /// Build the store, returning a Result.
-    ///
-    /// This will try to hydrate from DOM data. If hydration fails
-    /// and a fallback was provided, the fallback will be returned.
-    pub fn try_build(self) -> Result<S, StoreHydrationError> {
-        match hydrate_store::<S>() {
-            Ok(store) => Ok(store),
-            Err(e) => {
-                if let Some(fallback) = self.fallback {
-                    Ok(fallback)
-                } else {
-                    Err(e)
+            for _ in 0..(segment.len() - name.len()) / 2 {
+                match node {
+                    SchemaNode::Array(item) => node = item,
+                    _ => return self,
                 }
             }
         }
+        *node = SchemaNode::Any;
+        self
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
 
-    #[test]
-    fn test_hydration_error_display() {
-        let err = StoreHydrationError::Serialization("test error".to_string());
-        assert_eq!(err.to_string(), "Serialization error: test error");
+    /// Check `value` against the schema.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StoreHydrationError::Schema`] naming the first field that
+    /// doesn't match.
+    pub fn validate(&self, value: &serde_json::Value) -> Result<(), StoreHydrationError> {
+        self.root.validate(value, "$")
+    }
 
-        let err = StoreHydrationError::Deserialization("parse error".to_string());
-        assert_eq!(err.to_string(), "Deserialization error: parse error");
+    /// Parse `data`, check it against the schema, then deserialize it.
+    pub fn deserialize<T: serde::de::DeserializeOwned>(
+        &self,
+        data: &str,
+    ) -> Result<T, StoreHydrationError> {
+        let value: serde_json::Value = serde_json::from_str(data)
+            .map_err(|e| StoreHydrationError::Deserialization(e.to_string()))?;
+        self.validate(&value)?;
+        serde_json::from_value(value)
+            .map_err(|e| StoreHydrationError::Deserialization(e.to_string()))
+    }
+}
 
-        let err = StoreHydrationError::NotFound("my_store".to_string());
-        assert_eq!(
-            err.to_string(),
-            "Hydration data not found for key: my_store"
-        );
+/// Deserialize a JSON hydration payload after checking it against the
+/// [`HydrationSchema`] of `T::default()`.
+#[cfg(feature = "hydrate")]
+pub fn deserialize_validated<T>(data: &str) -> Result<T, StoreHydrationError>
+where
+    T: serde::Serialize + serde::de::DeserializeOwned + Default,
+{
+    HydrationSchema::of::<T>()?.deserialize(data)
+}
 
-        let err = StoreHydrationError::InvalidData("bad format".to_string());
-        assert_eq!(err.to_string(), "Invalid hydration data: bad format");
+/// Longest payload excerpt shown by [`HydrationDiagnostics`], in bytes.
+pub const DIAGNOSTIC_EXCERPT_LEN: usize = 80;
 
-        let err = StoreHydrationError::DomError("no window".to_string());
-        assert_eq!(err.to_string(), "DOM error: no window");
-    }
+/// What is known about a payload that failed to hydrate, for debugging.
+///
+/// Debug builds log these diagnostics whenever a payload from the page
+/// can't be turned into a store, instead of just the serde message with
+/// a line and column in a one-line payload. The `Display` output names the
+/// store, where in the state the error is, an excerpt of the payload around
+/// it, and the likely cause when it's a common one.
+///
+/// ```text
+/// Store `auth_store` failed to hydrate: missing field `token` at line 1 column 16
+///   at: $.user
+///   payload: {"user":{"id":1}}
+///   hint: the server didn't send `token` ...
+/// ```
+#[cfg(feature = "hydrate")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HydrationDiagnostics {
+    /// The store's key.
+    pub store_key: String,
+    /// The error, as displayed.
+    pub error: String,
+    /// The JSON path where the error is, e.g. `$.tokens[3].price`.
+    pub path: Option<String>,
+    /// The payload around the error, or its beginning.
+    pub excerpt: String,
+    /// The likely cause, for common mistakes.
+    pub hint: Option<String>,
+}
 
-    #[test]
-    fn test_hydration_script_id() {
-        #[cfg(feature = "hydrate")]
-        {
-            let id = hydration_script_id("my_store");
-            assert_eq!(id, "__LEPTOS_STORE_STATE__my_store");
+#[cfg(feature = "hydrate")]
+impl HydrationDiagnostics {
+    /// Diagnose `error`, raised while hydrating the store `store_key` from
+    /// `data`.
+    pub fn new(store_key: &str, data: &str, error: &StoreHydrationError) -> Self {
+        let message = match error {
+            StoreHydrationError::Deserialization(message) => Some(message.as_str()),
+            _ => None,
+        };
+        let offset = message.and_then(|message| error_offset(data, message));
+        // A missing field is reported at the end of its object: include
+        // the `}` so the path is the object's
+        let missing = message.is_some_and(|message| message.starts_with("missing field"));
+        let path = match error {
+            StoreHydrationError::Schema { path, .. } => Some(path.clone()),
+            _ => offset.map(|offset| json_path_at(data, offset + usize::from(missing))),
+        };
+        Self {
+            store_key: store_key.to_string(),
+            error: error.to_string(),
+            path,
+            excerpt: excerpt(data, offset.unwrap_or(0)),
+            hint: message.and_then(|message| deserialization_hint(data, message)),
         }
     }
+}
 
-    #[test]
-    fn test_hydration_script_html() {
-        #[cfg(feature = "hydrate")]
-        {
-            let html = hydration_script_html("counter", r#"{"count":42}"#);
-            assert!(html.contains(r#"id="__LEPTOS_STORE_STATE__counter""#));
-            assert!(html.contains(r#"type="application/json""#));
-            assert!(html.contains(r#"{"count":42}"#));
+#[cfg(feature = "hydrate")]
+impl std::fmt::Display for HydrationDiagnostics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Store `{}` failed to hydrate: {}",
+            self.store_key, self.error
+        )?;
+        if let Some(path) = &self.path {
+            write!(f, "\n  at: {path}")?;
         }
+        write!(f, "\n  payload: {}", self.excerpt)?;
+        if let Some(hint) = &self.hint {
+            write!(f, "\n  hint: {hint}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the byte offset of the last character serde_json read, from the
+/// `at line L column C` suffix of its error messages.
+#[cfg(feature = "hydrate")]
+fn error_offset(data: &str, message: &str) -> Option<usize> {
+    let (_, position) = message.rsplit_once(" at line ")?;
+    let (line, column) = position.split_once(" column ")?;
+    let line: usize = line.parse().ok()?;
+    let column: usize = column.trim().parse().ok()?;
+    let line_start: usize = data
+        .split_inclusive('\n')
+        .take(line.checked_sub(1)?)
+        .map(str::len)
+        .sum();
+    Some((line_start + column.saturating_sub(1)).min(data.len()))
+}
+
+/// Returns the JSON path of the value `data` is in at byte `offset`.
+#[cfg(feature = "hydrate")]
+fn json_path_at(data: &str, offset: usize) -> String {
+    enum Frame {
+        Object { key: Option<String>, in_key: bool },
+        Array(usize),
+    }
+
+    let mut stack: Vec<Frame> = Vec::new();
+    let mut chars = data.char_indices().take_while(|(i, _)| *i < offset);
+    while let Some((_, c)) = chars.next() {
+        match c {
+            '"' => {
+                let mut text = String::new();
+                let mut escaped = false;
+                for (_, c) in chars.by_ref() {
+                    match c {
+                        _ if escaped => {
+                            escaped = false;
+                            text.push(c);
+                        }
+                        '\\' => escaped = true,
+                        '"' => break,
+                        _ => text.push(c),
+                    }
+                }
+                if let Some(Frame::Object { key, in_key: true }) = stack.last_mut() {
+                    *key = Some(text);
+                }
+            }
+            '{' => stack.push(Frame::Object {
+                key: None,
+                in_key: true,
+            }),
+            '[' => stack.push(Frame::Array(0)),
+            '}' | ']' => {
+                stack.pop();
+            }
+            ':' => {
+                if let Some(Frame::Object { in_key, .. }) = stack.last_mut() {
+                    *in_key = false;
+                }
+            }
+            ',' => match stack.last_mut() {
+                Some(Frame::Object { key, in_key }) => {
+                    *key = None;
+                    *in_key = true;
+                }
+                Some(Frame::Array(index)) => *index += 1,
+                None => {}
+            },
+            _ => {}
+        }
+    }
+
+    let mut path = String::from("$");
+    for frame in &stack {
+        match frame {
+            Frame::Object { key: Some(key), .. } => {
+                path.push('.');
+                path.push_str(key);
+            }
+            Frame::Object { key: None, .. } => {}
+            Frame::Array(index) => path.push_str(&format!("[{index}]")),
+        }
+    }
+    path
+}
+
+/// Returns about [`DIAGNOSTIC_EXCERPT_LEN`] bytes of `data` around `offset`.
+#[cfg(feature = "hydrate")]
+fn excerpt(data: &str, offset: usize) -> String {
+    let floor = |mut i: usize| {
+        while !data.is_char_boundary(i) {
+            i -= 1;
+        }
+        i
+    };
+    let start = floor(offset.saturating_sub(DIAGNOSTIC_EXCERPT_LEN / 2));
+    let end = floor((start + DIAGNOSTIC_EXCERPT_LEN).min(data.len()));
+    let mut excerpt = String::new();
+    if start > 0 {
+        excerpt.push('…');
+    }
+    excerpt.push_str(&data[start..end]);
+    if end < data.len() {
+        excerpt.push('…');
+    }
+    excerpt
+}
+
+/// Explain common causes of serde errors in hydration payloads.
+#[cfg(feature = "hydrate")]
+fn deserialization_hint(data: &str, message: &str) -> Option<String> {
+    let field = |prefix: &str| {
+        let rest = message.strip_prefix(prefix)?;
+        rest.split('`').next().map(str::to_string)
+    };
+    if let Some(field) = field("missing field `") {
+        return Some(format!(
+            "the server didn't send `{field}`; if it's skipped when serializing \
+             (`#[serde(skip)]`, `skip_serializing_if`), add `#[serde(default)]` \
+             so the client can fill it in"
+        ));
+    }
+    if let Some(field) = field("unknown field `") {
+        return Some(format!(
+            "the client's state has no `{field}`; server and client were likely \
+             built from different versions of the state"
+        ));
+    }
+    if message.starts_with("invalid type") || message.starts_with("invalid value") {
+        return Some(
+            "the value doesn't fit the client's field type; server and client were \
+             likely built from different versions of the state"
+                .to_string(),
+        );
+    }
+    if !data.trim_start().starts_with(['{', '[', '"']) {
+        return Some(
+            "the payload isn't JSON; check that server and client use the same \
+             codec and `hydrate-*` features"
+                .to_string(),
+        );
+    }
+    None
+}
+
+/// Create a store from a payload read from the page, logging diagnostics
+/// in debug builds if that fails.
+#[cfg(feature = "hydrate")]
+pub(crate) fn from_payload<S: HydratableStore>(
+    store_key: &str,
+    data: &str,
+) -> Result<S, StoreHydrationError> {
+    report_parse(store_key, data, || S::from_hydrated_state(data))
+        .inspect_err(|e| log_diagnostics(store_key, data, e))
+}
+
+/// Replace a store's state with a payload read from the page, in place,
+/// logging diagnostics in debug builds if that fails.
+#[cfg(feature = "hydrate")]
+pub(crate) fn payload_into<S: HydratableStore>(
+    store: &S,
+    store_key: &str,
+    data: &str,
+) -> Result<(), StoreHydrationError> {
+    report_parse(store_key, data, || store.hydrate_into(data))
+        .inspect_err(|e| log_diagnostics(store_key, data, e))
+}
+
+#[cfg(feature = "hydrate")]
+fn log_diagnostics(store_key: &str, data: &str, error: &StoreHydrationError) {
+    #[cfg(debug_assertions)]
+    leptos::logging::warn!("{}", HydrationDiagnostics::new(store_key, data, error));
+    #[cfg(not(debug_assertions))]
+    let _ = (store_key, data, error);
+}
+
+/// The cost of hydrating one store, reported to [`on_hydration_event`]
+/// listeners.
+///
+/// The server reports each state it serializes into the page, the client
+/// each state it parses from it, including states streamed in after the
+/// shell. Failures aren't reported; they are logged instead.
+#[cfg(feature = "hydrate")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HydrationEvent<'a> {
+    /// The server serialized a store's state for the page.
+    Serialized {
+        /// The key of the store, or of the island store instance.
+        store_key: &'a str,
+        /// The size of the serialized state, before it's escaped for HTML.
+        bytes: usize,
+        /// How long serializing took.
+        duration: Duration,
+    },
+    /// The client parsed a store's state from the page.
+    Parsed {
+        /// The key of the store, or of the island store instance.
+        store_key: &'a str,
+        /// The size of the serialized state.
+        bytes: usize,
+        /// How long parsing took.
+        duration: Duration,
+    },
+}
+
+#[cfg(feature = "hydrate")]
+impl<'a> HydrationEvent<'a> {
+    /// Returns the key of the store the event is about.
+    pub fn store_key(&self) -> &'a str {
+        match self {
+            Self::Serialized { store_key, .. } | Self::Parsed { store_key, .. } => store_key,
+        }
+    }
+
+    /// Returns the size of the serialized state.
+    pub fn bytes(&self) -> usize {
+        match self {
+            Self::Serialized { bytes, .. } | Self::Parsed { bytes, .. } => *bytes,
+        }
+    }
+
+    /// Returns how long serializing or parsing took.
+    pub fn duration(&self) -> Duration {
+        match self {
+            Self::Serialized { duration, .. } | Self::Parsed { duration, .. } => *duration,
+        }
+    }
+}
+
+#[cfg(feature = "hydrate")]
+type HydrationListener = Arc<dyn Fn(&HydrationEvent<'_>) + Send + Sync>;
+
+#[cfg(feature = "hydrate")]
+static HYDRATION_LISTENERS: Mutex<Vec<(u64, HydrationListener)>> = Mutex::new(Vec::new());
+#[cfg(feature = "hydrate")]
+static HYDRATION_LISTENER_COUNT: AtomicUsize = AtomicUsize::new(0);
+#[cfg(feature = "hydrate")]
+static NEXT_HYDRATION_LISTENER_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Register a listener called with the payload size and serialization or
+/// parse time of every hydrated store.
+///
+/// Like [`on_action_event`](crate::middleware::on_action_event), the
+/// listener is global and stays registered until the returned subscription
+/// is dropped; call [`HydrationSubscription::detach`] to keep it for the
+/// lifetime of the app. Forward the events to your RUM or metrics client
+/// to track what hydration costs in production.
+///
+/// ```rust
+/// use leptos_store::hydration::{HydrationEvent, on_hydration_event};
+///
+/// on_hydration_event(|event| {
+///     if let HydrationEvent::Parsed { store_key, bytes, duration } = event {
+///         eprintln!("parsed {bytes} bytes of `{store_key}` in {duration:?}");
+///     }
+/// })
+/// .detach();
+/// ```
+#[cfg(feature = "hydrate")]
+pub fn on_hydration_event(
+    listener: impl Fn(&HydrationEvent<'_>) + Send + Sync + 'static,
+) -> HydrationSubscription {
+    let id = NEXT_HYDRATION_LISTENER_ID.fetch_add(1, Ordering::Relaxed);
+    let mut listeners = HYDRATION_LISTENERS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    listeners.push((id, Arc::new(listener)));
+    HYDRATION_LISTENER_COUNT.store(listeners.len(), Ordering::Release);
+    HydrationSubscription { id: Some(id) }
+}
+
+/// A registered hydration event listener, removed when dropped.
+#[cfg(feature = "hydrate")]
+#[must_use = "the listener is removed when the subscription is dropped"]
+#[derive(Debug)]
+pub struct HydrationSubscription {
+    id: Option<u64>,
+}
+
+#[cfg(feature = "hydrate")]
+impl HydrationSubscription {
+    /// Keep the listener registered for the lifetime of the app.
+    pub fn detach(mut self) {
+        self.id = None;
+    }
+}
+
+#[cfg(feature = "hydrate")]
+impl Drop for HydrationSubscription {
+    fn drop(&mut self) {
+        let Some(id) = self.id else {
+            return;
+        };
+        let mut listeners = HYDRATION_LISTENERS
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        listeners.retain(|(listener_id, _)| *listener_id != id);
+        HYDRATION_LISTENER_COUNT.store(listeners.len(), Ordering::Release);
+    }
+}
+
+#[cfg(feature = "hydrate")]
+fn notify_hydration(event: HydrationEvent<'_>) {
+    let listeners: Vec<HydrationListener> = HYDRATION_LISTENERS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .iter()
+        .map(|(_, listener)| Arc::clone(listener))
+        .collect();
+    for listener in listeners {
+        listener(&event);
+    }
+}
+
+/// A monotonic clock for timing hydration, which `std::time::Instant`
+/// isn't in the browser.
+#[cfg(feature = "hydrate")]
+struct Stopwatch {
+    #[cfg(not(target_arch = "wasm32"))]
+    started: std::time::Instant,
+    #[cfg(target_arch = "wasm32")]
+    started_ms: f64,
+}
+
+#[cfg(feature = "hydrate")]
+impl Stopwatch {
+    fn start() -> Self {
+        Self {
+            #[cfg(not(target_arch = "wasm32"))]
+            started: std::time::Instant::now(),
+            #[cfg(target_arch = "wasm32")]
+            started_ms: Self::now_ms(),
+        }
+    }
+
+    fn elapsed(&self) -> Duration {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.started.elapsed()
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            Duration::from_secs_f64((Self::now_ms() - self.started_ms).max(0.0) / 1000.0)
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn now_ms() -> f64 {
+        web_sys::window()
+            .and_then(|window| window.performance())
+            .map_or_else(js_sys::Date::now, |performance| performance.now())
+    }
+}
+
+/// Serialize a store's state for the page, reporting the cost to
+/// [`on_hydration_event`] listeners.
+#[cfg(feature = "hydrate")]
+pub(crate) fn report_serialize(
+    store_key: &str,
+    serialize: impl FnOnce() -> Result<String, StoreHydrationError>,
+) -> Result<String, StoreHydrationError> {
+    if HYDRATION_LISTENER_COUNT.load(Ordering::Acquire) == 0 {
+        return serialize();
+    }
+    let stopwatch = Stopwatch::start();
+    let data = serialize()?;
+    notify_hydration(HydrationEvent::Serialized {
+        store_key,
+        bytes: data.len(),
+        duration: stopwatch.elapsed(),
+    });
+    Ok(data)
+}
+
+/// Parse a store's state from the page, reporting the cost to
+/// [`on_hydration_event`] listeners.
+#[cfg(feature = "hydrate")]
+pub(crate) fn report_parse<T>(
+    store_key: &str,
+    data: &str,
+    parse: impl FnOnce() -> Result<T, StoreHydrationError>,
+) -> Result<T, StoreHydrationError> {
+    if HYDRATION_LISTENER_COUNT.load(Ordering::Acquire) == 0 {
+        return parse();
+    }
+    let stopwatch = Stopwatch::start();
+    let parsed = parse()?;
+    notify_hydration(HydrationEvent::Parsed {
+        store_key,
+        bytes: data.len(),
+        duration: stopwatch.elapsed(),
+    });
+    Ok(parsed)
+}
+
+/// How a state of type `T` is written into and read from a hydration
+/// payload.
+///
+/// `#[hydrate(codec = MyCodec)]` makes the derived [`HydratableStore`] use
+/// a codec, so a different format (simd-json, rkyv, encrypted JSON) is
+/// written once and shared by every store instead of hand-implementing the
+/// trait per store. Codecs are types rather than values, and can be
+/// implemented for every serde type or only for the states they support.
+///
+/// ```rust,ignore
+/// struct SealedCodec;
+///
+/// impl<T: Serialize + DeserializeOwned> StateCodec<T> for SealedCodec {
+///     fn encode(state: &T) -> Result<String, StoreHydrationError> {
+///         Ok(seal(&JsonCodec::encode(state)?))
+///     }
+///
+///     fn decode(data: &str) -> Result<T, StoreHydrationError> {
+///         JsonCodec::decode(&unseal(data)?)
+///     }
+/// }
+///
+/// #[derive(Clone, Store, HydratableStore)]
+/// #[hydrate(key = "session", codec = SealedCodec)]
+/// struct SessionStore {
+///     state: RwSignal<SessionState>,
+/// }
+/// ```
+#[cfg(feature = "hydrate")]
+pub trait StateCodec<T> {
+    /// Encode `state` as payload text.
+    fn encode(state: &T) -> Result<String, StoreHydrationError>;
+
+    /// Decode a payload written by [`encode`](Self::encode).
+    fn decode(data: &str) -> Result<T, StoreHydrationError>;
+}
+
+/// Plain JSON, with [`serialize_state`] and [`deserialize_state`]. The
+/// default codec.
+#[cfg(feature = "hydrate")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct JsonCodec;
+
+#[cfg(feature = "hydrate")]
+impl<T: serde::Serialize + serde::de::DeserializeOwned> StateCodec<T> for JsonCodec {
+    fn encode(state: &T) -> Result<String, StoreHydrationError> {
+        serialize_state(state)
+    }
+
+    fn decode(data: &str) -> Result<T, StoreHydrationError> {
+        deserialize_state(data)
+    }
+}
+
+/// JSON with a schema version, with [`serialize_versioned`] and
+/// [`deserialize_versioned`].
+#[cfg(feature = "hydrate")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct VersionedCodec;
+
+#[cfg(feature = "hydrate")]
+impl<T: VersionedState> StateCodec<T> for VersionedCodec {
+    fn encode(state: &T) -> Result<String, StoreHydrationError> {
+        serialize_versioned(state)
+    }
+
+    fn decode(data: &str) -> Result<T, StoreHydrationError> {
+        deserialize_versioned(data)
+    }
+}
+
+/// JSON checked against the [`HydrationSchema`] of `T::default()` when
+/// decoding, with [`deserialize_validated`].
+#[cfg(feature = "hydrate")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ValidatedCodec;
+
+#[cfg(feature = "hydrate")]
+impl<T> StateCodec<T> for ValidatedCodec
+where
+    T: serde::Serialize + serde::de::DeserializeOwned + Default,
+{
+    fn encode(state: &T) -> Result<String, StoreHydrationError> {
+        serialize_state(state)
+    }
+
+    fn decode(data: &str) -> Result<T, StoreHydrationError> {
+        deserialize_validated(data)
+    }
+}
+
+/// Postcard + base64, with `serialize_binary` and `deserialize_binary`.
+#[cfg(feature = "hydrate-binary")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BinaryCodec;
+
+#[cfg(feature = "hydrate-binary")]
+impl<T: serde::Serialize + serde::de::DeserializeOwned> StateCodec<T> for BinaryCodec {
+    fn encode(state: &T) -> Result<String, StoreHydrationError> {
+        serialize_binary(state)
+    }
+
+    fn decode(data: &str) -> Result<T, StoreHydrationError> {
+        deserialize_binary(data)
+    }
+}
+
+/// Encrypts and decrypts the payloads of [`EncryptedCodec`].
+///
+/// leptos-store doesn't ship a cipher: implement this with an AEAD from
+/// your crypto crate (AES-GCM, ChaCha20-Poly1305), generating a fresh
+/// nonce per call and prepending it to the ciphertext. `decrypt` must fail
+/// on tampered or foreign data rather than return garbage.
+///
+/// ```rust,ignore
+/// struct Aes;
+///
+/// fn error(e: impl std::fmt::Display) -> StoreHydrationError {
+///     StoreHydrationError::Encryption(e.to_string())
+/// }
+///
+/// impl PayloadCipher for Aes {
+///     fn encrypt(key: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, StoreHydrationError> {
+///         let cipher = Aes256Gcm::new_from_slice(key).map_err(error)?;
+///         let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+///         let mut sealed = nonce.to_vec();
+///         sealed.extend(cipher.encrypt(&nonce, plaintext).map_err(error)?);
+///         Ok(sealed)
+///     }
+///
+///     fn decrypt(key: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, StoreHydrationError> {
+///         if ciphertext.len() < 12 {
+///             return Err(error("payload too short"));
+///         }
+///         let (nonce, sealed) = ciphertext.split_at(12);
+///         let cipher = Aes256Gcm::new_from_slice(key).map_err(error)?;
+///         cipher.decrypt(nonce.into(), sealed).map_err(error)
+///     }
+/// }
+/// ```
+#[cfg(feature = "hydrate-encrypt")]
+pub trait PayloadCipher {
+    /// Encrypt `plaintext` with `key`.
+    fn encrypt(key: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, StoreHydrationError>;
+
+    /// Decrypt data written by [`encrypt`](Self::encrypt) with the same key.
+    fn decrypt(key: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, StoreHydrationError>;
+}
+
+/// The per-session key [`EncryptedCodec`] uses, provided with
+/// [`provide_hydration_key`].
+#[cfg(feature = "hydrate-encrypt")]
+#[derive(Clone)]
+pub struct HydrationKey(Arc<[u8]>);
+
+#[cfg(feature = "hydrate-encrypt")]
+impl std::fmt::Debug for HydrationKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("HydrationKey(..)")
+    }
+}
+
+/// Provide the key encrypted payloads are sealed and opened with.
+///
+/// Call it on both sides before the stores using [`EncryptedCodec`] are
+/// provided or hydrated. The key must never travel in the HTML: on the
+/// server, derive it from the session (e.g. an HKDF of a secret and the
+/// session ID); on the client, get it from a channel scripts in the page
+/// can't read, like a server function answering only with the HttpOnly
+/// session cookie.
+#[cfg(feature = "hydrate-encrypt")]
+pub fn provide_hydration_key(key: impl Into<Vec<u8>>) {
+    provide_context(HydrationKey(key.into().into()));
+}
+
+/// A codec encrypting another codec's payload with a [`PayloadCipher`].
+///
+/// The payload is `C::encrypt(key, Inner::encode(state))` in base64, with
+/// the key from [`provide_hydration_key`]. Without a key in context,
+/// encoding and decoding fail with [`StoreHydrationError::Encryption`], so
+/// a store never silently falls back to plain text.
+///
+/// ```rust,ignore
+/// #[derive(Clone, Store, HydratableStore)]
+/// #[hydrate(key = "session", codec = EncryptedCodec<Aes>)]
+/// struct SessionStore {
+///     state: RwSignal<SessionState>,
+/// }
+/// ```
+#[cfg(feature = "hydrate-encrypt")]
+pub struct EncryptedCodec<C, Inner = JsonCodec>(std::marker::PhantomData<(C, Inner)>);
+
+#[cfg(feature = "hydrate-encrypt")]
+impl<C, Inner> EncryptedCodec<C, Inner> {
+    /// The key in context.
+    fn key() -> Result<HydrationKey, StoreHydrationError> {
+        use_context::<HydrationKey>().ok_or_else(|| {
+            StoreHydrationError::Encryption(
+                "no hydration key in context; call provide_hydration_key".to_string(),
+            )
+        })
+    }
+}
+
+#[cfg(feature = "hydrate-encrypt")]
+impl<T, C: PayloadCipher, Inner: StateCodec<T>> StateCodec<T> for EncryptedCodec<C, Inner> {
+    fn encode(state: &T) -> Result<String, StoreHydrationError> {
+        use base64::Engine;
+
+        let key = Self::key()?;
+        let sealed = C::encrypt(&key.0, Inner::encode(state)?.as_bytes())?;
+        Ok(base64::engine::general_purpose::STANDARD.encode(sealed))
+    }
+
+    fn decode(data: &str) -> Result<T, StoreHydrationError> {
+        use base64::Engine;
+
+        let key = Self::key()?;
+        let sealed = base64::engine::general_purpose::STANDARD
+            .decode(data.trim())
+            .map_err(|e| StoreHydrationError::InvalidData(e.to_string()))?;
+        let plaintext = String::from_utf8(C::decrypt(&key.0, &sealed)?)
+            .map_err(|e| StoreHydrationError::Encryption(e.to_string()))?;
+        Inner::decode(&plaintext)
+    }
+}
+
+/// The ID prefix used for hydration script tags.
+pub const HYDRATION_SCRIPT_PREFIX: &str = "__LEPTOS_STORE_STATE__";
+
+/// Generate the full script element ID for a store.
+#[cfg(feature = "hydrate")]
+pub fn hydration_script_id(store_key: &str) -> String {
+    format!("{HYDRATION_SCRIPT_PREFIX}{store_key}")
+}
+
+/// Generate the key of a store's state in the island instance `instance`,
+/// as used by [`provide_island_store`](crate::context::provide_island_store).
+#[cfg(feature = "hydrate")]
+pub fn island_store_key(store_key: &str, instance: &str) -> String {
+    format!("{store_key}@{instance}")
+}
+
+/// Generate the name of the attribute carrying a store's state on an
+/// island root, as used by
+/// [`provide_island_store_attrs`](crate::context::provide_island_store_attrs).
+///
+/// The instance is carried in the attribute of the same name suffixed with
+/// `-instance`.
+#[cfg(feature = "hydrate")]
+pub fn island_store_attribute(store_key: &str) -> String {
+    format!("data-store-{store_key}")
+}
+
+/// Serialize a store's state to JSON for embedding in HTML.
+///
+/// # Arguments
+///
+/// * `store` - The store to serialize
+///
+/// # Returns
+///
+/// A JSON string representation of the store's state. The cost is reported
+/// to [`on_hydration_event`] listeners.
+#[cfg(feature = "hydrate")]
+pub fn serialize_store_state<S: HydratableStore>(store: &S) -> Result<String, StoreHydrationError> {
+    report_serialize(S::store_key(), || store.serialize_state())
+}
+
+/// Read hydration data from the DOM.
+///
+/// This function looks for a script tag with the store's hydration ID
+/// and extracts the serialized state data.
+///
+/// # Arguments
+///
+/// * `store_key` - The unique key for the store
+///
+/// # Returns
+///
+/// The serialized state data as a string.
+#[cfg(all(feature = "hydrate", target_arch = "wasm32"))]
+pub fn read_hydration_data(store_key: &str) -> Result<String, StoreHydrationError> {
+    use wasm_bindgen::JsCast;
+
+    let window = web_sys::window()
+        .ok_or_else(|| StoreHydrationError::DomError("No window object".to_string()))?;
+
+    let document = window
+        .document()
+        .ok_or_else(|| StoreHydrationError::DomError("No document object".to_string()))?;
+
+    if let Some(data) = read_global_payload(&window, store_key)
+        .or_else(|| read_bundled_payload(&document, store_key))
+    {
+        return decode_payload(data);
+    }
+
+    let script_id = hydration_script_id(store_key);
+    let element = document
+        .get_element_by_id(&script_id)
+        .ok_or_else(|| StoreHydrationError::NotFound(store_key.to_string()))?;
+
+    let script = element
+        .dyn_into::<web_sys::HtmlScriptElement>()
+        .map_err(|_| StoreHydrationError::InvalidData("Element is not a script tag".to_string()))?;
+
+    let content = script.text().map_err(|e| {
+        StoreHydrationError::DomError(format!("Failed to read script content: {:?}", e))
+    })?;
+
+    decode_payload(content)
+}
+
+/// Stub for non-WASM targets.
+#[cfg(all(feature = "hydrate", not(target_arch = "wasm32")))]
+pub fn read_hydration_data(store_key: &str) -> Result<String, StoreHydrationError> {
+    Err(StoreHydrationError::DomError(format!(
+        "DOM access not available on this platform for key: {store_key}"
+    )))
+}
+
+/// Read the state of the island instance `instance` of a store from the
+/// [`island_store_attribute`] of its root element.
+#[cfg(all(feature = "hydrate", target_arch = "wasm32"))]
+pub fn read_island_attribute(
+    store_key: &str,
+    instance: &str,
+) -> Result<String, StoreHydrationError> {
+    use wasm_bindgen::JsCast;
+
+    let document = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or_else(|| StoreHydrationError::DomError("No document object".to_string()))?;
+
+    let name = island_store_attribute(store_key);
+    let instance_name = format!("{name}-instance");
+    let roots = document
+        .query_selector_all(&format!("[{instance_name}]"))
+        .map_err(|e| StoreHydrationError::DomError(format!("Invalid store key: {e:?}")))?;
+    let not_found = || StoreHydrationError::NotFound(island_store_key(store_key, instance));
+    let root = (0..roots.length())
+        .filter_map(|i| roots.item(i)?.dyn_into::<web_sys::Element>().ok())
+        .find(|root| root.get_attribute(&instance_name).as_deref() == Some(instance))
+        .ok_or_else(not_found)?;
+    decode_payload(root.get_attribute(&name).ok_or_else(not_found)?)
+}
+
+/// Stub for non-WASM targets.
+#[cfg(all(feature = "hydrate", not(target_arch = "wasm32")))]
+pub fn read_island_attribute(
+    store_key: &str,
+    instance: &str,
+) -> Result<String, StoreHydrationError> {
+    Err(StoreHydrationError::DomError(format!(
+        "DOM access not available on this platform for key: {}",
+        island_store_key(store_key, instance)
+    )))
+}
+
+/// Hydrate a store from DOM data.
+///
+/// This function reads the serialized state from the DOM and creates
+/// a new store instance with the hydrated state, then
+/// [reconciles](HydratableStore::reconcile) it.
+///
+/// # Type Parameters
+///
+/// * `S` - The store type to hydrate
+///
+/// # Returns
+///
+/// A new store instance with the hydrated state, or an error if
+/// hydration fails.
+#[cfg(feature = "hydrate")]
+pub fn hydrate_store<S: HydratableStore>() -> Result<S, StoreHydrationError> {
+    let data = read_hydration_data(S::store_key())?;
+    let store = from_payload::<S>(S::store_key(), &data)?;
+    store.reconcile();
+    Ok(store)
+}
+
+/// Hydrate an existing store from DOM data, in place.
+///
+/// Like [`hydrate_store`], but the state replaces the one inside `store`
+/// through [`HydratableStore::hydrate_into`] instead of building a new
+/// store, so a store that was already provided, and every clone and
+/// subscriber of it, sees the hydrated state. The store is then
+/// [reconciled](HydratableStore::reconcile). On error, the store keeps
+/// its state.
+///
+/// ```rust,ignore
+/// let store = use_store::<CartStore>();
+/// if let Err(e) = hydrate_store_into(&store) {
+///     leptos::logging::warn!("Cart stays client-side: {e}");
+/// }
+/// ```
+#[cfg(feature = "hydrate")]
+pub fn hydrate_store_into<S: HydratableStore>(store: &S) -> Result<(), StoreHydrationError> {
+    let data = read_hydration_data(S::store_key())?;
+    payload_into(store, S::store_key(), &data)?;
+    store.reconcile();
+    Ok(())
+}
+
+/// Check if hydration data is available for a store.
+///
+/// This is useful for conditional hydration logic where you want
+/// to fall back to default state if no hydration data exists.
+#[cfg(all(feature = "hydrate", target_arch = "wasm32"))]
+pub fn has_hydration_data(store_key: &str) -> bool {
+    web_sys::window().is_some_and(|window| {
+        read_global_payload(&window, store_key).is_some()
+            || window.document().is_some_and(|document| {
+                read_bundled_payload(&document, store_key).is_some()
+                    || document
+                        .get_element_by_id(&hydration_script_id(store_key))
+                        .is_some()
+            })
+    })
+}
+
+/// Stub for non-WASM targets.
+#[cfg(all(feature = "hydrate", not(target_arch = "wasm32")))]
+pub fn has_hydration_data(_store_key: &str) -> bool {
+    false
+}
+
+/// Generate the HTML for a hydration script tag.
+///
+/// This is used during SSR to embed the serialized store state
+/// in the HTML document.
+///
+/// # Arguments
+///
+/// * `store_key` - The unique key for the store
+/// * `data` - The serialized state data
+///
+/// # Returns
+///
+/// An HTML string containing the script tag with the embedded data.
+#[cfg(feature = "hydrate")]
+pub fn hydration_script_html(store_key: &str, data: &str) -> String {
+    let script_id = hydration_script_id(store_key);
+    let escaped_data = script_payload(data);
+    format!(r#"<script id="{script_id}" type="application/json">{escaped_data}</script>"#)
+}
+
+/// Escape serialized state for embedding inside a `<script>` element.
+///
+/// `<`, `>` and `&` are written as JSON unicode escapes, so the data can't
+/// close the script, open a comment, or start a nested script, and U+2028
+/// and U+2029 are escaped for parsers that treat them as line breaks. JSON
+/// only allows these characters inside strings, where the escapes decode
+/// back to the original text.
+#[cfg(feature = "hydrate")]
+pub(crate) fn escape_script_data(data: &str) -> String {
+    let mut escaped = String::with_capacity(data.len());
+    for c in data.chars() {
+        match c {
+            '<' => escaped.push_str(r"\u003c"),
+            '>' => escaped.push_str(r"\u003e"),
+            '&' => escaped.push_str(r"\u0026"),
+            '\u{2028}' => escaped.push_str(r"\u2028"),
+            '\u{2029}' => escaped.push_str(r"\u2029"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Encode and escape serialized state for a hydration script.
+#[cfg(feature = "hydrate")]
+pub(crate) fn script_payload(data: &str) -> String {
+    escape_script_data(&encode_payload(data))
+}
+
+/// Compress serialized state if it's over the configured threshold.
+#[cfg(feature = "hydrate")]
+pub(crate) fn encode_payload(data: &str) -> std::borrow::Cow<'_, str> {
+    #[cfg(feature = "hydrate-compress")]
+    if data.len() > HydrationCompression::current().threshold_bytes {
+        return compress_payload(data).into();
+    }
+    data.into()
+}
+
+/// Name of the `window` property holding the payloads written by
+/// `<StoreHydrationScripts global=true/>`, as a map from store key to
+/// serialized state.
+pub const HYDRATION_GLOBAL: &str = "__LEPTOS_STORE__";
+
+/// ID of the `type="application/json"` script written by
+/// `<StoreHydrationScripts bundle=true/>`, holding every payload as a map
+/// from store key to serialized state.
+pub const HYDRATION_BUNDLE_ID: &str = "__LEPTOS_STORE_BUNDLE__";
+
+/// Build the JSON object mapping the store key of each of `payloads` to
+/// its serialized state.
+///
+/// Every payload is embedded as a string, so the client reads back exactly
+/// what the server wrote: as JS values, integers above 2^53 would lose
+/// precision and keys like `__proto__` would be dropped. The result is not
+/// escaped for HTML.
+#[cfg(feature = "hydrate")]
+fn payload_map(payloads: &[(&str, String)]) -> String {
+    let entries: Vec<_> = payloads
+        .iter()
+        .map(|(store_key, data)| {
+            let data = encode_payload(data).into_owned();
+            format!(
+                "{}:{}",
+                serde_json::Value::from(*store_key),
+                serde_json::Value::from(data)
+            )
+        })
+        .collect();
+    format!("{{{}}}", entries.join(","))
+}
+
+/// Build the script assigning `payloads` to [`HYDRATION_GLOBAL`].
+///
+/// The map is merged into an existing one, so the script can be rendered
+/// more than once.
+#[cfg(feature = "hydrate")]
+pub(crate) fn global_payload_script(payloads: &[(&str, String)]) -> String {
+    escape_script_data(&format!(
+        "window.{HYDRATION_GLOBAL}=Object.assign(window.{HYDRATION_GLOBAL}||{{}},{});",
+        payload_map(payloads)
+    ))
+}
+
+/// Build the content of the [`HYDRATION_BUNDLE_ID`] script for `payloads`,
+/// escaped once for the whole map.
+#[cfg(feature = "hydrate")]
+pub(crate) fn bundled_payload_script(payloads: &[(&str, String)]) -> String {
+    escape_script_data(&payload_map(payloads))
+}
+
+/// Read the payload of `store_key` from [`HYDRATION_GLOBAL`], if any.
+#[cfg(all(feature = "hydrate", target_arch = "wasm32"))]
+fn read_global_payload(window: &web_sys::Window, store_key: &str) -> Option<String> {
+    let payloads = js_sys::Reflect::get(window, &HYDRATION_GLOBAL.into()).ok()?;
+    map_payload(&payloads, store_key)
+}
+
+/// Read the payload of `store_key` from the [`HYDRATION_BUNDLE_ID`]
+/// script, if any.
+#[cfg(all(feature = "hydrate", target_arch = "wasm32"))]
+fn read_bundled_payload(document: &web_sys::Document, store_key: &str) -> Option<String> {
+    let content = document
+        .get_element_by_id(HYDRATION_BUNDLE_ID)?
+        .text_content()?;
+    let payloads = js_sys::JSON::parse(&content).ok()?;
+    map_payload(&payloads, store_key)
+}
+
+/// Get the payload of `store_key` from a `{key: state}` map.
+#[cfg(all(feature = "hydrate", target_arch = "wasm32"))]
+fn map_payload(payloads: &wasm_bindgen::JsValue, store_key: &str) -> Option<String> {
+    if !payloads.is_object() {
+        return None;
+    }
+    js_sys::Reflect::get(payloads, &store_key.into())
+        .ok()?
+        .as_string()
+}
+
+/// Prefix marking a deflate-compressed, base64-encoded hydration payload.
+///
+/// Base64 and JSON payloads can't start with it, so uncompressed payloads
+/// are read as-is.
+pub const COMPRESSED_PAYLOAD_PREFIX: &str = "deflate:";
+
+/// Compression of large hydration payloads.
+///
+/// With the `hydrate-compress` feature, payloads longer than
+/// `threshold_bytes` are deflated and base64-encoded before they're
+/// embedded, and inflated again when read on the client. Set it in the
+/// [`StoreEnv`](crate::env::StoreEnv) provided to the app to change the
+/// threshold; without one, [`HydrationCompression::default`] applies.
+///
+/// ```rust,ignore
+/// let env = StoreEnv::builder()
+///     .config(HydrationCompression { threshold_bytes: 4 * 1024 })
+///     .build(Profile::Production);
+/// ```
+#[cfg(feature = "hydrate-compress")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HydrationCompression {
+    /// Payloads longer than this many bytes are compressed.
+    pub threshold_bytes: usize,
+}
+
+#[cfg(feature = "hydrate-compress")]
+impl Default for HydrationCompression {
+    /// Compress payloads over 16 KiB.
+    fn default() -> Self {
+        Self {
+            threshold_bytes: 16 * 1024,
+        }
+    }
+}
+
+#[cfg(feature = "hydrate-compress")]
+impl HydrationCompression {
+    /// Never compress payloads.
+    pub const DISABLED: Self = Self {
+        threshold_bytes: usize::MAX,
+    };
+
+    /// Returns the settings configured in the store environment in context.
+    pub fn current() -> Self {
+        crate::env::try_use_store_env()
+            .ok()
+            .and_then(|env| env.get::<Self>().copied())
+            .unwrap_or_default()
+    }
+}
+
+/// Deflate and base64-encode a payload, adding [`COMPRESSED_PAYLOAD_PREFIX`].
+#[cfg(feature = "hydrate-compress")]
+fn compress_payload(data: &str) -> String {
+    use base64::Engine;
+
+    let compressed = miniz_oxide::deflate::compress_to_vec(data.as_bytes(), 6);
+    let encoded = base64::engine::general_purpose::STANDARD.encode(compressed);
+    format!("{COMPRESSED_PAYLOAD_PREFIX}{encoded}")
+}
+
+/// Decode a payload read from a hydration script, inflating it if it was
+/// compressed.
+#[cfg(feature = "hydrate")]
+pub fn decode_payload(data: String) -> Result<String, StoreHydrationError> {
+    let Some(compressed) = data.strip_prefix(COMPRESSED_PAYLOAD_PREFIX) else {
+        return Ok(data);
+    };
+    #[cfg(feature = "hydrate-compress")]
+    {
+        use base64::Engine;
+
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(compressed.trim())
+            .map_err(|e| StoreHydrationError::InvalidData(e.to_string()))?;
+        let inflated = miniz_oxide::inflate::decompress_to_vec(&bytes)
+            .map_err(|e| StoreHydrationError::InvalidData(e.to_string()))?;
+        String::from_utf8(inflated).map_err(|e| StoreHydrationError::InvalidData(e.to_string()))
+    }
+    #[cfg(not(feature = "hydrate-compress"))]
+    {
+        let _ = compressed;
+        Err(StoreHydrationError::InvalidData(
+            "compressed payload requires the `hydrate-compress` feature".to_string(),
+        ))
+    }
+}
+
+/// Serializes a registered store when the scripts are rendered, or returns
+/// `None` if it's [skipped](HydratableStore::skip_hydration).
+#[cfg(feature = "hydrate")]
+type PayloadSerializer = Arc<dyn Fn() -> Option<Result<String, StoreHydrationError>> + Send + Sync>;
+
+/// A store registered for [`StoreHydrationScripts`].
+#[cfg(feature = "hydrate")]
+struct RegisteredPayload {
+    store_key: &'static str,
+    store_type: TypeId,
+    serialize: PayloadSerializer,
+}
+
+/// Stores registered during SSR, and the types of stores read since.
+#[cfg(feature = "hydrate")]
+#[derive(Default)]
+struct PayloadList {
+    registered: Vec<RegisteredPayload>,
+    used: Vec<TypeId>,
+}
+
+/// Hydration payloads registered during SSR, by store key.
+#[cfg(feature = "hydrate")]
+#[derive(Clone, Default)]
+struct HydrationPayloads(Arc<Mutex<PayloadList>>);
+
+#[cfg(feature = "hydrate")]
+impl HydrationPayloads {
+    /// Returns the payloads in context, providing them if needed.
+    fn current() -> Self {
+        use_context::<Self>().unwrap_or_else(|| {
+            let payloads = Self::default();
+            provide_context(payloads.clone());
+            payloads
+        })
+    }
+
+    /// Serialize the registered stores, or only those read with
+    /// `use_store` if `used_only`, returning their keys and data.
+    fn serialize(&self, used_only: bool) -> Vec<(&'static str, String)> {
+        let payloads: Vec<_> = {
+            let list = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+            list.registered
+                .iter()
+                .filter(|payload| !used_only || list.used.contains(&payload.store_type))
+                .map(|payload| (payload.store_key, payload.serialize.clone()))
+                .collect()
+        };
+        payloads
+            .into_iter()
+            .filter_map(|(store_key, serialize)| match serialize()? {
+                Ok(data) => Some((store_key, data)),
+                Err(e) => {
+                    leptos::logging::error!(
+                        "Failed to serialize store `{store_key}` for hydration: {e}"
+                    );
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+/// Hydration keys seen so far, mapped to the store type name.
+#[cfg(all(feature = "hydrate", debug_assertions))]
+static HYDRATION_KEYS: Mutex<std::collections::BTreeMap<String, &'static str>> =
+    Mutex::new(std::collections::BTreeMap::new());
+
+/// Panic if a different store type already hydrates under `key`.
+///
+/// `key` is the key `S` is actually embedded under: its
+/// [`store_key`](HydratableStore::store_key), an override, or an island
+/// key. Two stores sharing a key write the same script ID, so one would
+/// silently shadow the other. Only checked in debug builds.
+#[cfg(feature = "hydrate")]
+#[cfg_attr(not(debug_assertions), allow(clippy::extra_unused_type_parameters))]
+pub(crate) fn check_store_key<S: HydratableStore>(key: &str) {
+    #[cfg(debug_assertions)]
+    {
+        let name = std::any::type_name::<S>();
+        let mut keys = HYDRATION_KEYS
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let existing = *keys.entry(key.to_string()).or_insert(name);
+        if existing != name {
+            drop(keys);
+            panic!("Duplicate hydration store key `{key}`: used by both {existing} and {name}");
+        }
+    }
+    #[cfg(not(debug_assertions))]
+    let _ = key;
+}
+
+/// Register `store` so [`StoreHydrationScripts`] renders its hydration script.
+///
+/// A store registered again under the same key replaces the earlier one.
+/// `store` is called and its state serialized when the scripts are
+/// rendered, not here.
+#[cfg(feature = "hydrate")]
+pub(crate) fn register_hydration_payload<S: HydratableStore + 'static>(
+    store: impl Fn() -> S + Send + Sync + 'static,
+) {
+    check_store_key::<S>(S::store_key());
+    let payloads = HydrationPayloads::current();
+    let mut payloads = payloads.0.lock().unwrap_or_else(PoisonError::into_inner);
+    payloads
+        .registered
+        .retain(|payload| payload.store_key != S::store_key());
+    payloads.registered.push(RegisteredPayload {
+        store_key: S::store_key(),
+        store_type: TypeId::of::<S>(),
+        serialize: Arc::new(move || {
+            let store = store();
+            (!store.skip_hydration())
+                .then(|| report_serialize(S::store_key(), || store.serialize_state()))
+        }),
+    });
+}
+
+/// Record that the store of type `S` was read while rendering, for
+/// `<StoreHydrationScripts used_only=true />`.
+#[cfg(feature = "hydrate")]
+pub(crate) fn mark_store_used<S: 'static>() {
+    if let Some(payloads) = use_context::<HydrationPayloads>() {
+        let mut payloads = payloads.0.lock().unwrap_or_else(PoisonError::into_inner);
+        if !payloads.used.contains(&TypeId::of::<S>()) {
+            payloads.used.push(TypeId::of::<S>());
+        }
+    }
+}
+
+/// Renders the hydration scripts of every store registered with
+/// [`register_hydrated_store`] during SSR.
+///
+/// This is the store counterpart of Leptos' `HydrationScripts`: put it in
+/// the `<head>` of your application shell instead of rendering a script per
+/// store inside the app. Stores must be registered while the app is built,
+/// outside `<Suspense>` and route views that render later.
+///
+/// ```rust,ignore
+/// view! {
+///     <head>
+///         <HydrationScripts options=leptos_options.clone() />
+///         <StoreHydrationScripts />
+///     </head>
+///     <body>
+///         <App/>
+///     </body>
+/// }
+/// ```
+///
+/// # Route-aware hydration
+///
+/// With `used_only`, only stores read with `use_store` (or
+/// `use_hydrated_store`) while rendering are embedded, so global stores
+/// that the matched route never touches don't bloat the response. Route
+/// views render after the `<head>`, so put the component at the end of the
+/// `<body>` instead:
+///
+/// ```rust,ignore
+/// <body>
+///     <App/>
+///     <StoreHydrationScripts used_only=true />
+/// </body>
+/// ```
+///
+/// # Global payload
+///
+/// With `global`, every store's state goes into one inline script
+/// assigning a `{key: state}` map to `window.__LEPTOS_STORE__`
+/// ([`HYDRATION_GLOBAL`]) instead of a script tag per store, which keeps
+/// the HTML small and gives CSP a single script to allow; pass the
+/// response's nonce if the policy requires one. The client reads from the
+/// global first and needs no configuration.
+///
+/// ```rust,ignore
+/// <StoreHydrationScripts global=true nonce=use_nonce().map(|n| n.to_string()) />
+/// ```
+///
+/// # Bundled payload
+///
+/// With `bundle`, the states go into a single `type="application/json"`
+/// script ([`HYDRATION_BUNDLE_ID`]) holding the same `{key: state}` map.
+/// Like `global` it saves a DOM node and an escaping pass per store, but
+/// the payload stays inert data, so it needs no CSP nonce. The client
+/// looks keys up in the bundle when it finds no global. Render it once per
+/// page; `global` takes precedence if both are set.
+///
+/// ```rust,ignore
+/// <StoreHydrationScripts bundle=true />
+/// ```
+///
+/// [`register_hydrated_store`]: crate::context::register_hydrated_store
+#[cfg(feature = "hydrate")]
+#[component]
+pub fn StoreHydrationScripts(
+    /// Only embed stores read while rendering the current route.
+    #[prop(optional)]
+    used_only: bool,
+    /// Assign every payload to `window.__LEPTOS_STORE__` in one script.
+    #[prop(optional)]
+    global: bool,
+    /// CSP nonce for the `global` script.
+    #[prop(optional, into)]
+    nonce: Option<String>,
+    /// Embed every payload in one `type="application/json"` script.
+    #[prop(optional)]
+    bundle: bool,
+) -> impl IntoView {
+    let payloads = HydrationPayloads::current();
+    move || {
+        let payloads = payloads.serialize(used_only);
+        if global {
+            if payloads.is_empty() {
+                return Vec::new();
+            }
+            return vec![
+                leptos::html::script()
+                    .nonce(nonce.clone())
+                    .inner_html(global_payload_script(&payloads))
+                    .into_any(),
+            ];
+        }
+        if bundle {
+            if payloads.is_empty() {
+                return Vec::new();
+            }
+            return vec![
+                leptos::html::script()
+                    .id(HYDRATION_BUNDLE_ID)
+                    .attr("type", "application/json")
+                    .inner_html(bundled_payload_script(&payloads))
+                    .into_any(),
+            ];
+        }
+        payloads
+            .into_iter()
+            .map(|(store_key, data)| {
+                leptos::html::script()
+                    .id(hydration_script_id(store_key))
+                    .attr("type", "application/json")
+                    .inner_html(script_payload(&data))
+                    .into_any()
+            })
+            .collect::<Vec<_>>()
+    }
+}
+
+/// ID suffix of the elements written by [`streamed_hydration_script`].
+pub const STREAMED_SCRIPT_SUFFIX: &str = "__streamed";
+
+/// Generate the ID of the element carrying a store's streamed state.
+#[cfg(feature = "hydrate")]
+pub fn streamed_hydration_id(store_key: &str) -> String {
+    format!("{}{STREAMED_SCRIPT_SUFFIX}", hydration_script_id(store_key))
+}
+
+/// Render `store`'s state once `ready` resolves, for out-of-order
+/// streaming.
+///
+/// With streaming SSR the shell, and the scripts in it, are flushed before
+/// async data has loaded, so stores filled by resources would hydrate with
+/// empty state. This renders the state inside its own `<Suspense>`, which
+/// Leptos streams as a later chunk once `ready` completes. Pair it with
+/// [`hydrate_streamed`] on the client, which waits for the chunk and
+/// replaces the store's state in place.
+///
+/// `ready` runs on both sides, so it should await something that resolves
+/// in the browser too, usually the resource loading the store. The state
+/// is carried in an attribute of an inert `<template>` element, which the
+/// browser creates whole even while the chunk is still arriving.
+///
+/// ```rust,ignore
+/// let tokens = store_resource(&store, || (), |_| LoadTokens, |store, r| store.set_tokens(r.tokens));
+///
+/// view! {
+///     <TokenList/>
+///     {streamed_hydration_script(store.clone(), async move { let _ = tokens.await; })}
+/// }
+/// ```
+#[cfg(feature = "hydrate")]
+pub fn streamed_hydration_script<S, Fut>(store: S, ready: Fut) -> impl IntoView
+where
+    S: HydratableStore,
+    Fut: std::future::Future<Output = ()> + Send + 'static,
+{
+    Suspense(
+        SuspenseProps::builder()
+            .children(TypedChildren::to_children(move || {
+                Suspend::new(async move {
+                    ready.await;
+                    match report_serialize(S::store_key(), || store.serialize_state()) {
+                        Ok(data) => Some(
+                            leptos::html::template()
+                                .id(streamed_hydration_id(S::store_key()))
+                                .attr("data-state", encode_payload(&data).into_owned()),
+                        ),
+                        Err(e) => {
+                            leptos::logging::error!(
+                                "Failed to serialize store `{}` for hydration: {e}",
+                                S::store_key()
+                            );
+                            None
+                        }
+                    }
+                })
+            }))
+            .build(),
+    )
+}
+
+/// Wait for the state of `store_key` streamed by
+/// [`streamed_hydration_script`].
+///
+/// Resolves as soon as the chunk is in the document. Returns
+/// [`StoreHydrationError::NotFound`] if the document finished loading
+/// without it.
+#[cfg(all(feature = "hydrate", target_arch = "wasm32"))]
+pub async fn wait_for_streamed_data(store_key: &str) -> Result<String, StoreHydrationError> {
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen::closure::Closure;
+
+    let document = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or_else(|| StoreHydrationError::DomError("No document object".to_string()))?;
+    let id = streamed_hydration_id(store_key);
+    let ready = {
+        let document = document.clone();
+        let id = id.clone();
+        move || document.get_element_by_id(&id).is_some() || document.ready_state() != "loading"
+    };
+
+    if !ready() {
+        let (tx, rx) = futures::channel::oneshot::channel::<()>();
+        let tx = std::cell::RefCell::new(Some(tx));
+        let check = Closure::<dyn Fn()>::new(move || {
+            if ready()
+                && let Some(tx) = tx.borrow_mut().take()
+            {
+                let _ = tx.send(());
+            }
+        });
+        let observer = web_sys::MutationObserver::new(check.as_ref().unchecked_ref())
+            .map_err(|e| StoreHydrationError::DomError(format!("{e:?}")))?;
+        let options = web_sys::MutationObserverInit::new();
+        options.set_child_list(true);
+        options.set_subtree(true);
+        observer
+            .observe_with_options(&document, &options)
+            .map_err(|e| StoreHydrationError::DomError(format!("{e:?}")))?;
+        let _ = document
+            .add_event_listener_with_callback("readystatechange", check.as_ref().unchecked_ref());
+        let _ = rx.await;
+        observer.disconnect();
+        let _ = document.remove_event_listener_with_callback(
+            "readystatechange",
+            check.as_ref().unchecked_ref(),
+        );
+    }
+
+    let data = document
+        .get_element_by_id(&id)
+        .and_then(|element| element.get_attribute("data-state"))
+        .ok_or_else(|| StoreHydrationError::NotFound(store_key.to_string()))?;
+    decode_payload(data)
+}
+
+/// Stub for non-WASM targets.
+#[cfg(all(feature = "hydrate", not(target_arch = "wasm32")))]
+pub async fn wait_for_streamed_data(store_key: &str) -> Result<String, StoreHydrationError> {
+    Err(StoreHydrationError::DomError(format!(
+        "DOM access not available on this platform for key: {store_key}"
+    )))
+}
+
+/// Wait for the state streamed by [`streamed_hydration_script`] and
+/// replace `store`'s state with it, in place.
+///
+/// Call it on the client right after creating or hydrating the store from
+/// the shell, and spawn the returned future; everything holding the store
+/// sees the streamed state when it arrives.
+///
+/// ```rust,ignore
+/// let store = use_hydrated_store::<TokenStore>();
+/// leptos::task::spawn_local({
+///     let store = store.clone();
+///     async move { let _ = hydrate_streamed(&store).await; }
+/// });
+/// ```
+#[cfg(feature = "hydrate")]
+pub async fn hydrate_streamed<S: HydratableStore>(store: &S) -> Result<(), StoreHydrationError> {
+    let data = wait_for_streamed_data(S::store_key()).await?;
+    payload_into(store, S::store_key(), &data)
+}
+
+/// Fills in a store created by [`HydrationBuilder`] once fetched state
+/// arrives.
+#[cfg(feature = "hydrate")]
+type AsyncFallback<S> = Box<dyn FnOnce(&S) -> futures::future::LocalBoxFuture<'static, ()>>;
+
+/// A builder for creating hydration-aware stores.
+///
+/// This builder provides a fluent API for creating stores that
+/// automatically handle hydration on the client.
+#[cfg(feature = "hydrate")]
+pub struct HydrationBuilder<S: HydratableStore> {
+    fallback: Option<S>,
+    key: Option<String>,
+    async_fallback: Option<AsyncFallback<S>>,
+}
+
+#[cfg(feature = "hydrate")]
+impl<S: HydratableStore> Default for HydrationBuilder<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "hydrate")]
+impl<S: HydratableStore> HydrationBuilder<S> {
+    /// Create a new hydration builder.
+    pub fn new() -> Self {
+        Self {
+            fallback: None,
+            key: None,
+            async_fallback: None,
+        }
+    }
+
+    /// Read the state embedded under `key` instead of
+    /// [`S::store_key()`](HydratableStore::store_key), as written by
+    /// [`HydratedStoreProvider::key`](crate::context::HydratedStoreProvider::key).
+    pub fn key(mut self, key: impl Into<String>) -> Self {
+        self.key = Some(key.into());
+        self
+    }
+
+    /// Hydrate from the configured key.
+    fn hydrate(&self) -> Result<S, StoreHydrationError> {
+        match &self.key {
+            Some(key) => {
+                let store = from_payload::<S>(key, &read_hydration_data(key)?)?;
+                store.reconcile();
+                Ok(store)
+            }
+            None => hydrate_store::<S>(),
+        }
+    }
+
+    /// Set a fallback store to use if hydration fails.
+    ///
+    /// If hydration data is not found or deserialization fails,
+    /// this fallback store will be used instead.
+    pub fn with_fallback(mut self, store: S) -> Self {
+        self.fallback = Some(store);
+        self
+    }
+
+    /// Fetch the state with `fetch` when the fallback store is used.
+    ///
+    /// Without hydration data, e.g. after client-side navigation, the store
+    /// from [`with_fallback`](Self::with_fallback) is returned right away,
+    /// and `fetch`, usually calling a server function, runs in the
+    /// background; its result replaces the fallback's state in place. Use
+    /// [`build_with_loading`](Self::build_with_loading) to show a loading
+    /// state meanwhile. A failed fetch is logged and keeps the fallback
+    /// state.
+    ///
+    /// `fetch` only runs in the browser, never during SSR.
+    ///
+    /// ```rust,ignore
+    /// let (store, loading) = HydrationBuilder::new()
+    ///     .with_fallback(TokenStore::new())
+    ///     .with_async_fallback(|| async {
+    ///         fetch_tokens_server().await.map(TokenStore::with_tokens)
+    ///     })
+    ///     .build_with_loading();
+    /// ```
+    pub fn with_async_fallback<F, Fut, E>(mut self, fetch: F) -> Self
+    where
+        S: Clone + 'static,
+        F: FnOnce() -> Fut + 'static,
+        Fut: std::future::Future<Output = Result<S, E>> + 'static,
+        E: std::fmt::Display,
+    {
+        self.async_fallback = Some(Box::new(move |store: &S| {
+            let store = store.clone();
+            Box::pin(async move {
+                let result = match fetch().await {
+                    Ok(fetched) => fetched
+                        .serialize_state()
+                        .and_then(|data| store.hydrate_into(&data)),
+                    Err(e) => Err(StoreHydrationError::Rehydration(e.to_string())),
+                };
+                if let Err(e) = result {
+                    leptos::logging::warn!("Async hydration fallback failed: {}", e);
+                }
+            })
+        }));
+        self
+    }
+
+    /// Build the store, attempting hydration first.
+    ///
+    /// This will try to hydrate from DOM data. If hydration fails
+    /// and a fallback was provided, the fallback will be used.
+    ///
+    /// # Panics
+    ///
+    /// Panics if hydration fails and no fallback was provided.
+    pub fn build(self) -> S {
+        self.build_with_loading().0
+    }
+
+    /// Build the store, returning a Result.
+    ///
+    /// This will try to hydrate from DOM data. If hydration fails
+    /// and a fallback was provided, the fallback will be returned.
+    pub fn try_build(self) -> Result<S, StoreHydrationError> {
+        self.try_build_with_loading().map(|(store, _)| store)
+    }
+
+    /// Build the store like [`build`](Self::build), along with a signal
+    /// that is `true` while the
+    /// [async fallback](Self::with_async_fallback) is fetching its state.
+    ///
+    /// # Panics
+    ///
+    /// Panics if hydration fails and no fallback was provided.
+    pub fn build_with_loading(self) -> (S, ReadSignal<bool>) {
+        self.try_build_with_loading()
+            .unwrap_or_else(|e| panic!("Store hydration failed and no fallback provided: {e}"))
+    }
+
+    /// Build the store like [`try_build`](Self::try_build), along with a
+    /// signal that is `true` while the
+    /// [async fallback](Self::with_async_fallback) is fetching its state.
+    pub fn try_build_with_loading(self) -> Result<(S, ReadSignal<bool>), StoreHydrationError> {
+        let loading = RwSignal::new(false);
+        let store = match self.hydrate() {
+            Ok(store) => return Ok((store, loading.read_only())),
+            Err(e) => self.fallback.ok_or(e)?,
+        };
+
+        let on_server = Owner::current_shared_context().is_some_and(|sc| !sc.is_browser());
+        if let Some(fetch) = self.async_fallback
+            && !on_server
+        {
+            loading.set(true);
+            let fetched = fetch(&store);
+            leptos::task::spawn_local(async move {
+                fetched.await;
+                loading.set(false);
+            });
+        }
+        Ok((store, loading.read_only()))
+    }
+}
+
+/// Hydrates one registered store and provides it, returning why its
+/// factory was used instead, if it was.
+#[cfg(feature = "hydrate")]
+type StoreHydrator = Arc<dyn Fn() -> Option<StoreHydrationError> + Send + Sync>;
+
+/// Stores registered with [`register_hydratable`], by store key.
+#[cfg(feature = "hydrate")]
+static HYDRATABLE_STORES: Mutex<Vec<(&'static str, StoreHydrator)>> = Mutex::new(Vec::new());
+
+/// Register a hydratable store for [`hydrate_all`].
+///
+/// `factory` creates the store when the page has no hydration data for it
+/// (always the case on the server) or the data can't be read. Registering
+/// a store key again replaces the earlier registration.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[component]
+/// pub fn App() -> impl IntoView {
+///     register_hydratable(TokenStore::new);
+///     register_hydratable(AuthStore::new);
+///     hydrate_all();
+///
+///     view! { <MainContent /> }
+/// }
+/// ```
+#[cfg(feature = "hydrate")]
+pub fn register_hydratable<S, F>(factory: F)
+where
+    S: HydratableStore + Clone + Send + Sync + 'static,
+    F: Fn() -> S + Send + Sync + 'static,
+{
+    check_store_key::<S>(S::store_key());
+    let hydrator: StoreHydrator = Arc::new(move || {
+        let (store, error) = match hydrate_store::<S>() {
+            Ok(store) => (store, None),
+            Err(e) => (factory(), Some(e)),
+        };
+        crate::context::register_hydrated_store(store);
+        error
+    });
+    let mut stores = HYDRATABLE_STORES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    stores.retain(|(store_key, _)| *store_key != S::store_key());
+    stores.push((S::store_key(), hydrator));
+}
+
+/// Hydrate and provide every store registered with [`register_hydratable`].
+///
+/// Each store is restored from the page's hydration data, or created by its
+/// factory if there is none, then provided to context and registered for
+/// [`StoreHydrationScripts`] like [`register_hydrated_store`]. Call it once
+/// at the top of the app, on both the server and the client.
+///
+/// [`register_hydrated_store`]: crate::context::register_hydrated_store
+#[cfg(feature = "hydrate")]
+pub fn hydrate_all() -> HydrationReport {
+    let stores = HYDRATABLE_STORES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone();
+    let mut report = HydrationReport::default();
+    for (store_key, hydrate) in stores {
+        match hydrate() {
+            None => report.hydrated.push(store_key),
+            Some(e) => report.fallbacks.push((store_key, e)),
+        }
+    }
+    report
+}
+
+/// The result of [`hydrate_all`].
+#[cfg(feature = "hydrate")]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HydrationReport {
+    hydrated: Vec<&'static str>,
+    fallbacks: Vec<(&'static str, StoreHydrationError)>,
+}
+
+#[cfg(feature = "hydrate")]
+impl HydrationReport {
+    /// Returns the keys of the stores restored from hydration data.
+    pub fn hydrated(&self) -> &[&'static str] {
+        &self.hydrated
+    }
+
+    /// Returns the keys of the stores created by their factories, with the
+    /// reason hydration data couldn't be used.
+    pub fn fallbacks(&self) -> &[(&'static str, StoreHydrationError)] {
+        &self.fallbacks
+    }
+}
+
+/// Builds the serialized state of a fresh store.
+#[cfg(feature = "hydrate")]
+type RehydrationSource = Arc<
+    dyn Fn() -> futures::future::BoxFuture<'static, Result<String, StoreHydrationError>>
+        + Send
+        + Sync,
+>;
+
+/// Sources registered with [`register_rehydration_source`], by store key.
+#[cfg(feature = "hydrate")]
+static REHYDRATION_SOURCES: Mutex<Vec<(&'static str, RehydrationSource)>> = Mutex::new(Vec::new());
+
+/// Register how the server builds a fresh `S` for [`rehydrate`].
+///
+/// Call it once on the server, before serving requests. `source` builds
+/// the store with its canonical state, usually the same way the SSR
+/// handler does; its serialized state is what
+/// [`serve_rehydration`] returns. Registering the same key again
+/// replaces the source.
+///
+/// ```rust,ignore
+/// register_rehydration_source(|| async {
+///     let tokens = fetch_tokens_server().await?;
+///     Ok::<_, String>(TokenStore::with_tokens(tokens))
+/// });
+/// ```
+#[cfg(feature = "hydrate")]
+pub fn register_rehydration_source<S, F, Fut, E>(source: F)
+where
+    S: HydratableStore + 'static,
+    F: Fn() -> Fut + Send + Sync + 'static,
+    Fut: std::future::Future<Output = Result<S, E>> + Send + 'static,
+    E: std::fmt::Display,
+{
+    use futures::FutureExt;
+
+    check_store_key::<S>(S::store_key());
+    let source: RehydrationSource = Arc::new(move || {
+        source()
+            .map(|store| {
+                store
+                    .map_err(|e| StoreHydrationError::Rehydration(e.to_string()))?
+                    .serialize_state()
+            })
+            .boxed()
+    });
+    let mut sources = REHYDRATION_SOURCES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    sources.retain(|(store_key, _)| *store_key != S::store_key());
+    sources.push((S::store_key(), source));
+}
+
+/// Build the store keyed `store_key` with its registered source and return
+/// its serialized state.
+///
+/// This is the body of the endpoint generated by
+/// [`rehydrate_endpoint!`](crate::rehydrate_endpoint).
+///
+/// # Errors
+///
+/// Returns [`StoreHydrationError::NotFound`] if no source is registered
+/// for `store_key`.
+#[cfg(feature = "hydrate")]
+pub async fn serve_rehydration(store_key: &str) -> Result<String, StoreHydrationError> {
+    let source = REHYDRATION_SOURCES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .iter()
+        .find(|(key, _)| *key == store_key)
+        .map(|(_, source)| Arc::clone(source))
+        .ok_or_else(|| StoreHydrationError::NotFound(store_key.to_string()))?;
+    source().await
+}
+
+/// Fetch fresh state for `store` with `fetch` and replace its state in
+/// place.
+///
+/// `fetch` is called with the store key and returns the serialized state,
+/// usually the server function generated by
+/// [`rehydrate_endpoint!`](crate::rehydrate_endpoint). Use it to re-sync a
+/// hydrated store after reconnecting, or on demand; everything holding the
+/// store sees the new state.
+///
+/// ```rust,ignore
+/// leptos_store::rehydrate_endpoint!(pub fn rehydrate_state);
+///
+/// let store = use_store::<TokenStore>();
+/// rehydrate(&store, rehydrate_state).await?;
+/// ```
+#[cfg(feature = "hydrate")]
+pub async fn rehydrate<S, F, Fut, E>(store: &S, fetch: F) -> Result<(), StoreHydrationError>
+where
+    S: HydratableStore,
+    F: FnOnce(String) -> Fut,
+    Fut: std::future::Future<Output = Result<String, E>>,
+    E: std::fmt::Display,
+{
+    let data = fetch(S::store_key().to_string())
+        .await
+        .map_err(|e| StoreHydrationError::Rehydration(e.to_string()))?;
+    store.hydrate_into(&data)
+}
+
+/// [`rehydrate`] `store` with `fetch` whenever the page is restored from
+/// the back/forward cache.
+///
+/// A restored page keeps the state it had when the user navigated away,
+/// which may be long out of date, and nothing hydrates again. Opt in for
+/// stores whose state goes stale; failures are logged and leave the state
+/// as it was. The listener is removed when the current owner is cleaned
+/// up, and nothing happens outside the browser. See
+/// [`on_page_restore`](crate::r#async::on_page_restore).
+///
+/// ```rust,ignore
+/// let store = use_store::<TokenStore>();
+/// rehydrate_on_restore(&store, rehydrate_state);
+/// ```
+#[cfg(feature = "hydrate")]
+pub fn rehydrate_on_restore<S, F, Fut, E>(store: &S, fetch: F)
+where
+    S: HydratableStore,
+    F: Fn(String) -> Fut + 'static,
+    Fut: std::future::Future<Output = Result<String, E>> + 'static,
+    E: std::fmt::Display,
+{
+    let store = store.clone();
+    let fetch = std::rc::Rc::new(fetch);
+    crate::r#async::on_page_restore(move || {
+        let store = store.clone();
+        let fetch = std::rc::Rc::clone(&fetch);
+        leptos::task::spawn_local(async move {
+            if let Err(e) = rehydrate(&store, |key| fetch(key)).await {
+                leptos::logging::warn!(
+                    "Failed to rehydrate store `{}` after restore: {e}",
+                    S::store_key()
+                );
+            }
+        });
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hydration_error_display() {
+        let err = StoreHydrationError::Serialization("test error".to_string());
+        assert_eq!(err.to_string(), "Serialization error: test error");
+
+        let err = StoreHydrationError::Deserialization("parse error".to_string());
+        assert_eq!(err.to_string(), "Deserialization error: parse error");
+
+        let err = StoreHydrationError::NotFound("my_store".to_string());
+        assert_eq!(
+            err.to_string(),
+            "Hydration data not found for key: my_store"
+        );
+
+        let err = StoreHydrationError::InvalidData("bad format".to_string());
+        assert_eq!(err.to_string(), "Invalid hydration data: bad format");
+
+        let err = StoreHydrationError::DomError("no window".to_string());
+        assert_eq!(err.to_string(), "DOM error: no window");
+    }
+
+    #[cfg(feature = "hydrate")]
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct ProfileState {
+        full_name: String,
+    }
+
+    #[cfg(feature = "hydrate")]
+    impl VersionedState for ProfileState {
+        const VERSION: u32 = 2;
+
+        fn migrate(
+            from_version: u32,
+            value: serde_json::Value,
+        ) -> Result<Self, StoreHydrationError> {
+            // v0/v1 stored the name as `name`
+            let name = value["name"].as_str().ok_or_else(|| {
+                StoreHydrationError::InvalidData(format!("v{from_version} payload missing name"))
+            })?;
+            Ok(Self {
+                full_name: name.to_string(),
+            })
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "hydrate")]
+    fn test_versioned_state_roundtrip_and_migration() {
+        let state = ProfileState {
+            full_name: "Ada Lovelace".to_string(),
+        };
+        let data = serialize_versioned(&state).unwrap();
+        assert!(data.contains(r#""__version":2"#));
+        assert_eq!(deserialize_versioned::<ProfileState>(&data).unwrap(), state);
+
+        // Older envelope and unversioned payloads are migrated
+        let v1 = r#"{"__version":1,"state":{"name":"Ada"}}"#;
+        assert_eq!(
+            deserialize_versioned::<ProfileState>(v1).unwrap().full_name,
+            "Ada"
+        );
+        let v0 = r#"{"name":"Grace"}"#;
+        assert_eq!(
+            deserialize_versioned::<ProfileState>(v0).unwrap().full_name,
+            "Grace"
+        );
+
+        // Newer payloads are rejected explicitly
+        let v3 = r#"{"__version":3,"state":{"full_name":"Ada"}}"#;
+        assert_eq!(
+            deserialize_versioned::<ProfileState>(v3),
+            Err(StoreHydrationError::UnsupportedVersion {
+                supported: 2,
+                found: 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_hydration_script_id() {
+        #[cfg(feature = "hydrate")]
+        {
+            let id = hydration_script_id("my_store");
+            assert_eq!(id, "__LEPTOS_STORE_STATE__my_store");
+        }
+    }
+
+    #[test]
+    fn test_hydration_script_html() {
+        #[cfg(feature = "hydrate")]
+        {
+            let html = hydration_script_html("counter", r#"{"count":42}"#);
+            assert!(html.contains(r#"id="__LEPTOS_STORE_STATE__counter""#));
+            assert!(html.contains(r#"type="application/json""#));
+            assert!(html.contains(r#"{"count":42}"#));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "hydrate-compress")]
+    fn test_compressed_payloads() {
+        use crate::env::{Profile, StoreEnv, provide_store_env};
+
+        let owner = Owner::new();
+        owner.with(|| {
+            let data = format!(r#"{{"items":"{}"}}"#, "abc".repeat(100));
+            // Under the default threshold payloads are embedded as-is
+            assert_eq!(script_payload(&data), data);
+
+            provide_store_env(
+                StoreEnv::builder()
+                    .config(HydrationCompression {
+                        threshold_bytes: 64,
+                    })
+                    .build(Profile::Test),
+            );
+            let payload = script_payload(&data);
+            assert!(payload.starts_with(COMPRESSED_PAYLOAD_PREFIX));
+            assert!(payload.len() < data.len());
+            assert_eq!(decode_payload(payload).unwrap(), data);
+
+            // Uncompressed payloads are read as-is
+            assert_eq!(decode_payload(data.clone()).unwrap(), data);
+            assert!(matches!(
+                decode_payload(format!("{COMPRESSED_PAYLOAD_PREFIX}!!")),
+                Err(StoreHydrationError::InvalidData(_))
+            ));
+        });
     }
 
     #[test]
@@ -400,148 +2762,1185 @@ mod tests {
         #[cfg(feature = "hydrate")]
         {
             let html = hydration_script_html("test", r#"{"value":"</script>"}"#);
-            assert!(html.contains(r#"<\/script>"#));
+            assert!(html.contains(r#"\u003c/script\u003e"#));
             assert!(!html.contains(r#"</script>"}"#));
+
+            let data = "{\"value\":\"<!-- <script> & \u{2028}\u{2029}\"}";
+            let escaped = escape_script_data(data);
+            assert_eq!(
+                escaped,
+                r#"{"value":"\u003c!-- \u003cscript\u003e \u0026 \u2028\u2029"}"#
+            );
+            #[derive(serde::Deserialize)]
+            struct Value {
+                value: String,
+            }
+            let value: Value = serde_json::from_str(&escaped).unwrap();
+            assert_eq!(value.value, "<!-- <script> & \u{2028}\u{2029}");
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "hydrate")]
+    fn test_global_payload_script() {
+        let script = global_payload_script(&[
+            ("settings", r#"{"theme":"</script>"}"#.to_string()),
+            ("chart", "AAEC".to_string()),
+        ]);
+        // Every payload is embedded as a string
+        assert_eq!(
+            script,
+            r#"window.__LEPTOS_STORE__=Object.assign(window.__LEPTOS_STORE__||{},{"settings":"{\"theme\":\"\u003c/script\u003e\"}","chart":"AAEC"});"#
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "hydrate")]
+    fn test_payload_map_keeps_large_integers() {
+        #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+        struct Ledger {
+            supply: u64,
+            balances: std::collections::BTreeMap<String, i64>,
+        }
+
+        let ledger = Ledger {
+            supply: u64::MAX,
+            balances: [("__proto__".to_string(), i64::MIN)].into(),
+        };
+        let data = serialize_state(&ledger).unwrap();
+        let map: serde_json::Value =
+            serde_json::from_str(&bundled_payload_script(&[("ledger", data)])).unwrap();
+
+        // The payload is a string, not a JS object that would round big
+        // integers to doubles or treat `__proto__` as the prototype
+        let payload = map["ledger"].as_str().unwrap();
+        assert_eq!(deserialize_state::<Ledger>(payload).unwrap(), ledger);
+    }
+
+    #[test]
+    #[cfg(feature = "hydrate")]
+    fn test_bundled_payload_script() {
+        let bundle = bundled_payload_script(&[
+            ("settings", r#"{"theme":"</script>"}"#.to_string()),
+            ("chart", "AAEC".to_string()),
+        ]);
+        assert_eq!(
+            bundle,
+            r#"{"settings":"{\"theme\":\"\u003c/script\u003e\"}","chart":"AAEC"}"#
+        );
+        // The escaped bundle is still the JSON map
+        let map: serde_json::Value = serde_json::from_str(&bundle).unwrap();
+        assert_eq!(map["settings"], r#"{"theme":"</script>"}"#);
+        assert_eq!(map["chart"], "AAEC");
+    }
+
+    // ========================================================================
+    // Integration tests for the full hydration workflow
+    // ========================================================================
+
+    #[cfg(feature = "hydrate")]
+    mod hydration_integration {
+        use super::*;
+        use crate::store::Store;
+        use serde::{Deserialize, Serialize};
+
+        /// Test state with various field types
+        #[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+        struct TestState {
+            count: i32,
+            name: String,
+            items: Vec<String>,
+            optional: Option<bool>,
+        }
+
+        /// Test store that implements HydratableStore
+        #[derive(Clone)]
+        struct TestHydratableStore {
+            state: RwSignal<TestState>,
+        }
+
+        impl TestHydratableStore {
+            fn new() -> Self {
+                Self {
+                    state: RwSignal::new(TestState::default()),
+                }
+            }
+
+            fn with_state(state: TestState) -> Self {
+                Self {
+                    state: RwSignal::new(state),
+                }
+            }
+        }
+
+        impl Store for TestHydratableStore {
+            type State = TestState;
+
+            fn state(&self) -> leptos::prelude::ReadSignal<Self::State> {
+                self.state.read_only()
+            }
+        }
+
+        impl HydratableStore for TestHydratableStore {
+            fn serialize_state(&self) -> Result<String, StoreHydrationError> {
+                let state = self.state.get();
+                serde_json::to_string(&state)
+                    .map_err(|e| StoreHydrationError::Serialization(e.to_string()))
+            }
+
+            fn from_hydrated_state(data: &str) -> Result<Self, StoreHydrationError> {
+                let state: TestState = serde_json::from_str(data)
+                    .map_err(|e| StoreHydrationError::Deserialization(e.to_string()))?;
+                Ok(Self::with_state(state))
+            }
+
+            fn store_key() -> &'static str {
+                "test_store"
+            }
+        }
+
+        #[test]
+        fn test_store_serialization_roundtrip() {
+            // Create a store with specific state
+            let original_state = TestState {
+                count: 42,
+                name: "Test Store".to_string(),
+                items: vec!["item1".to_string(), "item2".to_string()],
+                optional: Some(true),
+            };
+
+            let store = TestHydratableStore::with_state(original_state.clone());
+
+            // Serialize
+            let serialized = store
+                .serialize_state()
+                .expect("Serialization should succeed");
+
+            // Verify JSON structure
+            assert!(serialized.contains("42"));
+            assert!(serialized.contains("Test Store"));
+            assert!(serialized.contains("item1"));
+
+            // Deserialize into new store
+            let restored_store = TestHydratableStore::from_hydrated_state(&serialized)
+                .expect("Deserialization should succeed");
+
+            // Verify state matches
+            let restored_state = restored_store.state.get();
+            assert_eq!(restored_state, original_state);
+        }
+
+        #[test]
+        fn test_store_default_state_roundtrip() {
+            let store = TestHydratableStore::new();
+
+            let serialized = store
+                .serialize_state()
+                .expect("Serialization should succeed");
+            let restored = TestHydratableStore::from_hydrated_state(&serialized)
+                .expect("Deserialization should succeed");
+
+            assert_eq!(restored.state.get(), TestState::default());
+        }
+
+        #[test]
+        fn test_store_key_is_correct() {
+            assert_eq!(TestHydratableStore::store_key(), "test_store");
+        }
+
+        #[test]
+        fn test_derive_hydratable_store() {
+            #[derive(Clone, crate::store::Store, HydratableStore)]
+            #[hydrate(key = "derived_store", state = data)]
+            struct DerivedStore {
+                #[store(state)]
+                data: RwSignal<TestState>,
+                visits: u32,
+            }
+
+            let store = DerivedStore {
+                data: RwSignal::new(TestState {
+                    count: 7,
+                    ..Default::default()
+                }),
+                visits: 3,
+            };
+            let serialized = store.serialize_state().unwrap();
+            let restored = DerivedStore::from_hydrated_state(&serialized).unwrap();
+
+            assert_eq!(DerivedStore::store_key(), "derived_store");
+            assert_eq!(restored.data.get_untracked().count, 7);
+            assert_eq!(restored.visits, 0);
+            assert_eq!(store.visits, 3);
+        }
+
+        #[test]
+        fn test_derive_hydratable_store_versioned() {
+            #[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+            struct CounterState {
+                count: i32,
+            }
+
+            impl VersionedState for CounterState {
+                const VERSION: u32 = 2;
+
+                fn migrate(
+                    _from_version: u32,
+                    value: serde_json::Value,
+                ) -> Result<Self, StoreHydrationError> {
+                    serde_json::from_value(value)
+                        .map_err(|e| StoreHydrationError::Deserialization(e.to_string()))
+                }
+            }
+
+            #[derive(Clone, crate::store::Store, HydratableStore)]
+            #[hydrate(key = "counter", versioned)]
+            struct CounterStore {
+                counter: RwSignal<CounterState>,
+            }
+
+            let store = CounterStore {
+                counter: RwSignal::new(CounterState { count: 5 }),
+            };
+            let serialized = store.serialize_state().unwrap();
+            assert!(serialized.contains(STATE_VERSION_KEY));
+
+            let restored = CounterStore::from_hydrated_state(&serialized).unwrap();
+            assert_eq!(restored.counter.get_untracked(), CounterState { count: 5 });
+        }
+
+        #[test]
+        fn test_derive_hydratable_store_masked() {
+            use crate::env::{Profile, StoreEnv, provide_store_env};
+
+            #[derive(Clone, crate::store::Store, HydratableStore)]
+            #[hydrate(key = "test_store", masked)]
+            struct MaskedStore {
+                state: RwSignal<TestState>,
+            }
+
+            let state = TestState {
+                count: 4,
+                name: "filters".to_string(),
+                items: vec!["a".to_string(), "b".to_string()],
+                optional: Some(true),
+            };
+            let owner = Owner::new();
+            owner.with(|| {
+                let store = MaskedStore {
+                    state: RwSignal::new(state.clone()),
+                };
+                // Without a mask everything is embedded
+                let full = store.serialize_state().unwrap();
+                assert_eq!(
+                    MaskedStore::from_hydrated_state(&full)
+                        .unwrap()
+                        .state
+                        .get_untracked(),
+                    state
+                );
+
+                provide_store_env(
+                    StoreEnv::builder()
+                        .config(HydrationMask::default().skip("test_store", ["items", "optional"]))
+                        .build(Profile::Test),
+                );
+                let masked = store.serialize_state().unwrap();
+                assert!(!masked.contains("items"));
+                let restored = MaskedStore::from_hydrated_state(&masked).unwrap();
+                assert_eq!(
+                    restored.state.get_untracked(),
+                    TestState {
+                        count: 4,
+                        name: "filters".to_string(),
+                        ..TestState::default()
+                    }
+                );
+            });
+        }
+
+        #[test]
+        fn test_derive_hydratable_store_validated() {
+            #[derive(Clone, crate::store::Store, HydratableStore)]
+            #[hydrate(key = "test_store", validated)]
+            struct ValidatedStore {
+                state: RwSignal<TestState>,
+            }
+
+            let schema_error = |data: &str| match ValidatedStore::from_hydrated_state(data) {
+                Err(StoreHydrationError::Schema { path, message }) => format!("{path}: {message}"),
+                other => panic!("expected a schema error, got {:?}", other.err()),
+            };
+
+            let owner = Owner::new();
+            owner.with(|| {
+                // `None` fields may be left out
+                let restored = ValidatedStore::from_hydrated_state(
+                    r#"{"count":3,"name":"a","items":["x","y"]}"#,
+                )
+                .unwrap();
+                assert_eq!(restored.state.get_untracked().items, ["x", "y"]);
+
+                assert_eq!(
+                    schema_error(r#"{"count":"3","name":"a","items":[]}"#),
+                    "$.count: expected an integer, found a string"
+                );
+                assert_eq!(
+                    schema_error(r#"{"count":3,"items":[]}"#),
+                    "$: missing field `name`"
+                );
+            });
+
+            let sample = serde_json::json!({"tokens": [{"price": 1.5, "meta": "x"}]});
+            let payload = serde_json::json!({
+                "tokens": [{"price": 1, "meta": "x"}, {"price": "2", "meta": {}}]
+            });
+            let schema = HydrationSchema::from_sample(&sample).unwrap();
+            assert_eq!(
+                schema.validate(&payload).unwrap_err().to_string(),
+                "Invalid hydration data at `$.tokens[1].meta`: expected a string, found an object"
+            );
+            let schema = schema.allow("tokens[].meta");
+            assert_eq!(
+                schema.validate(&payload).unwrap_err().to_string(),
+                "Invalid hydration data at `$.tokens[1].price`: expected a number, found a string"
+            );
+            assert!(schema.allow("tokens[].price").validate(&payload).is_ok());
+        }
+
+        #[test]
+        #[cfg(feature = "hydrate-binary")]
+        fn test_derive_hydratable_store_binary() {
+            #[derive(Clone, crate::store::Store, HydratableStore)]
+            #[hydrate(key = "test_store", binary)]
+            struct BinaryStore {
+                state: RwSignal<TestState>,
+            }
+
+            let state = TestState {
+                count: 1_000_000,
+                name: "</script>".to_string(),
+                items: (0..50).map(|i| i.to_string()).collect(),
+                optional: Some(false),
+            };
+            let store = BinaryStore {
+                state: RwSignal::new(state.clone()),
+            };
+            let serialized = store.serialize_state().unwrap();
+            let json = TestHydratableStore::with_state(state.clone())
+                .serialize_state()
+                .unwrap();
+            assert!(serialized.len() < json.len());
+            assert_eq!(escape_script_data(&serialized), serialized);
+
+            let restored = BinaryStore::from_hydrated_state(&serialized).unwrap();
+            assert_eq!(restored.state.get_untracked(), state);
+            assert!(matches!(
+                deserialize_binary::<TestState>("not base64!"),
+                Err(StoreHydrationError::InvalidData(_))
+            ));
+        }
+
+        #[test]
+        fn test_full_hydration_html_generation() {
+            let state = TestState {
+                count: 100,
+                name: "Hydration Test".to_string(),
+                items: vec!["a".to_string(), "b".to_string()],
+                optional: None,
+            };
+
+            let store = TestHydratableStore::with_state(state);
+            let serialized = store.serialize_state().unwrap();
+
+            // Generate the full hydration HTML
+            let html = hydration_script_html(TestHydratableStore::store_key(), &serialized);
+
+            // Verify the HTML structure
+            assert!(html.starts_with("<script"));
+            assert!(html.ends_with("</script>"));
+            assert!(html.contains("__LEPTOS_STORE_STATE__test_store"));
+            assert!(html.contains("application/json"));
+            assert!(html.contains("100")); // count value
+            assert!(html.contains("Hydration Test")); // name value
+        }
+
+        #[test]
+        fn test_store_hydration_scripts() {
+            let owner = Owner::new();
+            owner.with(|| {
+                let payloads = HydrationPayloads::current();
+                let store = TestHydratableStore::new();
+                crate::context::register_hydrated_store(store.clone());
+                // Registering the same key again replaces the earlier store
+                crate::context::register_hydrated_store(store.clone());
+
+                // State is serialized when the scripts render
+                store.state.update(|state| state.count = 7);
+                let scripts = payloads.serialize(false);
+                assert_eq!(scripts.len(), 1);
+                assert_eq!(scripts[0].0, "test_store");
+                assert!(scripts[0].1.contains(r#""count":7"#));
+
+                // Only stores read while rendering are embedded with `used_only`
+                assert!(payloads.serialize(true).is_empty());
+                assert!(crate::context::try_use_store::<TestHydratableStore>().is_ok());
+                assert_eq!(payloads.serialize(true), scripts);
+            });
+        }
+
+        #[test]
+        fn test_state_codec() {
+            /// Stands in for an encrypting codec.
+            struct ReversedCodec;
+
+            impl<T: Serialize + serde::de::DeserializeOwned> StateCodec<T> for ReversedCodec {
+                fn encode(state: &T) -> Result<String, StoreHydrationError> {
+                    Ok(JsonCodec::encode(state)?.chars().rev().collect())
+                }
+
+                fn decode(data: &str) -> Result<T, StoreHydrationError> {
+                    JsonCodec::decode(&data.chars().rev().collect::<String>())
+                }
+            }
+
+            #[derive(Clone, crate::store::Store, HydratableStore)]
+            #[hydrate(key = "test_store", codec = ReversedCodec)]
+            struct DerivedStore {
+                state: RwSignal<TestState>,
+            }
+
+            #[derive(Clone, crate::store::Store)]
+            struct MacroStore {
+                state: RwSignal<TestState>,
+            }
+            crate::impl_hydratable_store!(MacroStore, "test_store", codec = ReversedCodec);
+
+            let state = TestState {
+                count: 2,
+                name: "sealed".to_string(),
+                ..TestState::default()
+            };
+            let owner = Owner::new();
+            owner.with(|| {
+                let derived = DerivedStore {
+                    state: RwSignal::new(state.clone()),
+                };
+                let data = derived.serialize_state().unwrap();
+                assert!(data.starts_with('}'));
+                let restored = DerivedStore::from_hydrated_state(&data).unwrap();
+                assert_eq!(restored.state.get_untracked(), state);
+
+                let from_macro = MacroStore::from_hydrated_state(&data).unwrap();
+                assert_eq!(from_macro.serialize_state().unwrap(), data);
+                assert!(
+                    DerivedStore::from_hydrated_state(&serialize_state(&state).unwrap()).is_err()
+                );
+            });
         }
-    }
 
-    // ========================================================================
-    // Integration tests for the full hydration workflow
-    // ========================================================================
+        #[test]
+        fn test_streamed_hydration() {
+            use futures::executor::block_on;
 
-    #[cfg(feature = "hydrate")]
-    mod hydration_integration {
-        use super::*;
-        use crate::store::Store;
-        use leptos::prelude::*;
-        use serde::{Deserialize, Serialize};
+            assert_eq!(
+                streamed_hydration_id("test_store"),
+                "__LEPTOS_STORE_STATE__test_store__streamed"
+            );
 
-        /// Test state with various field types
-        #[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
-        struct TestState {
-            count: i32,
-            name: String,
-            items: Vec<String>,
-            optional: Option<bool>,
+            let owner = Owner::new();
+            owner.with(|| {
+                let store = TestHydratableStore::new();
+                let _view = streamed_hydration_script(store.clone(), async {});
+
+                // There's no document to stream into off the browser
+                assert!(matches!(
+                    block_on(hydrate_streamed(&store)),
+                    Err(StoreHydrationError::DomError(_))
+                ));
+            });
         }
 
-        /// Test store that implements HydratableStore
-        #[derive(Clone)]
-        struct TestHydratableStore {
-            state: RwSignal<TestState>,
+        #[test]
+        fn test_provide_island_store() {
+            assert_eq!(island_store_key("cart", "42"), "cart@42");
+
+            let owner = Owner::new();
+            owner.with(|| {
+                // Off the browser there's no island data, so the factory is used
+                let _script = crate::context::provide_island_store("42", || {
+                    TestHydratableStore::with_state(TestState {
+                        count: 42,
+                        ..TestState::default()
+                    })
+                });
+                let store = crate::context::use_store::<TestHydratableStore>();
+                assert_eq!(store.state.get_untracked().count, 42);
+            });
         }
 
-        impl TestHydratableStore {
-            fn new() -> Self {
-                Self {
-                    state: RwSignal::new(TestState::default()),
-                }
+        #[test]
+        fn test_provide_island_store_attrs() {
+            use leptos::attr::Attribute;
+
+            assert_eq!(island_store_attribute("cart"), "data-store-cart");
+
+            let owner = Owner::new();
+            owner.with(|| {
+                let attrs = crate::context::provide_island_store_attrs("42", || {
+                    TestHydratableStore::with_state(TestState {
+                        count: 42,
+                        name: "\"quoted\"".to_string(),
+                        ..TestState::default()
+                    })
+                });
+                let store = crate::context::use_store::<TestHydratableStore>();
+                assert_eq!(store.state.get_untracked().count, 42);
+
+                // The state is carried by the root element, escaped as an attribute
+                let mut html = String::new();
+                attrs.to_html(&mut html, &mut String::new(), &mut String::new(), &mut String::new());
+                let key = TestHydratableStore::store_key();
+                assert!(html.contains(&format!(r#"data-store-{key}-instance="42""#)));
+                assert!(html.contains(&format!(
+                    r#"data-store-{key}="{{&quot;count&quot;:42,&quot;name&quot;:&quot;\&quot;quoted\&quot;&quot;"#
+                )));
+            });
+        }
+
+        #[test]
+        fn test_hydrated_store_provider() {
+            use crate::context::HydratedStoreProvider;
+
+            let owner = Owner::new();
+            owner.with(|| {
+                let store = TestHydratableStore::with_state(TestState {
+                    count: 1,
+                    name: "<b>".to_string(),
+                    ..TestState::default()
+                });
+
+                let payload = HydratedStoreProvider::new(store.clone())
+                    .payload()
+                    .unwrap()
+                    .unwrap();
+                assert!(payload.starts_with(r#"{"count":1,"name":"\u003cb\u003e""#));
+
+                // Pretty-printing is for debug builds only
+                let payload = HydratedStoreProvider::new(store.clone())
+                    .pretty(true)
+                    .payload()
+                    .unwrap()
+                    .unwrap();
+                assert_eq!(payload.contains('\n'), cfg!(debug_assertions));
+
+                // The hook runs before escaping, the custom escaper replaces it
+                let payload = HydratedStoreProvider::new(store.clone())
+                    .post_process(|data| data.replace("\"count\":1", "\"count\":2"))
+                    .escape(|data| data.replace('<', "&lt;"))
+                    .payload()
+                    .unwrap()
+                    .unwrap();
+                assert!(payload.starts_with(r#"{"count":2,"name":"&lt;b>""#));
+                assert_eq!(
+                    TestHydratableStore::from_hydrated_state(
+                        &HydratedStoreProvider::new(store.clone())
+                            .post_process(|data| data.replace("\"count\":1", "\"count\":2"))
+                            .payload()
+                            .unwrap()
+                            .unwrap()
+                    )
+                    .unwrap()
+                    .state
+                    .get_untracked()
+                    .count,
+                    2
+                );
+
+                assert!(
+                    HydratedStoreProvider::new(TestHydratableStore::new())
+                        .skip_if_default()
+                        .payload()
+                        .is_none()
+                );
+                assert!(
+                    HydratedStoreProvider::new(store.clone())
+                        .skip_if_default()
+                        .payload()
+                        .is_some()
+                );
+
+                // A key override skips the duplicate key check and provides
+                // the store as usual
+                let _script = HydratedStoreProvider::new(store).key("other").provide();
+                let store = crate::context::use_store::<TestHydratableStore>();
+                assert_eq!(store.state.get_untracked().count, 1);
+            });
+        }
+
+        #[test]
+        fn test_provide_request_store() {
+            use futures::executor::block_on;
+
+            #[derive(Clone, Default, crate::store::Store, HydratableStore)]
+            #[hydrate(key = "test_store")]
+            struct RequestStore {
+                state: RwSignal<TestState>,
             }
 
-            fn with_state(state: TestState) -> Self {
-                Self {
-                    state: RwSignal::new(state),
+            fn load(count: i32) -> RequestStore {
+                RequestStore {
+                    state: RwSignal::new(TestState {
+                        count,
+                        ..TestState::default()
+                    }),
                 }
             }
+
+            let _ = any_spawner::Executor::init_futures_executor();
+            let owner = Owner::new();
+            owner.with(|| {
+                let request =
+                    crate::context::provide_request_store(|| async { Ok::<_, String>(load(5)) });
+                let store = crate::context::use_store::<RequestStore>();
+
+                let ready = block_on(request.ready());
+                assert_eq!(ready.state.get_untracked().count, 5);
+                assert_eq!(store.state.get_untracked().count, 5);
+
+                // A failed factory keeps the default state
+                let request = crate::context::provide_request_store(|| async {
+                    Err::<RequestStore, _>("offline")
+                });
+                assert_eq!(block_on(request.ready()).state.get_untracked().count, 0);
+            });
         }
 
-        impl Store for TestHydratableStore {
-            type State = TestState;
+        #[test]
+        fn test_provide_shared_store() {
+            use futures::{StreamExt, executor::block_on};
+            use hydration_context::{
+                PinnedFuture, PinnedStream, SerializedDataId, SharedContext, SsrSharedContext,
+            };
+            use leptos::error::{Error, ErrorId};
+            use std::sync::Arc;
 
-            fn state(&self) -> leptos::prelude::ReadSignal<Self::State> {
-                self.state.read_only()
+            /// A browser context serving data written on the server.
+            #[derive(Debug)]
+            struct BrowserContext(Vec<String>);
+
+            impl SharedContext for BrowserContext {
+                fn is_browser(&self) -> bool {
+                    true
+                }
+                fn next_id(&self) -> SerializedDataId {
+                    SerializedDataId::new(0)
+                }
+                fn write_async(&self, _: SerializedDataId, _: PinnedFuture<String>) {}
+                fn read_data(&self, id: &SerializedDataId) -> Option<String> {
+                    self.0.get(id.clone().into_inner()).cloned()
+                }
+                fn await_data(&self, id: &SerializedDataId) -> Option<String> {
+                    self.read_data(id)
+                }
+                fn pending_data(&self) -> Option<PinnedStream<String>> {
+                    None
+                }
+                fn during_hydration(&self) -> bool {
+                    true
+                }
+                fn hydration_complete(&self) {}
+                fn get_is_hydrating(&self) -> bool {
+                    true
+                }
+                fn set_is_hydrating(&self, _: bool) {}
+                fn take_errors(&self) -> Vec<(SerializedDataId, ErrorId, Error)> {
+                    Vec::new()
+                }
+                fn errors(&self, _: &SerializedDataId) -> Vec<(ErrorId, Error)> {
+                    Vec::new()
+                }
+                fn seal_errors(&self, _: &SerializedDataId) {}
+                fn register_error(&self, _: SerializedDataId, _: ErrorId, _: Error) {}
+                fn defer_stream(&self, _: PinnedFuture<()>) {}
+                fn await_deferred(&self) -> Option<PinnedFuture<()>> {
+                    None
+                }
+                fn set_incomplete_chunk(&self, _: SerializedDataId) {}
+                fn get_incomplete_chunk(&self, _: &SerializedDataId) -> bool {
+                    false
+                }
             }
+
+            // Without a shared context the factory is used
+            let owner = Owner::new();
+            owner.with(|| {
+                crate::context::provide_shared_store(|| {
+                    TestHydratableStore::with_state(TestState {
+                        count: 3,
+                        ..TestState::default()
+                    })
+                });
+                let store = crate::context::use_store::<TestHydratableStore>();
+                assert_eq!(store.state.get_untracked().count, 3);
+            });
+
+            // Server: the state lands in the resource data, including
+            // changes made after the store was provided
+            let server = Arc::new(SsrSharedContext::new());
+            let owner = Owner::new_root(Some(server.clone()));
+            owner.with(|| {
+                crate::context::provide_shared_store(TestHydratableStore::new);
+                let store = crate::context::use_store::<TestHydratableStore>();
+                store.state.update(|s| s.count = 7);
+
+                let chunks = block_on(server.pending_data().unwrap().collect::<String>());
+                assert!(chunks.contains("__RESOLVED_RESOURCES"));
+                assert!(chunks.contains(r#"\"count\":7"#));
+            });
+
+            // Client: restored from the shared context instead of the factory
+            let data = TestHydratableStore::with_state(TestState {
+                count: 7,
+                ..TestState::default()
+            })
+            .serialize_state()
+            .unwrap();
+            let owner = Owner::new_root(Some(Arc::new(BrowserContext(vec![data]))));
+            owner.with(|| {
+                crate::context::provide_shared_store(TestHydratableStore::new);
+                let store = crate::context::use_store::<TestHydratableStore>();
+                assert_eq!(store.state.get_untracked().count, 7);
+            });
         }
 
-        impl HydratableStore for TestHydratableStore {
-            fn serialize_state(&self) -> Result<String, StoreHydrationError> {
-                let state = self.state.get();
-                serde_json::to_string(&state)
-                    .map_err(|e| StoreHydrationError::Serialization(e.to_string()))
+        #[test]
+        fn test_hydrate_store_into() {
+            #[derive(Clone, crate::store::Store, HydratableStore)]
+            #[hydrate(key = "hydrated_in_place")]
+            struct InPlaceStore {
+                state: RwSignal<TestState>,
             }
 
-            fn from_hydrated_state(data: &str) -> Result<Self, StoreHydrationError> {
-                let state: TestState = serde_json::from_str(data)
-                    .map_err(|e| StoreHydrationError::Deserialization(e.to_string()))?;
-                Ok(Self::with_state(state))
-            }
+            let owner = Owner::new();
+            owner.with(|| {
+                let store = InPlaceStore {
+                    state: RwSignal::new(TestState::default()),
+                };
+                crate::context::provide_store(store.clone());
+                let held = crate::context::use_store::<InPlaceStore>();
 
-            fn store_key() -> &'static str {
-                "test_store"
+                // The provided store and its clones see the payload's state
+                let data = serialize_state(&TestState {
+                    count: 5,
+                    ..TestState::default()
+                })
+                .unwrap();
+                payload_into(&store, InPlaceStore::store_key(), &data).unwrap();
+                assert_eq!(held.state.get_untracked().count, 5);
+
+                // A bad payload leaves the state alone
+                assert!(payload_into(&store, InPlaceStore::store_key(), "{").is_err());
+                assert_eq!(held.state.get_untracked().count, 5);
+
+                // Off the browser there's no page to read from
+                assert!(matches!(
+                    hydrate_store_into(&store),
+                    Err(StoreHydrationError::DomError(_))
+                ));
+                assert_eq!(held.state.get_untracked().count, 5);
+            });
+        }
+
+        #[test]
+        fn test_hydrate_store_into_syncs_field_signals() {
+            crate::store! {
+                pub PanelStore(key = "hydrated_panel") {
+                    #[derive(serde::Serialize, serde::Deserialize)]
+                    state(signals = PanelSignals) PanelState {
+                        title: String,
+                        open: bool,
+                    }
+                }
             }
+
+            let owner = Owner::new();
+            owner.with(|| {
+                let store = PanelStore::new();
+                let title = store.signals().title();
+
+                let data = serialize_state(&PanelState {
+                    title: "Inbox".to_string(),
+                    open: true,
+                })
+                .unwrap();
+                payload_into(&store, PanelStore::store_key(), &data).unwrap();
+
+                // Subscribers of the field signals see the hydrated values
+                assert_eq!(title.get_untracked(), "Inbox");
+                assert!(store.signals().open().get_untracked());
+            });
         }
 
         #[test]
-        fn test_store_serialization_roundtrip() {
-            // Create a store with specific state
-            let original_state = TestState {
-                count: 42,
-                name: "Test Store".to_string(),
-                items: vec!["item1".to_string(), "item2".to_string()],
-                optional: Some(true),
-            };
+        fn test_rehydrate() {
+            use futures::executor::block_on;
 
-            let store = TestHydratableStore::with_state(original_state.clone());
+            #[derive(Clone, crate::store::Store, HydratableStore)]
+            #[hydrate(key = "rehydrated_store")]
+            struct RehydratedStore {
+                state: RwSignal<TestState>,
+            }
 
-            // Serialize
-            let serialized = store
-                .serialize_state()
-                .expect("Serialization should succeed");
+            let owner = Owner::new();
+            owner.with(|| {
+                register_rehydration_source(|| async {
+                    Ok::<_, String>(RehydratedStore {
+                        state: RwSignal::new(TestState {
+                            count: 8,
+                            ..TestState::default()
+                        }),
+                    })
+                });
 
-            // Verify JSON structure
-            assert!(serialized.contains("42"));
-            assert!(serialized.contains("Test Store"));
-            assert!(serialized.contains("item1"));
+                // Clones handed out earlier see the fresh state
+                let store = RehydratedStore {
+                    state: RwSignal::new(TestState::default()),
+                };
+                let held = store.clone();
+                block_on(rehydrate(&store, |key| async move {
+                    serve_rehydration(&key).await
+                }))
+                .unwrap();
+                assert_eq!(held.state.get_untracked().count, 8);
 
-            // Deserialize into new store
-            let restored_store = TestHydratableStore::from_hydrated_state(&serialized)
-                .expect("Deserialization should succeed");
+                assert_eq!(
+                    block_on(rehydrate(&store, |_| async { Err::<String, _>("offline") })),
+                    Err(StoreHydrationError::Rehydration("offline".to_string()))
+                );
+                assert_eq!(
+                    block_on(serve_rehydration("missing_store")),
+                    Err(StoreHydrationError::NotFound("missing_store".to_string()))
+                );
 
-            // Verify state matches
-            let restored_state = restored_store.state.get();
-            assert_eq!(restored_state, original_state);
+                // Off the browser, pages are never restored
+                rehydrate_on_restore(&store, |_| async { Err::<String, _>("unreachable") });
+                assert_eq!(held.state.get_untracked().count, 8);
+
+                // Hand-written impls can't be hydrated in place by default
+                assert!(matches!(
+                    TestHydratableStore::new().hydrate_into("{}"),
+                    Err(StoreHydrationError::InvalidData(_))
+                ));
+            });
         }
 
         #[test]
-        fn test_store_default_state_roundtrip() {
-            let store = TestHydratableStore::new();
+        #[cfg(debug_assertions)]
+        #[should_panic(expected = "Duplicate hydration store key `duplicate_store`")]
+        fn test_duplicate_store_key_panics() {
+            #[derive(Clone, crate::store::Store, HydratableStore)]
+            #[hydrate(key = "duplicate_store")]
+            struct FirstStore {
+                state: RwSignal<TestState>,
+            }
 
-            let serialized = store
-                .serialize_state()
-                .expect("Serialization should succeed");
-            let restored = TestHydratableStore::from_hydrated_state(&serialized)
-                .expect("Deserialization should succeed");
+            #[derive(Clone, crate::store::Store, HydratableStore)]
+            #[hydrate(key = "duplicate_store")]
+            struct SecondStore {
+                state: RwSignal<TestState>,
+            }
 
-            assert_eq!(restored.state.get(), TestState::default());
+            let owner = Owner::new();
+            owner.with(|| {
+                let first = FirstStore {
+                    state: RwSignal::new(TestState::default()),
+                };
+                crate::context::register_hydrated_store(first.clone());
+                // The same store type again is fine
+                let _ = crate::context::provide_hydrated_store(first);
+                crate::context::register_hydrated_store(SecondStore {
+                    state: RwSignal::new(TestState::default()),
+                });
+            });
         }
 
         #[test]
-        fn test_store_key_is_correct() {
-            assert_eq!(TestHydratableStore::store_key(), "test_store");
+        #[cfg(debug_assertions)]
+        #[should_panic(expected = "Duplicate hydration store key `shared_override`")]
+        fn test_duplicate_override_key_panics() {
+            use crate::context::HydratedStoreProvider;
+
+            #[derive(Clone, crate::store::Store, HydratableStore)]
+            #[hydrate(key = "override_first")]
+            struct FirstStore {
+                state: RwSignal<TestState>,
+            }
+
+            #[derive(Clone, crate::store::Store, HydratableStore)]
+            #[hydrate(key = "override_second")]
+            struct SecondStore {
+                state: RwSignal<TestState>,
+            }
+
+            let owner = Owner::new();
+            owner.with(|| {
+                let _ = HydratedStoreProvider::new(FirstStore {
+                    state: RwSignal::new(TestState::default()),
+                })
+                .key("shared_override")
+                .provide();
+                let _ = HydratedStoreProvider::new(SecondStore {
+                    state: RwSignal::new(TestState::default()),
+                })
+                .key("shared_override")
+                .provide();
+            });
         }
 
         #[test]
-        fn test_full_hydration_html_generation() {
+        fn test_lazy_hydrated_store() {
+            use std::sync::atomic::{AtomicUsize, Ordering};
+
+            static CREATED: AtomicUsize = AtomicUsize::new(0);
+            let owner = Owner::new();
+            owner.with(|| {
+                let payloads = HydrationPayloads::current();
+                crate::context::register_lazy_hydrated_store(|| {
+                    CREATED.fetch_add(1, Ordering::SeqCst);
+                    TestHydratableStore::with_state(TestState {
+                        count: 9,
+                        ..TestState::default()
+                    })
+                });
+                assert_eq!(CREATED.load(Ordering::SeqCst), 0);
+
+                // First use in a child scope creates the store under the
+                // scope it was registered in
+                let child = owner.child();
+                let store = child.with(crate::context::use_store::<TestHydratableStore>);
+                child.cleanup();
+                assert_eq!(CREATED.load(Ordering::SeqCst), 1);
+                store.state.update(|state| state.count += 1);
+
+                let again = crate::context::use_hydrated_store::<TestHydratableStore>();
+                assert_eq!(again.state.get_untracked().count, 10);
+                assert_eq!(CREATED.load(Ordering::SeqCst), 1);
+                assert_eq!(payloads.serialize(true).len(), 1);
+            });
+        }
+
+        #[test]
+        #[cfg(feature = "hydrate-encrypt")]
+        fn test_encrypted_codec() {
+            /// Not a cipher: XOR with the key, tagged with the key's first
+            /// byte so a wrong key is detected.
+            struct XorCipher;
+
+            impl PayloadCipher for XorCipher {
+                fn encrypt(key: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, StoreHydrationError> {
+                    let xored = plaintext.iter().zip(key.iter().cycle()).map(|(b, k)| b ^ k);
+                    Ok(std::iter::once(key[0]).chain(xored).collect())
+                }
+
+                fn decrypt(key: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, StoreHydrationError> {
+                    match ciphertext.split_first() {
+                        Some((tag, data)) if *tag == key[0] => Ok(data
+                            .iter()
+                            .zip(key.iter().cycle())
+                            .map(|(b, k)| b ^ k)
+                            .collect()),
+                        _ => Err(StoreHydrationError::Encryption("wrong key".to_string())),
+                    }
+                }
+            }
+
+            #[derive(Clone, crate::store::Store, HydratableStore)]
+            #[hydrate(key = "test_store", codec = EncryptedCodec<XorCipher>)]
+            struct SecretStore {
+                state: RwSignal<TestState>,
+            }
+
             let state = TestState {
-                count: 100,
-                name: "Hydration Test".to_string(),
-                items: vec!["a".to_string(), "b".to_string()],
-                optional: None,
+                name: "secret".to_string(),
+                ..TestState::default()
             };
+            let owner = Owner::new();
+            owner.with(|| {
+                let store = SecretStore {
+                    state: RwSignal::new(state.clone()),
+                };
+                assert!(matches!(
+                    store.serialize_state(),
+                    Err(StoreHydrationError::Encryption(_))
+                ));
 
-            let store = TestHydratableStore::with_state(state);
-            let serialized = store.serialize_state().unwrap();
+                provide_hydration_key(b"session-key".to_vec());
+                let payload = store.serialize_state().unwrap();
+                assert!(!payload.contains("secret"));
+                let restored = SecretStore::from_hydrated_state(&payload).unwrap();
+                assert_eq!(restored.state.get_untracked(), state);
 
-            // Generate the full hydration HTML
-            let html = hydration_script_html(TestHydratableStore::store_key(), &serialized);
+                // Another session's key can't open it
+                let other = owner.child();
+                other.with(|| {
+                    provide_hydration_key(b"other-key".to_vec());
+                    assert_eq!(
+                        SecretStore::from_hydrated_state(&payload).err(),
+                        Some(StoreHydrationError::Encryption("wrong key".to_string()))
+                    );
+                });
+            });
+        }
 
-            // Verify the HTML structure
-            assert!(html.starts_with("<script"));
-            assert!(html.ends_with("</script>"));
-            assert!(html.contains("__LEPTOS_STORE_STATE__test_store"));
-            assert!(html.contains("application/json"));
-            assert!(html.contains("100")); // count value
-            assert!(html.contains("Hydration Test")); // name value
+        #[test]
+        fn test_sanitize_hydrated_state() {
+            fn sanitize(state: TestState) -> TestState {
+                TestState {
+                    count: state.count.min(100),
+                    name: String::new(),
+                    ..state
+                }
+            }
+
+            #[derive(Clone, crate::store::Store, HydratableStore)]
+            #[hydrate(key = "test_store", sanitize = sanitize)]
+            struct SanitizedStore {
+                state: RwSignal<TestState>,
+            }
+
+            let owner = Owner::new();
+            owner.with(|| {
+                let data = serialize_state(&TestState {
+                    count: 500,
+                    name: "token".to_string(),
+                    items: vec!["kept".to_string()],
+                    optional: None,
+                })
+                .unwrap();
+
+                let store = SanitizedStore::from_hydrated_state(&data).unwrap();
+                let expected = TestState {
+                    count: 100,
+                    items: vec!["kept".to_string()],
+                    ..TestState::default()
+                };
+                assert_eq!(store.state.get_untracked(), expected);
+
+                store.state.set(TestState::default());
+                store.hydrate_into(&data).unwrap();
+                assert_eq!(store.state.get_untracked(), expected);
+
+                // Serializing is left alone
+                store.state.set(TestState {
+                    name: "server".to_string(),
+                    ..TestState::default()
+                });
+                assert!(store.serialize_state().unwrap().contains("server"));
+            });
+        }
+
+        #[test]
+        fn test_skip_hydration() {
+            use crate::context::{HydratedStoreProvider, register_hydrated_store};
+
+            #[derive(Clone, crate::store::Store, HydratableStore)]
+            #[hydrate(key = "skip_default_store", skip_default)]
+            struct SkipDefaultStore {
+                state: RwSignal<TestState>,
+            }
+
+            fn no_items(state: &TestState) -> bool {
+                state.items.is_empty()
+            }
+
+            #[derive(Clone, crate::store::Store, HydratableStore)]
+            #[hydrate(key = "skip_if_store", skip_if = no_items)]
+            struct SkipIfStore {
+                state: RwSignal<TestState>,
+            }
+
+            let owner = Owner::new();
+            owner.with(|| {
+                let payloads = HydrationPayloads::current();
+                let skip_default = SkipDefaultStore {
+                    state: RwSignal::new(TestState::default()),
+                };
+                let skip_if = SkipIfStore {
+                    state: RwSignal::new(TestState {
+                        count: 1,
+                        ..TestState::default()
+                    }),
+                };
+                assert!(skip_default.skip_hydration());
+                assert!(skip_if.skip_hydration());
+                assert!(!TestHydratableStore::new().skip_hydration());
+
+                register_hydrated_store(skip_default.clone());
+                register_hydrated_store(skip_if.clone());
+                assert!(payloads.serialize(false).is_empty());
+
+                // Checked when the scripts render, not when registering
+                skip_default.state.update(|state| state.count = 1);
+                skip_if
+                    .state
+                    .update(|state| state.items.push("a".to_string()));
+                let keys: Vec<_> = payloads
+                    .serialize(false)
+                    .into_iter()
+                    .map(|(key, _)| key)
+                    .collect();
+                assert_eq!(keys, ["skip_default_store", "skip_if_store"]);
+
+                let store = TestHydratableStore::with_state(TestState {
+                    count: 1,
+                    ..TestState::default()
+                });
+                assert!(
+                    HydratedStoreProvider::new(store.clone())
+                        .skip_if(|state| state.count < 5)
+                        .payload()
+                        .is_none()
+                );
+                assert!(
+                    HydratedStoreProvider::new(store)
+                        .skip_if(|state| state.count > 5)
+                        .payload()
+                        .is_some()
+                );
+                assert!(
+                    HydratedStoreProvider::new(SkipDefaultStore {
+                        state: RwSignal::new(TestState::default()),
+                    })
+                    .payload()
+                    .is_none()
+                );
+            });
+        }
+
+        #[test]
+        fn test_hydrate_all_falls_back_to_factories() {
+            register_hydratable(TestHydratableStore::new);
+            register_hydratable(|| {
+                TestHydratableStore::with_state(TestState {
+                    count: 3,
+                    ..TestState::default()
+                })
+            });
+
+            let owner = Owner::new();
+            owner.with(|| {
+                let payloads = HydrationPayloads::current();
+                let report = hydrate_all();
+
+                // No DOM here, so the latest factory is used
+                assert!(report.hydrated().is_empty());
+                assert_eq!(report.fallbacks().len(), 1);
+                assert_eq!(report.fallbacks()[0].0, "test_store");
+                let store = crate::context::use_store::<TestHydratableStore>();
+                assert_eq!(store.state.get_untracked().count, 3);
+                assert_eq!(payloads.serialize(false).len(), 1);
+            });
         }
 
         #[test]
@@ -574,6 +3973,145 @@ mod tests {
             assert_eq!(store.state.get().name, "Fallback");
         }
 
+        #[test]
+        fn test_hydration_builder_async_fallback() {
+            #[derive(Clone, crate::store::Store, HydratableStore)]
+            #[hydrate(key = "test_store")]
+            struct FetchedStore {
+                state: RwSignal<TestState>,
+            }
+
+            let _ = any_spawner::Executor::init_futures_executor();
+            let owner = Owner::new();
+            owner.with(|| {
+                let (store, loading) = HydrationBuilder::new()
+                    .with_fallback(FetchedStore {
+                        state: RwSignal::new(TestState::default()),
+                    })
+                    .with_async_fallback(|| async {
+                        Ok::<_, String>(FetchedStore {
+                            state: RwSignal::new(TestState {
+                                count: 5,
+                                ..TestState::default()
+                            }),
+                        })
+                    })
+                    .build_with_loading();
+                assert!(loading.get_untracked());
+                assert_eq!(store.state.get_untracked().count, 0);
+
+                any_spawner::Executor::poll_local();
+                assert!(!loading.get_untracked());
+                assert_eq!(store.state.get_untracked().count, 5);
+
+                // A failed fetch keeps the fallback state
+                let (store, loading) = HydrationBuilder::new()
+                    .with_fallback(FetchedStore {
+                        state: RwSignal::new(TestState::default()),
+                    })
+                    .with_async_fallback(|| async { Err::<FetchedStore, _>("offline") })
+                    .build_with_loading();
+                any_spawner::Executor::poll_local();
+                assert!(!loading.get_untracked());
+                assert_eq!(store.state.get_untracked().count, 0);
+            });
+        }
+
+        #[test]
+        fn test_hydration_diagnostics() {
+            #[derive(Debug, Deserialize)]
+            #[allow(dead_code)]
+            struct Token {
+                symbol: String,
+                price: f64,
+            }
+
+            #[derive(Debug, Deserialize)]
+            #[allow(dead_code)]
+            struct Tokens {
+                tokens: Vec<Token>,
+            }
+
+            let diagnose = |data: &str| {
+                let error = deserialize_state::<Tokens>(data).unwrap_err();
+                HydrationDiagnostics::new("tokens", data, &error)
+            };
+
+            // A field of the wrong type is located in the state
+            let data = r#"{"tokens":[{"symbol":"A","price":1.0},{"symbol":"B\"","price":"2"}]}"#;
+            let diagnostics = diagnose(data);
+            assert_eq!(diagnostics.path.as_deref(), Some("$.tokens[1].price"));
+            assert!(diagnostics.hint.unwrap().contains("field type"));
+
+            // A missing field points at its object and at `#[serde(skip)]`
+            let diagnostics = diagnose(r#"{"tokens":[{"symbol":"A","price":1.0},{"symbol":"B"}]}"#);
+            assert_eq!(diagnostics.path.as_deref(), Some("$.tokens[1]"));
+            assert!(diagnostics.hint.as_ref().unwrap().contains("`price`"));
+            assert!(diagnostics.hint.unwrap().contains("#[serde(default)]"));
+
+            // A payload in another format
+            let diagnostics = diagnose("bm90IGpzb24");
+            assert!(diagnostics.hint.unwrap().contains("codec"));
+
+            // Long payloads are cut around the error
+            let symbols = "X".repeat(200);
+            let data = format!(r#"{{"tokens":[{{"symbol":"{symbols}","price":true}}]}}"#);
+            let diagnostics = diagnose(&data);
+            assert!(diagnostics.excerpt.starts_with('…'));
+            assert!(diagnostics.excerpt.contains("\"price\":true"));
+            assert!(diagnostics.excerpt.len() < data.len());
+
+            let report = diagnostics.to_string();
+            assert!(report.starts_with("Store `tokens` failed to hydrate: Deserialization error:"));
+            assert!(report.contains("\n  at: $.tokens[0].price"));
+
+            // Schema errors already carry their path
+            let error = StoreHydrationError::Schema {
+                path: "$.count".to_string(),
+                message: "expected an integer, found a string".to_string(),
+            };
+            let diagnostics = HydrationDiagnostics::new("counter", r#"{"count":"1"}"#, &error);
+            assert_eq!(diagnostics.path.as_deref(), Some("$.count"));
+            assert_eq!(diagnostics.hint, None);
+            assert_eq!(diagnostics.excerpt, r#"{"count":"1"}"#);
+        }
+
+        #[test]
+        fn test_hydration_events() {
+            let owner = Owner::new();
+            owner.with(|| {
+                // Listeners are global, so only keep the events of this test
+                let events = Arc::new(Mutex::new(Vec::new()));
+                let subscription = on_hydration_event({
+                    let events = Arc::clone(&events);
+                    move |event| {
+                        if event.store_key() == "telemetry" {
+                            let serialized = matches!(event, HydrationEvent::Serialized { .. });
+                            events.lock().unwrap().push((serialized, event.bytes()));
+                        }
+                    }
+                });
+
+                let store = TestHydratableStore::with_state(TestState {
+                    count: 3,
+                    ..Default::default()
+                });
+                let data = report_serialize("telemetry", || store.serialize_state()).unwrap();
+                let restored = from_payload::<TestHydratableStore>("telemetry", &data).unwrap();
+                assert_eq!(restored.state.get_untracked().count, 3);
+                // Failures aren't reported
+                assert!(from_payload::<TestHydratableStore>("telemetry", "{").is_err());
+
+                let recorded = events.lock().unwrap().clone();
+                assert_eq!(recorded, [(true, data.len()), (false, data.len())]);
+
+                // Dropping the subscription removes the listener
+                drop(subscription);
+                report_serialize("telemetry", || store.serialize_state()).unwrap();
+                assert_eq!(events.lock().unwrap().len(), 2);
+            });
+        }
+
         #[test]
         fn test_deserialization_error_handling() {
             // Invalid JSON
@@ -611,6 +4149,7 @@ mod tests {
             let html = hydration_script_html(TestHydratableStore::store_key(), &serialized);
             // Script tags in the content should be escaped
             assert!(!html.contains("</script>\""));
+            assert_eq!(html.matches('<').count(), 2);
         }
 
         #[test]
@@ -36,7 +36,14 @@
 //! |---------|---------|-------------|
 //! | `ssr` | ✅ Yes | Server-side rendering support |
 //! | `hydrate` | ❌ No | SSR hydration with automatic state serialization |
+//! | `hydrate-binary` | ❌ No | Compact postcard + base64 hydration payloads |
+//! | `hydrate-compress` | ❌ No | Compress large hydration payloads |
+//! | `hydrate-encrypt` | ❌ No | Encrypt hydration payloads with a per-session key |
+//! | `axum` | ❌ No | Per-request stores for `leptos_axum` servers |
+//! | `actix` | ❌ No | Per-request stores for `leptos_actix` servers |
 //! | `csr` | ❌ No | Client-side rendering only |
+//! | `persist` | ❌ No | Persist store state to browser storage |
+//! | `describe` | ❌ No | Export store descriptions as JSON, DOT, or Mermaid |
 //!
 //! ### Choosing Features
 //!
@@ -57,13 +64,17 @@
 //!
 //! | Macro | Purpose | Feature |
 //! |-------|---------|---------|
-//! | `define_state!` | Define state structs with default values | - |
+//! | `define_state!` | Define state structs with default values and `with_<field>` setters | - |
 //! | `define_hydratable_state!` | Define state with serde derives | `hydrate` |
-//! | `define_action!` | Define synchronous action structs | - |
-//! | `define_async_action!` | Define async action structs with error types | - |
+//! | `define_action!` | Define synchronous action structs, optionally implementing `Action` | - |
+//! | `define_async_action!` | Define async action structs, optionally implementing `AsyncAction` | - |
 //! | `impl_store!` | Implement Store trait for an existing type | - |
 //! | `impl_hydratable_store!` | Implement HydratableStore trait | `hydrate` |
+//! | `#[derive(HydratableStore)]` | Derive HydratableStore, reading state untracked | `hydrate` |
+//! | `rehydrate_endpoint!` | Server function serving fresh store state | `hydrate` |
 //! | `store!` | Complete store definition in one macro | - |
+//! | `entity_store!` | Store for a normalized, ID-keyed entity collection | - |
+//! | `mock_store!` | Stub store recording calls, for component tests | - |
 //!
 //! See the [`macros`] module for detailed documentation and examples.
 //!
@@ -132,14 +143,36 @@
 #![warn(clippy::all)]
 #![deny(unsafe_code)]
 
+// Allows `#[derive(Store)]` output (which names `::leptos_store`) to be used
+// inside this crate.
+extern crate self as leptos_store;
+
+#[cfg(all(feature = "actix", not(target_arch = "wasm32")))]
+pub mod actix;
 pub mod r#async;
+#[cfg(feature = "axum")]
+pub mod axum;
 pub mod context;
+pub mod deps;
+#[cfg(feature = "describe")]
+pub mod describe;
+pub mod entity;
+pub mod env;
 pub mod macros;
+pub mod middleware;
+pub mod mock;
+pub mod mutation;
+pub mod prefetch;
+pub mod request;
 pub mod store;
+pub mod tasks;
 
 #[cfg(feature = "hydrate")]
 pub mod hydration;
 
+#[cfg(feature = "persist")]
+pub mod persistence;
+
 pub mod prelude;
 
 pub use prelude::*;
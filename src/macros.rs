@@ -10,13 +10,15 @@
 //!
 //! | Macro | Purpose | Feature |
 //! |-------|---------|---------|
-//! | `define_state!` | Define state structs with default values | - |
+//! | `define_state!` | Define state structs with default values and `with_<field>` setters | - |
 //! | `define_hydratable_state!` | Define state with serde derives for hydration | `hydrate` |
 //! | `define_action!` | Define synchronous action structs | - |
 //! | `define_async_action!` | Define async action structs with error types | - |
 //! | `impl_store!` | Implement Store trait for a type | - |
 //! | `impl_hydratable_store!` | Implement HydratableStore trait | `hydrate` |
 //! | `store!` | Complete store definition in one macro | - |
+//! | `deprecated_alias!` | Deprecated forwarding aliases for renamed methods | - |
+//! | `mock_store!` | Stub store recording calls, for component tests | - |
 //!
 //! # Quick Start
 //!
@@ -92,18 +94,25 @@
 // define_state! macro
 // ============================================================================
 
+#[doc(hidden)]
+pub use leptos_store_macros::state_builders as __state_builders;
+
 /// Define a state struct with optional default values.
 ///
 /// This macro creates a struct with public fields and generates a `Default`
 /// implementation. Fields can have explicit default values or use the type's
 /// `Default` implementation.
 ///
+/// Each field also gets a `with_<field>` setter, with the visibility of the
+/// struct, so partial initial states can start from the defaults and
+/// override a few fields.
+///
 /// # Syntax
 ///
 /// ```text
 /// define_state! {
 ///     #[derive(...)]           // Optional: derive macros
-///     pub struct StateName {   // Visibility and name
+///     pub struct StateName {   // Visibility, name, optional generics
 ///         field1: Type1,       // Uses Type1::default()
 ///         field2: Type2 = val, // Uses explicit value
 ///     }
@@ -173,6 +182,63 @@
 ///     }
 /// }
 /// ```
+///
+/// ## Builder Setters
+///
+/// ```rust
+/// use leptos_store::define_state;
+///
+/// define_state! {
+///     #[derive(Clone, Debug)]
+///     pub struct SessionState {
+///         user: Option<String>,
+///         locale: String = "en".to_string(),
+///         dark_mode: bool = false,
+///     }
+/// }
+///
+/// // e.g. an SSR initial state with the request's locale
+/// let session = SessionState::default()
+///     .with_locale("de".to_string())
+///     .with_dark_mode(true);
+/// assert_eq!(session.locale, "de");
+/// assert!(session.user.is_none());
+/// ```
+///
+/// ## With Generics
+///
+/// Generic parameters, bounds, and `where` clauses are propagated to the
+/// generated `Default` implementation. Default values for generic
+/// parameters (`T = u32`) are not supported.
+///
+/// ```rust
+/// use leptos_store::define_state;
+///
+/// define_state! {
+///     #[derive(Clone, Debug)]
+///     pub struct Paged<T> {
+///         items: Vec<T>,
+///         page: usize = 1,
+///     }
+/// }
+///
+/// define_state! {
+///     #[derive(Clone, Debug)]
+///     pub struct Selection<K: Clone + Ord, V>
+///     where
+///         V: Default,
+///     {
+///         selected: Option<K>,
+///         value: V,
+///     }
+/// }
+///
+/// let paged: Paged<String> = Paged::default();
+/// assert_eq!(paged.page, 1);
+///
+/// let selection: Selection<u32, String> = Selection::default();
+/// assert!(selection.selected.is_none());
+/// ```
 #[macro_export]
 macro_rules! define_state {
     (
@@ -201,6 +267,133 @@ macro_rules! define_state {
                 }
             }
         }
+
+        $crate::macros::__state_builders! {
+            $vis struct $name {
+                $(
+                    $(#[$field_meta])*
+                    $field: $ty,
+                )*
+            }
+        }
+    };
+
+    // Generic struct - munch the generics header
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident < $($rest:tt)*
+    ) => {
+        $crate::define_state!(@generics [$(#[$meta])*] [$vis] $name [] [] [] start $($rest)*);
+    };
+
+    // Generics helpers - start of a parameter: record its name
+    (@generics $meta:tt $vis:tt $name:ident [$($gen:tt)*] [$($params:tt)*] [] start
+        const $param:ident $($rest:tt)*
+    ) => {
+        $crate::define_state!(@generics $meta $vis $name
+            [$($gen)* const $param] [$($params)* $param] [] more $($rest)*);
+    };
+    (@generics $meta:tt $vis:tt $name:ident [$($gen:tt)*] [$($params:tt)*] [] start
+        $param:lifetime $($rest:tt)*
+    ) => {
+        $crate::define_state!(@generics $meta $vis $name
+            [$($gen)* $param] [$($params)* $param] [] more $($rest)*);
+    };
+    (@generics $meta:tt $vis:tt $name:ident [$($gen:tt)*] [$($params:tt)*] [] start
+        $param:ident $($rest:tt)*
+    ) => {
+        $crate::define_state!(@generics $meta $vis $name
+            [$($gen)* $param] [$($params)* $param] [] more $($rest)*);
+    };
+
+    // Generics helpers - next parameter
+    (@generics $meta:tt $vis:tt $name:ident [$($gen:tt)*] [$($params:tt)*] [] more
+        , $($rest:tt)*
+    ) => {
+        $crate::define_state!(@generics $meta $vis $name
+            [$($gen)* ,] [$($params)* ,] [] start $($rest)*);
+    };
+
+    // Generics helpers - end of the generics list
+    (@generics $meta:tt $vis:tt $name:ident [$($gen:tt)*] [$($params:tt)*] [] more
+        > $($rest:tt)*
+    ) => {
+        $crate::define_state!(@where $meta $vis $name [$($gen)*] [$($params)*] [] $($rest)*);
+    };
+
+    // Generics helpers - nested angle brackets in bounds
+    (@generics $meta:tt $vis:tt $name:ident [$($gen:tt)*] $params:tt [$($depth:tt)*] more
+        < $($rest:tt)*
+    ) => {
+        $crate::define_state!(@generics $meta $vis $name
+            [$($gen)* <] $params [x $($depth)*] more $($rest)*);
+    };
+    (@generics $meta:tt $vis:tt $name:ident [$($gen:tt)*] $params:tt [x $($depth:tt)*] more
+        > $($rest:tt)*
+    ) => {
+        $crate::define_state!(@generics $meta $vis $name
+            [$($gen)* >] $params [$($depth)*] more $($rest)*);
+    };
+    (@generics $meta:tt $vis:tt $name:ident [$($gen:tt)*] $params:tt [x $($depth:tt)*] more
+        >> $($rest:tt)*
+    ) => {
+        $crate::define_state!(@generics $meta $vis $name
+            [$($gen)* >] $params [$($depth)*] more > $($rest)*);
+    };
+
+    // Generics helpers - any other token of a bound
+    (@generics $meta:tt $vis:tt $name:ident [$($gen:tt)*] $params:tt $depth:tt more
+        $token:tt $($rest:tt)*
+    ) => {
+        $crate::define_state!(@generics $meta $vis $name
+            [$($gen)* $token] $params $depth more $($rest)*);
+    };
+
+    // Where clause helpers - the fields block ends the header
+    (@where $meta:tt $vis:tt $name:ident $gen:tt $params:tt [$($where:tt)*]
+        { $($fields:tt)* }
+    ) => {
+        $crate::define_state!(@emit $meta $vis $name $gen $params [$($where)*] { $($fields)* });
+    };
+    (@where $meta:tt $vis:tt $name:ident $gen:tt $params:tt [$($where:tt)*]
+        $token:tt $($rest:tt)*
+    ) => {
+        $crate::define_state!(@where $meta $vis $name $gen $params [$($where)* $token] $($rest)*);
+    };
+
+    // Generic struct output
+    (@emit [$(#[$meta:meta])*] [$vis:vis] $name:ident [$($gen:tt)*] [$($params:tt)*] [$($where:tt)*] {
+        $(
+            $(#[$field_meta:meta])*
+            $field_vis:vis $field:ident : $ty:ty $(= $default:expr)?
+        ),* $(,)?
+    }) => {
+        $(#[$meta])*
+        $vis struct $name < $($gen)* > $($where)* {
+            $(
+                $(#[$field_meta])*
+                $field_vis $field: $ty,
+            )*
+        }
+
+        impl < $($gen)* > Default for $name < $($params)* > $($where)* {
+            fn default() -> Self {
+                Self {
+                    $(
+                        $field: $crate::define_state!(@default $ty $(, $default)?),
+                    )*
+                }
+            }
+        }
+
+        $crate::macros::__state_builders! {
+            $vis struct $name < $($gen)* > $($where)* {
+                $(
+                    $(#[$field_meta])*
+                    $field: $ty,
+                )*
+            }
+        }
     };
 
     // Default value helper - with explicit default
@@ -274,9 +467,92 @@ macro_rules! define_state {
 /// let restored: CounterState = serde_json::from_str(&json).unwrap();
 /// assert_eq!(restored.count, 0);
 /// ```
+///
+/// # Versioning
+///
+/// Prefix the struct with `version = N;` to implement
+/// [`VersionedState`](crate::hydration::VersionedState). Payloads then carry
+/// the version, and older payloads go through an optional `migrate` hook
+/// instead of failing to deserialize. Without a hook, older payloads are
+/// deserialized as-is, which works when fields were only added with
+/// `#[serde(default)]`.
+///
+/// ```rust,ignore
+/// define_hydratable_state! {
+///     version = 2;
+///     migrate(from, value) {
+///         let text = |field: &str| value[field].as_str().unwrap_or_default().to_string();
+///         let full_name = match from {
+///             // v1 split the name in two
+///             1 => format!("{} {}", text("first"), text("last")),
+///             // Unversioned payloads stored it as `name`
+///             _ => text("name"),
+///         };
+///         Ok(ProfileState { full_name })
+///     }
+///     #[derive(Clone, Debug)]
+///     pub struct ProfileState {
+///         full_name: String,
+///     }
+/// }
+///
+/// impl_hydratable_store!(ProfileStore, "profile", versioned);
+/// ```
 #[cfg(feature = "hydrate")]
 #[macro_export]
 macro_rules! define_hydratable_state {
+    // Versioned state with a migration hook
+    (
+        version = $version:literal;
+        migrate ( $from:ident, $value:ident ) $migrate_body:block
+        $($rest:tt)*
+    ) => {
+        $crate::define_hydratable_state!(@versioned $version, ($from, $value) $migrate_body; $($rest)*);
+    };
+
+    // Versioned state without a migration hook
+    (
+        version = $version:literal;
+        $($rest:tt)*
+    ) => {
+        $crate::define_hydratable_state!(@versioned $version, ; $($rest)*);
+    };
+
+    // Versioned state - emit the struct and implement VersionedState
+    (
+        @versioned $version:literal, $( ($from:ident, $value:ident) $migrate_body:block )? ;
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident {
+            $(
+                $(#[$field_meta:meta])*
+                $field_vis:vis $field:ident : $ty:ty $(= $default:expr)?
+            ),* $(,)?
+        }
+    ) => {
+        $crate::define_hydratable_state! {
+            $(#[$meta])*
+            $vis struct $name {
+                $(
+                    $(#[$field_meta])*
+                    $field_vis $field : $ty $(= $default)?
+                ),*
+            }
+        }
+
+        impl $crate::hydration::VersionedState for $name {
+            const VERSION: u32 = $version;
+
+            fn migrate(
+                from_version: u32,
+                value: ::serde_json::Value,
+            ) -> Result<Self, $crate::hydration::StoreHydrationError> {
+                $crate::define_hydratable_state!(
+                    @migrate from_version value $( ($from, $value) $migrate_body )?
+                )
+            }
+        }
+    };
+
     (
         $(#[$meta:meta])*
         $vis:vis struct $name:ident {
@@ -306,6 +582,23 @@ macro_rules! define_hydratable_state {
         }
     };
 
+    // Migration helper - user-provided hook
+    (@migrate $version_arg:ident $value_arg:ident ($from:ident, $value:ident) $body:block) => {{
+        let $from = $version_arg;
+        let $value = $value_arg;
+        $body
+    }};
+
+    // Migration helper - no hook, so rely on serde defaults for added fields
+    (@migrate $version_arg:ident $value_arg:ident) => {
+        ::serde_json::from_value($value_arg).map_err(|e| {
+            $crate::hydration::StoreHydrationError::Deserialization(format!(
+                "cannot migrate state from version {}: {}",
+                $version_arg, e
+            ))
+        })
+    };
+
     // Default value helper - with explicit default
     (@default $ty:ty, $default:expr) => { $default };
 
@@ -324,12 +617,19 @@ macro_rules! define_hydratable_state {
 ///
 /// ```text
 /// impl_hydratable_store!(StoreName, "store_key");
+/// impl_hydratable_store!(StoreName, "store_key", versioned);
+/// impl_hydratable_store!(StoreName, "store_key", codec = CodecType);
 /// ```
 ///
 /// # Arguments
 ///
 /// - `StoreName` - The store type to implement HydratableStore for
 /// - `"store_key"` - A unique string key for this store (used in DOM)
+/// - `versioned` - Optional; serialize through
+///   [`VersionedState`](crate::hydration::VersionedState) so payloads carry
+///   a schema version and older ones are migrated
+/// - `codec = CodecType` - Optional; encode the state with a
+///   [`StateCodec`](crate::hydration::StateCodec)
 ///
 /// # Example
 ///
@@ -360,10 +660,77 @@ macro_rules! define_hydratable_state {
 #[cfg(feature = "hydrate")]
 #[macro_export]
 macro_rules! impl_hydratable_store {
+    ($store:ty, $key:literal, versioned) => {
+        impl $crate::hydration::HydratableStore for $store {
+            fn serialize_state(&self) -> Result<String, $crate::hydration::StoreHydrationError> {
+                $crate::hydration::serialize_versioned(
+                    &::leptos::prelude::GetUntracked::get_untracked(&self.state),
+                )
+            }
+
+            fn from_hydrated_state(
+                data: &str,
+            ) -> Result<Self, $crate::hydration::StoreHydrationError> {
+                let state: <Self as $crate::store::Store>::State =
+                    $crate::hydration::deserialize_versioned(data)?;
+                Ok(Self {
+                    state: ::leptos::prelude::RwSignal::new(state),
+                })
+            }
+
+            fn hydrate_into(
+                &self,
+                data: &str,
+            ) -> Result<(), $crate::hydration::StoreHydrationError> {
+                let state: <Self as $crate::store::Store>::State =
+                    $crate::hydration::deserialize_versioned(data)?;
+                ::leptos::prelude::Set::set(&self.state, state);
+                Ok(())
+            }
+
+            fn store_key() -> &'static str {
+                $key
+            }
+        }
+    };
+
+    ($store:ty, $key:literal, codec = $codec:ty) => {
+        impl $crate::hydration::HydratableStore for $store {
+            fn serialize_state(&self) -> Result<String, $crate::hydration::StoreHydrationError> {
+                <$codec as $crate::hydration::StateCodec<<Self as $crate::store::Store>::State>>::encode(
+                    &::leptos::prelude::GetUntracked::get_untracked(&self.state),
+                )
+            }
+
+            fn from_hydrated_state(
+                data: &str,
+            ) -> Result<Self, $crate::hydration::StoreHydrationError> {
+                let state = <$codec as $crate::hydration::StateCodec<
+                    <Self as $crate::store::Store>::State,
+                >>::decode(data)?;
+                Ok(Self {
+                    state: ::leptos::prelude::RwSignal::new(state),
+                })
+            }
+
+            fn hydrate_into(&self, data: &str) -> Result<(), $crate::hydration::StoreHydrationError> {
+                let state = <$codec as $crate::hydration::StateCodec<
+                    <Self as $crate::store::Store>::State,
+                >>::decode(data)?;
+                ::leptos::prelude::Set::set(&self.state, state);
+                Ok(())
+            }
+
+            fn store_key() -> &'static str {
+                $key
+            }
+        }
+    };
+
     ($store:ty, $key:literal) => {
         impl $crate::hydration::HydratableStore for $store {
             fn serialize_state(&self) -> Result<String, $crate::hydration::StoreHydrationError> {
-                let state = self.state.get();
+                let state = ::leptos::prelude::GetUntracked::get_untracked(&self.state);
                 ::serde_json::to_string(&state).map_err(|e| {
                     $crate::hydration::StoreHydrationError::Serialization(e.to_string())
                 })
@@ -381,6 +748,16 @@ macro_rules! impl_hydratable_store {
                 })
             }
 
+            fn hydrate_into(
+                &self,
+                data: &str,
+            ) -> Result<(), $crate::hydration::StoreHydrationError> {
+                let state: <Self as $crate::store::Store>::State =
+                    $crate::hydration::deserialize_state(data)?;
+                ::leptos::prelude::Set::set(&self.state, state);
+                Ok(())
+            }
+
             fn store_key() -> &'static str {
                 $key
             }
@@ -388,6 +765,52 @@ macro_rules! impl_hydratable_store {
     };
 }
 
+/// Define the server function serving fresh store state for
+/// [`rehydrate`](crate::hydration::rehydrate).
+///
+/// Expands to a `#[server]` function taking a store key and returning the
+/// state serialized by the source registered for it with
+/// [`register_rehydration_source`](crate::hydration::register_rehydration_source).
+/// One endpoint serves every store. Invoke it in the app crate, which needs
+/// the usual `ssr` feature enabling `leptos/ssr`, like any server function.
+///
+/// # Syntax
+///
+/// ```text
+/// rehydrate_endpoint!(pub fn rehydrate_state);
+/// rehydrate_endpoint!(pub fn rehydrate_state, "/api");
+/// ```
+///
+/// # Example
+///
+/// ```rust,ignore
+/// leptos_store::rehydrate_endpoint!(pub fn rehydrate_state);
+///
+/// // On the server, before serving requests
+/// register_rehydration_source(|| async { Ok::<_, String>(TokenStore::load().await?) });
+///
+/// // On the client, e.g. after reconnecting
+/// rehydrate(&use_store::<TokenStore>(), rehydrate_state).await?;
+/// ```
+#[cfg(feature = "hydrate")]
+#[macro_export]
+macro_rules! rehydrate_endpoint {
+    ($vis:vis fn $name:ident) => {
+        $crate::rehydrate_endpoint!($vis fn $name, "/api");
+    };
+
+    ($vis:vis fn $name:ident, $prefix:literal) => {
+        #[::leptos::prelude::server(prefix = $prefix)]
+        $vis async fn $name(
+            store_key: String,
+        ) -> Result<String, ::leptos::prelude::ServerFnError> {
+            $crate::hydration::serve_rehydration(&store_key)
+                .await
+                .map_err(::leptos::prelude::ServerFnError::new)
+        }
+    };
+}
+
 // ============================================================================
 // define_action! macro
 // ============================================================================
@@ -408,6 +831,17 @@ macro_rules! impl_hydratable_store {
 ///         field2: Type2,       // Another field
 ///     }
 /// }
+///
+/// // With a target store, implementing `Action<StoreName>`
+/// define_action! {
+///     pub ActionName for StoreName {
+///         field1: Type1,
+///     } -> OutputType;             // Optional: defaults to ()
+///
+///     execute(this, store) {
+///         // Body with access to `this` (the action) and `store`
+///     }
+/// }
 /// ```
 ///
 /// # Generated Code
@@ -415,6 +849,9 @@ macro_rules! impl_hydratable_store {
 /// The macro generates:
 /// - A struct with public fields
 /// - A `new()` constructor that takes all fields as arguments
+/// - With a target store, an [`Action`](crate::r#async::Action) implementation
+///   so the action can be passed to
+///   [`StoreActionExt::dispatch`](crate::r#async::StoreActionExt::dispatch)
 ///
 /// # Examples
 ///
@@ -479,43 +916,140 @@ macro_rules! impl_hydratable_store {
 ///     }
 /// }
 /// ```
+///
+/// ## Action With an Execute Body
+///
+/// ```rust
+/// use leptos::prelude::*;
+/// use leptos_store::prelude::*;
+/// use leptos_store::{define_action, store};
+///
+/// store! {
+///     pub CounterStore {
+///         state CounterState {
+///             count: i32,
+///         }
+///
+///         mutators {
+///             add(this, amount: i32) {
+///                 this.mutate(|s| s.count += amount);
+///             }
+///         }
+///     }
+/// }
+///
+/// define_action! {
+///     /// Adds an amount and returns the new count
+///     pub AddAction for CounterStore {
+///         amount: i32,
+///     } -> i32;
+///
+///     execute(this, store) {
+///         store.add(this.amount);
+///         store.state().get_untracked().count
+///     }
+/// }
+///
+/// let store = CounterStore::new();
+/// assert_eq!(store.dispatch(AddAction::new(3)), 3);
+/// ```
 #[macro_export]
 macro_rules! define_action {
+    // Version with a target store, execute body, and output type
     (
         $(#[$meta:meta])*
-        $vis:vis $name:ident {
+        $vis:vis $name:ident for $store:ty {
             $(
                 $(#[$field_meta:meta])*
                 $field:ident : $ty:ty
             ),* $(,)?
-        }
+        } -> $output:ty;
+
+        execute($this:ident, $store_arg:ident) $body:block
     ) => {
-        $(#[$meta])*
-        $vis struct $name {
-            $(
-                $(#[$field_meta])*
-                pub $field: $ty,
-            )*
+        $crate::define_action! {
+            $(#[$meta])*
+            $vis $name {
+                $(
+                    $(#[$field_meta])*
+                    $field : $ty
+                ),*
+            }
         }
 
-        impl $name {
-            /// Create a new action with the given parameters.
-            pub fn new($($field: $ty),*) -> Self {
-                Self { $($field),* }
+        impl $crate::r#async::Action<$store> for $name {
+            type Output = $output;
+
+            fn execute(&self, $store_arg: &$store) -> Self::Output {
+                #[allow(unused_variables)]
+                let $this = self;
+                #[allow(unused_variables)]
+                let $store_arg = $store_arg;
+                $body
             }
         }
     };
-}
-
-// ============================================================================
-// define_async_action! macro
-// ============================================================================
 
-/// Define an async action struct with associated result types.
-///
-/// Async actions are command objects for asynchronous operations like API calls,
-/// database queries, or file I/O. This macro creates a struct with fields and
-/// type aliases for the success and error types.
+    // Version with a target store and execute body, no output
+    (
+        $(#[$meta:meta])*
+        $vis:vis $name:ident for $store:ty {
+            $(
+                $(#[$field_meta:meta])*
+                $field:ident : $ty:ty
+            ),* $(,)?
+        }
+
+        execute($this:ident, $store_arg:ident) $body:block
+    ) => {
+        $crate::define_action! {
+            $(#[$meta])*
+            $vis $name for $store {
+                $(
+                    $(#[$field_meta])*
+                    $field : $ty
+                ),*
+            } -> ();
+
+            execute($this, $store_arg) $body
+        }
+    };
+
+    (
+        $(#[$meta:meta])*
+        $vis:vis $name:ident {
+            $(
+                $(#[$field_meta:meta])*
+                $field:ident : $ty:ty
+            ),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis struct $name {
+            $(
+                $(#[$field_meta])*
+                pub $field: $ty,
+            )*
+        }
+
+        impl $name {
+            /// Create a new action with the given parameters.
+            pub fn new($($field: $ty),*) -> Self {
+                Self { $($field),* }
+            }
+        }
+    };
+}
+
+// ============================================================================
+// define_async_action! macro
+// ============================================================================
+
+/// Define an async action struct with associated result types.
+///
+/// Async actions are command objects for asynchronous operations like API calls,
+/// database queries, or file I/O. This macro creates a struct with fields and
+/// type aliases for the success and error types.
 ///
 /// # Syntax
 ///
@@ -536,6 +1070,26 @@ macro_rules! define_action {
 /// - Type aliases: `{ActionName}Output` and `{ActionName}Error`
 /// - A `result_type()` method for documentation
 ///
+/// # Implementing AsyncAction
+///
+/// Name a target store with `for Store` and add an `execute` body to also
+/// implement [`AsyncAction`](crate::r#async::AsyncAction) for that store. The
+/// first parameter binds `&self` and the second binds `&Store`. With a plain
+/// output type instead of `Result`, the action is infallible and the body
+/// returns the output directly.
+///
+/// ```text
+/// define_async_action! {
+///     pub ActionName for StoreType {
+///         field1: Type1,
+///     } -> Result<Output, Error>;
+///
+///     async execute(this, store) {
+///         // ... return Result<Output, Error>
+///     }
+/// }
+/// ```
+///
 /// # Examples
 ///
 /// ## API Fetch Action
@@ -641,8 +1195,125 @@ macro_rules! define_action {
 ///     } -> Result<UploadResult, UploadError>
 /// }
 /// ```
+///
+/// ## Action With an Execute Body
+///
+/// ```rust
+/// use leptos::prelude::*;
+/// use leptos_store::prelude::*;
+/// use leptos_store::{define_async_action, store};
+///
+/// store! {
+///     pub CounterStore {
+///         state CounterState {
+///             count: i32,
+///         }
+///
+///         getters {
+///             count(this) -> i32 {
+///                 this.read(|s| s.count)
+///             }
+///         }
+///     }
+/// }
+///
+/// define_async_action! {
+///     /// Doubles the current count
+///     pub DoubleAction for CounterStore {
+///         offset: i32,
+///     } -> i32;
+///
+///     async execute(this, store) {
+///         store.count() * 2 + this.offset
+///     }
+/// }
+///
+/// # futures::executor::block_on(async {
+/// let store = CounterStore::new();
+/// let result = DoubleAction::new(1).execute(&store).await;
+/// assert_eq!(result, Ok(1));
+/// # });
+/// ```
 #[macro_export]
 macro_rules! define_async_action {
+    // Version with a target store and execute body, Result<Output, Error>
+    (
+        $(#[$meta:meta])*
+        $vis:vis $name:ident for $store:ty {
+            $(
+                $(#[$field_meta:meta])*
+                $field:ident : $ty:ty
+            ),* $(,)?
+        } -> Result<$output:ty, $error:ty>;
+
+        async execute($this:ident, $store_arg:ident) $body:block
+    ) => {
+        $crate::define_async_action! {
+            $(#[$meta])*
+            $vis $name {
+                $(
+                    $(#[$field_meta])*
+                    $field : $ty
+                ),*
+            } -> Result<$output, $error>
+        }
+
+        impl $crate::r#async::AsyncAction<$store> for $name {
+            type Output = $output;
+            type Error = $error;
+
+            async fn execute(
+                &self,
+                $store_arg: &$store,
+            ) -> $crate::r#async::ActionResult<Self::Output, Self::Error> {
+                #[allow(unused_variables)]
+                let $this = self;
+                #[allow(unused_variables)]
+                let $store_arg = $store_arg;
+                $body
+            }
+        }
+    };
+
+    // Version with a target store and execute body, infallible
+    (
+        $(#[$meta:meta])*
+        $vis:vis $name:ident for $store:ty {
+            $(
+                $(#[$field_meta:meta])*
+                $field:ident : $ty:ty
+            ),* $(,)?
+        } -> $output:ty;
+
+        async execute($this:ident, $store_arg:ident) $body:block
+    ) => {
+        $crate::define_async_action! {
+            $(#[$meta])*
+            $vis $name {
+                $(
+                    $(#[$field_meta])*
+                    $field : $ty
+                ),*
+            } -> $output
+        }
+
+        impl $crate::r#async::AsyncAction<$store> for $name {
+            type Output = $output;
+            type Error = ::std::convert::Infallible;
+
+            async fn execute(
+                &self,
+                $store_arg: &$store,
+            ) -> $crate::r#async::ActionResult<Self::Output, Self::Error> {
+                #[allow(unused_variables)]
+                let $this = self;
+                #[allow(unused_variables)]
+                let $store_arg = $store_arg;
+                Ok($body)
+            }
+        }
+    };
+
     // Version with Result<Output, Error>
     (
         $(#[$meta:meta])*
@@ -739,6 +1410,9 @@ macro_rules! define_async_action {
 ///
 /// ```text
 /// impl_store!(StoreName, StateName, field_name);
+/// impl_store!(StoreName, StateName, field_name: SignalKind);
+/// impl_store!(StoreName, StateName, field_name: SourceKind => reader_field);
+/// impl_store!(StoreName, StateName, |this| accessor_expression);
 /// ```
 ///
 /// # Arguments
@@ -746,6 +1420,20 @@ macro_rules! define_async_action {
 /// - `StoreName` - The type to implement Store for
 /// - `StateName` - The state type
 /// - `field_name` - The name of the RwSignal field
+/// - `SignalKind` - The field's signal type: `RwSignal` (the default) or
+///   `ReadSignal`
+/// - `SourceKind => reader_field` - For a field of a type that can't be read
+///   as a `ReadSignal` for free, `ArcRwSignal` or `Memo`: the field holding
+///   a `ReadSignal` made from it once, when the store is built
+/// - `|this| accessor_expression` - An expression returning
+///   `ReadSignal<StateName>`, for custom wrappers
+///
+/// [`Store::state`](crate::store::Store::state) is called often, so the
+/// accessor must not allocate. Turning an `ArcRwSignal` into a `ReadSignal`
+/// allocates a new arena signal each time, and a `Memo` can't be turned into
+/// one at all, so these need the cached reader: `ReadSignal::from(
+/// signal.read_only())` for an `ArcRwSignal`, and
+/// [`memo_reader`](crate::store::memo_reader) for a `Memo`.
 ///
 /// # Example
 ///
@@ -767,23 +1455,149 @@ macro_rules! define_async_action {
 ///
 /// // Now CounterStore implements the Store trait
 /// ```
+///
+/// # Other Signal Types
+///
+/// ```rust
+/// use leptos::prelude::*;
+/// use leptos_store::{impl_store, store::Store};
+///
+/// #[derive(Clone, Default, PartialEq)]
+/// struct CounterState {
+///     count: i32,
+/// }
+///
+/// // Reference-counted signal, read through a `ReadSignal` made once
+/// #[derive(Clone)]
+/// struct ArcCounterStore {
+///     state: ArcRwSignal<CounterState>,
+///     read: ReadSignal<CounterState>,
+/// }
+///
+/// impl ArcCounterStore {
+///     fn new() -> Self {
+///         let state = ArcRwSignal::new(CounterState::default());
+///         let read = ReadSignal::from(state.read_only());
+///         Self { state, read }
+///     }
+/// }
+///
+/// impl_store!(ArcCounterStore, CounterState, state: ArcRwSignal => read);
+///
+/// // State derived from another signal, mirrored once into a `ReadSignal`
+/// #[derive(Clone)]
+/// struct DoubledStore {
+///     state: Memo<CounterState>,
+///     read: ReadSignal<CounterState>,
+/// }
+///
+/// impl DoubledStore {
+///     fn new(source: RwSignal<i32>) -> Self {
+///         let state = Memo::new(move |_| CounterState { count: source.get() * 2 });
+///         let read = leptos_store::store::memo_reader(state);
+///         Self { state, read }
+///     }
+/// }
+///
+/// impl_store!(DoubledStore, CounterState, state: Memo => read);
+///
+/// // Custom wrapper with a configurable accessor
+/// #[derive(Clone)]
+/// struct Versioned<T: 'static> {
+///     current: ReadSignal<T>,
+///     set_current: WriteSignal<T>,
+/// }
+///
+/// #[derive(Clone)]
+/// struct VersionedCounterStore {
+///     inner: Versioned<CounterState>,
+/// }
+///
+/// impl_store!(VersionedCounterStore, CounterState, |this| this.inner.current);
+///
+/// let store = ArcCounterStore::new();
+/// assert_eq!(store.state().get_untracked().count, 0);
+/// ```
 #[macro_export]
 macro_rules! impl_store {
-    ($store:ty, $state:ty, $field:ident) => {
+    (@accessor RwSignal $signal:expr) => {
+        $signal.read_only()
+    };
+    (@accessor ArcRwSignal $signal:expr) => {
+        ::std::compile_error!(
+            "impl_store! can't read an ArcRwSignal field without allocating on every call; \
+             keep `ReadSignal::from(signal.read_only())` in a field and use `field: ArcRwSignal => reader`"
+        )
+    };
+    (@accessor Memo $signal:expr) => {
+        ::std::compile_error!(
+            "impl_store! can't read a Memo field as a ReadSignal; \
+             keep `memo_reader(memo)` in a field and use `field: Memo => reader`"
+        )
+    };
+    (@accessor ReadSignal $signal:expr) => {
+        $signal
+    };
+    (@accessor $kind:ident $signal:expr) => {
+        ::std::compile_error!(concat!(
+            "impl_store! supports RwSignal and ReadSignal fields; ",
+            "use `|this| expr` to return a ReadSignal from `",
+            stringify!($kind),
+            "`"
+        ))
+    };
+
+    // Sources read through a cached reader - check the field's type
+    (@source ArcRwSignal $state:ty, $signal:expr) => {
+        let _: &::leptos::prelude::ArcRwSignal<$state> = &$signal;
+    };
+    (@source Memo $state:ty, $signal:expr) => {
+        let _: &::leptos::prelude::Memo<$state> = &$signal;
+    };
+    (@source $kind:ident $state:ty, $signal:expr) => {
+        ::std::compile_error!(concat!(
+            "impl_store! supports `=> reader` for ArcRwSignal and Memo fields, not `",
+            stringify!($kind),
+            "`"
+        ));
+    };
+
+    ($store:ty, $state:ty, |$this:ident| $accessor:expr) => {
         impl $crate::store::Store for $store {
             type State = $state;
 
             fn state(&self) -> ::leptos::prelude::ReadSignal<Self::State> {
-                self.$field.read_only()
+                let $this = self;
+                $accessor
             }
         }
     };
+
+    ($store:ty, $state:ty, $field:ident : $kind:ident => $reader:ident) => {
+        $crate::impl_store!($store, $state, |this| {
+            $crate::impl_store!(@source $kind $state, this.$field);
+            this.$reader
+        });
+    };
+
+    ($store:ty, $state:ty, $field:ident : $kind:ident) => {
+        $crate::impl_store!($store, $state, |this| $crate::impl_store!(@accessor $kind this.$field));
+    };
+
+    ($store:ty, $state:ty, $field:ident) => {
+        $crate::impl_store!($store, $state, $field: RwSignal);
+    };
 }
 
 // ============================================================================
 // store! macro
 // ============================================================================
 
+#[doc(hidden)]
+pub use leptos_store_macros::{
+    mutation_enum as __mutation_enum, mutation_variant as __mutation_variant,
+};
+
 /// Define a complete store with state, getters, and mutators in one macro.
 ///
 /// This is the most comprehensive macro that generates:
@@ -803,6 +1617,10 @@ macro_rules! impl_store {
 ///             field2: Type2 = default_value,
 ///         }
 ///
+///         deps {
+///             dependency: DependencyType,
+///         }
+///
 ///         getters {
 ///             getter_name() -> ReturnType {
 ///                 // body using self.state()
@@ -918,8 +1736,8 @@ macro_rules! impl_store {
 ///
 /// ```text
 /// store! {
-///     pub StoreName {
-///         state StateName {
+///     pub StoreName {                        // or: pub StoreName(key = "store_key")
+///         state StateName {                  // or: state(signals = SignalsName, patch = PatchName) StateName
 ///             field1: Type1,
 ///             field2: Type2 = default_value,
 ///         }
@@ -930,7 +1748,7 @@ macro_rules! impl_store {
 ///             }
 ///         }
 ///
-///         mutators {
+///         mutators {                         // or: mutators(pub(crate)), mutators(pub(self))
 ///             mutator_name(this) {
 ///                 this.mutate(|s| s.field = value);
 ///             }
@@ -938,6 +1756,16 @@ macro_rules! impl_store {
 ///                 this.mutate(|s| s.field = param);
 ///             }
 ///         }
+///
+///         actions(loading = loading_field, error = error_field) {
+///             action_name(this, param: Type) -> Result<Output, Error> {
+///                 // orchestration calling mutators
+///             }
+///         }
+///
+///         transitions(state_field) {
+///             FromValue => ToValue: action_name,
+///         }
 ///     }
 /// }
 /// ```
@@ -980,337 +1808,4182 @@ macro_rules! impl_store {
 ///     }
 /// }
 /// ```
-#[macro_export]
-macro_rules! store {
-    (
-        $store_vis:vis $store_name:ident {
-            state $state_name:ident {
-                $(
-                    $field:ident : $field_ty:ty $(= $field_default:expr)?
-                ),* $(,)?
-            }
-
-            $(
-                getters {
-                    $(
-                        $getter_name:ident ( $getter_self:ident ) -> $getter_ty:ty $getter_body:block
-                    )*
-                }
-            )?
-
-            $(
-                mutators {
-                    $(
-                        $mutator_name:ident ( $mutator_self:ident $(, $mutator_param:ident : $mutator_param_ty:ty)* ) $mutator_body:block
-                    )*
-                }
-            )?
-        }
-    ) => {
-        // Generate state struct
-        #[derive(Clone, Debug)]
-        $store_vis struct $state_name {
-            $(
-                pub $field: $field_ty,
-            )*
-        }
-
-        impl Default for $state_name {
-            fn default() -> Self {
-                Self {
-                    $(
-                        $field: $crate::store!(@default $field_ty $(, $field_default)?),
-                    )*
-                }
-            }
-        }
-
-        // Generate store struct
-        #[derive(Clone)]
-        $store_vis struct $store_name {
-            state: ::leptos::prelude::RwSignal<$state_name>,
-        }
-
-        impl $store_name {
-            /// Create a new store with default state.
-            pub fn new() -> Self {
-                Self {
-                    state: ::leptos::prelude::RwSignal::new($state_name::default()),
-                }
-            }
-
-            /// Create a new store with custom initial state.
-            #[allow(dead_code)]
-            pub fn with_state(state: $state_name) -> Self {
-                Self {
-                    state: ::leptos::prelude::RwSignal::new(state),
-                }
-            }
-
-            // Generate getters - use captured self identifier
-            // Note: Users should use this.get_state() for reading
-            $(
-                $(
-                    #[allow(dead_code)]
-                    pub fn $getter_name(&self) -> $getter_ty {
-                        let $getter_self = self;
-                        $getter_body
-                    }
-                )*
-            )?
-
-            // Generate mutators - use captured self identifier
-            // Note: Users should use this.mutate() for writing
-            $(
-                $(
-                    #[allow(dead_code)]
-                    pub fn $mutator_name(&self $(, $mutator_param: $mutator_param_ty)*) {
-                        let $mutator_self = self;
-                        $mutator_body
-                    }
-                )*
+///
+/// # Debug Output
+///
+/// The store implements `Debug` by printing the store name and an untracked
+/// snapshot of the current state. Dependencies from the `deps` section are
+/// left out, so they don't need to implement `Debug`.
+///
+/// ```rust
+/// # use leptos_store::store;
+/// # store! {
+/// #     pub CounterStore {
+/// #         state CounterState {
+/// #             count: i32 = 0,
+/// #         }
+/// #     }
+/// # }
+/// let store = CounterStore::new();
+/// assert_eq!(
+///     format!("{store:?}"),
+///     "CounterStore { state: CounterState { count: 0 } }"
+/// );
+/// ```
+///
+/// # Actions
+///
+/// An optional `actions` section defines orchestration methods. Actions may be
+/// `async` and may return any type. Actions returning `Result<T, E>` can have
+/// their bookkeeping wired automatically by naming a `bool` loading field and
+/// an `Option<E>` error field on the section:
+///
+/// - the loading field is set to `true` before the action runs
+/// - on completion, loading is cleared once no other fallible action of the
+///   store is still running, and the error field is set to the error (or
+///   cleared on success)
+///
+/// ```rust
+/// use leptos_store::store;
+///
+/// #[derive(Clone, Debug, PartialEq)]
+/// pub enum SaveError {
+///     Empty,
+/// }
+///
+/// store! {
+///     pub DraftStore {
+///         state DraftState {
+///             text: String,
+///             saved: Option<String>,
+///             saving: bool = false,
+///             error: Option<SaveError>,
+///         }
+///
+///         mutators {
+///             mark_saved(this, text: String) {
+///                 this.mutate(|s| s.saved = Some(text));
+///             }
+///         }
+///
+///         actions(loading = saving, error = error) {
+///             save(this, text: String) -> Result<usize, SaveError> {
+///                 if text.is_empty() {
+///                     return Err(SaveError::Empty);
+///                 }
+///                 let len = text.len();
+///                 this.mark_saved(text);
+///                 Ok(len)
+///             }
+///         }
+///     }
+/// }
+///
+/// let store = DraftStore::new();
+/// assert_eq!(store.save(String::new()), Err(SaveError::Empty));
+/// assert_eq!(store.read(|s| s.error.clone()), Some(SaveError::Empty));
+///
+/// assert_eq!(store.save("hello".to_string()), Ok(5));
+/// assert_eq!(store.read(|s| s.error.clone()), None);
+/// assert!(!store.read(|s| s.saving));
+/// ```
+///
+/// # Dependencies
+///
+/// An optional `deps` section declares extra fields on the store struct for
+/// injected services such as API clients or configuration. Dependencies are
+/// passed to the constructors in declaration order and are available to
+/// getters, mutators, and actions as `this.<name>`. Since the store derives
+/// `Clone`, dependency types must be `Clone` (wrap services in `Arc`).
+///
+/// A store with dependencies has no `Default` implementation.
+///
+/// ```rust
+/// use leptos_store::store;
+/// use std::sync::Arc;
+///
+/// pub struct ApiClient {
+///     base_url: String,
+/// }
+///
+/// store! {
+///     pub ProfileStore {
+///         state ProfileState {
+///             last_url: Option<String>,
+///         }
+///
+///         deps {
+///             client: Arc<ApiClient>,
+///             retries: u32,
+///         }
+///
+///         actions {
+///             fetch(this, id: u32) -> String {
+///                 let url = format!("{}/users/{}", this.client.base_url, id);
+///                 this.mutate(|s| s.last_url = Some(url.clone()));
+///                 url
+///             }
+///         }
+///     }
+/// }
+///
+/// let client = Arc::new(ApiClient { base_url: "https://api.example.com".to_string() });
+/// let store = ProfileStore::new(client, 3);
+/// assert_eq!(store.fetch(7), "https://api.example.com/users/7");
+/// ```
+///
+/// # Mutator Visibility
+///
+/// Mutators are `pub` by default. Give the `mutators` section a visibility
+/// to narrow it, for example `mutators(pub(crate))` so actions in other
+/// modules of your crate can call mutators that stay hidden from downstream
+/// crates, `mutators(pub(in crate::stores))` for a named module scope, or
+/// `mutators(pub(self))` to keep them private to the defining module.
+///
+/// ```rust
+/// mod stores {
+///     leptos_store::store! {
+///         pub CartStore {
+///             state CartState {
+///                 items: Vec<String>,
+///             }
+///
+///             mutators(pub(crate)) {
+///                 push_item(this, item: String) {
+///                     this.mutate(|s| s.items.push(item));
+///                 }
+///             }
+///         }
+///     }
+/// }
+///
+/// // Code outside the `stores` module, but in the same crate
+/// let cart = stores::CartStore::new();
+/// cart.push_item("gift wrap".to_string());
+/// ```
+///
+/// # Attributes and Conditional Methods
+///
+/// Getters, mutators, and actions accept attributes, such as doc comments
+/// or `#[cfg(...)]`. A `#[cfg]` removes the method from the build and from
+/// the generated method lists, so server-only actions (database calls) and
+/// client-only actions (browser storage) can share one store definition,
+/// even under the same name.
+///
+/// ```rust
+/// use leptos_store::store;
+///
+/// store! {
+///     pub DraftStore {
+///         state DraftState {
+///             text: String,
+///         }
+///
+///         mutators {
+///             set_text(this, text: String) {
+///                 this.mutate(|s| s.text = text);
+///             }
+///         }
+///
+///         actions {
+///             /// Load the draft from the database.
+///             #[cfg(feature = "ssr")]
+///             load(this) {
+///                 this.set_text("from the database".to_string());
+///             }
+///             /// Load the draft from browser storage.
+///             #[cfg(not(feature = "ssr"))]
+///             load(this) {
+///                 this.set_text("from local storage".to_string());
+///             }
+///         }
+///     }
+/// }
+///
+/// let store = DraftStore::new();
+/// store.load();
+/// ```
+///
+/// # Generic Parameters
+///
+/// Mutator and action parameters accept any type, including `impl Trait`.
+/// Named generic parameters with bounds go between the method name and
+/// its parameter list, as in a regular `fn`.
+///
+/// ```rust
+/// use leptos::prelude::*;
+/// use leptos_store::store;
+///
+/// store! {
+///     pub TagStore {
+///         state TagState {
+///             tags: Vec<String>,
+///         }
+///
+///         mutators {
+///             add_tag(this, tag: impl Into<String>) {
+///                 this.mutate(|s| s.tags.push(tag.into()));
+///             }
+///             add_tags<I: IntoIterator<Item = T>, T: Into<String>>(this, tags: I) {
+///                 this.mutate(|s| s.tags.extend(tags.into_iter().map(Into::into)));
+///             }
+///         }
+///     }
+/// }
+///
+/// let store = TagStore::new();
+/// store.add_tag("rust");
+/// store.add_tags(["leptos", "ssr"]);
+/// assert_eq!(store.state.get_untracked().tags.len(), 3);
+/// ```
+///
+/// # Effects
+///
+/// An optional `effects` section declares reactions to state changes, such
+/// as persisting the state or logging transitions. Each effect receives the
+/// store and the previous and new state, and runs after every change. The
+/// effect name only documents its purpose.
+///
+/// Effects are started by the store's constructors and live as long as the
+/// reactive owner that created the store, so a store provided at the app
+/// root keeps its effects across component unmounts. Like Leptos effects,
+/// they only run in the browser. Effect bodies are untracked, and should
+/// not call the store's own mutators.
+///
+/// ```rust
+/// use leptos::prelude::*;
+/// use leptos_store::store;
+///
+/// store! {
+///     pub ThemeStore {
+///         state ThemeState {
+///             dark: bool,
+///         }
+///
+///         mutators {
+///             toggle(this) {
+///                 this.mutate(|s| s.dark = !s.dark);
+///             }
+///         }
+///
+///         effects {
+///             log_theme(this, old, new) {
+///                 leptos::logging::log!("dark mode: {} -> {}", old.dark, new.dark);
+///             }
+///         }
+///     }
+/// }
+///
+/// let owner = Owner::new();
+/// owner.set();
+/// let store = ThemeStore::new();
+/// store.toggle();
+/// ```
+///
+/// # Mutation Enum
+///
+/// Name an enum with `mutators as CartMutation { ... }` and the macro
+/// generates it with one variant per mutator, named after the mutator in
+/// CamelCase and holding a
+/// [`PayloadSummary`](crate::mutation::PayloadSummary) of each parameter;
+/// `name()` returns the mutator name. Every mutator call is then reported
+/// to the listeners registered with
+/// [`on_mutation`](crate::mutation::on_mutation), so loggers and devtools
+/// show `AddItems { items: len(2) }` instead of an anonymous update.
+/// Nothing is summarized while no listener is registered.
+///
+/// Parameters are summarized with their
+/// [`MutationPayload`](crate::mutation::MutationPayload) implementation,
+/// which is picked when the macro expands, before generic parameters are
+/// known. A generic or `impl Trait` parameter is therefore shown by its type
+/// name, unless it is bounded by `MutationPayload`:
+/// `push<T: Into<String> + MutationPayload>(this, item: T)`.
+///
+/// ```rust
+/// use leptos_store::mutation::on_mutation;
+/// use leptos_store::store;
+///
+/// store! {
+///     pub CartStore {
+///         state CartState {
+///             items: Vec<String>,
+///         }
+///
+///         mutators as CartMutation {
+///             add_items(this, items: Vec<String>) {
+///                 this.mutate(|s| s.items.extend(items));
+///             }
+///         }
+///     }
+/// }
+///
+/// let subscription = on_mutation(|event| leptos::logging::log!("{event}"));
+/// CartStore::new().add_items(vec!["apple".into(), "pear".into()]);
+/// drop(subscription);
+/// ```
+///
+/// # Persistence
+///
+/// With the `persist` feature, an optional `persist` section right after
+/// the state saves the state to a
+/// [`StorageBackend`](crate::persistence::StorageBackend) under `key`:
+///
+/// - `new()` starts from the persisted state, falling back to the defaults
+///   (`with_state` always uses the given state)
+/// - every change is saved, at most once per `debounce_ms` if given
+/// - `persist()` saves and `restore()` reloads on demand, and `persister()`
+///   exposes the [`StatePersister`](crate::persistence::StatePersister)
+///   with the storage health
+/// - stores persisting to `LocalStorage` or `SessionStorage` share one
+///   [`PersistentStorage`](crate::persistence::PersistentStorage), so quota
+///   eviction considers the entries of all of them
+///
+/// A store with a key is also hydrated from the server, so it starts with
+/// both the server's state and the persisted one. By default the server's
+/// wins; `reconcile: Reconcile::ClientWins` (or `Reconcile::Merge(f)`)
+/// picks another [`Reconcile`](crate::persistence::Reconcile) strategy,
+/// applied when the store is hydrated.
+///
+/// The state must implement `Serialize` and `Deserialize`.
+///
+/// ```rust,ignore
+/// use leptos_store::persistence::LocalStorage;
+/// use leptos_store::store;
+///
+/// store! {
+///     pub SettingsStore {
+///         #[derive(serde::Serialize, serde::Deserialize)]
+///         state SettingsState {
+///             theme: String = "light".to_string(),
+///             font_size: u8 = 14,
+///         }
+///
+///         persist {
+///             key: "settings",
+///             backend: LocalStorage,
+///             debounce_ms: 500,
+///         }
+///
+///         mutators {
+///             set_theme(this, theme: impl Into<String>) {
+///                 this.mutate(|s| s.theme = theme.into());
+///             }
+///         }
+///     }
+/// }
+///
+/// // Restored from localStorage, and saved again 500ms after each change
+/// let settings = SettingsStore::new();
+/// settings.set_theme("dark");
+/// ```
+///
+/// # State Machines
+///
+/// A state that is one of several phases is better modeled as an enum than
+/// as a struct of flags, where `loading`, `error`, and `data` can all be set
+/// at once. Write `state enum Name { ... }` for an enum state, marking the
+/// initial variant with `#[default]`, and a `transitions` section in place
+/// of mutators and actions. Each transition declares the variants it may
+/// run in (`_` for any) and the variant it moves to:
+///
+/// - in any other state it returns
+///   [`StoreError::InvalidTransition`](crate::store::StoreError::InvalidTransition)
+///   without running its body
+/// - its body returns the next state, which must be the declared variant;
+///   otherwise the state is kept and an error is returned
+///
+/// `variant()` returns the name of the current variant, and getters work
+/// as in other stores.
+///
+/// ```rust
+/// use leptos_store::store;
+///
+/// store! {
+///     pub FetchStore {
+///         state enum FetchState {
+///             #[default]
+///             Idle,
+///             Loading,
+///             Loaded(Vec<String>),
+///             Failed(String),
+///         }
+///
+///         transitions {
+///             start(this): Idle | Failed => Loading {
+///                 FetchState::Loading
+///             }
+///             finish(this, items: Vec<String>): Loading => Loaded {
+///                 FetchState::Loaded(items)
+///             }
+///             fail(this, error: String): Loading => Failed {
+///                 FetchState::Failed(error)
+///             }
+///             reset(this): _ => Idle {
+///                 FetchState::Idle
+///             }
+///         }
+///     }
+/// }
+///
+/// let store = FetchStore::new();
+/// assert!(store.finish(vec![]).is_err());
+///
+/// store.start().unwrap();
+/// store.finish(vec!["a".to_string()]).unwrap();
+/// assert_eq!(store.variant(), "Loaded");
+/// ```
+///
+/// # Store Key
+///
+/// Write `pub Name(key = "name")` to give the store a stable key, declared
+/// once for every place that needs it:
+///
+/// - the store implements [`KeyedStore`](crate::store::KeyedStore), for
+///   [`StoreRegistry::register_keyed`](crate::store::StoreRegistry::register_keyed)
+/// - each new store records its key with
+///   [`register_store_key`](crate::store::register_store_key), so devtools
+///   can list keys with
+///   [`registered_store_keys`](crate::store::registered_store_keys), and
+///   two store types sharing a key are reported with a warning
+/// - with the `hydrate` feature, stores without `deps` implement
+///   [`HydratableStore`](crate::hydration::HydratableStore) under the key,
+///   which requires the state to implement `Serialize` and `Deserialize`
+///
+/// ```rust
+/// use leptos_store::store::{KeyedStore, registered_store_keys};
+/// use leptos_store::store;
+///
+/// store! {
+///     pub TokenStore(key = "token_store") {
+///         #[cfg_attr(feature = "hydrate", derive(serde::Serialize, serde::Deserialize))]
+///         state TokenState {
+///             token: Option<String>,
+///         }
+///     }
+/// }
+///
+/// let _store = TokenStore::new();
+/// assert_eq!(TokenStore::KEY, "token_store");
+/// assert!(registered_store_keys().iter().any(|(key, _)| *key == "token_store"));
+/// ```
+///
+/// # Per-Field Signals
+///
+/// Components reading `store.state()` re-run on every state change. For
+/// bigger stores, write `state(signals = Name)` to also generate a `Name`
+/// struct holding one `RwSignal` per field. `store.signals().field()`
+/// returns a read-only signal that only notifies when that field changes.
+/// `store.state()` stays available as the combined read view.
+///
+/// After each `mutate`, changed fields are copied into their signals, so
+/// every field type must implement `Clone` and `PartialEq`.
+///
+/// ```rust
+/// use leptos::prelude::*;
+/// use leptos_store::store;
+///
+/// store! {
+///     pub FeedStore {
+///         state(signals = FeedSignals) FeedState {
+///             count: u32,
+///             loading: bool,
+///         }
+///
+///         mutators {
+///             set_loading(this, loading: bool) {
+///                 this.mutate(|s| s.loading = loading);
+///             }
+///         }
+///     }
+/// }
+///
+/// let store = FeedStore::new();
+/// let count = store.signals().count();
+///
+/// // Subscribers of `count` are not notified when `loading` changes
+/// store.set_loading(true);
+/// assert!(store.signals().loading().get_untracked());
+/// assert_eq!(count.get_untracked(), 0);
+/// ```
+///
+/// # State Patches
+///
+/// Write `state(patch = Name)` to generate a `Name` struct with an
+/// `Option` of every state field, and an `apply_patch` method that sets the
+/// `Some` fields in a single update. This covers forms that change several
+/// fields at once without a mutator per field. Both options can be combined,
+/// as in `state(signals = FormSignals, patch = FormPatch)`.
+///
+/// ```rust
+/// use leptos::prelude::*;
+/// use leptos_store::store;
+///
+/// store! {
+///     pub ProfileStore {
+///         state(patch = ProfilePatch) ProfileState {
+///             name: String,
+///             email: String,
+///             newsletter: bool,
+///         }
+///     }
+/// }
+///
+/// let store = ProfileStore::new();
+/// store.apply_patch(ProfilePatch {
+///     name: Some("Ada".to_string()),
+///     newsletter: Some(true),
+///     ..Default::default()
+/// });
+///
+/// let state = store.state.get_untracked();
+/// assert_eq!(state.name, "Ada");
+/// assert_eq!(state.email, "");
+/// assert!(state.newsletter);
+/// ```
+///
+/// # Transitions
+///
+/// An optional `transitions` section documents how a state field moves
+/// between values, and which getter, mutator, or action performs each
+/// transition. Transitions are descriptive: they are checked to name an
+/// existing method, and are included in the store's
+/// [`StoreDescription`](crate::describe::StoreDescription) (with the
+/// `describe` feature) for DOT/Mermaid export.
+///
+/// Every store also implements [`DescribeStore`](crate::store::DescribeStore),
+/// listing its getter, mutator, and action names.
+///
+/// # Custom Attributes and Derives
+///
+/// The state struct always derives `Clone` and `Debug`, and the store struct
+/// always derives `Clone`. Attributes placed before the store name are added
+/// to the store struct, attributes placed before `state` are added to the
+/// state struct, and attributes on individual fields are kept as-is:
+///
+/// ```rust
+/// use leptos_store::store;
+///
+/// store! {
+///     /// Application settings.
+///     pub SettingsStore {
+///         #[derive(PartialEq, Eq)]
+///         state SettingsState {
+///             /// Preferred color scheme
+///             theme: String = "dark".to_string(),
+///             #[allow(dead_code)]
+///             font_size: u8 = 14,
+///         }
+///     }
+/// }
+///
+/// assert_eq!(SettingsState::default(), SettingsState::default());
+/// ```
+#[macro_export]
+macro_rules! store {
+    // State machine - the state is an enum, changed only by transitions
+    (
+        $(#[$store_meta:meta])*
+        $store_vis:vis $store_name:ident {
+            $(#[$state_meta:meta])*
+            state enum $state_name:ident {
+                $(
+                    $(#[$variant_meta:meta])*
+                    $variant:ident
+                        $( ( $($variant_ty:ty),* $(,)? ) )?
+                        $( { $($variant_field:ident : $variant_field_ty:ty),* $(,)? } )?
+                ),* $(,)?
+            }
+
+            $(
+                getters {
+                    $(
+                        $(#[$($getter_attr:tt)*])*
+                        $getter_name:ident ( $getter_self:ident ) -> $getter_ty:ty $getter_body:block
+                    )*
+                }
             )?
 
-            /// Read state with a closure (for getters).
-            /// Uses the With trait internally.
-            #[allow(dead_code)]
-            #[inline]
-            fn read<R>(&self, f: impl FnOnce(&$state_name) -> R) -> R {
-                use ::leptos::prelude::With;
-                self.state.with(f)
-            }
+            transitions {
+                $(
+                    $(#[doc = $transition_doc:literal])*
+                    $transition_name:ident ( $transition_self:ident $(, $transition_param:ident : $transition_param_ty:ty)* )
+                        : $($transition_from:tt)|+ => $transition_to:ident $transition_body:block
+                )*
+            }
+        }
+    ) => {
+        #[derive(Clone, Debug, Default)]
+        $(#[$state_meta])*
+        $store_vis enum $state_name {
+            $(
+                $(#[$variant_meta])*
+                $variant
+                    $( ( $($variant_ty),* ) )?
+                    $( { $($variant_field: $variant_field_ty),* } )?,
+            )*
+        }
+
+        #[derive(Clone)]
+        $(#[$store_meta])*
+        $store_vis struct $store_name {
+            state: ::leptos::prelude::RwSignal<$state_name>,
+        }
+
+        impl ::std::fmt::Debug for $store_name {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                let mut debug = f.debug_struct(stringify!($store_name));
+                match ::leptos::prelude::GetUntracked::try_get_untracked(&self.state) {
+                    Some(state) => debug.field("state", &state),
+                    None => debug.field("state", &::std::format_args!("<disposed>")),
+                };
+                debug.finish()
+            }
+        }
+
+        impl $store_name {
+            /// Create a new store in the default state.
+            pub fn new() -> Self {
+                Self::with_state(<$state_name as Default>::default())
+            }
+
+            /// Create a new store in the given state.
+            #[allow(dead_code)]
+            pub fn with_state(state: $state_name) -> Self {
+                Self {
+                    state: ::leptos::prelude::RwSignal::new(state),
+                }
+            }
+
+            /// Returns the name of the current state variant.
+            #[allow(dead_code)]
+            pub fn variant(&self) -> &'static str {
+                self.read(Self::__variant_name)
+            }
+
+            fn __variant_name(state: &$state_name) -> &'static str {
+                match state {
+                    $( $state_name::$variant { .. } => stringify!($variant), )*
+                }
+            }
+
+            $($(
+                $(#[$($getter_attr)*])*
+                #[allow(dead_code)]
+                pub fn $getter_name(&self) -> $getter_ty {
+                    let $getter_self = self;
+                    $getter_body
+                }
+            )*)?
+
+            $(
+                $(#[doc = $transition_doc])*
+                #[allow(dead_code)]
+                pub fn $transition_name(
+                    &self $(, $transition_param: $transition_param_ty)*
+                ) -> Result<(), $crate::store::StoreError> {
+                    let from = self.variant();
+                    let allowed = self.read(|state| {
+                        matches!(state, $( $crate::store!(@machine_from $state_name $transition_from) )|+)
+                    });
+                    if !allowed {
+                        return Err($crate::store::StoreError::InvalidTransition(format!(
+                            "`{}` is not allowed in state {}",
+                            stringify!($transition_name),
+                            from
+                        )));
+                    }
+
+                    #[allow(unused_variables)]
+                    let $transition_self = self;
+                    let next: $state_name = $transition_body;
+                    if !matches!(next, $state_name::$transition_to { .. }) {
+                        return Err($crate::store::StoreError::InvalidTransition(format!(
+                            "`{}` must move to {}, not {}",
+                            stringify!($transition_name),
+                            stringify!($transition_to),
+                            Self::__variant_name(&next)
+                        )));
+                    }
+                    ::leptos::prelude::Set::set(&self.state, next);
+                    Ok(())
+                }
+            )*
+
+            /// Read state with a closure (for getters).
+            #[allow(dead_code)]
+            #[inline]
+            fn read<R>(&self, f: impl FnOnce(&$state_name) -> R) -> R {
+                use ::leptos::prelude::With;
+                self.state.with(f)
+            }
+        }
+
+        impl Default for $store_name {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl $crate::store::Store for $store_name {
+            type State = $state_name;
+
+            fn state(&self) -> ::leptos::prelude::ReadSignal<Self::State> {
+                self.state.read_only()
+            }
+        }
+
+        impl $crate::store::DescribeStore for $store_name {
+            fn methods() -> $crate::store::StoreMethods {
+                $crate::store::StoreMethods {
+                    getters: $crate::store!(
+                        @cfg_list [] $($( [$(#[$($getter_attr)*])*] (stringify!($getter_name)) )*)?
+                    ),
+                    mutators: &[],
+                    actions: &[$( stringify!($transition_name) ),*],
+                }
+            }
+        }
+
+        $crate::__store_describe! {
+            impl $crate::describe::StoreSchema for $store_name {
+                fn describe() -> $crate::describe::StoreDescription {
+                    $crate::describe::StoreDescription {
+                        name: stringify!($store_name),
+                        state: stringify!($state_name),
+                        fields: &[],
+                        getters: $crate::store!(@cfg_list [] $($(
+                            [$(#[$($getter_attr)*])*] ($crate::describe::MethodDescription {
+                                name: stringify!($getter_name),
+                                params: &[],
+                                returns: Some(stringify!($getter_ty)),
+                                is_async: false,
+                            })
+                        )*)?),
+                        mutators: &[],
+                        actions: &[
+                            $(
+                                $crate::describe::MethodDescription {
+                                    name: stringify!($transition_name),
+                                    params: &[
+                                        $(
+                                            $crate::describe::FieldDescription {
+                                                name: stringify!($transition_param),
+                                                ty: stringify!($transition_param_ty),
+                                            },
+                                        )*
+                                    ],
+                                    returns: Some("Result<(), StoreError>"),
+                                    is_async: false,
+                                },
+                            )*
+                        ],
+                        transitions: &[
+                            $($(
+                                $crate::describe::TransitionDescription {
+                                    field: "state",
+                                    from: $crate::store!(@machine_from_name $transition_from),
+                                    to: stringify!($transition_to),
+                                    action: stringify!($transition_name),
+                                },
+                            )+)*
+                        ],
+                    }
+                }
+            }
+        }
+    };
+
+    (
+        $(#[$store_meta:meta])*
+        $store_vis:vis $store_name:ident $( ( key = $store_key:literal ) )? {
+            $(#[$state_meta:meta])*
+            state $( (
+                $(signals = $signals_name:ident)? $(,)?
+                $(patch = $patch_name:ident)? $(,)?
+            ) )? $state_name:ident {
+                $(
+                    $(#[$field_meta:meta])*
+                    $field:ident : $field_ty:ty $(= $field_default:expr)?
+                ),* $(,)?
+            }
+
+            $(
+                persist {
+                    key: $persist_key:expr,
+                    backend: $persist_backend:expr
+                    $(, debounce_ms: $persist_debounce:expr)?
+                    $(, reconcile: $persist_reconcile:expr)? $(,)?
+                }
+            )?
+
+            $(
+                deps {
+                    $(
+                        $(#[$dep_meta:meta])*
+                        $dep:ident : $dep_ty:ty
+                    ),* $(,)?
+                }
+            )?
+
+            $(
+                getters {
+                    $(
+                        $(#[$($getter_attr:tt)*])*
+                        $getter_name:ident ( $getter_self:ident ) -> $getter_ty:ty $getter_body:block
+                    )*
+                }
+            )?
+
+            $(
+                mutators $( ( $mutator_vis:vis ) )? $( as $mutation_enum:ident )? {
+                    $($mutators:tt)*
+                }
+            )?
+
+            $(
+                actions $( (
+                    $(loading = $loading_field:ident)? $(,)?
+                    $(error = $error_field:ident)? $(,)?
+                ) )? {
+                    $($actions:tt)*
+                }
+            )?
+
+            $(
+                effects {
+                    $(
+                        $effect_name:ident ( $effect_self:ident, $effect_old:ident, $effect_new:ident ) $effect_body:block
+                    )*
+                }
+            )?
+
+            $(
+                transitions ( $transition_field:ident ) {
+                    $( $transition_from:ident => $transition_to:ident : $transition_via:ident ),* $(,)?
+                }
+            )?
+        }
+    ) => {
+        // Generate state struct
+        #[derive(Clone, Debug)]
+        $(#[$state_meta])*
+        $store_vis struct $state_name {
+            $(
+                $(#[$field_meta])*
+                pub $field: $field_ty,
+            )*
+        }
+
+        impl Default for $state_name {
+            fn default() -> Self {
+                Self {
+                    $(
+                        $field: $crate::store!(@default $field_ty $(, $field_default)?),
+                    )*
+                }
+            }
+        }
+
+        // Generate store struct
+        #[derive(Clone)]
+        $(#[$store_meta])*
+        $store_vis struct $store_name {
+            state: ::leptos::prelude::RwSignal<$state_name>,
+            $($( signals: $signals_name, )?)?
+            $( __persister: $crate::store!(@persister_ty $persist_key), )?
+            $($($( __in_flight: $crate::store!(@in_flight_ty $loading_field), )?)?)?
+            $($(
+                $(#[$dep_meta])*
+                $dep: $dep_ty,
+            )*)?
+        }
+
+        // Print the current state rather than the signal IDs
+        impl ::std::fmt::Debug for $store_name {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                let mut debug = f.debug_struct(stringify!($store_name));
+                match ::leptos::prelude::GetUntracked::try_get_untracked(&self.state) {
+                    Some(state) => debug.field("state", &state),
+                    None => debug.field("state", &::std::format_args!("<disposed>")),
+                };
+                debug.finish()
+            }
+        }
+
+        $crate::store!(
+            @constructors $store_name $state_name [$($($signals_name)?)?] [$($persist_key)?]
+            [$($($($loading_field)?)?)?]
+            [$($($dep : $dep_ty),*)?]
+        );
+
+        $crate::store!(
+            @keyed $store_name $state_name [$($store_key)?] [$($($dep : $dep_ty),*)?]
+            [$($($persist_reconcile)?)?]
+        );
+
+        $crate::store!(@signals $store_vis [$($($signals_name)?)?] $state_name [$($field : $field_ty),*]);
+
+        $crate::store!(
+            @patch $store_vis $store_name [$($($patch_name)?)?] $state_name [$($field : $field_ty),*]
+        );
+
+        $(
+            $crate::store!(@mutation_enum [$store_vis] [$($mutation_enum)?] [] $($mutators)*);
+        )?
+
+        impl $store_name {
+            $($(
+                /// Returns the per-field signals, for fine-grained subscriptions.
+                #[allow(dead_code)]
+                pub fn signals(&self) -> $signals_name {
+                    self.signals
+                }
+            )?)?
+
+            // Generate getters - use captured self identifier
+            // Note: Users should use this.get_state() for reading
+            $(
+                $(
+                    $(#[$($getter_attr)*])*
+                    #[allow(dead_code)]
+                    pub fn $getter_name(&self) -> $getter_ty {
+                        let $getter_self = self;
+                        $getter_body
+                    }
+                )*
+            )?
+
+            // Generate mutators - use captured self identifier
+            // Note: Users should use this.mutate() for writing
+            $(
+                $crate::store!(
+                    @mutators [$( ($mutator_vis) )?] $store_name [$($mutation_enum)?]
+                    $($mutators)*
+                );
+            )?
+
+            // Generate actions
+            $(
+                $crate::store!(
+                    @actions $store_name
+                    [$($($loading_field)?)?] [$($($error_field)?)?]
+                    $($actions)*
+                );
+            )?
+
+            $(
+                $crate::__store_persist! {
+                    /// Create the persister declared in the `persist` section.
+                    fn __persister() -> $crate::persistence::StatePersister {
+                        $crate::store!(@persister $persist_key, $persist_backend $(, $persist_debounce)?)
+                    }
+
+                    /// Returns the persister saving this store's state.
+                    #[allow(dead_code)]
+                    pub fn persister(&self) -> &$crate::persistence::StatePersister {
+                        &self.__persister
+                    }
+
+                    /// Save the current state immediately.
+                    #[allow(dead_code)]
+                    pub fn persist(&self) -> Result<(), $crate::persistence::PersistenceError> {
+                        use ::leptos::prelude::WithUntracked;
+                        self.state.with_untracked(|s| self.__persister.save(s))
+                    }
+
+                    /// Replace the state with the persisted state.
+                    ///
+                    /// Returns `false` if nothing was persisted.
+                    #[allow(dead_code)]
+                    pub fn restore(&self) -> Result<bool, $crate::persistence::PersistenceError> {
+                        match self.__persister.load::<$state_name>()? {
+                            Some(state) => {
+                                self.mutate(|s| *s = state);
+                                Ok(true)
+                            }
+                            None => Ok(false),
+                        }
+                    }
+                }
+            )?
+
+            /// Run after construction: register the store key, and start the
+            /// declared effects and autosave.
+            #[inline]
+            fn __init(&self) {
+                $(
+                    if let Err(e) = $crate::store::register_store_key::<Self>() {
+                        ::leptos::logging::warn!("Store key {:?}: {}", $store_key, e);
+                    }
+                )?
+                $($(
+                    {
+                        let $effect_self = self.clone();
+                        let previous = ::std::sync::Mutex::new(None::<$state_name>);
+                        ::leptos::prelude::ImmediateEffect::new_scoped(move || {
+                            use ::leptos::prelude::Get;
+                            let current = $effect_self.state.get();
+                            let old = previous
+                                .lock()
+                                .unwrap_or_else(|e| e.into_inner())
+                                .replace(current.clone());
+                            if let Some(old) = old {
+                                ::leptos::prelude::untrack(|| {
+                                    #[allow(unused_variables)]
+                                    let $effect_old = &old;
+                                    #[allow(unused_variables)]
+                                    let $effect_new = &current;
+                                    $effect_body
+                                });
+                            }
+                        });
+                    }
+                )*)?
+                $( $crate::store!(@autosave self $persist_key); )?
+            }
+
+            /// Read state with a closure (for getters).
+            /// Uses the With trait internally.
+            #[allow(dead_code)]
+            #[inline]
+            fn read<R>(&self, f: impl FnOnce(&$state_name) -> R) -> R {
+                use ::leptos::prelude::With;
+                self.state.with(f)
+            }
+
+            /// Update state with a closure (for mutators).
+            /// Uses the Update trait internally.
+            #[allow(dead_code)]
+            #[inline]
+            fn mutate<R>(&self, f: impl FnOnce(&mut $state_name) -> R) -> R {
+                use ::leptos::prelude::Update;
+                let result = self.state.try_update(f).expect("signal disposed");
+                $($( $signals_name::sync(&self.signals, self.state); )?)?
+                result
+            }
+        }
+
+        impl $crate::store::Store for $store_name {
+            type State = $state_name;
+
+            fn state(&self) -> ::leptos::prelude::ReadSignal<Self::State> {
+                self.state.read_only()
+            }
+        }
+
+        impl $crate::store::DryRunStore for $store_name {
+            fn shadow(&self, state: Self::State) -> Self {
+                Self {
+                    $($( signals: $signals_name::new(&state), )?)?
+                    state: ::leptos::prelude::RwSignal::new(state),
+                    $( __persister: $crate::store!(@persister_clone $persist_key, self), )?
+                    $($($( __in_flight: $crate::store!(@in_flight_init $loading_field), )?)?)?
+                    $($( $dep: ::std::clone::Clone::clone(&self.$dep), )*)?
+                }
+            }
+
+            fn replace_state(&self, state: Self::State) {
+                self.mutate(|s| *s = state);
+            }
+        }
+
+        impl $crate::store::DescribeStore for $store_name {
+            fn methods() -> $crate::store::StoreMethods {
+                $crate::store::StoreMethods {
+                    getters: $crate::store!(
+                        @cfg_list [] $($( [$(#[$($getter_attr)*])*] (stringify!($getter_name)) )*)?
+                    ),
+                    mutators: $crate::store!(@method_names [] $($($mutators)*)?),
+                    actions: $crate::store!(@method_names [] $($($actions)*)?),
+                }
+            }
+        }
+
+        // Transitions must name an existing store method
+        $(
+            const _: () = {
+                $( let _ = $store_name::$transition_via; )*
+            };
+        )?
+
+        $crate::__store_describe! {
+            impl $crate::describe::StoreSchema for $store_name {
+                fn describe() -> $crate::describe::StoreDescription {
+                    $crate::describe::StoreDescription {
+                        name: stringify!($store_name),
+                        state: stringify!($state_name),
+                        fields: &[
+                            $(
+                                $crate::describe::FieldDescription {
+                                    name: stringify!($field),
+                                    ty: stringify!($field_ty),
+                                },
+                            )*
+                        ],
+                        getters: $crate::store!(@cfg_list [] $($(
+                            [$(#[$($getter_attr)*])*] ($crate::describe::MethodDescription {
+                                name: stringify!($getter_name),
+                                params: &[],
+                                returns: Some(stringify!($getter_ty)),
+                                is_async: false,
+                            })
+                        )*)?),
+                        mutators: $crate::store!(@method_descriptions [] $($($mutators)*)?),
+                        actions: $crate::store!(@method_descriptions [] $($($actions)*)?),
+                        transitions: &[
+                            $($(
+                                $crate::describe::TransitionDescription {
+                                    field: stringify!($transition_field),
+                                    from: stringify!($transition_from),
+                                    to: stringify!($transition_to),
+                                    action: stringify!($transition_via),
+                                },
+                            )*)?
+                        ],
+                    }
+                }
+            }
+        }
+    };
+
+    // Mutator and action name lists for DescribeStore
+    (@method_names [$($names:tt)*]) => { &[$($names)*] };
+    (@method_names [$($names:tt)*]
+        $(#[$($action_attr:tt)*])*
+        async $action_name:ident < $($rest:tt)*
+    ) => {
+        $crate::store! {
+            @generics [@method_names [$($names)*] $(#[$($action_attr)*])* async $action_name] [] []
+            $($rest)*
+        }
+    };
+    (@method_names [$($names:tt)*]
+        $(#[$($action_attr:tt)*])*
+        $action_name:ident < $($rest:tt)*
+    ) => {
+        $crate::store! {
+            @generics [@method_names [$($names)*] $(#[$($action_attr)*])* $action_name] [] []
+            $($rest)*
+        }
+    };
+    (@method_names [$($names:tt)*]
+        $(#[$($action_attr:tt)*])*
+        async $action_name:ident $([$($action_gen:tt)*])? ( $($action_params:tt)* )
+            $(-> $action_ty:ty)? $action_body:block
+        $($rest:tt)*
+    ) => {
+        $crate::store! {
+            @cfg_attrs [@cfg_push [@method_names] [$($names)*] (stringify!($action_name))] []
+            [$(#[$($action_attr)*])*] $($rest)*
+        }
+    };
+    (@method_names [$($names:tt)*]
+        $(#[$($action_attr:tt)*])*
+        $action_name:ident $([$($action_gen:tt)*])? ( $($action_params:tt)* )
+            $(-> $action_ty:ty)? $action_body:block
+        $($rest:tt)*
+    ) => {
+        $crate::store! {
+            @cfg_attrs [@cfg_push [@method_names] [$($names)*] (stringify!($action_name))] []
+            [$(#[$($action_attr)*])*] $($rest)*
+        }
+    };
+
+    // Mutator and action descriptions for StoreSchema
+    (@method_descriptions [$($descriptions:tt)*]) => { &[$($descriptions)*] };
+    (@method_descriptions [$($descriptions:tt)*]
+        $(#[$($action_attr:tt)*])*
+        async $action_name:ident < $($rest:tt)*
+    ) => {
+        $crate::store! {
+            @generics [@method_descriptions [$($descriptions)*] $(#[$($action_attr)*])* async $action_name]
+            [] [] $($rest)*
+        }
+    };
+    (@method_descriptions [$($descriptions:tt)*]
+        $(#[$($action_attr:tt)*])*
+        $action_name:ident < $($rest:tt)*
+    ) => {
+        $crate::store! {
+            @generics [@method_descriptions [$($descriptions)*] $(#[$($action_attr)*])* $action_name]
+            [] [] $($rest)*
+        }
+    };
+    (@method_descriptions [$($descriptions:tt)*]
+        $(#[$($action_attr:tt)*])*
+        async $action_name:ident $([$($action_gen:tt)*])?
+            ( $action_self:ident $(, $action_param:ident : $action_param_ty:ty)* )
+            $(-> $action_ty:ty)? $action_body:block
+        $($rest:tt)*
+    ) => {
+        $crate::store! {
+            @cfg_attrs [@cfg_push [@method_descriptions] [$($descriptions)*] (
+                $crate::store!(@action_description true $action_name [$($action_param : $action_param_ty),*] $($action_ty)?)
+            )] [] [$(#[$($action_attr)*])*] $($rest)*
+        }
+    };
+    (@method_descriptions [$($descriptions:tt)*]
+        $(#[$($action_attr:tt)*])*
+        $action_name:ident $([$($action_gen:tt)*])?
+            ( $action_self:ident $(, $action_param:ident : $action_param_ty:ty)* )
+            $(-> $action_ty:ty)? $action_body:block
+        $($rest:tt)*
+    ) => {
+        $crate::store! {
+            @cfg_attrs [@cfg_push [@method_descriptions] [$($descriptions)*] (
+                $crate::store!(@action_description false $action_name [$($action_param : $action_param_ty),*] $($action_ty)?)
+            )] [] [$(#[$($action_attr)*])*] $($rest)*
+        }
+    };
+    (@action_description $is_async:literal $action_name:ident [$($action_param:ident : $action_param_ty:ty),*] $($action_ty:ty)?) => {
+        $crate::describe::MethodDescription {
+            name: stringify!($action_name),
+            params: &[
+                $(
+                    $crate::describe::FieldDescription {
+                        name: stringify!($action_param),
+                        ty: stringify!($action_param_ty),
+                    },
+                )*
+            ],
+            returns: $crate::store!(@returns $($action_ty)?),
+            is_async: $is_async,
+        }
+    };
+    (@returns) => { None };
+    (@returns $ty:ty) => { Some(stringify!($ty)) };
+
+    // Constructors - no dependencies, so the store can implement Default
+    (@constructors $store_name:ident $state_name:ident [$($signals:ident)?] [$($persist_key:expr)?]
+        [$($loading:ident)?] []
+    ) => {
+        impl $store_name {
+            /// Create a new store with default state.
+            pub fn new() -> Self {
+                $crate::store!(@new $state_name [$($persist_key)?] [])
+            }
+
+            /// Create a new store with custom initial state.
+            #[allow(dead_code)]
+            pub fn with_state(state: $state_name) -> Self {
+                Self::__assemble(state, $( $crate::store!(@persister_init $persist_key), )?)
+            }
+
+            fn __assemble(
+                state: $state_name,
+                $( persister: $crate::store!(@persister_ty $persist_key), )?
+            ) -> Self {
+                let store = Self {
+                    $( signals: $signals::new(&state), )?
+                    state: ::leptos::prelude::RwSignal::new(state),
+                    $( __persister: $crate::store!(@persister_arg $persist_key, persister), )?
+                    $( __in_flight: $crate::store!(@in_flight_init $loading), )?
+                };
+                store.__init();
+                store
+            }
+        }
+
+        impl Default for $store_name {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+    };
+
+    // Constructors - dependencies must be passed in
+    (@constructors $store_name:ident $state_name:ident [$($signals:ident)?] [$($persist_key:expr)?]
+        [$($loading:ident)?] [$($dep:ident : $dep_ty:ty),+]
+    ) => {
+        impl $store_name {
+            /// Create a new store with default state and the given dependencies.
+            pub fn new($($dep: $dep_ty),+) -> Self {
+                $crate::store!(@new $state_name [$($persist_key)?] [$($dep),+])
+            }
+
+            /// Create a new store with custom initial state and the given dependencies.
+            #[allow(dead_code)]
+            pub fn with_state(state: $state_name, $($dep: $dep_ty),+) -> Self {
+                Self::__assemble(
+                    state,
+                    $( $crate::store!(@persister_init $persist_key), )?
+                    $($dep),+
+                )
+            }
+
+            fn __assemble(
+                state: $state_name,
+                $( persister: $crate::store!(@persister_ty $persist_key), )?
+                $($dep: $dep_ty),+
+            ) -> Self {
+                let store = Self {
+                    $( signals: $signals::new(&state), )?
+                    state: ::leptos::prelude::RwSignal::new(state),
+                    $( __persister: $crate::store!(@persister_arg $persist_key, persister), )?
+                    $( __in_flight: $crate::store!(@in_flight_init $loading), )?
+                    $($dep,)+
+                };
+                store.__init();
+                store
+            }
+        }
+    };
+
+    // Store key - implement KeyedStore, and HydratableStore (with the
+    // `hydrate` feature) when the store can be built from its state alone
+    (@keyed $store_name:ident $state_name:ident [] $deps:tt $reconcile:tt) => {};
+    (@keyed $store_name:ident $state_name:ident [$key:literal] [$($deps:tt)*] $reconcile:tt) => {
+        impl $crate::store::KeyedStore for $store_name {
+            const KEY: &'static str = $key;
+        }
+
+        $crate::store!(@hydratable $store_name $state_name [$($deps)*] $reconcile);
+    };
+    (@hydratable $store_name:ident $state_name:ident [$($deps:tt)+] $reconcile:tt) => {};
+    (@hydratable $store_name:ident $state_name:ident [] [$($reconcile:expr)?]) => {
+        $crate::__store_hydrate! {
+            impl $crate::hydration::HydratableStore for $store_name {
+                fn serialize_state(&self) -> Result<String, $crate::hydration::StoreHydrationError> {
+                    let state = ::leptos::prelude::GetUntracked::get_untracked(&self.state);
+                    ::serde_json::to_string(&state).map_err(|e| {
+                        $crate::hydration::StoreHydrationError::Serialization(e.to_string())
+                    })
+                }
+
+                fn from_hydrated_state(
+                    data: &str,
+                ) -> Result<Self, $crate::hydration::StoreHydrationError> {
+                    let state: $state_name = $crate::hydration::deserialize_state(data)?;
+                    Ok(Self::with_state(state))
+                }
+
+                fn hydrate_into(&self, data: &str) -> Result<(), $crate::hydration::StoreHydrationError> {
+                    let state: $state_name = $crate::hydration::deserialize_state(data)?;
+                    // Go through `mutate` so per-field signals are synced too
+                    self.mutate(|s| *s = state);
+                    Ok(())
+                }
+
+                $(
+                    fn reconcile(&self) {
+                        let strategy: $crate::persistence::Reconcile<$state_name> = $reconcile;
+                        let server = ::leptos::prelude::GetUntracked::get_untracked(&self.state);
+                        if let Some(state) = strategy.reconcile(server, &self.__persister) {
+                            self.mutate(|s| *s = state);
+                        }
+                    }
+                )?
+
+                fn store_key() -> &'static str {
+                    <Self as $crate::store::KeyedStore>::KEY
+                }
+            }
+        }
+    };
+
+    // Persistence helpers - the key only marks that a `persist` section exists
+    (@persister_ty $key:expr) => { $crate::persistence::StatePersister };
+    (@persister_init $key:expr) => { Self::__persister() };
+    (@persister_arg $key:expr, $persister:ident) => { $persister };
+    (@persister_clone $key:expr, $this:ident) => { ::std::clone::Clone::clone(&$this.__persister) };
+
+    // Number of fallible actions running, shared by clones of the store
+    (@in_flight_ty $loading:ident) => { ::std::sync::Arc<::std::sync::atomic::AtomicUsize> };
+    (@in_flight_init $loading:ident) => { ::std::default::Default::default() };
+    (@persister $key:expr, $backend:expr) => {
+        $crate::persistence::StatePersister::new(
+            $crate::persistence::PersistentStorage::shared($backend),
+            $key,
+        )
+    };
+    (@persister $key:expr, $backend:expr, $debounce_ms:expr) => {
+        $crate::store!(@persister $key, $backend)
+            .with_debounce(::std::time::Duration::from_millis($debounce_ms))
+    };
+    // `new()`: start from the persisted state if any, or the default
+    (@new $state_name:ident [] [$($dep:ident),*]) => {
+        Self::with_state($state_name::default(), $($dep),*)
+    };
+    (@new $state_name:ident [$key:expr] [$($dep:ident),*]) => {{
+        let persister = Self::__persister();
+        let state = match persister.load::<$state_name>() {
+            Ok(state) => state.unwrap_or_default(),
+            Err(e) => {
+                ::leptos::logging::warn!("Ignoring persisted state under {}: {}", $key, e);
+                $state_name::default()
+            }
+        };
+        Self::__assemble(state, persister, $($dep),*)
+    }};
+    (@autosave $this:ident $key:expr) => {
+        $this.__persister.autosave($this.state.read_only())
+    };
+
+    // Per-field signals - not requested
+    (@signals $vis:vis [] $state_name:ident [$($field:ident : $field_ty:ty),*]) => {};
+
+    // Per-field signals - one RwSignal per state field, exposed read-only
+    (@signals $vis:vis [$signals_name:ident] $state_name:ident [$($field:ident : $field_ty:ty),*]) => {
+        #[doc = concat!("Per-field signals of [`", stringify!($state_name), "`].")]
+        #[derive(Clone, Copy)]
+        $vis struct $signals_name {
+            $( $field: ::leptos::prelude::RwSignal<$field_ty>, )*
+        }
+
+        impl $signals_name {
+            fn new(state: &$state_name) -> Self {
+                Self {
+                    $( $field: ::leptos::prelude::RwSignal::new(state.$field.clone()), )*
+                }
+            }
+
+            /// Copy changed fields from the combined state, notifying only
+            /// the subscribers of those fields.
+            fn sync(&self, state: ::leptos::prelude::RwSignal<$state_name>) {
+                use ::leptos::prelude::{Set, WithUntracked};
+                $(
+                    let changed = state.with_untracked(|s| {
+                        self.$field.with_untracked(|current| *current != s.$field)
+                    });
+                    if changed {
+                        self.$field.set(state.with_untracked(|s| s.$field.clone()));
+                    }
+                )*
+            }
+
+            $(
+                #[doc = concat!("Read-only signal for `", stringify!($field), "`.")]
+                #[allow(dead_code)]
+                pub fn $field(&self) -> ::leptos::prelude::ReadSignal<$field_ty> {
+                    self.$field.read_only()
+                }
+            )*
+        }
+    };
+
+    // State patch - not requested
+    (@patch $vis:vis $store_name:ident [] $state_name:ident [$($field:ident : $field_ty:ty),*]) => {};
+
+    // State patch - one optional field per state field, applied in a single mutation
+    (@patch $vis:vis $store_name:ident [$patch_name:ident] $state_name:ident
+        [$($field:ident : $field_ty:ty),*]
+    ) => {
+        #[doc = concat!("A partial update of [`", stringify!($state_name), "`]; only `Some` fields are applied.")]
+        #[derive(Clone, Debug, Default)]
+        $vis struct $patch_name {
+            $(
+                #[doc = concat!("New value for `", stringify!($field), "`.")]
+                pub $field: Option<$field_ty>,
+            )*
+        }
+
+        impl $patch_name {
+            /// Check if the patch sets no fields.
+            #[allow(dead_code)]
+            pub fn is_empty(&self) -> bool {
+                true $( && self.$field.is_none() )*
+            }
+        }
+
+        impl $store_name {
+            /// Apply the set fields of `patch` in a single state update.
+            ///
+            /// Subscribers are notified once; an empty patch leaves the state
+            /// untouched.
+            #[allow(dead_code)]
+            pub fn apply_patch(&self, patch: $patch_name) {
+                if patch.is_empty() {
+                    return;
+                }
+                self.mutate(|state| {
+                    $(
+                        if let Some(value) = patch.$field {
+                            state.$field = value;
+                        }
+                    )*
+                });
+            }
+        }
+    };
+
+    // Mutators - public unless a visibility is given
+    (@mutators [] $($rest:tt)*) => {
+        $crate::store!(@mutators [(pub)] $($rest)*);
+    };
+    (@mutators [($vis:vis)] $store:ident $enum:tt) => {};
+    (@mutators [($vis:vis)] $store:ident $enum:tt
+        $(#[$($mutator_attr:tt)*])*
+        $mutator_name:ident < $($rest:tt)*
+    ) => {
+        $crate::store! {
+            @generics [@mutators [($vis)] $store $enum $(#[$($mutator_attr)*])* $mutator_name] [] []
+            $($rest)*
+        }
+    };
+    (@mutators [($vis:vis)] $store:ident []
+        $(#[$($mutator_attr:tt)*])*
+        $mutator_name:ident $([$($mutator_gen:tt)*])?
+            ( $mutator_self:ident $(, $mutator_param:ident : $mutator_param_ty:ty)* ) $mutator_body:block
+        $($rest:tt)*
+    ) => {
+        $(#[$($mutator_attr)*])*
+        #[allow(dead_code)]
+        $vis fn $mutator_name $(< $($mutator_gen)* >)? (&self $(, $mutator_param: $mutator_param_ty)*) {
+            $crate::store::record_mutation::<Self>(
+                stringify!($mutator_name),
+                || ::leptos::prelude::GetUntracked::get_untracked(&self.state),
+                move || {
+                    let $mutator_self = self;
+                    $mutator_body
+                },
+            );
+        }
+
+        $crate::store!(@mutators [($vis)] $store [] $($rest)*);
+    };
+    // With a mutation enum - summarize the parameters before the body
+    // consumes them, and report the mutation once the body has run
+    (@mutators [($vis:vis)] $store:ident [$enum:ident]
+        $(#[$($mutator_attr:tt)*])*
+        $mutator_name:ident $([$($mutator_gen:tt)*])?
+            ( $mutator_self:ident $(, $mutator_param:ident : $mutator_param_ty:ty)* ) $mutator_body:block
+        $($rest:tt)*
+    ) => {
+        // The body runs in a closure so an early `return` still reports.
+        // Mutations recorded by a dry run aren't reported.
+        $(#[$($mutator_attr)*])*
+        #[allow(dead_code)]
+        $vis fn $mutator_name $(< $($mutator_gen)* >)? (&self $(, $mutator_param: $mutator_param_ty)*) {
+            let mutation = ($crate::mutation::is_mutation_logging_enabled()
+                && !$crate::store::in_dry_run::<Self>())
+            .then(|| $crate::macros::__mutation_variant!($enum::$mutator_name {
+                $( $mutator_param: $crate::__summarize_payload!($mutator_param), )*
+            }));
+            $crate::store::record_mutation::<Self>(
+                stringify!($mutator_name),
+                || ::leptos::prelude::GetUntracked::get_untracked(&self.state),
+                move || {
+                    let $mutator_self = self;
+                    $mutator_body
+                },
+            );
+            if let Some(mutation) = mutation {
+                $crate::mutation::notify_mutation(&$crate::mutation::MutationEvent::new(
+                    stringify!($store),
+                    stringify!($mutator_name),
+                    &mutation,
+                ));
+            }
+        }
+
+        $crate::store!(@mutators [($vis)] $store [$enum] $($rest)*);
+    };
+
+    // Mutation enum - one variant per mutator, with summarized parameters
+    (@mutation_enum $vis:tt [] $($rest:tt)*) => {};
+    (@mutation_enum [$vis:vis] [$enum:ident]
+        [$( $(#[$variant_meta:meta])* $variant:ident { $($variant_field:ident : $variant_ty:ty,)* }, )*]
+    ) => {
+        // Variants are renamed to CamelCase, `add_items` to `AddItems`
+        $crate::macros::__mutation_enum! {
+            /// Mutations of the store, reported to [`on_mutation`] listeners.
+            ///
+            /// [`on_mutation`]: $crate::mutation::on_mutation
+            #[allow(dead_code)]
+            #[derive(Clone, Debug, PartialEq)]
+            $vis enum $enum {
+                $( $(#[$variant_meta])* $variant { $($variant_field: $variant_ty,)* }, )*
+            }
+        }
+    };
+    (@mutation_enum $vis:tt $enum:tt $variants:tt
+        $(#[$($mutator_attr:tt)*])*
+        $mutator_name:ident < $($rest:tt)*
+    ) => {
+        $crate::store! {
+            @generics [@mutation_enum $vis $enum $variants $(#[$($mutator_attr)*])* $mutator_name] [] []
+            $($rest)*
+        }
+    };
+    (@mutation_enum $vis:tt $enum:tt [$($variants:tt)*]
+        $(#[$($mutator_attr:tt)*])*
+        $mutator_name:ident $([$($mutator_gen:tt)*])?
+            ( $mutator_self:ident $(, $mutator_param:ident : $mutator_param_ty:ty)* ) $mutator_body:block
+        $($rest:tt)*
+    ) => {
+        $crate::store! {
+            @cfg_attrs [@cfg_push [@mutation_enum $vis $enum] [$($variants)*] (
+                $mutator_name { $( $mutator_param: $crate::mutation::PayloadSummary, )* }
+            )] [] [$(#[$($mutator_attr)*])*] $($rest)*
+        }
+    };
+
+    // Generic method helpers - move the `<...>` list after a method name
+    // into brackets, then continue with the given rule
+    (@generics [$($cont:tt)*] [$($gen:tt)*] [] > $($rest:tt)*) => {
+        $crate::store! { $($cont)* [$($gen)*] $($rest)* }
+    };
+    (@generics $cont:tt [$($gen:tt)*] [$($depth:tt)*] < $($rest:tt)*) => {
+        $crate::store! { @generics $cont [$($gen)* <] [x $($depth)*] $($rest)* }
+    };
+    (@generics $cont:tt [$($gen:tt)*] [x $($depth:tt)*] > $($rest:tt)*) => {
+        $crate::store! { @generics $cont [$($gen)* >] [$($depth)*] $($rest)* }
+    };
+    (@generics $cont:tt [$($gen:tt)*] [x $($depth:tt)*] >> $($rest:tt)*) => {
+        $crate::store! { @generics $cont [$($gen)* >] [$($depth)*] > $($rest)* }
+    };
+    (@generics $cont:tt [$($gen:tt)*] $depth:tt $token:tt $($rest:tt)*) => {
+        $crate::store! { @generics $cont [$($gen)* $token] $depth $($rest)* }
+    };
+
+    // State machine helpers - `_` allows a transition from any state
+    (@machine_from $state_name:ident _) => { _ };
+    (@machine_from $state_name:ident $variant:ident) => { $state_name::$variant { .. } };
+    (@machine_from_name _) => { "*" };
+    (@machine_from_name $variant:ident) => { stringify!($variant) };
+
+    // Attribute helpers - keep only the `#[cfg]` attributes of a method, so
+    // generated lists and enums follow the method in and out of the build
+    (@cfg_attrs [$($cont:tt)*] [$($kept:tt)*] [] $($rest:tt)*) => {
+        $crate::store! { $($cont)* [$($kept)*] $($rest)* }
+    };
+    (@cfg_attrs $cont:tt [$($kept:tt)*] [#[cfg $($cfg:tt)*] $($attrs:tt)*] $($rest:tt)*) => {
+        $crate::store! { @cfg_attrs $cont [$($kept)* #[cfg $($cfg)*]] [$($attrs)*] $($rest)* }
+    };
+    (@cfg_attrs $cont:tt $kept:tt [#[$($attr:tt)*] $($attrs:tt)*] $($rest:tt)*) => {
+        $crate::store! { @cfg_attrs $cont $kept [$($attrs)*] $($rest)* }
+    };
+
+    // Append an item, behind its `#[cfg]` attributes, to a list being built
+    (@cfg_push [$($cont:tt)*] [$($items:tt)*] ($($item:tt)*) [$($kept:tt)*] $($rest:tt)*) => {
+        $crate::store! { $($cont)* [$($items)* $($kept)* $($item)*,] $($rest)* }
+    };
+
+    // Slice of items, each preceded by its method's attributes
+    (@cfg_list [$($items:tt)*]) => { &[$($items)*] };
+    (@cfg_list [$($items:tt)*] [$($attrs:tt)*] ($($item:tt)*) $($rest:tt)*) => {
+        $crate::store! { @cfg_attrs [@cfg_push [@cfg_list] [$($items)*] ($($item)*)] [] [$($attrs)*] $($rest)* }
+    };
+
+    // Default value helpers
+    (@default $ty:ty, $default:expr) => { $default };
+    (@default $ty:ty) => { <$ty as Default>::default() };
+
+    // Action helpers - one rule per action shape, munching the list
+    (@actions $store_name:ident [$($loading:ident)?] [$($error:ident)?]) => {};
+
+    // Generic action - split off the generics first
+    (@actions $store_name:ident [$($loading:ident)?] [$($error:ident)?]
+        $(#[$($action_attr:tt)*])*
+        async $action_name:ident < $($rest:tt)*
+    ) => {
+        $crate::store!(@generics
+            [@actions $store_name [$($loading)?] [$($error)?] $(#[$($action_attr)*])* async $action_name]
+            [] [] $($rest)*);
+    };
+    (@actions $store_name:ident [$($loading:ident)?] [$($error:ident)?]
+        $(#[$($action_attr:tt)*])*
+        $action_name:ident < $($rest:tt)*
+    ) => {
+        $crate::store!(@generics
+            [@actions $store_name [$($loading)?] [$($error)?] $(#[$($action_attr)*])* $action_name]
+            [] [] $($rest)*);
+    };
+
+    // Fallible action with loading/error bookkeeping
+    (@actions $store_name:ident [$($loading:ident)?] [$($error:ident)?]
+        $(#[$($action_attr:tt)*])*
+        $action_name:ident $([$($action_gen:tt)*])?
+            ( $action_self:ident $(, $action_param:ident : $action_param_ty:ty)* )
+            -> Result<$ok_ty:ty, $err_ty:ty> $action_body:block
+        $($rest:tt)*
+    ) => {
+        $(#[$($action_attr)*])*
+        #[allow(dead_code)]
+        pub fn $action_name $(< $($action_gen)* >)? (
+            &self $(, $action_param: $action_param_ty)*
+        ) -> Result<$ok_ty, $err_ty> {
+            fn __action $(< $($action_gen)* >)? (
+                $action_self: &$store_name $(, $action_param: $action_param_ty)*
+            ) -> Result<$ok_ty, $err_ty> $action_body
+
+            $crate::store!(@start self, [$($loading)?]);
+            let result = __action(self $(, $action_param)*);
+            $crate::store!(@track self, result, [$($loading)?] [$($error)?]);
+            result
+        }
+
+        $crate::store!(@actions $store_name [$($loading)?] [$($error)?] $($rest)*);
+    };
+
+    // Async fallible action with loading/error bookkeeping
+    (@actions $store_name:ident [$($loading:ident)?] [$($error:ident)?]
+        $(#[$($action_attr:tt)*])*
+        async $action_name:ident $([$($action_gen:tt)*])?
+            ( $action_self:ident $(, $action_param:ident : $action_param_ty:ty)* )
+            -> Result<$ok_ty:ty, $err_ty:ty> $action_body:block
+        $($rest:tt)*
+    ) => {
+        $(#[$($action_attr)*])*
+        #[allow(dead_code)]
+        pub async fn $action_name $(< $($action_gen)* >)? (
+            &self $(, $action_param: $action_param_ty)*
+        ) -> Result<$ok_ty, $err_ty> {
+            async fn __action $(< $($action_gen)* >)? (
+                $action_self: &$store_name $(, $action_param: $action_param_ty)*
+            ) -> Result<$ok_ty, $err_ty> $action_body
+
+            $crate::store!(@start self, [$($loading)?]);
+            let result = __action(self $(, $action_param)*).await;
+            $crate::store!(@track self, result, [$($loading)?] [$($error)?]);
+            result
+        }
+
+        $crate::store!(@actions $store_name [$($loading)?] [$($error)?] $($rest)*);
+    };
+
+    // Plain action
+    (@actions $store_name:ident [$($loading:ident)?] [$($error:ident)?]
+        $(#[$($action_attr:tt)*])*
+        $action_name:ident $([$($action_gen:tt)*])?
+            ( $action_self:ident $(, $action_param:ident : $action_param_ty:ty)* )
+            $(-> $action_ty:ty)? $action_body:block
+        $($rest:tt)*
+    ) => {
+        $(#[$($action_attr)*])*
+        #[allow(dead_code)]
+        pub fn $action_name $(< $($action_gen)* >)? (&self $(, $action_param: $action_param_ty)*) $(-> $action_ty)? {
+            let $action_self = self;
+            $action_body
+        }
+
+        $crate::store!(@actions $store_name [$($loading)?] [$($error)?] $($rest)*);
+    };
+
+    // Async plain action
+    (@actions $store_name:ident [$($loading:ident)?] [$($error:ident)?]
+        $(#[$($action_attr:tt)*])*
+        async $action_name:ident $([$($action_gen:tt)*])?
+            ( $action_self:ident $(, $action_param:ident : $action_param_ty:ty)* )
+            $(-> $action_ty:ty)? $action_body:block
+        $($rest:tt)*
+    ) => {
+        $(#[$($action_attr)*])*
+        #[allow(dead_code)]
+        pub async fn $action_name $(< $($action_gen)* >)? (&self $(, $action_param: $action_param_ty)*) $(-> $action_ty)? {
+            let $action_self = self;
+            $action_body
+        }
+
+        $crate::store!(@actions $store_name [$($loading)?] [$($error)?] $($rest)*);
+    };
+
+    // Bookkeeping after a fallible action - nothing declared
+    (@track $this:ident, $result:ident, [] []) => {};
+
+    // Bookkeeping after a fallible action - record the error
+    (@track $this:ident, $result:ident, [] [$error:ident]) => {
+        $this.mutate(|s| s.$error = $result.as_ref().err().cloned());
+    };
+
+    // Bookkeeping after a fallible action - clear loading once no other
+    // action is in flight, record the error
+    (@track $this:ident, $result:ident, [$loading:ident] [$($error:ident)?]) => {
+        let idle = $this.__in_flight.fetch_sub(1, ::std::sync::atomic::Ordering::SeqCst) == 1;
+        $this.mutate(|s| {
+            if idle {
+                s.$loading = false;
+            }
+            $( s.$error = $result.as_ref().err().cloned(); )?
+        });
+    };
+
+    // Bookkeeping before a fallible action - count it in flight, set loading
+    (@start $this:ident, []) => {};
+    (@start $this:ident, [$loading:ident]) => {
+        $this.__in_flight.fetch_add(1, ::std::sync::atomic::Ordering::SeqCst);
+        $this.mutate(|s| s.$loading = true);
+    };
+}
+
+/// Emits its input only when the `describe` feature is enabled.
+#[cfg(feature = "describe")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __store_describe {
+    ($($tokens:tt)*) => { $($tokens)* };
+}
+
+/// Emits its input only when the `describe` feature is enabled.
+#[cfg(not(feature = "describe"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __store_describe {
+    ($($tokens:tt)*) => {};
+}
+
+/// Emits its input when the `persist` feature is enabled, and a compile
+/// error otherwise.
+#[cfg(feature = "persist")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __store_persist {
+    ($($tokens:tt)*) => { $($tokens)* };
+}
+
+/// Emits its input when the `persist` feature is enabled, and a compile
+/// error otherwise.
+#[cfg(not(feature = "persist"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __store_persist {
+    ($($tokens:tt)*) => {
+        ::std::compile_error!("the `persist` section of `store!` requires the `persist` feature");
+    };
+}
+
+/// Emits its input only when the `hydrate` feature is enabled.
+#[cfg(feature = "hydrate")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __store_hydrate {
+    ($($tokens:tt)*) => { $($tokens)* };
+}
+
+/// Emits its input only when the `hydrate` feature is enabled.
+#[cfg(not(feature = "hydrate"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __store_hydrate {
+    ($($tokens:tt)*) => {};
+}
+
+// ============================================================================
+// entity_store! macro
+// ============================================================================
+
+/// Define a store for a normalized, ID-keyed collection of entities.
+///
+/// The generated store keeps its entities in an
+/// [`EntityState`](crate::entity::EntityState), so lookups by ID don't scan
+/// the collection, and its state type is `EntityState<IdType, Entity>`.
+///
+/// # Syntax
+///
+/// ```text
+/// entity_store! {
+///     #[derive(...)]                  // Optional: attributes on the store
+///     pub StoreName for Entity {         // Store name and entity type
+///         id_field: IdType,              // The entity's ID field
+///     }
+/// }
+/// ```
+///
+/// The entity type must be `Clone`, and the ID type `Clone + Eq + Hash`.
+///
+/// # Generated Methods
+///
+/// | Method | Kind | Description |
+/// |--------|------|-------------|
+/// | `new()`, `with_entities(iter)` | Constructor | Empty store, or one holding the given entities in order |
+/// | `select_by_id(&id)` | Getter | The entity with `id`, if any |
+/// | `ids()` | Getter | All IDs in collection order |
+/// | `all()` | Getter | All entities in collection order |
+/// | `len()`, `is_empty()`, `contains(&id)` | Getter | Collection size and membership |
+/// | `upsert(entity)` | Mutator | Insert or replace an entity, keeping its position |
+/// | `upsert_many(iter)` | Mutator | Upsert several entities with a single notification |
+/// | `remove(&id)` | Mutator | Remove and return an entity |
+/// | `clear()` | Mutator | Remove all entities |
+/// | `sort_by(cmp)`, `sort_by_key(f)` | Mutator | Reorder the collection |
+/// | `move_to(&id, index)` | Mutator | Move one entity to a new position |
+///
+/// # Example
+///
+/// ```rust
+/// use leptos::prelude::*;
+/// use leptos_store::entity_store;
+///
+/// #[derive(Clone, Debug, PartialEq)]
+/// pub struct Todo {
+///     id: u64,
+///     title: String,
+///     done: bool,
+/// }
+///
+/// entity_store! {
+///     /// All todos, keyed by ID.
+///     pub TodoStore for Todo {
+///         id: u64,
+///     }
+/// }
+///
+/// let store = TodoStore::new();
+/// store.upsert_many([
+///     Todo { id: 2, title: "Write docs".to_string(), done: false },
+///     Todo { id: 1, title: "Ship".to_string(), done: false },
+/// ]);
+/// store.upsert(Todo { id: 2, title: "Write docs".to_string(), done: true });
+///
+/// assert_eq!(store.ids(), [2, 1]);
+/// assert!(store.select_by_id(&2).unwrap().done);
+///
+/// store.sort_by_key(|todo| todo.id);
+/// assert_eq!(store.ids(), [1, 2]);
+/// ```
+#[macro_export]
+macro_rules! entity_store {
+    (
+        $(#[$meta:meta])*
+        $vis:vis $store_name:ident for $entity:ty { $id_field:ident : $id_ty:ty $(,)? }
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone)]
+        $vis struct $store_name {
+            state: ::leptos::prelude::RwSignal<$crate::entity::EntityState<$id_ty, $entity>>,
+        }
+
+        #[allow(dead_code)]
+        impl $store_name {
+            /// Create an empty store.
+            pub fn new() -> Self {
+                Self::with_entities(::std::iter::empty())
+            }
+
+            /// Create a store holding `entities`, in order.
+            pub fn with_entities(entities: impl IntoIterator<Item = $entity>) -> Self {
+                Self {
+                    state: ::leptos::prelude::RwSignal::new(
+                        entities
+                            .into_iter()
+                            .map(|entity| (entity.$id_field.clone(), entity))
+                            .collect(),
+                    ),
+                }
+            }
+
+            /// Returns the entity with `id`.
+            pub fn select_by_id(&self, id: &$id_ty) -> Option<$entity> {
+                use ::leptos::prelude::With;
+                self.state.with(|s| s.get(id).cloned())
+            }
+
+            /// Returns all IDs in collection order.
+            pub fn ids(&self) -> Vec<$id_ty> {
+                use ::leptos::prelude::With;
+                self.state.with(|s| s.ids().to_vec())
+            }
+
+            /// Returns all entities in collection order.
+            pub fn all(&self) -> Vec<$entity> {
+                use ::leptos::prelude::With;
+                self.state.with(|s| s.iter().cloned().collect())
+            }
+
+            /// Returns the number of entities.
+            pub fn len(&self) -> usize {
+                use ::leptos::prelude::With;
+                self.state.with(|s| s.len())
+            }
+
+            /// Check if the store holds no entities.
+            pub fn is_empty(&self) -> bool {
+                use ::leptos::prelude::With;
+                self.state.with(|s| s.is_empty())
+            }
+
+            /// Check if an entity with `id` exists.
+            pub fn contains(&self, id: &$id_ty) -> bool {
+                use ::leptos::prelude::With;
+                self.state.with(|s| s.contains(id))
+            }
+
+            /// Insert or replace an entity, keeping the position of a replaced one.
+            pub fn upsert(&self, entity: $entity) {
+                use ::leptos::prelude::Update;
+                self.state.update(|s| {
+                    s.upsert(entity.$id_field.clone(), entity);
+                });
+            }
+
+            /// Upsert several entities with a single notification.
+            pub fn upsert_many(&self, entities: impl IntoIterator<Item = $entity>) {
+                use ::leptos::prelude::Update;
+                self.state.update(|s| {
+                    s.extend(
+                        entities
+                            .into_iter()
+                            .map(|entity| (entity.$id_field.clone(), entity)),
+                    )
+                });
+            }
+
+            /// Remove the entity with `id`.
+            pub fn remove(&self, id: &$id_ty) -> Option<$entity> {
+                use ::leptos::prelude::Update;
+                self.state.try_update(|s| s.remove(id)).flatten()
+            }
+
+            /// Remove all entities.
+            pub fn clear(&self) {
+                use ::leptos::prelude::Update;
+                self.state.update(|s| s.clear());
+            }
+
+            /// Sort the collection order with a comparator.
+            pub fn sort_by(&self, compare: impl FnMut(&$entity, &$entity) -> ::std::cmp::Ordering) {
+                use ::leptos::prelude::Update;
+                self.state.update(|s| s.sort_by(compare));
+            }
+
+            /// Sort the collection order by a key extracted from each entity.
+            pub fn sort_by_key<O: Ord>(&self, key: impl FnMut(&$entity) -> O) {
+                use ::leptos::prelude::Update;
+                self.state.update(|s| s.sort_by_key(key));
+            }
+
+            /// Move the entity with `id` to `index` in the collection order.
+            ///
+            /// Returns `false` if there is no entity with `id`.
+            pub fn move_to(&self, id: &$id_ty, index: usize) -> bool {
+                use ::leptos::prelude::Update;
+                self.state
+                    .try_update(|s| s.move_to(id, index))
+                    .unwrap_or(false)
+            }
+        }
+
+        impl Default for $store_name {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl $crate::store::Store for $store_name {
+            type State = $crate::entity::EntityState<$id_ty, $entity>;
+
+            fn state(&self) -> ::leptos::prelude::ReadSignal<Self::State> {
+                self.state.read_only()
+            }
+        }
+    };
+}
+
+// ============================================================================
+// deprecated_alias! macro
+// ============================================================================
+
+/// Declare deprecated aliases for renamed store methods.
+///
+/// Use this inside an `impl` block to keep old getter, mutator, or action
+/// names working while a store is refactored. Each alias forwards to the new
+/// method and is marked `#[deprecated]`, so every call site gets a
+/// compile-time warning. Calls that can't be seen by the compiler (e.g.
+/// through generated code with `#[allow(deprecated)]`) are reported with a
+/// runtime warning, logged once per alias in debug builds.
+///
+/// # Syntax
+///
+/// ```text
+/// deprecated_alias! {
+///     old_name(param: Type) -> ReturnType => new_name, since = "0.5.0";
+///     async old_async(param: Type) -> ReturnType => new_async;
+/// }
+/// ```
+///
+/// # Example
+///
+/// ```rust
+/// use leptos_store::{deprecated_alias, store};
+///
+/// store! {
+///     pub AuthStore {
+///         state AuthState {
+///             user: Option<String>,
+///         }
+///
+///         getters {
+///             is_authenticated(this) -> bool {
+///                 this.read(|s| s.user.is_some())
+///             }
+///         }
+///
+///         mutators {
+///             sign_in(this, user: String) {
+///                 this.mutate(|s| s.user = Some(user));
+///             }
+///         }
+///     }
+/// }
+///
+/// impl AuthStore {
+///     deprecated_alias! {
+///         login(user: String) => sign_in, since = "0.5.0";
+///         is_logged_in() -> bool => is_authenticated;
+///     }
+/// }
+///
+/// let store = AuthStore::new();
+/// #[allow(deprecated)]
+/// store.login("ada".to_string());
+/// assert!(store.is_authenticated());
+/// ```
+#[macro_export]
+macro_rules! deprecated_alias {
+    () => {};
+
+    (
+        $(#[$alias_meta:meta])*
+        async $old:ident ( $($param:ident : $param_ty:ty),* $(,)? ) $(-> $ret:ty)?
+            => $new:ident $(, since = $since:literal)?;
+        $($rest:tt)*
+    ) => {
+        $(#[$alias_meta])*
+        #[deprecated = concat!("renamed to `", stringify!($new), "`" $(, " since ", $since)?)]
+        #[allow(dead_code)]
+        pub async fn $old(&self $(, $param: $param_ty)*) $(-> $ret)? {
+            $crate::deprecated_alias!(@warn $old, $new);
+            self.$new($($param),*).await
+        }
+
+        $crate::deprecated_alias!($($rest)*);
+    };
+
+    (
+        $(#[$alias_meta:meta])*
+        $old:ident ( $($param:ident : $param_ty:ty),* $(,)? ) $(-> $ret:ty)?
+            => $new:ident $(, since = $since:literal)?;
+        $($rest:tt)*
+    ) => {
+        $(#[$alias_meta])*
+        #[deprecated = concat!("renamed to `", stringify!($new), "`" $(, " since ", $since)?)]
+        #[allow(dead_code)]
+        pub fn $old(&self $(, $param: $param_ty)*) $(-> $ret)? {
+            $crate::deprecated_alias!(@warn $old, $new);
+            self.$new($($param),*)
+        }
+
+        $crate::deprecated_alias!($($rest)*);
+    };
+
+    // Runtime warning, logged once per alias in debug builds
+    (@warn $old:ident, $new:ident) => {
+        #[cfg(debug_assertions)]
+        {
+            static WARNED: ::std::sync::Once = ::std::sync::Once::new();
+            WARNED.call_once(|| {
+                ::leptos::logging::warn!(
+                    "{}::{} is deprecated; use `{}` instead",
+                    ::std::any::type_name::<Self>(),
+                    stringify!($old),
+                    stringify!($new)
+                );
+            });
+        }
+    };
+}
+
+// ============================================================================
+// mock_store! macro
+// ============================================================================
+
+/// Define a stub store for component tests.
+///
+/// The generated store implements [`Store`](crate::store::Store) for the
+/// given state type and has a stub for each listed method. Stubs don't run
+/// any business logic: they record the call with its arguments in the
+/// store's [`MockRecorder`](crate::mock::MockRecorder), apply the next
+/// scripted state if there is one, and return the given value, or
+/// `Default::default()` without one.
+///
+/// Give the mock the same name and methods as the real store (for example
+/// behind `#[cfg(test)]`) to render components against it.
+///
+/// # Syntax
+///
+/// ```text
+/// mock_store! {
+///     pub MockName for StateType {           // or: for StateType, key = "store_key"
+///         fn mutator(param: Type);
+///         fn getter() -> Type = value;        // `= value` is optional
+///         async fn action(param: Type) -> Type;
+///     }
+/// }
+/// ```
+///
+/// The state type must implement `Default`. With `key = "..."`, the mock
+/// implements [`KeyedStore`](crate::store::KeyedStore), and with the
+/// `hydrate` feature also
+/// [`HydratableStore`](crate::hydration::HydratableStore) under that key.
+///
+/// # Generated Methods
+///
+/// | Method | Description |
+/// |--------|-------------|
+/// | `new()` / `with_state(state)` | Create the mock |
+/// | `mock()` | The call recorder |
+/// | `set_state(state)` | Replace the state directly |
+/// | `script(states)` | Queue states to apply, one per stub call |
+/// | `advance()` | Apply the next scripted state without a call |
+///
+/// # Example
+///
+/// ```rust
+/// use leptos::prelude::*;
+/// use leptos_store::mock_store;
+///
+/// #[derive(Clone, Debug, Default, PartialEq)]
+/// pub struct AuthState {
+///     pub user: Option<String>,
+///     pub loading: bool,
+/// }
+///
+/// mock_store! {
+///     pub MockAuthStore for AuthState {
+///         fn is_authenticated() -> bool = true;
+///         async fn login(user: String, password: String) -> Result<(), String> = Ok(());
+///         fn logout();
+///     }
+/// }
+///
+/// let store = MockAuthStore::new();
+/// store.script([
+///     AuthState { user: None, loading: true },
+///     AuthState { user: Some("ada".to_string()), loading: false },
+/// ]);
+///
+/// # futures::executor::block_on(async {
+/// store.login("ada".to_string(), "secret".to_string()).await.unwrap();
+/// # });
+/// assert!(store.state.get_untracked().loading);
+/// store.advance();
+/// assert_eq!(store.state.get_untracked().user.as_deref(), Some("ada"));
+///
+/// assert_eq!(store.mock().call_names(), ["login"]);
+/// assert!(!store.mock().was_called("logout"));
+/// ```
+#[macro_export]
+macro_rules! mock_store {
+    (
+        $(#[$meta:meta])*
+        $vis:vis $name:ident for $state:ty $(, key = $key:literal)? {
+            $($methods:tt)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone)]
+        $vis struct $name {
+            state: ::leptos::prelude::RwSignal<$state>,
+            mock: $crate::mock::MockRecorder<$state>,
+        }
+
+        #[allow(dead_code)]
+        impl $name {
+            /// Create a mock with the default state.
+            pub fn new() -> Self {
+                Self::with_state(<$state as Default>::default())
+            }
+
+            /// Create a mock with the given state.
+            pub fn with_state(state: $state) -> Self {
+                Self {
+                    state: ::leptos::prelude::RwSignal::new(state),
+                    mock: $crate::mock::MockRecorder::new(),
+                }
+            }
+
+            /// Returns the call recorder.
+            pub fn mock(&self) -> &$crate::mock::MockRecorder<$state> {
+                &self.mock
+            }
+
+            /// Replace the state.
+            pub fn set_state(&self, state: $state) {
+                ::leptos::prelude::Set::set(&self.state, state);
+            }
+
+            /// Queue states to apply, one per stub call.
+            pub fn script(&self, states: impl IntoIterator<Item = $state>) {
+                self.mock.push_states(states);
+            }
+
+            /// Apply the next scripted state, returning `false` if there is none.
+            pub fn advance(&self) -> bool {
+                match self.mock.next_state() {
+                    Some(state) => {
+                        self.set_state(state);
+                        true
+                    }
+                    None => false,
+                }
+            }
+
+            $crate::mock_store!(@methods $($methods)*);
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl $crate::store::Store for $name {
+            type State = $state;
+
+            fn state(&self) -> ::leptos::prelude::ReadSignal<Self::State> {
+                self.state.read_only()
+            }
+        }
+
+        $(
+            impl $crate::store::KeyedStore for $name {
+                const KEY: &'static str = $key;
+            }
+
+            $crate::__store_hydrate! {
+                impl $crate::hydration::HydratableStore for $name {
+                    fn serialize_state(&self) -> Result<String, $crate::hydration::StoreHydrationError> {
+                        let state = ::leptos::prelude::GetUntracked::get_untracked(&self.state);
+                        ::serde_json::to_string(&state).map_err(|e| {
+                            $crate::hydration::StoreHydrationError::Serialization(e.to_string())
+                        })
+                    }
+
+                    fn from_hydrated_state(
+                        data: &str,
+                    ) -> Result<Self, $crate::hydration::StoreHydrationError> {
+                        let state: $state = ::serde_json::from_str(data).map_err(|e| {
+                            $crate::hydration::StoreHydrationError::Deserialization(e.to_string())
+                        })?;
+                        Ok(Self::with_state(state))
+                    }
+
+                    fn hydrate_into(&self, data: &str) -> Result<(), $crate::hydration::StoreHydrationError> {
+                        let state: $state = $crate::hydration::deserialize_state(data)?;
+                        ::leptos::prelude::Set::set(&self.state, state);
+                        Ok(())
+                    }
+
+                    fn store_key() -> &'static str {
+                        <Self as $crate::store::KeyedStore>::KEY
+                    }
+                }
+            }
+        )?
+    };
+
+    // Stub methods
+    (@methods) => {};
+    (@methods
+        $(#[$method_meta:meta])*
+        async fn $method:ident ( $($param:ident : $param_ty:ty),* $(,)? )
+            $(-> $ret:ty $(= $value:expr)?)?;
+        $($rest:tt)*
+    ) => {
+        $(#[$method_meta])*
+        pub async fn $method(&self $(, $param: $param_ty)*) $(-> $ret)? {
+            $crate::mock_store!(@record self $method $($param)*);
+            $crate::mock_store!(@return $($ret $(, $value)?)?)
+        }
+
+        $crate::mock_store!(@methods $($rest)*);
+    };
+    (@methods
+        $(#[$method_meta:meta])*
+        fn $method:ident ( $($param:ident : $param_ty:ty),* $(,)? )
+            $(-> $ret:ty $(= $value:expr)?)?;
+        $($rest:tt)*
+    ) => {
+        $(#[$method_meta])*
+        pub fn $method(&self $(, $param: $param_ty)*) $(-> $ret)? {
+            $crate::mock_store!(@record self $method $($param)*);
+            $crate::mock_store!(@return $($ret $(, $value)?)?)
+        }
+
+        $crate::mock_store!(@methods $($rest)*);
+    };
+
+    // Record the call, then apply the next scripted state
+    (@record $this:ident $method:ident $($param:ident)*) => {
+        $this.mock.record($crate::mock::MockCall::new(
+            stringify!($method),
+            vec![$( $crate::__summarize_payload!($param) ),*],
+        ));
+        $this.advance();
+    };
+
+    // Return value helpers
+    (@return) => { () };
+    (@return $ret:ty, $value:expr) => { $value };
+    (@return $ret:ty) => { <$ret as Default>::default() };
+}
+
+// ============================================================================
+// Helper macros (internal use)
+// ============================================================================
+
+/// Macro to define a getter function inside an impl block.
+///
+/// This is a helper macro for manual store definitions when not using
+/// the `store!` macro.
+///
+/// # Note
+///
+/// This macro must be used inside an impl block for a type that has
+/// a `state()` method returning a signal.
+#[macro_export]
+macro_rules! define_getter {
+    ($name:ident, $output:ty, $closure:expr) => {
+        pub fn $name(&self) -> $output {
+            let getter: fn(&Self::State) -> $output = $closure;
+            self.state().with(getter)
+        }
+    };
+}
+
+/// Macro to define a mutator function inside an impl block.
+///
+/// This is a helper macro for manual store definitions when not using
+/// the `store!` macro.
+///
+/// # Note
+///
+/// This macro must be used inside an impl block for a type that has
+/// a `state` field of type `RwSignal<State>`.
+#[macro_export]
+macro_rules! define_mutator {
+    ($name:ident, $closure:expr) => {
+        pub fn $name(&self) {
+            self.state.update($closure);
+        }
+    };
+
+    ($name:ident, $param:ident : $param_ty:ty, $closure:expr) => {
+        pub fn $name(&self, $param: $param_ty) {
+            self.state.update(|state| {
+                let mutator: fn(&mut Self::State, $param_ty) = $closure;
+                mutator(state, $param);
+            });
+        }
+    };
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use leptos::prelude::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_define_state_basic() {
+        define_state! {
+            #[derive(Clone, Debug, PartialEq)]
+            struct TestState {
+                count: i32 = 10,
+                name: String,
+            }
+        }
+
+        let state = TestState::default();
+        assert_eq!(state.count, 10);
+        assert_eq!(state.name, "");
+    }
+
+    #[test]
+    fn test_define_state_complex_types() {
+        define_state! {
+            #[derive(Clone, Debug)]
+            struct ComplexState {
+                items: Vec<String>,
+                cache: HashMap<String, i32>,
+                optional: Option<bool> = Some(true),
+            }
+        }
+
+        let state = ComplexState::default();
+        assert!(state.items.is_empty());
+        assert!(state.cache.is_empty());
+        assert_eq!(state.optional, Some(true));
+    }
+
+    #[test]
+    fn test_define_state_builders() {
+        define_state! {
+            #[derive(Clone, Debug, PartialEq)]
+            struct BuilderState {
+                name: String,
+                count: u32 = 1,
+            }
+        }
+
+        define_state! {
+            #[derive(Clone, Debug)]
+            struct GenericBuilderState<T: Clone> {
+                items: Vec<T>,
+                page: usize = 1,
+            }
+        }
+
+        let state = BuilderState::default().with_name("ada".to_string());
+        assert_eq!(
+            state,
+            BuilderState {
+                name: "ada".to_string(),
+                count: 1,
+            }
+        );
+
+        let paged = GenericBuilderState::default().with_items(vec!['a', 'b']);
+        assert_eq!(paged.items, ['a', 'b']);
+        assert_eq!(paged.with_page(3).page, 3);
+    }
+
+    #[test]
+    fn test_define_state_generics() {
+        define_state! {
+            #[derive(Clone, Debug)]
+            struct PagedState<T> {
+                items: Vec<T>,
+                page: usize = 1,
+            }
+        }
+
+        define_state! {
+            #[derive(Clone, Debug)]
+            struct LookupState<'a, K: Clone + Into<Vec<u8>>, V, const N: usize>
+            where
+                V: Default + Clone,
+            {
+                label: &'a str = "lookup",
+                keys: Vec<K>,
+                value: V,
+                slots: [u8; N] = [0; N],
+            }
+        }
+
+        let paged: PagedState<i32> = PagedState::default();
+        assert!(paged.items.is_empty());
+        assert_eq!(paged.page, 1);
+
+        let lookup: LookupState<'static, String, Option<u8>, 3> = LookupState::default();
+        assert_eq!(lookup.label, "lookup");
+        assert!(lookup.keys.is_empty());
+        assert_eq!(lookup.value, None);
+        assert_eq!(lookup.slots, [0; 3]);
+    }
+
+    #[test]
+    fn test_define_action_basic() {
+        define_action! {
+            #[derive(Debug, Clone, PartialEq)]
+            TestAction {
+                user_id: String,
+                amount: i32,
+            }
+        }
+
+        let action = TestAction::new("user123".to_string(), 100);
+        assert_eq!(action.user_id, "user123");
+        assert_eq!(action.amount, 100);
+    }
+
+    #[test]
+    fn test_define_action_single_field() {
+        define_action! {
+            #[derive(Debug)]
+            SimpleAction {
+                value: i32,
+            }
+        }
+
+        let action = SimpleAction::new(42);
+        assert_eq!(action.value, 42);
+    }
+
+    #[test]
+    fn test_define_action_execute() {
+        use crate::r#async::StoreActionExt;
+
+        store! {
+            pub QueueStore {
+                state QueueState {
+                    items: Vec<String>,
+                }
+
+                mutators {
+                    push(this, item: String) {
+                        this.mutate(|s| s.items.push(item));
+                    }
+                    clear(this) {
+                        this.mutate(|s| s.items.clear());
+                    }
+                }
+            }
+        }
+
+        define_action! {
+            #[derive(Debug, Clone)]
+            EnqueueAction for QueueStore {
+                item: String,
+                times: usize,
+            } -> usize;
+
+            execute(this, store) {
+                for _ in 0..this.times {
+                    store.push(this.item.clone());
+                }
+                store.state.with_untracked(|s| s.items.len())
+            }
+        }
+
+        define_action! {
+            ResetAction for QueueStore {}
+
+            execute(_this, store) {
+                store.clear();
+            }
+        }
+
+        let store = QueueStore::new();
+        assert_eq!(store.dispatch(EnqueueAction::new("a".to_string(), 2)), 2);
+        assert_eq!(store.dispatch(EnqueueAction::new("b".to_string(), 1)), 3);
+        store.dispatch(ResetAction::new());
+        assert!(store.state.with_untracked(|s| s.items.is_empty()));
+    }
+
+    #[test]
+    fn test_define_async_action() {
+        use std::fmt;
+
+        #[derive(Debug)]
+        #[allow(dead_code)]
+        struct TestError(String);
+        impl fmt::Display for TestError {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}", self.0)
+            }
+        }
+        impl std::error::Error for TestError {}
+
+        define_async_action! {
+            #[derive(Debug, Clone)]
+            FetchAction {
+                id: String,
+                limit: u32,
+            } -> Result<Vec<String>, TestError>
+        }
+
+        let action = FetchAction::new("test_id".to_string(), 10);
+        assert_eq!(action.id, "test_id");
+        assert_eq!(action.limit, 10);
+
+        // The result_type_description contains the stringified types
+        let desc = FetchAction::result_type_description();
+        assert!(desc.contains("Result"));
+        assert!(desc.contains("Vec"));
+        assert!(desc.contains("String"));
+        assert!(desc.contains("TestError"));
+
+        // Check helper methods
+        assert!(FetchAction::output_type_name().contains("Vec"));
+        assert_eq!(FetchAction::error_type_name(), "TestError");
+    }
+
+    #[test]
+    fn test_define_async_action_simple() {
+        define_async_action! {
+            #[derive(Debug)]
+            ComputeAction {
+                input: i32,
+            } -> String
+        }
+
+        let action = ComputeAction::new(42);
+        assert_eq!(action.input, 42);
+    }
+
+    #[test]
+    fn test_define_async_action_execute() {
+        use crate::r#async::AsyncAction;
+
+        #[derive(Debug, PartialEq, thiserror::Error)]
+        #[error("limit too high: {0}")]
+        struct LimitError(u32);
+
+        store! {
+            pub CatalogStore {
+                state CatalogState {
+                    items: Vec<String> = vec!["a".to_string(), "b".to_string(), "c".to_string()],
+                }
+
+                getters {
+                    items(this) -> Vec<String> {
+                        this.read(|s| s.items.clone())
+                    }
+                }
+            }
+        }
+
+        define_async_action! {
+            #[derive(Debug, Clone)]
+            ListAction for CatalogStore {
+                limit: u32,
+            } -> Result<Vec<String>, LimitError>;
+
+            async execute(this, store) {
+                if this.limit > 10 {
+                    return Err(LimitError(this.limit));
+                }
+                Ok(store.items().into_iter().take(this.limit as usize).collect())
+            }
+        }
+
+        define_async_action! {
+            CountAction for CatalogStore {} -> usize;
+
+            async execute(_this, store) {
+                store.items().len()
+            }
+        }
+
+        let store = CatalogStore::new();
+        futures::executor::block_on(async {
+            assert_eq!(
+                ListAction::new(2).execute(&store).await,
+                Ok(vec!["a".to_string(), "b".to_string()])
+            );
+            assert_eq!(
+                ListAction::new(11).execute(&store).await,
+                Err(LimitError(11))
+            );
+            assert_eq!(CountAction::new().execute(&store).await, Ok(3));
+        });
+        assert_eq!(ListAction::error_type_name(), "LimitError");
+    }
+
+    #[test]
+    fn test_store_macro_state_generation() {
+        store! {
+            pub TestStore {
+                state TestStoreState {
+                    value: i32 = 42,
+                    label: String = "test".to_string(),
+                }
+            }
+        }
+
+        let state = TestStoreState::default();
+        assert_eq!(state.value, 42);
+        assert_eq!(state.label, "test");
+
+        let store = TestStore::new();
+        assert_eq!(store.state.get().value, 42);
+    }
+
+    #[test]
+    fn test_store_macro_with_state() {
+        store! {
+            pub CustomStore {
+                state CustomState {
+                    count: i32 = 0,
+                }
+            }
+        }
+
+        let custom_state = CustomState { count: 100 };
+        let store = CustomStore::with_state(custom_state);
+        assert_eq!(store.state.get().count, 100);
+    }
+
+    #[test]
+    fn test_store_macro_actions() {
+        use futures::channel::oneshot;
+        use std::future::Future;
+        use std::task::{Context, Poll};
+
+        #[derive(Clone, Debug, PartialEq)]
+        enum FetchError {
+            NotFound(u32),
+        }
+
+        store! {
+            pub ItemStore {
+                state ItemState {
+                    items: Vec<u32>,
+                    loading: bool = false,
+                    error: Option<FetchError>,
+                }
+
+                mutators {
+                    push(this, item: u32) {
+                        this.mutate(|s| s.items.push(item));
+                    }
+                }
+
+                actions(loading = loading, error = error) {
+                    /// Plain actions keep their return type.
+                    count(this) -> usize {
+                        this.read(|s| s.items.len())
+                    }
+
+                    add(this, item: u32) -> Result<(), FetchError> {
+                        if item == 0 {
+                            return Err(FetchError::NotFound(item));
+                        }
+                        this.push(item);
+                        Ok(())
+                    }
+
+                    async fetch(this, item: u32) -> Result<u32, FetchError> {
+                        assert!(this.read(|s| s.loading));
+                        this.add(item)?;
+                        // The nested action doesn't clear loading
+                        assert!(this.read(|s| s.loading));
+                        Ok(item)
+                    }
+
+                    async wait(this, gate: oneshot::Receiver<u32>) -> Result<u32, FetchError> {
+                        let item = gate.await.map_err(|_| FetchError::NotFound(0))?;
+                        this.add(item)?;
+                        Ok(item)
+                    }
+                }
+            }
+        }
+
+        let store = ItemStore::new();
+        assert_eq!(store.add(0), Err(FetchError::NotFound(0)));
+        assert_eq!(store.state.get().error, Some(FetchError::NotFound(0)));
+
+        let fetched = futures::executor::block_on(store.fetch(3));
+        assert_eq!(fetched, Ok(3));
+        assert_eq!(store.count(), 1);
+
+        let state = store.state.get();
+        assert!(!state.loading);
+        assert_eq!(state.error, None);
+
+        // Overlapping actions keep loading set until the last one finishes
+        let (first_tx, first_rx) = oneshot::channel();
+        let (second_tx, second_rx) = oneshot::channel();
+        let mut first = Box::pin(store.wait(first_rx));
+        let mut second = Box::pin(store.wait(second_rx));
+        let mut cx = Context::from_waker(futures::task::noop_waker_ref());
+        assert!(first.as_mut().poll(&mut cx).is_pending());
+        assert!(second.as_mut().poll(&mut cx).is_pending());
+        assert!(store.state.get().loading);
+
+        second_tx.send(5).unwrap();
+        assert_eq!(second.as_mut().poll(&mut cx), Poll::Ready(Ok(5)));
+        assert!(store.state.get().loading);
+
+        first_tx.send(0).unwrap();
+        assert_eq!(
+            first.as_mut().poll(&mut cx),
+            Poll::Ready(Err(FetchError::NotFound(0)))
+        );
+        let state = store.state.get();
+        assert!(!state.loading);
+        assert_eq!(state.error, Some(FetchError::NotFound(0)));
+    }
+
+    #[test]
+    fn test_store_macro_untracked_actions() {
+        store! {
+            pub PlainStore {
+                state PlainState {
+                    value: i32 = 1,
+                }
+
+                actions {
+                    checked(this, divisor: i32) -> Result<i32, String> {
+                        if divisor == 0 {
+                            return Err("division by zero".to_string());
+                        }
+                        Ok(this.read(|s| s.value) / divisor)
+                    }
+                }
+            }
+        }
+
+        let store = PlainStore::new();
+        assert_eq!(store.checked(1), Ok(1));
+        assert!(store.checked(0).is_err());
+    }
+
+    #[test]
+    fn test_store_macro_custom_derives() {
+        store! {
+            /// Store with extra derives
+            #[derive(PartialEq)]
+            pub DerivedStore {
+                #[derive(PartialEq, Eq, Hash)]
+                state DerivedState {
+                    /// A documented field
+                    id: u32 = 7,
+                    #[allow(dead_code)]
+                    tag: String,
+                }
+            }
+        }
+
+        let mut set = std::collections::HashSet::new();
+        set.insert(DerivedState::default());
+        assert!(set.contains(&DerivedState {
+            id: 7,
+            tag: String::new(),
+        }));
+
+        let store = DerivedStore::new();
+        assert!(store == store.clone());
+    }
+
+    #[test]
+    fn test_store_macro_deps() {
+        #[derive(Clone)]
+        struct Pricing {
+            tax_rate: f64,
+        }
+
+        store! {
+            pub CheckoutStore {
+                state CheckoutState {
+                    subtotal: f64 = 0.0,
+                }
+
+                deps {
+                    /// Pricing rules
+                    pricing: std::sync::Arc<Pricing>,
+                    currency: &'static str,
+                }
+
+                getters {
+                    total(this) -> f64 {
+                        this.read(|s| s.subtotal * (1.0 + this.pricing.tax_rate))
+                    }
+                }
+
+                mutators {
+                    add(this, amount: f64) {
+                        this.mutate(|s| s.subtotal += amount);
+                    }
+                }
+
+                actions {
+                    label(this) -> String {
+                        format!("{:.2} {}", this.total(), this.currency)
+                    }
+                }
+            }
+        }
+
+        let pricing = std::sync::Arc::new(Pricing { tax_rate: 0.5 });
+        let store = CheckoutStore::new(pricing.clone(), "EUR");
+        store.add(10.0);
+        assert_eq!(store.total(), 15.0);
+        assert_eq!(store.label(), "15.00 EUR");
+
+        let seeded = CheckoutStore::with_state(CheckoutState { subtotal: 2.0 }, pricing, "USD");
+        assert_eq!(seeded.clone().label(), "3.00 USD");
+    }
+
+    store! {
+        pub RenamedStore {
+            state RenamedState {
+                items: Vec<String>,
+            }
+
+            getters {
+                item_count(this) -> usize {
+                    this.read(|s| s.items.len())
+                }
+            }
+
+            mutators {
+                push_item(this, item: String) {
+                    this.mutate(|s| s.items.push(item));
+                }
+            }
+
+            actions {
+                async load(this, items: Vec<String>) -> usize {
+                    for item in items {
+                        this.push_item(item);
+                    }
+                    this.item_count()
+                }
+            }
+        }
+    }
+
+    impl RenamedStore {
+        deprecated_alias! {
+            add_item(item: String) => push_item, since = "0.4.0";
+            /// Old name for `item_count`.
+            len() -> usize => item_count;
+            async fetch(items: Vec<String>) -> usize => load;
+        }
+    }
+
+    #[derive(Clone, Debug, Default, PartialEq)]
+    pub enum Door {
+        #[default]
+        Closed,
+        Open,
+    }
+
+    store! {
+        pub DoorStore {
+            state DoorState {
+                door: Door,
+            }
+
+            getters {
+                is_open(this) -> bool {
+                    this.read(|s| s.door == Door::Open)
+                }
+            }
+
+            mutators {
+                set_door(this, door: Door) {
+                    this.mutate(|s| s.door = door);
+                }
+            }
+
+            actions {
+                open(this) {
+                    this.set_door(Door::Open);
+                }
+                async close(this) -> bool {
+                    this.set_door(Door::Closed);
+                    true
+                }
+            }
+
+            transitions(door) {
+                Closed => Open: open,
+                Open => Closed: close,
+            }
+        }
+    }
+
+    #[test]
+    fn test_store_macro_describe() {
+        use crate::store::DescribeStore;
+
+        let methods = DoorStore::methods();
+        assert_eq!(methods.getters, &["is_open"]);
+        assert_eq!(methods.mutators, &["set_door"]);
+        assert_eq!(methods.actions, &["open", "close"]);
+
+        let store = DoorStore::new();
+        store.open();
+        assert!(store.is_open());
+
+        #[cfg(feature = "describe")]
+        {
+            use crate::describe::StoreSchema;
+
+            let description = DoorStore::describe();
+            assert_eq!(description.name, "DoorStore");
+            assert_eq!(description.fields[0].ty, "Door");
+            assert_eq!(description.mutators[0].params[0].name, "door");
+            assert!(description.actions[1].is_async);
+            assert_eq!(description.actions[1].returns, Some("bool"));
+            assert_eq!(description.transitions.len(), 2);
+            assert!(description.to_mermaid().contains("Open --> Closed: close"));
+        }
+    }
+
+    mod scoped_mutators {
+        crate::store! {
+            pub LedgerStore {
+                state LedgerState {
+                    balance: i64,
+                    entries: u32,
+                }
+
+                mutators(pub(super)) {
+                    credit(this, amount: i64) {
+                        this.mutate(|s| {
+                            s.balance += amount;
+                            s.entries += 1;
+                        });
+                    }
+                }
+            }
+        }
+
+        crate::store! {
+            pub AuditStore {
+                state AuditState {
+                    count: u32,
+                }
+
+                mutators(pub(self)) {
+                    bump(this) {
+                        this.mutate(|s| s.count += 1);
+                    }
+                }
+
+                actions {
+                    record(this) {
+                        this.bump();
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_store_macro_generic_params() {
+        #[derive(Clone, Debug, PartialEq)]
+        struct LabelError(String);
+
+        store! {
+            pub LabelStore {
+                state LabelState {
+                    labels: Vec<String>,
+                }
+
+                getters {
+                    count(this) -> usize {
+                        this.read(|s| s.labels.len())
+                    }
+                }
+
+                mutators {
+                    add(this, label: impl Into<String>) {
+                        this.mutate(|s| s.labels.push(label.into()));
+                    }
+                    extend<I: IntoIterator<Item = T>, T: Into<String>>(this, labels: I) {
+                        this.mutate(|s| s.labels.extend(labels.into_iter().map(Into::into)));
+                    }
+                }
+
+                actions {
+                    rename<F: Fn(&str) -> String>(this, f: F) {
+                        let renamed = this.read(|s| s.labels.iter().map(|l| f(l)).collect());
+                        this.mutate(|s| s.labels = renamed);
+                    }
+                    try_add<T: AsRef<str>>(this, label: T) -> Result<usize, LabelError> {
+                        if label.as_ref().is_empty() {
+                            return Err(LabelError("empty".to_string()));
+                        }
+                        this.add(label.as_ref());
+                        Ok(this.count())
+                    }
+                    async load<T: ToString + Send>(this, labels: Vec<T>) -> usize {
+                        this.extend(labels.iter().map(ToString::to_string));
+                        this.count()
+                    }
+                }
+            }
+        }
+
+        let store = LabelStore::new();
+        store.add("a");
+        store.add(String::from("b"));
+        store.extend(vec!["c", "d"]);
+        store.rename(|l| l.to_uppercase());
+        assert_eq!(store.try_add("e"), Ok(5));
+        assert_eq!(store.try_add(""), Err(LabelError("empty".to_string())));
+        assert_eq!(futures::executor::block_on(store.load(vec![1, 2])), 7);
+        assert_eq!(
+            store.state.get_untracked().labels,
+            ["A", "B", "C", "D", "e", "1", "2"]
+        );
+
+        let methods = <LabelStore as crate::store::DescribeStore>::methods();
+        assert_eq!(methods.mutators, ["add", "extend"]);
+        assert_eq!(methods.actions, ["rename", "try_add", "load"]);
+    }
+
+    #[test]
+    fn test_store_macro_state_machine() {
+        use crate::store::{DescribeStore, StoreError};
+
+        store! {
+            pub FetchStore {
+                #[derive(PartialEq)]
+                state enum FetchState {
+                    #[default]
+                    Idle,
+                    Loading,
+                    Loaded(Vec<String>),
+                    Failed { error: String },
+                }
+
+                getters {
+                    items(this) -> Vec<String> {
+                        this.read(|s| match s {
+                            FetchState::Loaded(items) => items.clone(),
+                            _ => Vec::new(),
+                        })
+                    }
+                }
+
+                transitions {
+                    /// Start loading.
+                    start(this): Idle | Failed => Loading {
+                        FetchState::Loading
+                    }
+                    finish(this, items: Vec<String>): Loading => Loaded {
+                        FetchState::Loaded(items)
+                    }
+                    fail(this, error: &str): Loading => Failed {
+                        FetchState::Failed { error: error.to_string() }
+                    }
+                    broken(this): Loaded => Idle {
+                        FetchState::Loading
+                    }
+                    reset(this): _ => Idle {
+                        FetchState::Idle
+                    }
+                }
+            }
+        }
+
+        let store = FetchStore::new();
+        assert_eq!(store.variant(), "Idle");
+        assert!(matches!(
+            store.finish(vec![]),
+            Err(StoreError::InvalidTransition(message)) if message == "`finish` is not allowed in state Idle"
+        ));
+
+        store.start().unwrap();
+        store.fail("offline").unwrap();
+        assert_eq!(
+            store.state.get_untracked(),
+            FetchState::Failed {
+                error: "offline".to_string()
+            }
+        );
+
+        store.start().unwrap();
+        store.finish(vec!["a".to_string()]).unwrap();
+        assert_eq!(store.items(), ["a"]);
+        assert!(store.start().is_err());
+
+        // The body must produce the declared target; the state is kept otherwise
+        assert!(matches!(
+            store.broken(),
+            Err(StoreError::InvalidTransition(message)) if message == "`broken` must move to Idle, not Loading"
+        ));
+        assert_eq!(store.variant(), "Loaded");
+
+        store.reset().unwrap();
+        assert_eq!(store.variant(), "Idle");
+        assert_eq!(format!("{store:?}"), "FetchStore { state: Idle }");
+        assert_eq!(
+            FetchStore::methods().actions,
+            ["start", "finish", "fail", "broken", "reset"]
+        );
+
+        #[cfg(feature = "describe")]
+        {
+            use crate::describe::StoreSchema;
+
+            let transitions = FetchStore::describe().transitions;
+            assert_eq!(transitions.len(), 6);
+            assert_eq!(
+                (transitions[1].from, transitions[1].to),
+                ("Failed", "Loading")
+            );
+            assert_eq!((transitions[5].from, transitions[5].action), ("*", "reset"));
+        }
+    }
+
+    #[test]
+    fn test_store_macro_key() {
+        use crate::store::{KeyedStore, StoreRegistry, registered_store_keys};
+
+        store! {
+            pub SessionKeyStore(key = "macro_session") {
+                #[cfg_attr(feature = "hydrate", derive(serde::Serialize, serde::Deserialize))]
+                state SessionKeyState {
+                    user: Option<String>,
+                }
+            }
+        }
+
+        store! {
+            pub ClientKeyStore(key = "macro_client") {
+                state ClientKeyState {
+                    retries: u32,
+                }
+
+                deps {
+                    base_url: &'static str,
+                }
+            }
+        }
+
+        assert_eq!(SessionKeyStore::KEY, "macro_session");
+        let store = SessionKeyStore::with_state(SessionKeyState {
+            user: Some("ada".to_string()),
+        });
+        assert_eq!(store.state.get_untracked().user.as_deref(), Some("ada"));
+        let client = ClientKeyStore::new("https://example.com");
+        assert_eq!(client.base_url, "https://example.com");
+        assert_eq!(client.state.get_untracked().retries, 0);
+
+        let keys = registered_store_keys();
+        assert!(
+            keys.iter()
+                .any(|(key, name)| *key == "macro_session" && name.ends_with("SessionKeyStore"))
+        );
+        assert!(keys.iter().any(|(key, _)| *key == "macro_client"));
+
+        let mut registry = StoreRegistry::new();
+        let id = registry.register_keyed(store.clone()).unwrap();
+        assert_eq!(registry.id_for_key("macro_session"), Some(id));
+
+        #[cfg(feature = "hydrate")]
+        {
+            use crate::hydration::HydratableStore;
+
+            assert_eq!(SessionKeyStore::store_key(), "macro_session");
+            let data = store.serialize_state().unwrap();
+            let restored = SessionKeyStore::from_hydrated_state(&data).unwrap();
+            assert_eq!(restored.state.get_untracked().user.as_deref(), Some("ada"));
+        }
+    }
+
+    #[test]
+    fn test_store_macro_cfg_methods() {
+        store! {
+            pub SyncStore {
+                state SyncState {
+                    source: String,
+                    saved: u32,
+                }
+
+                getters {
+                    /// Where the data came from.
+                    source(this) -> String {
+                        this.read(|s| s.source.clone())
+                    }
+                    #[cfg(any())]
+                    server_only(this) -> u32 {
+                        missing_server_helper(this)
+                    }
+                }
+
+                mutators as SyncMutation {
+                    #[cfg(feature = "ssr")]
+                    set_source(this, source: &str) {
+                        this.mutate(|s| s.source = format!("server:{source}"));
+                    }
+                    #[cfg(not(feature = "ssr"))]
+                    set_source(this, source: &str) {
+                        this.mutate(|s| s.source = format!("client:{source}"));
+                    }
+                    #[cfg(any())]
+                    wipe<T: MissingTrait>(this, value: T) {
+                        missing_storage_call(value);
+                    }
+                    #[inline]
+                    bump(this) {
+                        this.mutate(|s| s.saved += 1);
+                    }
+                }
+
+                actions {
+                    #[cfg(feature = "ssr")]
+                    load(this) -> Result<(), String> {
+                        this.set_source("db");
+                        Ok(())
+                    }
+                    #[cfg(not(feature = "ssr"))]
+                    load(this) -> Result<(), String> {
+                        this.set_source("local_storage");
+                        Ok(())
+                    }
+                    #[cfg(any())]
+                    async fetch(this) {
+                        missing_fetch().await;
+                    }
+                }
+            }
+        }
+
+        let store = SyncStore::new();
+        store.load().unwrap();
+        store.bump();
+        let expected = if cfg!(feature = "ssr") {
+            "server:db"
+        } else {
+            "client:local_storage"
+        };
+        assert_eq!(store.source(), expected);
+
+        let methods = <SyncStore as crate::store::DescribeStore>::methods();
+        assert_eq!(methods.getters, ["source"]);
+        assert_eq!(methods.mutators, ["set_source", "bump"]);
+        assert_eq!(methods.actions, ["load"]);
+
+        let variant = SyncMutation::Bump {};
+        assert_eq!(variant.name(), "bump");
+    }
+
+    #[test]
+    fn test_store_macro_patch() {
+        store! {
+            pub FormStore {
+                state(signals = FormSignals, patch = FormPatch) FormState {
+                    title: String,
+                    count: u32 = 1,
+                    tags: Vec<String>,
+                }
+            }
+        }
+
+        use std::sync::Arc;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let owner = Owner::new();
+        owner.set();
+        let store = FormStore::new();
+        let title = store.signals().title();
+
+        // Count state notifications; the effect runs once on creation
+        let runs = Arc::new(AtomicUsize::new(0));
+        let _effect = ImmediateEffect::new({
+            let runs = Arc::clone(&runs);
+            move || {
+                store.state.track();
+                runs.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+
+        assert!(FormPatch::default().is_empty());
+        store.apply_patch(FormPatch::default());
+
+        let patch = FormPatch {
+            title: Some("draft".to_string()),
+            count: Some(3),
+            ..Default::default()
+        };
+        assert!(!patch.is_empty());
+        store.apply_patch(patch);
+        assert_eq!(runs.load(Ordering::Relaxed), 2);
+
+        let state = store.state.get_untracked();
+        assert_eq!(state.title, "draft");
+        assert_eq!(state.count, 3);
+        assert!(state.tags.is_empty());
+        assert_eq!(title.get_untracked(), "draft");
+    }
+
+    #[test]
+    fn test_store_macro_debug() {
+        struct Client;
+
+        store! {
+            pub DebugStore {
+                state DebugState {
+                    name: String = "ada".to_string(),
+                }
+
+                deps {
+                    client: std::sync::Arc<Client>,
+                }
+            }
+        }
+
+        let owner = Owner::new();
+        let store = owner.with(|| DebugStore::new(std::sync::Arc::new(Client)));
+        assert_eq!(std::sync::Arc::strong_count(&store.client), 1);
+        assert_eq!(store.state.get_untracked().name, "ada");
+        assert_eq!(
+            format!("{store:?}"),
+            r#"DebugStore { state: DebugState { name: "ada" } }"#
+        );
+
+        owner.cleanup();
+        assert_eq!(format!("{store:?}"), "DebugStore { state: <disposed> }");
+    }
+
+    #[test]
+    fn test_store_macro_mutation_enum() {
+        use crate::mutation::MutationPayload;
+        use std::sync::{Arc, Mutex};
+
+        store! {
+            pub TokenStore {
+                state TokenState {
+                    tokens: Vec<String>,
+                    label: String,
+                }
+
+                mutators as TokenMutation {
+                    set_tokens(this, tokens: Vec<String>) {
+                        this.mutate(|s| s.tokens = tokens);
+                    }
+                    push<T: Into<String> + MutationPayload>(this, token: T) {
+                        this.mutate(|s| s.tokens.push(token.into()));
+                    }
+                    set_label(this, label: impl Into<String>) {
+                        let label = label.into();
+                        if label.is_empty() {
+                            return;
+                        }
+                        this.mutate(|s| s.label = label);
+                    }
+                }
+            }
+        }
+
+        let store = TokenStore::new();
+        store.set_tokens(vec!["a".to_string()]);
+
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let subscription = crate::mutation::on_mutation({
+            let log = Arc::clone(&log);
+            move |event| {
+                if event.store() == "TokenStore" {
+                    log.lock().unwrap().push(format!("{event}"));
+                }
+            }
+        });
+
+        store.set_tokens(vec![String::new(); 10]);
+        store.push("b");
+        store.set_label("");
+        drop(subscription);
+        store.push("c");
+
+        let log = log.lock().unwrap();
+        assert_eq!(log[0], "TokenStore: SetTokens { tokens: len(10) }");
+        // A bounded generic is summarized, an unbounded one shown by type
+        assert_eq!(log[1], "TokenStore: Push { token: \"b\" }");
+        assert!(log[2].starts_with("TokenStore: SetLabel { label: <"));
+        assert_eq!(log.len(), 3);
+        assert_eq!(store.state.get_untracked().tokens.len(), 12);
+
+        let mutation = TokenMutation::SetTokens {
+            tokens: crate::mutation::PayloadSummary::Len(2),
+        };
+        assert_eq!(mutation.name(), "set_tokens");
+    }
+
+    #[test]
+    fn test_store_macro_dry_run() {
+        use crate::r#async::{Action, StoreActionExt};
+        use crate::store::Store;
+        use std::sync::{Arc, Mutex};
+
+        store! {
+            pub CartStore {
+                #[derive(PartialEq)]
+                state CartState {
+                    items: Vec<String>,
+                    total: u32,
+                }
+
+                mutators as CartMutation {
+                    add_item(this, item: String, price: u32) {
+                        this.mutate(|s| s.items.push(item));
+                        this.add_to_total(price);
+                    }
+                    add_to_total(this, price: u32) {
+                        this.mutate(|s| s.total += price);
+                    }
+                    clear(this) {
+                        this.mutate(|s| *s = CartState::default());
+                    }
+                }
+            }
+        }
+
+        struct Checkout;
+
+        impl Action<CartStore> for Checkout {
+            type Output = u32;
+
+            fn execute(&self, store: &CartStore) -> u32 {
+                store.add_item("shipping".to_string(), 5);
+                let total = store.state().get_untracked().total;
+                store.clear();
+                total
+            }
+        }
+
+        let store = CartStore::new();
+        store.add_item("apple".to_string(), 3);
+
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let subscription = crate::mutation::on_mutation({
+            let log = Arc::clone(&log);
+            move |event| {
+                if event.store() == "CartStore" {
+                    log.lock().unwrap().push(event.name());
+                }
+            }
+        });
+
+        let plan = store.dry_run(Checkout);
+        drop(subscription);
+
+        // Only the outermost mutators are recorded, and nothing is committed
+        // or reported
+        assert_eq!(plan.names(), vec!["add_item", "clear"]);
+        assert_eq!(plan.mutations()[0].after().total, 8);
+        assert_eq!(plan.final_state(), &CartState::default());
+        assert_eq!(store.state().get_untracked().items, vec!["apple"]);
+        assert!(log.lock().unwrap().is_empty());
+
+        // A plan made stale by a later change is refused
+        let stale = plan.clone();
+        assert!(plan.commit_to(&store));
+        assert_eq!(store.state().get_untracked(), CartState::default());
+        store.add_item("pear".to_string(), 2);
+        assert!(!stale.commit_to(&store));
+        assert_eq!(store.state().get_untracked().items, vec!["pear"]);
+
+        // Outside a dry run the mutators write through as usual
+        assert_eq!(Checkout.execute(&store), 7);
+        assert_eq!(store.state().get_untracked(), CartState::default());
+    }
+
+    #[test]
+    fn test_store_macro_effects() {
+        use std::sync::{Arc, Mutex};
+
+        store! {
+            pub FeedStore {
+                state FeedState {
+                    page: u32,
+                    title: String,
+                }
+
+                deps {
+                    log: Arc<Mutex<Vec<String>>>,
+                }
+
+                mutators {
+                    next_page(this) {
+                        this.mutate(|s| s.page += 1);
+                    }
+                    set_title(this, title: &str) {
+                        this.mutate(|s| s.title = title.to_string());
+                    }
+                }
+
+                effects {
+                    log_pages(this, old, new) {
+                        if old.page != new.page {
+                            this.log.lock().unwrap().push(format!("{} -> {}", old.page, new.page));
+                        }
+                    }
+                    log_titles(this, old, new) {
+                        if old.title != new.title {
+                            this.log.lock().unwrap().push(new.title.clone());
+                        }
+                    }
+                }
+            }
+        }
+
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let owner = Owner::new();
+        let store = owner.with(|| FeedStore::new(Arc::clone(&log)));
+        assert!(log.lock().unwrap().is_empty());
+
+        store.next_page();
+        store.set_title("news");
+        store.next_page();
+        assert_eq!(*log.lock().unwrap(), ["0 -> 1", "news", "1 -> 2"]);
+
+        // Effects are created under the store's owner, not the caller's
+        let child = owner.child();
+        child.with(|| store.next_page());
+        child.cleanup();
+        store.next_page();
+        assert_eq!(log.lock().unwrap().len(), 5);
+    }
+
+    #[test]
+    #[cfg(feature = "persist")]
+    fn test_store_macro_persist() {
+        use crate::persistence::{MemoryStorage, StorageBackend};
+        use std::sync::LazyLock;
+
+        static STORAGE: LazyLock<MemoryStorage> = LazyLock::new(MemoryStorage::new);
+
+        store! {
+            pub PrefsStore {
+                #[derive(PartialEq, serde::Serialize, serde::Deserialize)]
+                state PrefsState {
+                    theme: String = "light".to_string(),
+                    volume: u8 = 5,
+                }
+
+                persist {
+                    key: "prefs",
+                    backend: STORAGE.clone(),
+                }
 
-            /// Update state with a closure (for mutators).
-            /// Uses the Update trait internally.
-            #[allow(dead_code)]
-            #[inline]
-            fn mutate<R>(&self, f: impl FnOnce(&mut $state_name) -> R) -> R {
-                use ::leptos::prelude::Update;
-                self.state.try_update(f).expect("signal disposed")
+                mutators {
+                    set_theme(this, theme: &str) {
+                        this.mutate(|s| s.theme = theme.to_string());
+                    }
+                    set_volume(this, volume: u8) {
+                        this.mutate(|s| s.volume = volume);
+                    }
+                }
             }
         }
 
-        impl Default for $store_name {
-            fn default() -> Self {
-                Self::new()
+        let owner = Owner::new();
+        owner.set();
+
+        // Nothing persisted yet: defaults, and construction doesn't save
+        let store = PrefsStore::new();
+        assert_eq!(store.state.get_untracked(), PrefsState::default());
+        assert_eq!(STORAGE.get("prefs").unwrap(), None);
+
+        // Every change is saved
+        store.set_theme("dark");
+        store.set_volume(9);
+        let saved: PrefsState =
+            serde_json::from_str(&STORAGE.get("prefs").unwrap().unwrap()).unwrap();
+        assert_eq!(saved.theme, "dark");
+        assert_eq!(saved.volume, 9);
+
+        // A new store starts from the persisted state
+        let restored = PrefsStore::new();
+        assert_eq!(restored.state.get_untracked(), saved);
+
+        // with_state ignores it, restore() brings it back
+        let fresh = PrefsStore::with_state(PrefsState::default());
+        assert_eq!(fresh.state.get_untracked().volume, 5);
+        assert_eq!(fresh.restore(), Ok(true));
+        assert_eq!(fresh.state.get_untracked().volume, 9);
+
+        fresh.persister().clear().unwrap();
+        assert_eq!(fresh.restore(), Ok(false));
+        fresh.persist().unwrap();
+        assert!(STORAGE.get("prefs").unwrap().is_some());
+        assert_eq!(store.persister().key(), "prefs");
+    }
+
+    #[test]
+    #[cfg(all(feature = "persist", feature = "hydrate"))]
+    fn test_store_macro_persist_reconcile() {
+        use crate::hydration::HydratableStore;
+        use crate::persistence::{MemoryStorage, Reconcile, StorageBackend, Timestamped};
+        use std::sync::LazyLock;
+
+        static STORAGE: LazyLock<MemoryStorage> = LazyLock::new(MemoryStorage::new);
+
+        fn merge(server: DraftState, client: DraftState) -> DraftState {
+            DraftState {
+                text: server.text.newest(client.text),
+                ..server
             }
         }
 
-        impl $crate::store::Store for $store_name {
-            type State = $state_name;
+        store! {
+            pub DraftStore (key = "reconciled_draft") {
+                #[derive(PartialEq, serde::Serialize, serde::Deserialize)]
+                state(signals = DraftSignals) DraftState {
+                    text: Timestamped<String> = Timestamped::default(),
+                    revision: u32 = 0,
+                }
 
-            fn state(&self) -> ::leptos::prelude::ReadSignal<Self::State> {
-                self.state.read_only()
+                persist {
+                    key: "draft",
+                    backend: STORAGE.clone(),
+                    reconcile: Reconcile::Merge(merge),
+                }
             }
         }
-    };
 
-    // Default value helpers
-    (@default $ty:ty, $default:expr) => { $default };
-    (@default $ty:ty) => { <$ty as Default>::default() };
-}
+        let owner = Owner::new();
+        owner.with(|| {
+            let server = |text: &str, at, revision| {
+                DraftStore::with_state(DraftState {
+                    text: Timestamped::at(text.to_string(), at),
+                    revision,
+                })
+                .serialize_state()
+                .unwrap()
+            };
 
-// ============================================================================
-// Helper macros (internal use)
-// ============================================================================
+            // Nothing persisted: the server's state is kept
+            let store = DraftStore::from_hydrated_state(&server("server", 10, 1)).unwrap();
+            store.reconcile();
+            assert_eq!(store.state.get_untracked().text.value, "server");
 
-/// Macro to define a getter function inside an impl block.
-///
-/// This is a helper macro for manual store definitions when not using
-/// the `store!` macro.
-///
-/// # Note
-///
-/// This macro must be used inside an impl block for a type that has
-/// a `state()` method returning a signal.
-#[macro_export]
-macro_rules! define_getter {
-    ($name:ident, $output:ty, $closure:expr) => {
-        pub fn $name(&self) -> $output {
-            let getter: fn(&Self::State) -> $output = $closure;
-            self.state().with(getter)
+            // A newer local draft wins over the server's text, not its revision
+            STORAGE
+                .set(
+                    "draft",
+                    r#"{"text":{"value":"local","updated_at":20},"revision":0}"#,
+                )
+                .unwrap();
+            let store = DraftStore::from_hydrated_state(&server("server", 10, 2)).unwrap();
+            store.reconcile();
+            let state = store.state.get_untracked();
+            assert_eq!((state.text.value.as_str(), state.revision), ("local", 2));
+            assert_eq!(store.signals().text().get_untracked().value, "local");
+            let saved: DraftState =
+                serde_json::from_str(&STORAGE.get("draft").unwrap().unwrap()).unwrap();
+            assert_eq!(saved, state);
+
+            // An older one doesn't
+            let store = DraftStore::from_hydrated_state(&server("edited", 30, 3)).unwrap();
+            store.reconcile();
+            assert_eq!(store.state.get_untracked().text.value, "edited");
+        });
+    }
+
+    #[test]
+    fn test_entity_store() {
+        use crate::store::Store;
+
+        #[derive(Clone, Debug, PartialEq)]
+        struct Contact {
+            email: String,
+            name: &'static str,
         }
-    };
-}
 
-/// Macro to define a mutator function inside an impl block.
-///
-/// This is a helper macro for manual store definitions when not using
-/// the `store!` macro.
-///
-/// # Note
-///
-/// This macro must be used inside an impl block for a type that has
-/// a `state` field of type `RwSignal<State>`.
-#[macro_export]
-macro_rules! define_mutator {
-    ($name:ident, $closure:expr) => {
-        pub fn $name(&self) {
-            self.state.update($closure);
+        fn contact(email: &str, name: &'static str) -> Contact {
+            Contact {
+                email: email.to_string(),
+                name,
+            }
         }
-    };
 
-    ($name:ident, $param:ident : $param_ty:ty, $closure:expr) => {
-        pub fn $name(&self, $param: $param_ty) {
-            self.state.update(|state| {
-                let mutator: fn(&mut Self::State, $param_ty) = $closure;
-                mutator(state, $param);
-            });
+        entity_store! {
+            ContactStore for Contact {
+                email: String,
+            }
         }
-    };
-}
 
-// ============================================================================
-// Tests
-// ============================================================================
+        let store = ContactStore::with_entities([contact("c@x", "Cy"), contact("a@x", "Al")]);
+        store.upsert(contact("b@x", "Bo"));
+        store.upsert(contact("c@x", "Cyd"));
+        assert_eq!(store.ids(), ["c@x", "a@x", "b@x"]);
+        assert_eq!(store.select_by_id(&"c@x".to_string()).unwrap().name, "Cyd");
+        assert_eq!(store.len(), 3);
 
-#[cfg(test)]
-mod tests {
-    use leptos::prelude::*;
-    use std::collections::HashMap;
+        store.sort_by_key(|c| c.name);
+        assert_eq!(
+            store.all().iter().map(|c| c.name).collect::<Vec<_>>(),
+            ["Al", "Bo", "Cyd"]
+        );
+        assert!(store.move_to(&"b@x".to_string(), 0));
+        assert_eq!(store.ids(), ["b@x", "a@x", "c@x"]);
+
+        assert_eq!(store.remove(&"a@x".to_string()), Some(contact("a@x", "Al")));
+        assert_eq!(store.remove(&"a@x".to_string()), None);
+        assert!(!store.contains(&"a@x".to_string()));
+        assert_eq!(store.state().get_untracked().ids(), ["b@x", "c@x"]);
+
+        store.clear();
+        assert!(store.is_empty());
+    }
 
     #[test]
-    fn test_define_state_basic() {
-        define_state! {
-            #[derive(Clone, Debug, PartialEq)]
-            struct TestState {
-                count: i32 = 10,
-                name: String,
-            }
-        }
+    fn test_store_macro_mutator_visibility() {
+        use crate::store::Store;
+        use scoped_mutators::{AuditStore, LedgerStore};
 
-        let state = TestState::default();
-        assert_eq!(state.count, 10);
-        assert_eq!(state.name, "");
+        let ledger = LedgerStore::new();
+        ledger.credit(50);
+        ledger.credit(-20);
+        assert_eq!(ledger.state().get_untracked().balance, 30);
+        assert_eq!(ledger.state().get_untracked().entries, 2);
+
+        // Private mutators are still reachable through actions
+        let audit = AuditStore::new();
+        audit.record();
+        assert_eq!(audit.state().get_untracked().count, 1);
     }
 
     #[test]
-    fn test_define_state_complex_types() {
-        define_state! {
-            #[derive(Clone, Debug)]
-            struct ComplexState {
-                items: Vec<String>,
-                cache: HashMap<String, i32>,
-                optional: Option<bool> = Some(true),
+    fn test_store_macro_per_field_signals() {
+        use crate::store::Store;
+        use std::sync::Arc;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        store! {
+            pub BoardStore {
+                state(signals = BoardSignals) BoardState {
+                    count: i32,
+                    loading: bool,
+                    title: String = "board".to_string(),
+                }
+
+                deps {
+                    step: i32,
+                }
+
+                mutators {
+                    increment(this) {
+                        this.mutate(|s| s.count += this.step);
+                    }
+                    set_loading(this, loading: bool) {
+                        this.mutate(|s| s.loading = loading);
+                    }
+                }
             }
         }
 
-        let state = ComplexState::default();
-        assert!(state.items.is_empty());
-        assert!(state.cache.is_empty());
-        assert_eq!(state.optional, Some(true));
+        let owner = Owner::new();
+        owner.set();
+
+        let store = BoardStore::new(2);
+        assert_eq!(store.signals().title().get_untracked(), "board");
+
+        let count_runs = Arc::new(AtomicUsize::new(0));
+        let _effect = ImmediateEffect::new_isomorphic({
+            let count = store.signals().count();
+            let count_runs = Arc::clone(&count_runs);
+            move || {
+                count.track();
+                count_runs.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+        assert_eq!(count_runs.load(Ordering::SeqCst), 1);
+
+        store.set_loading(true);
+        store.set_loading(true);
+        assert!(store.signals().loading().get_untracked());
+        assert_eq!(count_runs.load(Ordering::SeqCst), 1);
+
+        store.increment();
+        assert_eq!(store.signals().count().get_untracked(), 2);
+        assert_eq!(store.state().get_untracked().count, 2);
+        assert_eq!(count_runs.load(Ordering::SeqCst), 2);
     }
 
     #[test]
-    fn test_define_action_basic() {
-        define_action! {
-            #[derive(Debug, Clone, PartialEq)]
-            TestAction {
-                user_id: String,
-                amount: i32,
-            }
+    #[allow(deprecated)]
+    fn test_deprecated_alias() {
+        let store = RenamedStore::new();
+        store.add_item("a".to_string());
+        store.add_item("b".to_string());
+        assert_eq!(store.len(), 2);
+        assert_eq!(
+            futures::executor::block_on(store.fetch(vec!["c".to_string()])),
+            3
+        );
+    }
+
+    #[derive(Clone, Debug, Default, PartialEq)]
+    #[cfg_attr(feature = "hydrate", derive(serde::Serialize, serde::Deserialize))]
+    pub struct PlayerState {
+        track: Option<String>,
+        playing: bool,
+    }
+
+    crate::mock_store! {
+        pub MockPlayerStore for PlayerState {
+            fn play(track: String);
+            fn pause();
+            fn volume() -> u8 = 11;
+            fn is_playing() -> bool;
+            async fn load(ids: Vec<u32>) -> Result<usize, String> = Ok(ids.len());
         }
+    }
 
-        let action = TestAction::new("user123".to_string(), 100);
-        assert_eq!(action.user_id, "user123");
-        assert_eq!(action.amount, 100);
+    #[test]
+    fn test_mock_store() {
+        use crate::mutation::PayloadSummary;
+        use crate::store::Store;
+
+        let store = MockPlayerStore::new();
+        store.script([
+            PlayerState {
+                track: Some("intro".to_string()),
+                playing: true,
+            },
+            PlayerState::default(),
+        ]);
+
+        store.play("intro".to_string());
+        assert!(store.state().get_untracked().playing);
+        assert_eq!(store.volume(), 11);
+        assert!(!store.state().get_untracked().playing);
+        assert!(!store.is_playing());
+
+        assert_eq!(
+            futures::executor::block_on(store.load(vec![1, 2, 3])),
+            Ok(3)
+        );
+        assert!(!store.advance());
+
+        let calls = store.mock().calls();
+        assert_eq!(calls[0].args(), [PayloadSummary::text("intro")]);
+        assert_eq!(calls[3].to_string(), "load(len(3))");
+        assert_eq!(
+            store.mock().call_names(),
+            ["play", "volume", "is_playing", "load"]
+        );
+        assert!(!store.mock().was_called("pause"));
+
+        store.set_state(PlayerState {
+            track: None,
+            playing: true,
+        });
+        store.pause();
+        assert_eq!(store.mock().call_count("pause"), 1);
+        assert!(store.state().get_untracked().playing);
+    }
+
+    #[cfg(feature = "hydrate")]
+    crate::mock_store! {
+        MockHydratedPlayerStore for PlayerState, key = "player" {}
     }
 
     #[test]
-    fn test_define_action_single_field() {
-        define_action! {
-            #[derive(Debug)]
-            SimpleAction {
-                value: i32,
-            }
-        }
+    #[cfg(feature = "hydrate")]
+    fn test_mock_store_hydration() {
+        use crate::hydration::HydratableStore;
 
-        let action = SimpleAction::new(42);
-        assert_eq!(action.value, 42);
+        let store = MockHydratedPlayerStore::with_state(PlayerState {
+            track: Some("outro".to_string()),
+            playing: false,
+        });
+        let data = store.serialize_state().unwrap();
+        let restored = MockHydratedPlayerStore::from_hydrated_state(&data).unwrap();
+        assert_eq!(restored.state.get_untracked(), store.state.get_untracked());
+        assert_eq!(MockHydratedPlayerStore::store_key(), "player");
     }
 
     #[test]
-    fn test_define_async_action() {
-        use std::fmt;
+    fn test_impl_store_signal_kinds() {
+        use crate::store::Store;
 
-        #[derive(Debug)]
-        #[allow(dead_code)]
-        struct TestError(String);
-        impl fmt::Display for TestError {
-            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-                write!(f, "{}", self.0)
-            }
+        #[derive(Clone, Debug, Default, PartialEq)]
+        struct GaugeState {
+            level: u8,
         }
-        impl std::error::Error for TestError {}
 
-        define_async_action! {
-            #[derive(Debug, Clone)]
-            FetchAction {
-                id: String,
-                limit: u32,
-            } -> Result<Vec<String>, TestError>
+        #[derive(Clone)]
+        struct ArcGaugeStore {
+            state: ArcRwSignal<GaugeState>,
+            read: ReadSignal<GaugeState>,
         }
+        crate::impl_store!(ArcGaugeStore, GaugeState, state: ArcRwSignal => read);
 
-        let action = FetchAction::new("test_id".to_string(), 10);
-        assert_eq!(action.id, "test_id");
-        assert_eq!(action.limit, 10);
+        #[derive(Clone)]
+        struct MemoGaugeStore {
+            state: Memo<GaugeState>,
+            read: ReadSignal<GaugeState>,
+        }
+        crate::impl_store!(MemoGaugeStore, GaugeState, state: Memo => read);
 
-        // The result_type_description contains the stringified types
-        let desc = FetchAction::result_type_description();
-        assert!(desc.contains("Result"));
-        assert!(desc.contains("Vec"));
-        assert!(desc.contains("String"));
-        assert!(desc.contains("TestError"));
+        #[derive(Clone)]
+        struct SplitGaugeStore {
+            read: ReadSignal<GaugeState>,
+            write: WriteSignal<GaugeState>,
+        }
+        crate::impl_store!(SplitGaugeStore, GaugeState, read: ReadSignal);
 
-        // Check helper methods
-        assert!(FetchAction::output_type_name().contains("Vec"));
-        assert_eq!(FetchAction::error_type_name(), "TestError");
-    }
+        #[derive(Clone)]
+        struct Pair<T: Send + Sync + 'static>(ReadSignal<T>, WriteSignal<T>);
 
-    #[test]
-    fn test_define_async_action_simple() {
-        define_async_action! {
-            #[derive(Debug)]
-            ComputeAction {
-                input: i32,
-            } -> String
+        #[derive(Clone)]
+        struct PairGaugeStore {
+            pair: Pair<GaugeState>,
         }
+        crate::impl_store!(PairGaugeStore, GaugeState, |store| store.pair.0);
 
-        let action = ComputeAction::new(42);
-        assert_eq!(action.input, 42);
-    }
+        let state = ArcRwSignal::new(GaugeState::default());
+        let arc = ArcGaugeStore {
+            read: ReadSignal::from(state.read_only()),
+            state,
+        };
+        arc.state.update(|s| s.level = 3);
+        assert_eq!(arc.state().get_untracked().level, 3);
 
-    #[test]
-    fn test_store_macro_state_generation() {
-        store! {
-            pub TestStore {
-                state TestStoreState {
-                    value: i32 = 42,
-                    label: String = "test".to_string(),
-                }
+        let _ = any_spawner::Executor::init_futures_executor();
+        let owner = Owner::new();
+        let source = RwSignal::new(1u8);
+        let memo = owner.with(|| {
+            let state = Memo::new(move |_| GaugeState {
+                level: source.get() * 10,
+            });
+            MemoGaugeStore {
+                read: crate::store::memo_reader(state),
+                state,
             }
+        });
+        assert_eq!(memo.state().get_untracked().level, 10);
+        source.set(4);
+        assert_eq!(memo.state.get_untracked().level, 40);
+        // The reader catches up once the effect has run
+        let start = std::time::Instant::now();
+        while memo.state().get_untracked().level != 40 {
+            assert!(
+                start.elapsed() < std::time::Duration::from_secs(5),
+                "timed out"
+            );
+            std::thread::sleep(std::time::Duration::from_millis(1));
         }
 
-        let state = TestStoreState::default();
-        assert_eq!(state.value, 42);
-        assert_eq!(state.label, "test");
+        let (read, write) = signal(GaugeState::default());
+        let split = SplitGaugeStore { read, write };
+        split.write.update(|s| s.level = 5);
+        assert_eq!(split.state().get_untracked().level, 5);
 
-        let store = TestStore::new();
-        assert_eq!(store.state.get().value, 42);
+        let (read, write) = signal(GaugeState::default());
+        let pair = PairGaugeStore {
+            pair: Pair(read, write),
+        };
+        pair.pair.1.update(|s| s.level = 7);
+        assert_eq!(pair.state().get_untracked(), GaugeState { level: 7 });
     }
 
-    #[test]
-    fn test_store_macro_with_state() {
-        store! {
-            pub CustomStore {
-                state CustomState {
-                    count: i32 = 0,
-                }
-            }
+    #[cfg(feature = "hydrate")]
+    crate::define_hydratable_state! {
+        version = 2;
+        migrate(from, value) {
+            // v1 stored a single `count`
+            let count = value["count"].as_i64().unwrap_or_default() as i32;
+            Ok(TallyState { total: count, step: if from == 0 { 1 } else { 2 } })
         }
+        #[derive(Clone, Debug, PartialEq)]
+        pub struct TallyState {
+            total: i32,
+            step: i32 = 1,
+        }
+    }
 
-        let custom_state = CustomState { count: 100 };
-        let store = CustomStore::with_state(custom_state);
-        assert_eq!(store.state.get().count, 100);
+    #[cfg(feature = "hydrate")]
+    #[derive(Clone)]
+    struct TallyStore {
+        state: RwSignal<TallyState>,
+    }
+
+    #[cfg(feature = "hydrate")]
+    crate::impl_store!(TallyStore, TallyState, state);
+    #[cfg(feature = "hydrate")]
+    crate::impl_hydratable_store!(TallyStore, "tally", versioned);
+
+    #[test]
+    #[cfg(feature = "hydrate")]
+    fn test_versioned_hydratable_state() {
+        use crate::hydration::{HydratableStore, VersionedState};
+
+        assert_eq!(TallyState::VERSION, 2);
+
+        let store = TallyStore {
+            state: RwSignal::new(TallyState { total: 5, step: 3 }),
+        };
+        let data = store.serialize_state().unwrap();
+        let restored = TallyStore::from_hydrated_state(&data).unwrap();
+        assert_eq!(restored.state.get(), TallyState { total: 5, step: 3 });
+
+        let old =
+            TallyStore::from_hydrated_state(r#"{"__version":1,"state":{"count":7}}"#).unwrap();
+        assert_eq!(old.state.get(), TallyState { total: 7, step: 2 });
     }
 }
@@ -0,0 +1,868 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2026 web-mech
+
+//! Middleware wrapping action execution.
+//!
+//! A [`Middleware`] is called around every action dispatched through
+//! [`StoreActionExt`](crate::r#async::StoreActionExt) and
+//! [`StorePrefetchExt`](crate::prefetch::StorePrefetchExt): before it
+//! runs, after it succeeds, and when it fails. Use it for cross-cutting
+//! concerns such as logging, refreshing an auth token before requests,
+//! analytics, or translating errors into user-facing messages.
+//!
+//! Middleware is registered globally with [`register_middleware`], or for
+//! one store type with [`register_store_middleware`]. Middleware runs in
+//! registration order before the action and in reverse order after it, so
+//! the first registered middleware wraps the others.
+//!
+//! Actions run directly with `execute` bypass middleware, as do actions an
+//! [`AsyncActionBuilder`](crate::r#async::AsyncActionBuilder) executes.
+//!
+//! To only observe actions, such as for toasts, spinners, or analytics,
+//! subscribe to [`ActionEvent`]s with [`on_action_event`] or
+//! [`on_store_action_event`] instead. Listeners are told when each action
+//! starts, succeeds, or fails, after the middleware has run.
+//! To recover from the failures of one store instance, such as by calling
+//! its mutators, register a handler with
+//! [`StoreActionExt::on_action_error`](crate::r#async::StoreActionExt::on_action_error).
+//!
+//! # Example
+//!
+//! ```rust
+//! use leptos::prelude::*;
+//! use leptos_store::middleware::{ActionContext, Middleware, register_store_middleware};
+//! use leptos_store::prelude::{Action, *};
+//! use std::sync::{Arc, Mutex};
+//!
+//! #[derive(Clone, Debug, Default)]
+//! struct CounterState { count: i32 }
+//!
+//! #[derive(Clone)]
+//! struct CounterStore { state: RwSignal<CounterState> }
+//!
+//! impl Store for CounterStore {
+//!     type State = CounterState;
+//!     fn state(&self) -> ReadSignal<Self::State> { self.state.read_only() }
+//! }
+//!
+//! struct Increment;
+//!
+//! impl Action<CounterStore> for Increment {
+//!     type Output = ();
+//!     fn execute(&self, store: &CounterStore) {
+//!         store.state.update(|s| s.count += 1);
+//!     }
+//! }
+//!
+//! struct Logger(Arc<Mutex<Vec<String>>>);
+//!
+//! impl Middleware for Logger {
+//!     fn before(&self, cx: &ActionContext) {
+//!         self.0.lock().unwrap().push(format!("start {cx}"));
+//!     }
+//!
+//!     fn after(&self, cx: &ActionContext) {
+//!         self.0.lock().unwrap().push(format!("done {cx}"));
+//!     }
+//! }
+//!
+//! let log = Arc::new(Mutex::new(Vec::new()));
+//! let registration = register_store_middleware::<CounterStore>(Logger(Arc::clone(&log)));
+//!
+//! let store = CounterStore { state: RwSignal::new(CounterState::default()) };
+//! store.dispatch(Increment);
+//! drop(registration);
+//!
+//! assert_eq!(
+//!     *log.lock().unwrap(),
+//!     ["start CounterStore: Increment", "done CounterStore: Increment"]
+//! );
+//! ```
+
+use futures::future::BoxFuture;
+use leptos::prelude::{ArcReadSignal, on_cleanup};
+use std::any::{Any, TypeId};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::r#async::{ActionResult, now_ms};
+use crate::store::Store;
+
+/// The action a [`Middleware`] hook is called for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ActionContext {
+    store: &'static str,
+    action: &'static str,
+    is_async: bool,
+}
+
+impl ActionContext {
+    /// Create a context for the action `action` of `store`.
+    pub fn new(store: &'static str, action: &'static str, is_async: bool) -> Self {
+        Self {
+            store,
+            action,
+            is_async,
+        }
+    }
+
+    /// Create a context for action type `A` of store type `S`.
+    pub(crate) fn of<S: ?Sized, A: ?Sized>(is_async: bool) -> Self {
+        Self::new(short_type_name::<S>(), short_type_name::<A>(), is_async)
+    }
+
+    /// Returns the store type name.
+    pub fn store(&self) -> &'static str {
+        self.store
+    }
+
+    /// Returns the action type name.
+    pub fn action(&self) -> &'static str {
+        self.action
+    }
+
+    /// Check if the action is asynchronous.
+    pub fn is_async(&self) -> bool {
+        self.is_async
+    }
+}
+
+impl fmt::Display for ActionContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.store, self.action)
+    }
+}
+
+/// Returns the name of `T` without its module path.
+fn short_type_name<T: ?Sized>() -> &'static str {
+    let name = std::any::type_name::<T>();
+    let path = name.split('<').next().unwrap_or(name);
+    match path.rfind("::") {
+        Some(index) => &name[index + 2..],
+        None => name,
+    }
+}
+
+/// An error as seen by [`ActionFailure`].
+trait ErasedError: Error + 'static {
+    fn as_error(&self) -> &(dyn Error + 'static);
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<E: Error + 'static> ErasedError for E {
+    fn as_error(&self) -> &(dyn Error + 'static) {
+        self
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// The error of a failed action, passed to [`Middleware::on_error`].
+///
+/// The error can be inspected as a `dyn Error`, or downcast to its concrete
+/// type and modified in place to translate it.
+pub struct ActionFailure<'a> {
+    error: &'a mut dyn ErasedError,
+}
+
+impl<'a> ActionFailure<'a> {
+    /// Wrap the error of a failed action.
+    pub fn new<E: Error + 'static>(error: &'a mut E) -> Self {
+        Self { error }
+    }
+
+    /// Returns the error.
+    pub fn error(&self) -> &(dyn Error + 'static) {
+        self.error.as_error()
+    }
+
+    /// Returns the error if it is of type `E`.
+    pub fn downcast_ref<E: 'static>(&self) -> Option<&E> {
+        self.error.as_any().downcast_ref()
+    }
+
+    /// Returns the error mutably if it is of type `E`, to translate it.
+    pub fn downcast_mut<E: 'static>(&mut self) -> Option<&mut E> {
+        self.error.as_any_mut().downcast_mut()
+    }
+}
+
+impl fmt::Debug for ActionFailure<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ActionFailure").field(&self.error).finish()
+    }
+}
+
+impl fmt::Display for ActionFailure<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.error(), f)
+    }
+}
+
+/// Hooks called around action execution.
+///
+/// All hooks have empty default implementations, so a middleware only
+/// implements the ones it needs.
+///
+/// # Example
+///
+/// ```rust
+/// use leptos_store::middleware::{ActionContext, ActionFailure, Middleware};
+/// use leptos_store::prelude::*;
+///
+/// /// Replaces network errors with a message fit for the UI.
+/// struct FriendlyErrors;
+///
+/// impl Middleware for FriendlyErrors {
+///     fn on_error(&self, _cx: &ActionContext, failure: &mut ActionFailure<'_>) {
+///         if let Some(err @ ActionError::Network(_)) = failure.downcast_mut::<ActionError>() {
+///             *err = ActionError::network("You appear to be offline");
+///         }
+///     }
+/// }
+/// ```
+pub trait Middleware: Send + Sync + 'static {
+    /// Called before an action runs.
+    fn before(&self, cx: &ActionContext) {
+        let _ = cx;
+    }
+
+    /// Awaited before an async action runs, instead of
+    /// [`before`](Self::before).
+    ///
+    /// Override this for asynchronous preparation, such as refreshing an
+    /// expired auth token. Defaults to calling `before`.
+    fn before_async(&self, cx: &ActionContext) -> BoxFuture<'static, ()> {
+        self.before(cx);
+        Box::pin(std::future::ready(()))
+    }
+
+    /// Called after an action succeeded.
+    ///
+    /// Synchronous actions can't fail, so this is called after each of them.
+    fn after(&self, cx: &ActionContext) {
+        let _ = cx;
+    }
+
+    /// Called after an async action failed.
+    ///
+    /// Changes made to the error through `failure` are seen by the caller
+    /// and by the middleware registered before this one.
+    fn on_error(&self, cx: &ActionContext, failure: &mut ActionFailure<'_>) {
+        let _ = (cx, failure);
+    }
+}
+
+type Registered = (u64, Option<TypeId>, Arc<dyn Middleware>);
+
+static MIDDLEWARE: Mutex<Vec<Registered>> = Mutex::new(Vec::new());
+static MIDDLEWARE_COUNT: AtomicUsize = AtomicUsize::new(0);
+static NEXT_MIDDLEWARE_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Register a middleware called around the actions of every store.
+///
+/// The middleware stays registered until the returned registration is
+/// dropped; call [`MiddlewareRegistration::detach`] to keep it for the
+/// lifetime of the app. Like mutation listeners, middleware is global, so
+/// on the server it wraps the actions of every request.
+pub fn register_middleware(middleware: impl Middleware) -> MiddlewareRegistration {
+    register(None, Arc::new(middleware))
+}
+
+/// Register a middleware called around the actions of stores of type `S`.
+///
+/// See [`register_middleware`].
+pub fn register_store_middleware<S: Store>(middleware: impl Middleware) -> MiddlewareRegistration {
+    register(Some(TypeId::of::<S>()), Arc::new(middleware))
+}
+
+fn register(scope: Option<TypeId>, middleware: Arc<dyn Middleware>) -> MiddlewareRegistration {
+    let id = NEXT_MIDDLEWARE_ID.fetch_add(1, Ordering::Relaxed);
+    if let Ok(mut registered) = MIDDLEWARE.lock() {
+        registered.push((id, scope, middleware));
+        MIDDLEWARE_COUNT.store(registered.len(), Ordering::Release);
+    }
+    MiddlewareRegistration { id: Some(id) }
+}
+
+/// Returns the middleware applying to stores of type `S`, in registration
+/// order.
+fn chain<S: Store>() -> Vec<Arc<dyn Middleware>> {
+    if MIDDLEWARE_COUNT.load(Ordering::Acquire) == 0 {
+        return Vec::new();
+    }
+    let store = TypeId::of::<S>();
+    match MIDDLEWARE.lock() {
+        Ok(registered) => registered
+            .iter()
+            .filter(|(_, scope, _)| scope.is_none_or(|scope| scope == store))
+            .map(|(_, _, middleware)| Arc::clone(middleware))
+            .collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Run the synchronous action `A` of store `S` through the middleware chain.
+pub(crate) fn run_action<S: Store, A: ?Sized, T>(execute: impl FnOnce() -> T) -> T {
+    let chain = chain::<S>();
+    let listeners = action_listeners::<S>();
+    if chain.is_empty() && listeners.is_empty() {
+        return execute();
+    }
+    let cx = ActionContext::of::<S, A>(false);
+    for middleware in &chain {
+        middleware.before(&cx);
+    }
+    notify(&listeners, &ActionEvent::Started { cx: &cx });
+    let started_at = now_ms();
+    let output = execute();
+    for middleware in chain.iter().rev() {
+        middleware.after(&cx);
+    }
+    let elapsed_ms = now_ms().saturating_sub(started_at);
+    notify(
+        &listeners,
+        &ActionEvent::Succeeded {
+            cx: &cx,
+            elapsed_ms,
+        },
+    );
+    output
+}
+
+/// Run the async action `A` of `store` through the middleware chain.
+///
+/// `execute` is only called once the `before_async` hooks have finished.
+/// Failures are also passed to the store's recovery handlers.
+pub(crate) async fn run_async_action<S, A, O, E, F>(
+    store: &S,
+    execute: impl FnOnce() -> F,
+) -> ActionResult<O, E>
+where
+    S: Store,
+    A: ?Sized,
+    E: Error + 'static,
+    F: Future<Output = ActionResult<O, E>>,
+{
+    let chain = chain::<S>();
+    let listeners = action_listeners::<S>();
+    let handlers = recovery_handlers(store);
+    if chain.is_empty() && listeners.is_empty() && handlers.is_empty() {
+        return execute().await;
+    }
+    let cx = ActionContext::of::<S, A>(true);
+    for middleware in &chain {
+        middleware.before_async(&cx).await;
+    }
+    notify(&listeners, &ActionEvent::Started { cx: &cx });
+    let started_at = now_ms();
+    let mut result = execute().await;
+    match &mut result {
+        Ok(_) => {
+            for middleware in chain.iter().rev() {
+                middleware.after(&cx);
+            }
+        }
+        Err(err) => {
+            let mut failure = ActionFailure::new(err);
+            for middleware in chain.iter().rev() {
+                middleware.on_error(&cx, &mut failure);
+            }
+        }
+    }
+    if let Err(error) = &result {
+        for handler in &handlers {
+            handler(store, &cx, error);
+        }
+    }
+    let elapsed_ms = now_ms().saturating_sub(started_at);
+    let event = match &result {
+        Ok(_) => ActionEvent::Succeeded {
+            cx: &cx,
+            elapsed_ms,
+        },
+        Err(error) => ActionEvent::Failed {
+            cx: &cx,
+            elapsed_ms,
+            error,
+        },
+    };
+    notify(&listeners, &event);
+    result
+}
+
+/// A registered middleware, removed when dropped.
+#[must_use = "the middleware is removed when the registration is dropped"]
+#[derive(Debug)]
+pub struct MiddlewareRegistration {
+    id: Option<u64>,
+}
+
+impl MiddlewareRegistration {
+    /// Keep the middleware registered for the lifetime of the app.
+    pub fn detach(mut self) {
+        self.id = None;
+    }
+}
+
+impl Drop for MiddlewareRegistration {
+    fn drop(&mut self) {
+        let Some(id) = self.id else {
+            return;
+        };
+        if let Ok(mut registered) = MIDDLEWARE.lock() {
+            registered.retain(|(registered_id, _, _)| *registered_id != id);
+            MIDDLEWARE_COUNT.store(registered.len(), Ordering::Release);
+        }
+    }
+}
+
+/// A lifecycle event of an action, reported to [`on_action_event`]
+/// listeners.
+///
+/// Events are reported for the same actions [`Middleware`] wraps, after
+/// the middleware hooks have run, so a failure carries the error as the
+/// caller sees it.
+#[derive(Clone, Copy)]
+pub enum ActionEvent<'a> {
+    /// The action is about to run.
+    Started {
+        /// The action.
+        cx: &'a ActionContext,
+    },
+    /// The action succeeded.
+    Succeeded {
+        /// The action.
+        cx: &'a ActionContext,
+        /// How long the action ran, in milliseconds.
+        elapsed_ms: u64,
+    },
+    /// The action failed.
+    Failed {
+        /// The action.
+        cx: &'a ActionContext,
+        /// How long the action ran, in milliseconds.
+        elapsed_ms: u64,
+        /// The error returned to the caller.
+        error: &'a (dyn Error + 'static),
+    },
+}
+
+impl<'a> ActionEvent<'a> {
+    /// Returns the action the event is about.
+    pub fn context(&self) -> &'a ActionContext {
+        match self {
+            Self::Started { cx } | Self::Succeeded { cx, .. } | Self::Failed { cx, .. } => cx,
+        }
+    }
+
+    /// Returns how long the action ran, or `None` if it just started.
+    pub fn elapsed_ms(&self) -> Option<u64> {
+        match self {
+            Self::Started { .. } => None,
+            Self::Succeeded { elapsed_ms, .. } | Self::Failed { elapsed_ms, .. } => {
+                Some(*elapsed_ms)
+            }
+        }
+    }
+
+    /// Returns the error of a failed action.
+    pub fn error(&self) -> Option<&'a (dyn Error + 'static)> {
+        match self {
+            Self::Failed { error, .. } => Some(*error),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Debug for ActionEvent<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Started { cx } => f.debug_struct("Started").field("cx", cx).finish(),
+            Self::Succeeded { cx, elapsed_ms } => f
+                .debug_struct("Succeeded")
+                .field("cx", cx)
+                .field("elapsed_ms", elapsed_ms)
+                .finish(),
+            Self::Failed {
+                cx,
+                elapsed_ms,
+                error,
+            } => f
+                .debug_struct("Failed")
+                .field("cx", cx)
+                .field("elapsed_ms", elapsed_ms)
+                .field("error", &error.to_string())
+                .finish(),
+        }
+    }
+}
+
+impl fmt::Display for ActionEvent<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Started { cx } => write!(f, "{cx} started"),
+            Self::Succeeded { cx, .. } => write!(f, "{cx} succeeded"),
+            Self::Failed { cx, error, .. } => write!(f, "{cx} failed: {error}"),
+        }
+    }
+}
+
+type ActionListener = Arc<dyn Fn(&ActionEvent<'_>) + Send + Sync>;
+
+static ACTION_LISTENERS: Mutex<Vec<(u64, Option<TypeId>, ActionListener)>> = Mutex::new(Vec::new());
+static ACTION_LISTENER_COUNT: AtomicUsize = AtomicUsize::new(0);
+static NEXT_ACTION_LISTENER_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Register a listener called when the actions of any store start,
+/// succeed, or fail.
+///
+/// The listener stays registered until the returned subscription is
+/// dropped; call [`ActionSubscription::detach`] to keep it for the
+/// lifetime of the app. Like middleware, listeners are global, so on the
+/// server they see the actions of every request.
+///
+/// ```rust
+/// use leptos_store::middleware::{ActionEvent, on_action_event};
+///
+/// let subscription = on_action_event(|event| {
+///     if let ActionEvent::Failed { cx, error, .. } = event {
+///         eprintln!("{} failed: {error}", cx.action());
+///     }
+/// });
+/// # drop(subscription);
+/// ```
+pub fn on_action_event(
+    listener: impl Fn(&ActionEvent<'_>) + Send + Sync + 'static,
+) -> ActionSubscription {
+    subscribe(None, Arc::new(listener))
+}
+
+/// Register a listener called when the actions of stores of type `S`
+/// start, succeed, or fail.
+///
+/// See [`on_action_event`].
+pub fn on_store_action_event<S: Store>(
+    listener: impl Fn(&ActionEvent<'_>) + Send + Sync + 'static,
+) -> ActionSubscription {
+    subscribe(Some(TypeId::of::<S>()), Arc::new(listener))
+}
+
+fn subscribe(scope: Option<TypeId>, listener: ActionListener) -> ActionSubscription {
+    let id = NEXT_ACTION_LISTENER_ID.fetch_add(1, Ordering::Relaxed);
+    if let Ok(mut listeners) = ACTION_LISTENERS.lock() {
+        listeners.push((id, scope, listener));
+        ACTION_LISTENER_COUNT.store(listeners.len(), Ordering::Release);
+    }
+    ActionSubscription { id: Some(id) }
+}
+
+/// Returns the action listeners for stores of type `S`, in registration
+/// order.
+fn action_listeners<S: Store>() -> Vec<ActionListener> {
+    if ACTION_LISTENER_COUNT.load(Ordering::Acquire) == 0 {
+        return Vec::new();
+    }
+    let store = TypeId::of::<S>();
+    match ACTION_LISTENERS.lock() {
+        Ok(listeners) => listeners
+            .iter()
+            .filter(|(_, scope, _)| scope.is_none_or(|scope| scope == store))
+            .map(|(_, _, listener)| Arc::clone(listener))
+            .collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+fn notify(listeners: &[ActionListener], event: &ActionEvent<'_>) {
+    for listener in listeners {
+        listener(event);
+    }
+}
+
+/// A registered action event listener, removed when dropped.
+#[must_use = "the listener is removed when the subscription is dropped"]
+#[derive(Debug)]
+pub struct ActionSubscription {
+    id: Option<u64>,
+}
+
+impl ActionSubscription {
+    /// Keep the listener registered for the lifetime of the app.
+    pub fn detach(mut self) {
+        self.id = None;
+    }
+}
+
+impl Drop for ActionSubscription {
+    fn drop(&mut self) {
+        let Some(id) = self.id else {
+            return;
+        };
+        if let Ok(mut listeners) = ACTION_LISTENERS.lock() {
+            listeners.retain(|(listener_id, _, _)| *listener_id != id);
+            ACTION_LISTENER_COUNT.store(listeners.len(), Ordering::Release);
+        }
+    }
+}
+
+/// Called with the store when one of its async actions failed.
+type RecoveryHandler<S> = Arc<dyn Fn(&S, &ActionContext, &(dyn Error + 'static)) + Send + Sync>;
+
+/// Recovery handlers of the stores of one type, by store instance.
+type RecoveryHandlers<S> = Vec<(u64, ArcReadSignal<<S as Store>::State>, RecoveryHandler<S>)>;
+
+static RECOVERY_HANDLERS: Mutex<BTreeMap<TypeId, Box<dyn Any + Send>>> =
+    Mutex::new(BTreeMap::new());
+static RECOVERY_HANDLER_COUNT: AtomicUsize = AtomicUsize::new(0);
+static NEXT_RECOVERY_HANDLER_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Register `handler` for the failed async actions of `store`, until the
+/// current reactive owner is disposed.
+///
+/// See [`StoreActionExt::on_action_error`](crate::r#async::StoreActionExt::on_action_error).
+pub(crate) fn register_recovery_handler<S: Store>(store: &S, handler: RecoveryHandler<S>) {
+    let id = NEXT_RECOVERY_HANDLER_ID.fetch_add(1, Ordering::Relaxed);
+    let state = ArcReadSignal::from(store.state());
+    if let Ok(mut registry) = RECOVERY_HANDLERS.lock()
+        && let Some(handlers) = registry
+            .entry(TypeId::of::<S>())
+            .or_insert_with(|| Box::new(RecoveryHandlers::<S>::new()))
+            .downcast_mut::<RecoveryHandlers<S>>()
+    {
+        handlers.push((id, state, handler));
+        RECOVERY_HANDLER_COUNT.fetch_add(1, Ordering::Release);
+    }
+    on_cleanup(move || {
+        if let Ok(mut registry) = RECOVERY_HANDLERS.lock()
+            && let Some(handlers) = registry
+                .get_mut(&TypeId::of::<S>())
+                .and_then(|handlers| handlers.downcast_mut::<RecoveryHandlers<S>>())
+        {
+            let before = handlers.len();
+            handlers.retain(|(handler_id, _, _)| *handler_id != id);
+            RECOVERY_HANDLER_COUNT.fetch_sub(before - handlers.len(), Ordering::Release);
+        }
+    });
+}
+
+/// Returns the recovery handlers of `store`, in registration order.
+fn recovery_handlers<S: Store>(store: &S) -> Vec<RecoveryHandler<S>> {
+    if RECOVERY_HANDLER_COUNT.load(Ordering::Acquire) == 0 {
+        return Vec::new();
+    }
+    let state = ArcReadSignal::from(store.state());
+    match RECOVERY_HANDLERS.lock() {
+        Ok(registry) => registry
+            .get(&TypeId::of::<S>())
+            .and_then(|handlers| handlers.downcast_ref::<RecoveryHandlers<S>>())
+            .map(|handlers| {
+                handlers
+                    .iter()
+                    .filter(|(_, handler_state, _)| *handler_state == state)
+                    .map(|(_, _, handler)| Arc::clone(handler))
+                    .collect()
+            })
+            .unwrap_or_default(),
+        Err(_) => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::r#async::{Action, ActionError, AsyncAction, StoreActionExt};
+    use leptos::prelude::*;
+
+    #[derive(Clone, Debug, Default)]
+    struct ChainState;
+
+    #[derive(Clone)]
+    struct ChainStore {
+        state: RwSignal<ChainState>,
+    }
+
+    impl Store for ChainStore {
+        type State = ChainState;
+
+        fn state(&self) -> ReadSignal<Self::State> {
+            self.state.read_only()
+        }
+    }
+
+    struct Fetch {
+        fail: bool,
+    }
+
+    impl AsyncAction<ChainStore> for Fetch {
+        type Output = u32;
+        type Error = ActionError;
+
+        async fn execute(&self, _store: &ChainStore) -> ActionResult<u32> {
+            if self.fail {
+                Err(ActionError::network("503"))
+            } else {
+                Ok(7)
+            }
+        }
+    }
+
+    struct Noop;
+
+    impl Action<ChainStore> for Noop {
+        type Output = ();
+
+        fn execute(&self, _store: &ChainStore) {}
+    }
+
+    struct Recorder {
+        name: &'static str,
+        log: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl Middleware for Recorder {
+        fn before(&self, cx: &ActionContext) {
+            let mut log = self.log.lock().unwrap();
+            log.push(format!("{} before {cx}", self.name));
+        }
+
+        fn after(&self, _cx: &ActionContext) {
+            self.log
+                .lock()
+                .unwrap()
+                .push(format!("{} after", self.name));
+        }
+
+        fn on_error(&self, _cx: &ActionContext, failure: &mut ActionFailure<'_>) {
+            let mut log = self.log.lock().unwrap();
+            log.push(format!("{} on_error {failure}", self.name));
+            if let Some(err) = failure.downcast_mut::<ActionError>() {
+                *err = ActionError::failed(format!("{} saw {err}", self.name));
+            }
+        }
+    }
+
+    #[test]
+    fn test_short_type_name() {
+        assert_eq!(short_type_name::<ChainStore>(), "ChainStore");
+        assert_eq!(short_type_name::<u32>(), "u32");
+        assert_eq!(
+            short_type_name::<Vec<Option<u8>>>(),
+            "Vec<core::option::Option<u8>>"
+        );
+    }
+
+    #[test]
+    fn test_middleware_chain() {
+        let owner = Owner::new();
+        owner.with(|| {
+            let log = Arc::new(Mutex::new(Vec::new()));
+            let outer = register_store_middleware::<ChainStore>(Recorder {
+                name: "outer",
+                log: Arc::clone(&log),
+            });
+            let inner = register_store_middleware::<ChainStore>(Recorder {
+                name: "inner",
+                log: Arc::clone(&log),
+            });
+            let store = ChainStore {
+                state: RwSignal::new(ChainState),
+            };
+
+            let ok = futures::executor::block_on(run_async_action::<ChainStore, Fetch, _, _, _>(
+                &store,
+                || Fetch { fail: false }.execute(&store),
+            ));
+            assert_eq!(ok, Ok(7));
+            assert_eq!(
+                *log.lock().unwrap(),
+                [
+                    "outer before ChainStore: Fetch",
+                    "inner before ChainStore: Fetch",
+                    "inner after",
+                    "outer after",
+                ]
+            );
+
+            log.lock().unwrap().clear();
+            let action = Fetch { fail: true };
+            let err = futures::executor::block_on(run_async_action::<ChainStore, Fetch, _, _, _>(
+                &store,
+                || action.execute(&store),
+            ));
+            // Each middleware sees the error translated by the inner ones.
+            assert_eq!(
+                err,
+                Err(ActionError::failed(
+                    "outer saw Action failed: inner saw Network error: 503"
+                ))
+            );
+            assert_eq!(
+                log.lock().unwrap()[3],
+                "outer on_error Action failed: inner saw Network error: 503"
+            );
+
+            drop(inner);
+            log.lock().unwrap().clear();
+            store.dispatch(Noop);
+            drop(outer);
+            store.dispatch(Noop);
+            assert_eq!(
+                *log.lock().unwrap(),
+                ["outer before ChainStore: Noop", "outer after"]
+            );
+        });
+    }
+
+    #[test]
+    fn test_action_events() {
+        let owner = Owner::new();
+        owner.with(|| {
+            let log = Arc::new(Mutex::new(Vec::new()));
+            let subscription = on_store_action_event::<ChainStore>({
+                let log = Arc::clone(&log);
+                move |event| log.lock().unwrap().push(event.to_string())
+            });
+            let store = ChainStore {
+                state: RwSignal::new(ChainState),
+            };
+
+            store.dispatch(Noop);
+            let ok = futures::executor::block_on(run_async_action::<ChainStore, Fetch, _, _, _>(
+                &store,
+                || Fetch { fail: false }.execute(&store),
+            ));
+            assert_eq!(ok, Ok(7));
+            let action = Fetch { fail: true };
+            let _ = futures::executor::block_on(run_async_action::<ChainStore, Fetch, _, _, _>(
+                &store,
+                || action.execute(&store),
+            ));
+            drop(subscription);
+            store.dispatch(Noop);
+
+            assert_eq!(
+                *log.lock().unwrap(),
+                [
+                    "ChainStore: Noop started",
+                    "ChainStore: Noop succeeded",
+                    "ChainStore: Fetch started",
+                    "ChainStore: Fetch succeeded",
+                    "ChainStore: Fetch started",
+                    "ChainStore: Fetch failed: Network error: 503",
+                ]
+            );
+        });
+    }
+}
@@ -0,0 +1,219 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2026 web-mech
+
+//! Test doubles for stores.
+//!
+//! The [`mock_store!`](crate::mock_store!) macro generates a stub store with
+//! the same state type and method names as a real one, but without its
+//! business logic. Every stub call is recorded by a [`MockRecorder`], so
+//! component tests can assert which methods were invoked and with what
+//! arguments, and the state can be scripted as a sequence of snapshots
+//! applied one per call.
+//!
+//! Arguments are recorded as [`PayloadSummary`] values, so they don't need
+//! to implement `Debug` or `Clone`.
+//!
+//! # Example
+//!
+//! ```rust
+//! use leptos::prelude::*;
+//! use leptos_store::mock_store;
+//! use leptos_store::mutation::PayloadSummary;
+//!
+//! #[derive(Clone, Debug, Default, PartialEq)]
+//! pub struct CartState {
+//!     pub items: Vec<String>,
+//! }
+//!
+//! mock_store! {
+//!     pub MockCartStore for CartState {
+//!         fn add_item(item: String);
+//!         fn checkout() -> Result<(), String> = Ok(());
+//!     }
+//! }
+//!
+//! let store = MockCartStore::new();
+//! store.script([CartState { items: vec!["apple".to_string()] }]);
+//!
+//! store.add_item("apple".to_string());
+//! assert_eq!(store.state.get_untracked().items, ["apple"]);
+//!
+//! let calls = store.mock().calls();
+//! assert_eq!(calls[0].name(), "add_item");
+//! assert_eq!(calls[0].args(), [PayloadSummary::text("apple")]);
+//! assert!(store.checkout().is_ok());
+//! assert_eq!(store.mock().call_count("checkout"), 1);
+//! ```
+
+use crate::mutation::PayloadSummary;
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+/// A recorded call to a mock store method.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MockCall {
+    name: &'static str,
+    args: Vec<PayloadSummary>,
+}
+
+impl MockCall {
+    /// Create a call record.
+    pub fn new(name: &'static str, args: Vec<PayloadSummary>) -> Self {
+        Self { name, args }
+    }
+
+    /// Returns the method name.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns the summarized arguments, in declaration order.
+    pub fn args(&self) -> &[PayloadSummary] {
+        &self.args
+    }
+}
+
+impl fmt::Display for MockCall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}(", self.name)?;
+        for (i, arg) in self.args.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{arg:?}")?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// Records the calls of a mock store and holds its scripted states.
+///
+/// Clones share the same records, like clones of the store.
+pub struct MockRecorder<S> {
+    calls: Arc<Mutex<Vec<MockCall>>>,
+    script: Arc<Mutex<VecDeque<S>>>,
+}
+
+impl<S> MockRecorder<S> {
+    /// Create a recorder with no calls and an empty script.
+    pub fn new() -> Self {
+        Self {
+            calls: Arc::new(Mutex::new(Vec::new())),
+            script: Arc::new(Mutex::new(VecDeque::new())),
+        }
+    }
+
+    /// Record a call.
+    pub fn record(&self, call: MockCall) {
+        if let Ok(mut calls) = self.calls.lock() {
+            calls.push(call);
+        }
+    }
+
+    /// Returns all recorded calls, oldest first.
+    pub fn calls(&self) -> Vec<MockCall> {
+        self.calls
+            .lock()
+            .map(|calls| calls.clone())
+            .unwrap_or_default()
+    }
+
+    /// Returns the names of the recorded calls, oldest first.
+    pub fn call_names(&self) -> Vec<&'static str> {
+        self.calls
+            .lock()
+            .map(|calls| calls.iter().map(MockCall::name).collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns how many times the method `name` was called.
+    pub fn call_count(&self, name: &str) -> usize {
+        self.calls
+            .lock()
+            .map(|calls| calls.iter().filter(|call| call.name == name).count())
+            .unwrap_or_default()
+    }
+
+    /// Check if the method `name` was called at least once.
+    pub fn was_called(&self, name: &str) -> bool {
+        self.call_count(name) > 0
+    }
+
+    /// Forget all recorded calls.
+    pub fn clear_calls(&self) {
+        if let Ok(mut calls) = self.calls.lock() {
+            calls.clear();
+        }
+    }
+
+    /// Queue states to be applied, one per call.
+    pub fn push_states(&self, states: impl IntoIterator<Item = S>) {
+        if let Ok(mut script) = self.script.lock() {
+            script.extend(states);
+        }
+    }
+
+    /// Take the next scripted state.
+    pub fn next_state(&self) -> Option<S> {
+        self.script.lock().ok()?.pop_front()
+    }
+
+    /// Returns the number of scripted states not applied yet.
+    pub fn remaining_states(&self) -> usize {
+        self.script
+            .lock()
+            .map(|script| script.len())
+            .unwrap_or_default()
+    }
+}
+
+impl<S> Clone for MockRecorder<S> {
+    fn clone(&self) -> Self {
+        Self {
+            calls: Arc::clone(&self.calls),
+            script: Arc::clone(&self.script),
+        }
+    }
+}
+
+impl<S> Default for MockRecorder<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> fmt::Debug for MockRecorder<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MockRecorder")
+            .field("calls", &self.call_names())
+            .field("remaining_states", &self.remaining_states())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recorder() {
+        let recorder = MockRecorder::new();
+        let shared = recorder.clone();
+        shared.record(MockCall::new("add", vec![PayloadSummary::Int(2)]));
+        shared.record(MockCall::new("reset", Vec::new()));
+        shared.record(MockCall::new("add", vec![PayloadSummary::Int(3)]));
+
+        assert_eq!(recorder.call_names(), ["add", "reset", "add"]);
+        assert_eq!(recorder.call_count("add"), 2);
+        assert!(!recorder.was_called("remove"));
+        assert_eq!(recorder.calls()[2].to_string(), "add(3)");
+
+        recorder.push_states([1, 2]);
+        assert_eq!(recorder.next_state(), Some(1));
+        assert_eq!(shared.remaining_states(), 1);
+
+        recorder.clear_calls();
+        assert!(shared.calls().is_empty());
+    }
+}
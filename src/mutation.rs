@@ -0,0 +1,410 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2026 web-mech
+
+//! Mutation logging for devtools and diagnostics.
+//!
+//! Stores defined with [`store!`](crate::store!) can name a mutation enum
+//! with `mutators as CounterMutation { ... }`. The macro then generates an
+//! enum with one variant per mutator, and every mutator call is reported to
+//! the listeners registered with [`on_mutation`] as a [`MutationEvent`]
+//! carrying that enum. Loggers and devtools can then show
+//! `SetTokens { tokens: len(10) }` instead of an anonymous state update.
+//!
+//! Mutator parameters are recorded as [`PayloadSummary`] values rather than
+//! clones, so logging a mutator that receives a large collection only
+//! records its length. Implement [`MutationPayload`] to control how your own
+//! types are summarized; other types are shown by type name. So are generic
+//! and `impl Trait` parameters without a `MutationPayload` bound, since the
+//! summary is chosen before they are known.
+//!
+//! Listeners are global, and only called while at least one is registered,
+//! so stores pay nothing for logging unless something is listening.
+//!
+//! # Example
+//!
+//! ```rust
+//! use leptos_store::mutation::on_mutation;
+//! use leptos_store::store;
+//! use std::sync::{Arc, Mutex};
+//!
+//! store! {
+//!     pub CounterStore {
+//!         state CounterState {
+//!             count: i32,
+//!         }
+//!
+//!         mutators as CounterMutation {
+//!             set_count(this, value: i32) {
+//!                 this.mutate(|s| s.count = value);
+//!             }
+//!         }
+//!     }
+//! }
+//!
+//! let log = Arc::new(Mutex::new(Vec::new()));
+//! let subscription = on_mutation({
+//!     let log = Arc::clone(&log);
+//!     move |event| log.lock().unwrap().push(event.to_string())
+//! });
+//!
+//! CounterStore::new().set_count(10);
+//! drop(subscription);
+//!
+//! assert_eq!(*log.lock().unwrap(), ["CounterStore: SetCount { value: 10 }"]);
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Maximum number of characters kept by [`PayloadSummary::Text`].
+pub const MAX_TEXT_SUMMARY_LEN: usize = 32;
+
+/// A compact, loggable summary of a mutator parameter.
+#[derive(Clone, PartialEq)]
+pub enum PayloadSummary {
+    /// A boolean.
+    Bool(bool),
+    /// A character.
+    Char(char),
+    /// A signed integer.
+    Int(i128),
+    /// An unsigned integer.
+    UInt(u128),
+    /// A floating point number.
+    Float(f64),
+    /// Text, truncated to [`MAX_TEXT_SUMMARY_LEN`] characters.
+    Text(String),
+    /// A collection, summarized by its length.
+    Len(usize),
+    /// An optional value.
+    Optional(Option<Box<PayloadSummary>>),
+    /// A value without a summary, shown by its type name.
+    Opaque(&'static str),
+}
+
+impl PayloadSummary {
+    /// Summarize text, truncating it to [`MAX_TEXT_SUMMARY_LEN`] characters.
+    pub fn text(text: &str) -> Self {
+        match text.char_indices().nth(MAX_TEXT_SUMMARY_LEN) {
+            Some((end, _)) => Self::Text(format!("{}…", &text[..end])),
+            None => Self::Text(text.to_string()),
+        }
+    }
+}
+
+impl fmt::Debug for PayloadSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bool(value) => write!(f, "{value}"),
+            Self::Char(value) => write!(f, "{value:?}"),
+            Self::Int(value) => write!(f, "{value}"),
+            Self::UInt(value) => write!(f, "{value}"),
+            Self::Float(value) => write!(f, "{value}"),
+            Self::Text(value) => write!(f, "{value:?}"),
+            Self::Len(len) => write!(f, "len({len})"),
+            Self::Optional(Some(value)) => write!(f, "Some({value:?})"),
+            Self::Optional(None) => write!(f, "None"),
+            Self::Opaque(type_name) => write!(f, "<{type_name}>"),
+        }
+    }
+}
+
+/// Types that can be summarized in a [`MutationEvent`].
+///
+/// Implemented for primitives, strings, `Option`, and the standard
+/// collections. Implement it for your own types to replace the default
+/// type-name summary.
+pub trait MutationPayload {
+    /// Returns the summary of this value.
+    fn summary(&self) -> PayloadSummary;
+}
+
+macro_rules! impl_payload {
+    ($variant:ident as $repr:ty: $($ty:ty),*) => {
+        $(
+            impl MutationPayload for $ty {
+                fn summary(&self) -> PayloadSummary {
+                    PayloadSummary::$variant(*self as $repr)
+                }
+            }
+        )*
+    };
+}
+
+impl_payload!(Int as i128: i8, i16, i32, i64, i128, isize);
+impl_payload!(UInt as u128: u8, u16, u32, u64, u128, usize);
+impl_payload!(Float as f64: f32, f64);
+
+impl MutationPayload for bool {
+    fn summary(&self) -> PayloadSummary {
+        PayloadSummary::Bool(*self)
+    }
+}
+
+impl MutationPayload for char {
+    fn summary(&self) -> PayloadSummary {
+        PayloadSummary::Char(*self)
+    }
+}
+
+impl MutationPayload for str {
+    fn summary(&self) -> PayloadSummary {
+        PayloadSummary::text(self)
+    }
+}
+
+impl MutationPayload for String {
+    fn summary(&self) -> PayloadSummary {
+        PayloadSummary::text(self)
+    }
+}
+
+impl<T: MutationPayload + ?Sized> MutationPayload for &T {
+    fn summary(&self) -> PayloadSummary {
+        (**self).summary()
+    }
+}
+
+impl<T: MutationPayload> MutationPayload for Option<T> {
+    fn summary(&self) -> PayloadSummary {
+        PayloadSummary::Optional(self.as_ref().map(|value| Box::new(value.summary())))
+    }
+}
+
+macro_rules! impl_len_payload {
+    ($([$($params:tt)*] $ty:ty),* $(,)?) => {
+        $(
+            impl<$($params)*> MutationPayload for $ty {
+                fn summary(&self) -> PayloadSummary {
+                    PayloadSummary::Len(self.len())
+                }
+            }
+        )*
+    };
+}
+
+impl_len_payload!(
+    [T] [T],
+    [T] Vec<T>,
+    [T] VecDeque<T>,
+    [T, const N: usize] [T; N],
+    [K, V, S] HashMap<K, V, S>,
+    [T, S] HashSet<T, S>,
+    [K, V] BTreeMap<K, V>,
+    [T] BTreeSet<T>,
+);
+
+/// Summarizes values with a [`MutationPayload`] implementation, falling
+/// back to the type name. Used by the code generated by
+/// [`store!`](crate::store!).
+#[doc(hidden)]
+pub struct PayloadProbe<'a, T: ?Sized>(pub &'a T);
+
+#[doc(hidden)]
+pub trait SummarizeKnown {
+    fn summarize(&self) -> PayloadSummary;
+}
+
+impl<T: MutationPayload + ?Sized> SummarizeKnown for PayloadProbe<'_, T> {
+    fn summarize(&self) -> PayloadSummary {
+        self.0.summary()
+    }
+}
+
+#[doc(hidden)]
+pub trait SummarizeOpaque {
+    fn summarize(&self) -> PayloadSummary;
+}
+
+impl<T: ?Sized> SummarizeOpaque for &PayloadProbe<'_, T> {
+    fn summarize(&self) -> PayloadSummary {
+        PayloadSummary::Opaque(std::any::type_name::<T>())
+    }
+}
+
+/// Summarize a value, using its [`MutationPayload`] implementation if it
+/// has one and its type name otherwise.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __summarize_payload {
+    ($value:expr) => {{
+        #[allow(unused_imports)]
+        use $crate::mutation::{SummarizeKnown as _, SummarizeOpaque as _};
+        (&$crate::mutation::PayloadProbe(&$value)).summarize()
+    }};
+}
+
+/// A mutator call reported to [`on_mutation`] listeners.
+#[derive(Clone, Copy)]
+pub struct MutationEvent<'a> {
+    store: &'static str,
+    name: &'static str,
+    mutation: &'a dyn fmt::Debug,
+}
+
+impl<'a> MutationEvent<'a> {
+    /// Create an event for the mutator `name` of `store`.
+    pub fn new(store: &'static str, name: &'static str, mutation: &'a dyn fmt::Debug) -> Self {
+        Self {
+            store,
+            name,
+            mutation,
+        }
+    }
+
+    /// Returns the store type name.
+    pub fn store(&self) -> &'static str {
+        self.store
+    }
+
+    /// Returns the mutator name.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns the mutation, usually a generated mutation enum value.
+    pub fn mutation(&self) -> &'a dyn fmt::Debug {
+        self.mutation
+    }
+}
+
+impl fmt::Debug for MutationEvent<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MutationEvent")
+            .field("store", &self.store)
+            .field("mutation", self.mutation)
+            .finish()
+    }
+}
+
+impl fmt::Display for MutationEvent<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {:?}", self.store, self.mutation)
+    }
+}
+
+type Listener = Arc<dyn Fn(&MutationEvent<'_>) + Send + Sync>;
+
+static LISTENERS: Mutex<Vec<(u64, Listener)>> = Mutex::new(Vec::new());
+static LISTENER_COUNT: AtomicUsize = AtomicUsize::new(0);
+static NEXT_LISTENER_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Register a listener called for every reported mutation.
+///
+/// The listener stays registered until the returned subscription is
+/// dropped; call [`MutationSubscription::detach`] to keep it for the
+/// lifetime of the app. Listeners are global, so on the server they see
+/// the mutations of every request.
+pub fn on_mutation(
+    listener: impl Fn(&MutationEvent<'_>) + Send + Sync + 'static,
+) -> MutationSubscription {
+    let id = NEXT_LISTENER_ID.fetch_add(1, Ordering::Relaxed);
+    if let Ok(mut listeners) = LISTENERS.lock() {
+        listeners.push((id, Arc::new(listener)));
+        LISTENER_COUNT.store(listeners.len(), Ordering::Release);
+    }
+    MutationSubscription { id: Some(id) }
+}
+
+/// Check if any mutation listener is registered.
+///
+/// Stores check this before building a mutation value, so reporting costs
+/// nothing while nobody listens.
+pub fn is_mutation_logging_enabled() -> bool {
+    LISTENER_COUNT.load(Ordering::Acquire) > 0
+}
+
+/// Report a mutation to all registered listeners.
+///
+/// Called by stores generated with a mutation enum; hand-written stores can
+/// call it from their mutators as well.
+pub fn notify_mutation(event: &MutationEvent<'_>) {
+    let listeners: Vec<Listener> = match LISTENERS.lock() {
+        Ok(listeners) => listeners.iter().map(|(_, l)| Arc::clone(l)).collect(),
+        Err(_) => return,
+    };
+    for listener in listeners {
+        listener(event);
+    }
+}
+
+/// A registered mutation listener, removed when dropped.
+#[must_use = "the listener is removed when the subscription is dropped"]
+#[derive(Debug)]
+pub struct MutationSubscription {
+    id: Option<u64>,
+}
+
+impl MutationSubscription {
+    /// Keep the listener registered for the lifetime of the app.
+    pub fn detach(mut self) {
+        self.id = None;
+    }
+}
+
+impl Drop for MutationSubscription {
+    fn drop(&mut self) {
+        let Some(id) = self.id else {
+            return;
+        };
+        if let Ok(mut listeners) = LISTENERS.lock() {
+            listeners.retain(|(listener_id, _)| *listener_id != id);
+            LISTENER_COUNT.store(listeners.len(), Ordering::Release);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Token;
+
+    #[test]
+    fn test_payload_summaries() {
+        let tokens = vec![Token, Token, Token];
+        assert_eq!(crate::__summarize_payload!(tokens), PayloadSummary::Len(3));
+        assert_eq!(crate::__summarize_payload!(7u8), PayloadSummary::UInt(7));
+        assert_eq!(crate::__summarize_payload!(-2i64), PayloadSummary::Int(-2));
+        assert_eq!(
+            crate::__summarize_payload!("hi"),
+            PayloadSummary::text("hi")
+        );
+        assert_eq!(
+            format!("{:?}", crate::__summarize_payload!(Some(Token))),
+            format!("<{}>", std::any::type_name::<Option<Token>>())
+        );
+        assert_eq!(
+            format!("{:?}", crate::__summarize_payload!(Some(1.5))),
+            "Some(1.5)"
+        );
+
+        let long = "x".repeat(40);
+        assert_eq!(
+            format!("{:?}", PayloadSummary::text(&long)),
+            format!("\"{}…\"", "x".repeat(MAX_TEXT_SUMMARY_LEN))
+        );
+    }
+
+    #[test]
+    fn test_subscription_lifetime() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let subscription = on_mutation({
+            let seen = Arc::clone(&seen);
+            move |event| {
+                if event.store() == "LifetimeStore" {
+                    seen.lock().unwrap().push(event.to_string());
+                }
+            }
+        });
+        assert!(is_mutation_logging_enabled());
+
+        notify_mutation(&MutationEvent::new("LifetimeStore", "reset", &"reset"));
+        drop(subscription);
+        notify_mutation(&MutationEvent::new("LifetimeStore", "reset", &"reset"));
+
+        assert_eq!(*seen.lock().unwrap(), ["LifetimeStore: \"reset\""]);
+    }
+}
@@ -0,0 +1,1614 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2026 web-mech
+
+//! Persistence support for stores.
+//!
+//! This module provides a small storage abstraction for saving store state
+//! across page loads, with graceful handling of browser storage failures.
+//!
+//! # Overview
+//!
+//! Browser storage is unreliable in practice:
+//!
+//! - `localStorage` can be full (`QuotaExceededError`)
+//! - It can be blocked entirely (Safari private mode, disabled cookies,
+//!   sandboxed iframes throw `SecurityError`)
+//! - It does not exist at all on the server
+//!
+//! [`PersistentStorage`] wraps a [`StorageBackend`] and never lets these
+//! failures crash the app:
+//!
+//! 1. **Error classification** - raw storage errors are mapped to
+//!    [`PersistenceError`] variants
+//! 2. **LRU eviction** - on quota errors, the least-recently-used persisted
+//!    entries are evicted and the write is retried
+//! 3. **Memory fallback** - if the backend is unavailable, or eviction cannot
+//!    free enough space, writes go to an in-memory backend instead
+//! 4. **Health reporting** - the current [`StorageHealth`] is exposed as a
+//!    reactive signal so the UI can warn users that data won't survive a reload
+//!
+//! # Example
+//!
+//! ```rust
+//! use leptos::prelude::*;
+//! use leptos_store::persistence::{MemoryStorage, PersistentStorage, StorageHealth};
+//!
+//! let storage = PersistentStorage::new(MemoryStorage::new());
+//!
+//! storage.save("settings", &vec![1, 2, 3]).unwrap();
+//! let restored: Option<Vec<i32>> = storage.load("settings").unwrap();
+//!
+//! assert_eq!(restored, Some(vec![1, 2, 3]));
+//! assert_eq!(storage.health().get_untracked(), StorageHealth::Healthy);
+//! ```
+//!
+//! # Crash Recovery
+//!
+//! [`PersistedHistory`] keeps a checksummed snapshot plus a bounded journal
+//! of the last N mutations, so that after a crash or out-of-memory kill the
+//! app can restore the latest consistent snapshot and optionally re-apply
+//! the mutations recorded since. Torn or corrupted journal entries are
+//! detected and dropped on load.
+//!
+//! # Store Integration
+//!
+//! [`StatePersister`] saves a whole store state under one key, restoring it
+//! on startup and saving it (optionally debounced) after every change. The
+//! `persist` section of the [`store!`](crate::store!) macro generates this
+//! wiring.
+
+use leptos::prelude::*;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use thiserror::Error;
+
+/// Key under which the LRU access order of persisted entries is stored.
+pub const LRU_INDEX_KEY: &str = "__leptos_store_lru__";
+
+/// Errors that can occur when persisting store state.
+#[derive(Debug, Error, Clone, PartialEq)]
+pub enum PersistenceError {
+    /// The storage quota was exceeded.
+    #[error("Storage quota exceeded: {0}")]
+    QuotaExceeded(String),
+
+    /// Storage is unavailable (blocked, disabled, or not present).
+    #[error("Storage unavailable: {0}")]
+    Unavailable(String),
+
+    /// Failed to serialize state.
+    #[error("Serialization error: {0}")]
+    Serialization(String),
+
+    /// Failed to deserialize persisted state.
+    #[error("Deserialization error: {0}")]
+    Deserialization(String),
+
+    /// Persisted data failed an integrity check.
+    #[error("Corrupted data: {0}")]
+    Corrupted(String),
+
+    /// Any other backend failure.
+    #[error("Storage error: {0}")]
+    Backend(String),
+}
+
+impl PersistenceError {
+    /// Classify a storage error by its DOM exception name.
+    ///
+    /// Browsers report quota errors under several names, and blocked
+    /// storage as either `SecurityError` or `InvalidStateError`.
+    pub fn classify(name: &str, message: impl Into<String>) -> Self {
+        let message = message.into();
+        match name {
+            "QuotaExceededError" | "NS_ERROR_DOM_QUOTA_REACHED" => Self::QuotaExceeded(message),
+            "SecurityError" | "InvalidStateError" | "NS_ERROR_FILE_ACCESS_DENIED" => {
+                Self::Unavailable(message)
+            }
+            _ => Self::Backend(message),
+        }
+    }
+
+    /// Check if this is a quota error.
+    pub fn is_quota_exceeded(&self) -> bool {
+        matches!(self, Self::QuotaExceeded(_))
+    }
+
+    /// Check if storage is unavailable.
+    pub fn is_unavailable(&self) -> bool {
+        matches!(self, Self::Unavailable(_))
+    }
+}
+
+/// Health of the persistent storage layer.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum StorageHealth {
+    /// The primary backend is working normally.
+    #[default]
+    Healthy,
+    /// The primary backend is working, but entries had to be evicted to make room.
+    QuotaPressure {
+        /// Number of entries evicted so far.
+        evicted: usize,
+    },
+    /// A write to the primary backend failed; the affected entries are kept
+    /// in memory only and will not survive a page reload.
+    Fallback {
+        /// Why the primary backend rejected the write.
+        reason: String,
+    },
+}
+
+impl StorageHealth {
+    /// Check if storage is fully healthy.
+    pub fn is_healthy(&self) -> bool {
+        matches!(self, Self::Healthy)
+    }
+
+    /// Check if data is still being persisted to the primary backend.
+    pub fn is_persistent(&self) -> bool {
+        !matches!(self, Self::Fallback { .. })
+    }
+}
+
+/// The kind of a storage backend, for diagnostics.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BackendKind {
+    /// Browser `localStorage`.
+    Local,
+    /// Browser `sessionStorage`.
+    Session,
+    /// In-memory storage.
+    Memory,
+    /// A user-provided backend.
+    Custom,
+}
+
+/// A key-value storage backend for persisted state.
+///
+/// Implementations must report failures through [`PersistenceError`]
+/// rather than panicking.
+pub trait StorageBackend: Send + Sync + 'static {
+    /// Read the value stored under `key`.
+    fn get(&self, key: &str) -> Result<Option<String>, PersistenceError>;
+
+    /// Store `value` under `key`.
+    fn set(&self, key: &str, value: &str) -> Result<(), PersistenceError>;
+
+    /// Remove the value stored under `key`.
+    fn remove(&self, key: &str) -> Result<(), PersistenceError>;
+
+    /// Returns the kind of this backend.
+    fn kind(&self) -> BackendKind {
+        BackendKind::Custom
+    }
+}
+
+/// In-memory storage backend.
+///
+/// Used as the fallback when browser storage is unavailable, on the server,
+/// and in tests. An optional byte quota simulates a full `localStorage`.
+#[derive(Clone, Default)]
+pub struct MemoryStorage {
+    entries: Arc<Mutex<HashMap<String, String>>>,
+    quota: Option<usize>,
+}
+
+impl MemoryStorage {
+    /// Create a new, unbounded in-memory storage.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an in-memory storage that fails with
+    /// [`PersistenceError::QuotaExceeded`] once `bytes` would be exceeded.
+    pub fn with_quota(bytes: usize) -> Self {
+        Self {
+            entries: Arc::default(),
+            quota: Some(bytes),
+        }
+    }
+
+    /// Get the number of stored entries.
+    pub fn len(&self) -> usize {
+        self.entries.lock().map(|e| e.len()).unwrap_or(0)
+    }
+
+    /// Check if the storage is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl fmt::Debug for MemoryStorage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MemoryStorage")
+            .field("len", &self.len())
+            .field("quota", &self.quota)
+            .finish()
+    }
+}
+
+impl StorageBackend for MemoryStorage {
+    fn get(&self, key: &str) -> Result<Option<String>, PersistenceError> {
+        let entries = self
+            .entries
+            .lock()
+            .map_err(|e| PersistenceError::Backend(e.to_string()))?;
+        Ok(entries.get(key).cloned())
+    }
+
+    fn set(&self, key: &str, value: &str) -> Result<(), PersistenceError> {
+        let mut entries = self
+            .entries
+            .lock()
+            .map_err(|e| PersistenceError::Backend(e.to_string()))?;
+
+        if let Some(quota) = self.quota {
+            let used: usize = entries
+                .iter()
+                .filter(|(k, _)| k.as_str() != key)
+                .map(|(k, v)| k.len() + v.len())
+                .sum();
+            if used + key.len() + value.len() > quota {
+                return Err(PersistenceError::QuotaExceeded(format!(
+                    "writing {key} would exceed {quota} bytes"
+                )));
+            }
+        }
+
+        entries.insert(key.to_string(), value.to_string());
+        Ok(())
+    }
+
+    fn remove(&self, key: &str) -> Result<(), PersistenceError> {
+        let mut entries = self
+            .entries
+            .lock()
+            .map_err(|e| PersistenceError::Backend(e.to_string()))?;
+        entries.remove(key);
+        Ok(())
+    }
+
+    fn kind(&self) -> BackendKind {
+        BackendKind::Memory
+    }
+}
+
+/// Browser `localStorage` backend.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LocalStorage;
+
+/// Browser `sessionStorage` backend.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SessionStorage;
+
+#[cfg(target_arch = "wasm32")]
+mod web {
+    use super::PersistenceError;
+    use wasm_bindgen::{JsCast, JsValue};
+
+    pub(super) fn classify_js_error(err: JsValue) -> PersistenceError {
+        match err.dyn_into::<web_sys::DomException>() {
+            Ok(exception) => PersistenceError::classify(&exception.name(), exception.message()),
+            Err(other) => PersistenceError::Backend(format!("{other:?}")),
+        }
+    }
+
+    pub(super) fn storage(session: bool) -> Result<web_sys::Storage, PersistenceError> {
+        let window = web_sys::window()
+            .ok_or_else(|| PersistenceError::Unavailable("No window object".to_string()))?;
+        let storage = if session {
+            window.session_storage()
+        } else {
+            window.local_storage()
+        };
+        storage
+            .map_err(classify_js_error)?
+            .ok_or_else(|| PersistenceError::Unavailable("Storage is disabled".to_string()))
+    }
+}
+
+macro_rules! web_storage_backend {
+    ($backend:ty, $session:expr, $kind:expr) => {
+        #[cfg(target_arch = "wasm32")]
+        impl StorageBackend for $backend {
+            fn get(&self, key: &str) -> Result<Option<String>, PersistenceError> {
+                web::storage($session)?
+                    .get_item(key)
+                    .map_err(web::classify_js_error)
+            }
+
+            fn set(&self, key: &str, value: &str) -> Result<(), PersistenceError> {
+                web::storage($session)?
+                    .set_item(key, value)
+                    .map_err(web::classify_js_error)
+            }
+
+            fn remove(&self, key: &str) -> Result<(), PersistenceError> {
+                web::storage($session)?
+                    .remove_item(key)
+                    .map_err(web::classify_js_error)
+            }
+
+            fn kind(&self) -> BackendKind {
+                $kind
+            }
+        }
+
+        /// Stub for non-WASM targets: browser storage is always unavailable.
+        #[cfg(not(target_arch = "wasm32"))]
+        impl StorageBackend for $backend {
+            fn get(&self, _key: &str) -> Result<Option<String>, PersistenceError> {
+                Err(PersistenceError::Unavailable(
+                    "Browser storage not available on this platform".to_string(),
+                ))
+            }
+
+            fn set(&self, _key: &str, _value: &str) -> Result<(), PersistenceError> {
+                Err(PersistenceError::Unavailable(
+                    "Browser storage not available on this platform".to_string(),
+                ))
+            }
+
+            fn remove(&self, _key: &str) -> Result<(), PersistenceError> {
+                Err(PersistenceError::Unavailable(
+                    "Browser storage not available on this platform".to_string(),
+                ))
+            }
+
+            fn kind(&self) -> BackendKind {
+                $kind
+            }
+        }
+    };
+}
+
+web_storage_backend!(LocalStorage, false, BackendKind::Local);
+web_storage_backend!(SessionStorage, true, BackendKind::Session);
+
+/// Fault-tolerant persistent storage.
+///
+/// Wraps a primary [`StorageBackend`] with LRU eviction on quota errors and
+/// an automatic in-memory fallback. Cloning is cheap; clones share state.
+///
+/// # Example
+///
+/// ```rust
+/// use leptos::prelude::*;
+/// use leptos_store::persistence::{MemoryStorage, PersistentStorage};
+///
+/// // A backend that only fits a couple of small entries
+/// let storage = PersistentStorage::new(MemoryStorage::with_quota(96));
+///
+/// storage.save("a", &"x".repeat(20)).unwrap();
+/// storage.save("b", &"y".repeat(20)).unwrap();
+///
+/// // "a" is least recently used, so it is evicted to make room
+/// storage.save("c", &"z".repeat(20)).unwrap();
+///
+/// assert_eq!(storage.load::<String>("a").unwrap(), None);
+/// assert!(storage.health().get_untracked().is_persistent());
+/// ```
+#[derive(Clone)]
+pub struct PersistentStorage {
+    primary: Arc<dyn StorageBackend>,
+    fallback: MemoryStorage,
+    lru: Arc<Mutex<Vec<String>>>,
+    health: ArcRwSignal<StorageHealth>,
+}
+
+impl PersistentStorage {
+    /// Create a new persistent storage on top of `backend`.
+    ///
+    /// The LRU index is restored from the backend if present. If the backend
+    /// is unavailable, the storage starts in fallback mode.
+    pub fn new(backend: impl StorageBackend) -> Self {
+        let storage = Self {
+            primary: Arc::new(backend),
+            fallback: MemoryStorage::new(),
+            lru: Arc::default(),
+            health: ArcRwSignal::new(StorageHealth::Healthy),
+        };
+
+        match storage.primary.get(LRU_INDEX_KEY) {
+            Ok(Some(index)) => {
+                if let Ok(keys) = serde_json::from_str::<Vec<String>>(&index)
+                    && let Ok(mut lru) = storage.lru.lock()
+                {
+                    *lru = keys;
+                }
+            }
+            Ok(None) => {}
+            Err(e) => storage.enter_fallback(&e),
+        }
+
+        storage
+    }
+
+    /// Get the storage shared by everything persisting to `backend`.
+    ///
+    /// In the browser, `localStorage` and `sessionStorage` are each backed
+    /// by a single storage for the whole app, so that all stores using them
+    /// share one LRU index and health signal. Other backends, and every
+    /// backend off the browser, get a new storage: on the server a shared
+    /// in-memory fallback would leak state between requests.
+    pub fn shared(backend: impl StorageBackend) -> Self {
+        #[cfg(target_arch = "wasm32")]
+        {
+            thread_local! {
+                static LOCAL: PersistentStorage = PersistentStorage::new(LocalStorage);
+                static SESSION: PersistentStorage = PersistentStorage::new(SessionStorage);
+            }
+
+            match backend.kind() {
+                BackendKind::Local => return LOCAL.with(Clone::clone),
+                BackendKind::Session => return SESSION.with(Clone::clone),
+                BackendKind::Memory | BackendKind::Custom => {}
+            }
+        }
+
+        Self::new(backend)
+    }
+
+    /// Get the persistent storage backed by `localStorage`.
+    pub fn local() -> Self {
+        Self::shared(LocalStorage)
+    }
+
+    /// Get the persistent storage backed by `sessionStorage`.
+    pub fn session() -> Self {
+        Self::shared(SessionStorage)
+    }
+
+    /// Returns a reactive signal with the current storage health.
+    pub fn health(&self) -> ArcReadSignal<StorageHealth> {
+        self.health.read_only()
+    }
+
+    /// Check if every saved entry is currently held by the primary backend.
+    pub fn is_persistent(&self) -> bool {
+        self.health.get_untracked().is_persistent()
+    }
+
+    /// Serialize `value` and persist it under `key`.
+    ///
+    /// Quota errors trigger LRU eviction; if the primary backend still
+    /// can't accept the write, the value is kept in memory instead and
+    /// health switches to [`StorageHealth::Fallback`]. Only serialization
+    /// errors are returned.
+    pub fn save<T: Serialize>(&self, key: &str, value: &T) -> Result<(), PersistenceError> {
+        let data = serde_json::to_string(value)
+            .map_err(|e| PersistenceError::Serialization(e.to_string()))?;
+        self.save_raw(key, &data)
+    }
+
+    /// Persist a pre-serialized string under `key`.
+    ///
+    /// The primary backend is always tried first, so a successful write
+    /// after an earlier failure moves `key` back out of memory. Eviction
+    /// stops once the freed entries cover the new value; if the write still
+    /// fails, the evicted entries are restored before falling back.
+    pub fn save_raw(&self, key: &str, data: &str) -> Result<(), PersistenceError> {
+        let needed = key.len() + data.len();
+        let mut evicted = Vec::new();
+        let mut freed = 0;
+
+        let error = loop {
+            match self.primary.set(key, data) {
+                Ok(()) => {
+                    self.fallback.remove(key)?;
+                    self.touch(key);
+                    self.leave_fallback();
+                    self.record_evictions(&evicted);
+                    return Ok(());
+                }
+                // Evicting more than the new entry takes up can't make room
+                Err(e) if e.is_quota_exceeded() && freed < needed => match self.evict_one(key) {
+                    Some((victim, value)) => {
+                        freed += victim.len() + value.len();
+                        evicted.push((victim, value));
+                    }
+                    None => break e,
+                },
+                Err(e) => break e,
+            }
+        };
+
+        self.restore(evicted);
+        self.enter_fallback(&error);
+        self.fallback.set(key, data)
+    }
+
+    /// Load and deserialize the value persisted under `key`.
+    pub fn load<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>, PersistenceError> {
+        match self.load_raw(key)? {
+            Some(data) => serde_json::from_str(&data)
+                .map(Some)
+                .map_err(|e| PersistenceError::Deserialization(e.to_string())),
+            None => Ok(None),
+        }
+    }
+
+    /// Load the raw string persisted under `key`.
+    ///
+    /// The in-memory fallback only holds keys whose latest write missed the
+    /// primary backend, so it is checked first; every other key is read from
+    /// the primary backend.
+    pub fn load_raw(&self, key: &str) -> Result<Option<String>, PersistenceError> {
+        if let Some(value) = self.fallback.get(key)? {
+            return Ok(Some(value));
+        }
+
+        match self.primary.get(key) {
+            Ok(value) => {
+                if value.is_some() {
+                    self.touch(key);
+                }
+                Ok(value)
+            }
+            Err(e) => {
+                self.enter_fallback(&e);
+                Ok(None)
+            }
+        }
+    }
+
+    /// Remove the value persisted under `key`.
+    pub fn remove(&self, key: &str) -> Result<(), PersistenceError> {
+        self.fallback.remove(key)?;
+        if let Ok(mut lru) = self.lru.lock() {
+            lru.retain(|k| k != key);
+        }
+        if let Err(e) = self.primary.remove(key) {
+            self.enter_fallback(&e);
+        } else {
+            self.write_index();
+            self.leave_fallback();
+        }
+        Ok(())
+    }
+
+    /// Returns the persisted keys, least recently used first.
+    pub fn keys(&self) -> Vec<String> {
+        self.lru.lock().map(|lru| lru.clone()).unwrap_or_default()
+    }
+
+    /// Mark `key` as most recently used.
+    fn touch(&self, key: &str) {
+        if let Ok(mut lru) = self.lru.lock() {
+            lru.retain(|k| k != key);
+            lru.push(key.to_string());
+        }
+        self.write_index();
+    }
+
+    /// Evict the least recently used entry other than `keep`.
+    ///
+    /// Returns the evicted key and value, or `None` if there was nothing
+    /// left to evict.
+    fn evict_one(&self, keep: &str) -> Option<(String, String)> {
+        loop {
+            let victim = {
+                let mut lru = self.lru.lock().ok()?;
+                let pos = lru.iter().position(|k| k != keep)?;
+                lru.remove(pos)
+            };
+
+            // Entries missing from the backend free nothing; skip them
+            let Ok(Some(value)) = self.primary.get(&victim) else {
+                continue;
+            };
+            if self.primary.remove(&victim).is_err() {
+                self.restore(vec![(victim, value)]);
+                return None;
+            }
+            self.write_index();
+            return Some((victim, value));
+        }
+    }
+
+    /// Write evicted entries back, as least recently used.
+    fn restore(&self, evicted: Vec<(String, String)>) {
+        if evicted.is_empty() {
+            return;
+        }
+        if let Ok(mut lru) = self.lru.lock() {
+            for (key, value) in evicted.into_iter().rev() {
+                if self.primary.set(&key, &value).is_ok() {
+                    lru.insert(0, key);
+                }
+            }
+        }
+        self.write_index();
+    }
+
+    /// Report evictions that made room for a successful write.
+    fn record_evictions(&self, evicted: &[(String, String)]) {
+        if evicted.is_empty() {
+            return;
+        }
+        for (key, _) in evicted {
+            leptos::logging::warn!("Storage quota exceeded, evicted persisted entry: {}", key);
+        }
+
+        self.health.update(|h| {
+            *h = match h {
+                StorageHealth::Fallback { .. } => return,
+                StorageHealth::QuotaPressure { evicted: n } => StorageHealth::QuotaPressure {
+                    evicted: *n + evicted.len(),
+                },
+                StorageHealth::Healthy => StorageHealth::QuotaPressure {
+                    evicted: evicted.len(),
+                },
+            }
+        });
+    }
+
+    /// Persist the LRU index, ignoring failures (the index is advisory).
+    fn write_index(&self) {
+        let keys = self.keys();
+        if let Ok(index) = serde_json::to_string(&keys) {
+            let _ = self.primary.set(LRU_INDEX_KEY, &index);
+        }
+    }
+
+    /// Mark the storage as degraded to the in-memory fallback.
+    fn enter_fallback(&self, error: &PersistenceError) {
+        if self.is_persistent() {
+            leptos::logging::warn!(
+                "Persistent storage unavailable, falling back to memory: {}",
+                error
+            );
+            self.health.set(StorageHealth::Fallback {
+                reason: error.to_string(),
+            });
+        }
+    }
+
+    /// Clear the fallback state once no entry is held only in memory.
+    fn leave_fallback(&self) {
+        if !self.is_persistent() && self.fallback.is_empty() {
+            self.health.set(StorageHealth::Healthy);
+        }
+    }
+}
+
+impl fmt::Debug for PersistentStorage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PersistentStorage")
+            .field("backend", &self.primary.kind())
+            .field("health", &self.health.get_untracked())
+            .finish()
+    }
+}
+
+/// Saves a store's state under a single key, optionally debounced.
+///
+/// This is the wiring behind the `persist` section of the
+/// [`store!`](crate::store!) macro. [`StatePersister::autosave`] watches a
+/// state signal and saves it after every change; with a debounce, a burst
+/// of changes results in a single write once the state has been quiet for
+/// the debounce interval. Outside the browser there are no timers, so
+/// saves happen immediately.
+///
+/// # Example
+///
+/// ```rust
+/// use leptos::prelude::*;
+/// use leptos_store::persistence::{MemoryStorage, PersistentStorage, StatePersister};
+/// use std::time::Duration;
+///
+/// let persister = StatePersister::new(PersistentStorage::new(MemoryStorage::new()), "theme")
+///     .with_debounce(Duration::from_millis(250));
+///
+/// persister.save(&"dark".to_string()).unwrap();
+/// assert_eq!(persister.load::<String>().unwrap(), Some("dark".to_string()));
+/// ```
+#[derive(Clone)]
+pub struct StatePersister {
+    storage: PersistentStorage,
+    key: Arc<str>,
+    debounce: Duration,
+    #[cfg(target_arch = "wasm32")]
+    pending: Arc<Mutex<Option<TimeoutHandle>>>,
+}
+
+impl StatePersister {
+    /// Create a persister saving under `key` in `storage`, without debouncing.
+    pub fn new(storage: PersistentStorage, key: impl Into<String>) -> Self {
+        Self {
+            storage,
+            key: key.into().into(),
+            debounce: Duration::ZERO,
+            #[cfg(target_arch = "wasm32")]
+            pending: Arc::default(),
+        }
+    }
+
+    /// Delay automatic saves until the state has not changed for `debounce`.
+    pub fn with_debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+
+    /// Returns the underlying storage.
+    pub fn storage(&self) -> &PersistentStorage {
+        &self.storage
+    }
+
+    /// Returns the key the state is saved under.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Returns the debounce interval for automatic saves.
+    pub fn debounce(&self) -> Duration {
+        self.debounce
+    }
+
+    /// Save `state` immediately.
+    pub fn save<T: Serialize>(&self, state: &T) -> Result<(), PersistenceError> {
+        self.storage.save(&self.key, state)
+    }
+
+    /// Load the saved state, if any.
+    pub fn load<T: DeserializeOwned>(&self) -> Result<Option<T>, PersistenceError> {
+        self.storage.load(&self.key)
+    }
+
+    /// Remove the saved state.
+    pub fn clear(&self) -> Result<(), PersistenceError> {
+        self.storage.remove(&self.key)
+    }
+
+    /// Save `state` after every change, for as long as the current reactive
+    /// owner lives.
+    ///
+    /// The initial value is not saved. Like other effects, automatic saves
+    /// only run in the browser.
+    pub fn autosave<T>(&self, state: ReadSignal<T>)
+    where
+        T: Serialize + Send + Sync + 'static,
+    {
+        let persister = self.clone();
+        let first_run = AtomicBool::new(true);
+        ImmediateEffect::new_scoped(move || {
+            state.track();
+            if !first_run.swap(false, Ordering::Relaxed) {
+                persister.schedule_save(state);
+            }
+        });
+    }
+
+    /// Save the current value of `state`, after the debounce interval if set.
+    fn schedule_save<T>(&self, state: ReadSignal<T>)
+    where
+        T: Serialize + Send + Sync + 'static,
+    {
+        #[cfg(target_arch = "wasm32")]
+        if !self.debounce.is_zero() {
+            let Ok(mut pending) = self.pending.lock() else {
+                return;
+            };
+            if let Some(handle) = pending.take() {
+                handle.clear();
+            }
+            let persister = self.clone();
+            *pending = set_timeout_with_handle(
+                move || {
+                    if let Ok(mut pending) = persister.pending.lock() {
+                        pending.take();
+                    }
+                    persister.save_now(state);
+                },
+                self.debounce,
+            )
+            .ok();
+            return;
+        }
+
+        self.save_now(state);
+    }
+
+    fn save_now<T: Serialize + Send + Sync + 'static>(&self, state: ReadSignal<T>) {
+        if let Some(Err(e)) = state.try_with_untracked(|state| self.save(state)) {
+            leptos::logging::warn!("Failed to persist state under {}: {}", self.key, e);
+        }
+    }
+}
+
+impl fmt::Debug for StatePersister {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StatePersister")
+            .field("key", &self.key)
+            .field("debounce", &self.debounce)
+            .field("storage", &self.storage)
+            .finish()
+    }
+}
+
+/// How a store hydrated from the server reconciles its state with the
+/// state persisted on the client.
+///
+/// A store that is both hydrated and persisted starts with two states: the
+/// one rendered by the server and the one saved by an earlier visit. The
+/// strategy picks the state the store keeps, which is saved again so both
+/// agree. The `reconcile` option of the [`store!`](crate::store!) macro's
+/// `persist` section applies it when the store is hydrated.
+///
+/// # Example
+///
+/// ```rust
+/// use leptos_store::persistence::{
+///     MemoryStorage, PersistentStorage, Reconcile, StatePersister, Timestamped,
+/// };
+///
+/// #[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+/// struct Settings {
+///     theme: Timestamped<String>,
+///     items: Vec<String>,
+/// }
+///
+/// // The newest theme wins, the server's items always do
+/// let merge = Reconcile::Merge(|server: Settings, client: Settings| Settings {
+///     theme: server.theme.newest(client.theme),
+///     ..server
+/// });
+///
+/// let persister = StatePersister::new(PersistentStorage::new(MemoryStorage::new()), "settings");
+/// let client = Settings { theme: Timestamped::at("dark".into(), 20), items: vec![] };
+/// persister.save(&client).unwrap();
+///
+/// let server = Settings { theme: Timestamped::at("light".into(), 10), items: vec!["a".into()] };
+/// let state = merge.reconcile(server, &persister).unwrap();
+/// assert_eq!(state.theme.value, "dark");
+/// assert_eq!(state.items, vec!["a".to_string()]);
+/// ```
+pub enum Reconcile<T> {
+    /// Keep the server's state. This is what hydration does without a
+    /// strategy.
+    ServerWins,
+    /// Keep the persisted state, if there is one.
+    ClientWins,
+    /// Merge the server's state (first) with the persisted one (second),
+    /// e.g. field by field with [`Timestamped`] values.
+    Merge(fn(T, T) -> T),
+}
+
+impl<T> Clone for Reconcile<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Reconcile<T> {}
+
+impl<T> fmt::Debug for Reconcile<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ServerWins => f.write_str("ServerWins"),
+            Self::ClientWins => f.write_str("ClientWins"),
+            Self::Merge(_) => f.write_str("Merge(..)"),
+        }
+    }
+}
+
+impl<T: Serialize + DeserializeOwned> Reconcile<T> {
+    /// Reconcile the `server` state with the state saved by `persister`.
+    ///
+    /// Returns the state the store should switch to, or `None` if it should
+    /// keep the server's state. A persisted state that can't be loaded is
+    /// logged and ignored.
+    pub fn reconcile(&self, server: T, persister: &StatePersister) -> Option<T> {
+        let merge = match self {
+            Self::ServerWins => return None,
+            Self::ClientWins => None,
+            Self::Merge(merge) => Some(merge),
+        };
+        let client = match persister.load::<T>() {
+            Ok(client) => client?,
+            Err(e) => {
+                leptos::logging::warn!(
+                    "Failed to load persisted state under {}: {}",
+                    persister.key(),
+                    e
+                );
+                return None;
+            }
+        };
+        let state = match merge {
+            Some(merge) => merge(server, client),
+            None => client,
+        };
+        if let Err(e) = persister.save(&state) {
+            leptos::logging::warn!("Failed to persist state under {}: {}", persister.key(), e);
+        }
+        Some(state)
+    }
+}
+
+/// A value with the time it was last changed, for merging states field by
+/// field in [`Reconcile::Merge`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, serde::Deserialize)]
+pub struct Timestamped<T> {
+    /// The value.
+    pub value: T,
+    /// When the value was last changed, in milliseconds since the Unix
+    /// epoch.
+    pub updated_at: u64,
+}
+
+impl<T> Timestamped<T> {
+    /// Wrap a value changed now.
+    pub fn new(value: T) -> Self {
+        Self::at(value, crate::r#async::now_ms())
+    }
+
+    /// Wrap a value changed at `updated_at`.
+    pub fn at(value: T, updated_at: u64) -> Self {
+        Self { value, updated_at }
+    }
+
+    /// Replace the value, marking it changed now.
+    pub fn set(&mut self, value: T) {
+        *self = Self::new(value);
+    }
+
+    /// Returns the most recently changed of the two values, `self` on a tie.
+    pub fn newest(self, other: Self) -> Self {
+        if other.updated_at > self.updated_at {
+            other
+        } else {
+            self
+        }
+    }
+}
+
+/// Key suffix under which a [`PersistedHistory`] journal is stored.
+pub const JOURNAL_KEY_SUFFIX: &str = ".journal";
+
+/// Compute the integrity checksum (64-bit FNV-1a) of persisted data.
+///
+/// FNV-1a is used instead of `std`'s hasher because its output is stable
+/// across Rust releases, which matters for data read back by a later build.
+pub fn checksum(data: &str) -> u64 {
+    data.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// A persisted record with its sequence number and checksum.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Record {
+    seq: u64,
+    checksum: u64,
+    data: String,
+}
+
+impl Record {
+    fn new(seq: u64, data: String) -> Self {
+        Self {
+            seq,
+            checksum: checksum(&data),
+            data,
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        checksum(&self.data) == self.checksum
+    }
+}
+
+type ApplyFn<S, M> = Arc<dyn Fn(&mut S, &M) + Send + Sync>;
+
+struct HistoryInner<S, M> {
+    snapshot: Option<S>,
+    snapshot_seq: u64,
+    entries: VecDeque<(u64, M)>,
+}
+
+/// Bounded mutation history persisted for crash recovery.
+///
+/// The history is made of a snapshot of the state and a journal of at most
+/// `capacity` mutations recorded after it. When the journal is full, the
+/// oldest mutation is folded into the snapshot. Both are stored with
+/// checksums and sequence numbers, so a load after a crash can tell which
+/// parts are consistent.
+///
+/// Mutations are values of a serializable type `M`, applied to the state
+/// by the function passed to [`new`](Self::new). Cloning is cheap; clones
+/// share the same history.
+///
+/// # Example
+///
+/// ```rust
+/// use leptos_store::persistence::{MemoryStorage, PersistedHistory, PersistentStorage};
+///
+/// let backend = MemoryStorage::new();
+/// let storage = PersistentStorage::new(backend.clone());
+///
+/// let history = PersistedHistory::new(storage, "counter", 10, |count: &mut i32, delta: &i32| {
+///     *count += delta;
+/// });
+/// history.checkpoint(&0).unwrap();
+/// history.record(5).unwrap();
+/// history.record(-2).unwrap();
+///
+/// // After a reload, recover from the same storage
+/// let storage = PersistentStorage::new(backend);
+/// let history = PersistedHistory::new(storage, "counter", 10, |count: &mut i32, delta: &i32| {
+///     *count += delta;
+/// });
+/// let recovery = history.recover().unwrap().unwrap();
+///
+/// assert_eq!(recovery.snapshot, 0);
+/// assert_eq!(recovery.mutations, vec![5, -2]);
+/// assert_eq!(recovery.replay(), 3);
+/// ```
+pub struct PersistedHistory<S, M> {
+    storage: PersistentStorage,
+    key: String,
+    capacity: usize,
+    apply: ApplyFn<S, M>,
+    inner: Arc<Mutex<HistoryInner<S, M>>>,
+}
+
+impl<S, M> Clone for PersistedHistory<S, M> {
+    fn clone(&self) -> Self {
+        Self {
+            storage: self.storage.clone(),
+            key: self.key.clone(),
+            capacity: self.capacity,
+            apply: Arc::clone(&self.apply),
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<S, M> PersistedHistory<S, M>
+where
+    S: Clone + Serialize + DeserializeOwned,
+    M: Clone + Serialize + DeserializeOwned,
+{
+    /// Create a history persisted under `key`, keeping at most `capacity`
+    /// mutations in the journal.
+    ///
+    /// Nothing is read from storage until [`recover`](Self::recover) is
+    /// called. A `capacity` of zero is treated as one.
+    pub fn new(
+        storage: PersistentStorage,
+        key: impl Into<String>,
+        capacity: usize,
+        apply: impl Fn(&mut S, &M) + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            storage,
+            key: key.into(),
+            capacity: capacity.max(1),
+            apply: Arc::new(apply),
+            inner: Arc::new(Mutex::new(HistoryInner {
+                snapshot: None,
+                snapshot_seq: 0,
+                entries: VecDeque::new(),
+            })),
+        }
+    }
+
+    /// Returns the maximum number of journaled mutations.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of mutations currently in the journal.
+    pub fn len(&self) -> usize {
+        self.inner.lock().map(|i| i.entries.len()).unwrap_or(0)
+    }
+
+    /// Check if the journal is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Persist `state` as the new snapshot and clear the journal.
+    ///
+    /// Call this with the initial state before recording mutations, and
+    /// after [`recover`](Self::recover) once the recovered state is in use.
+    pub fn checkpoint(&self, state: &S) -> Result<(), PersistenceError> {
+        let mut inner = self.lock()?;
+        let seq = inner.snapshot_seq + inner.entries.len() as u64;
+        inner.snapshot = Some(state.clone());
+        inner.snapshot_seq = seq;
+        inner.entries.clear();
+        self.write_snapshot(&inner)?;
+        self.write_journal(&inner)
+    }
+
+    /// Append `mutation` to the journal.
+    ///
+    /// If the journal is full, the oldest mutation is applied to the
+    /// snapshot, which is persisted before the journal so that a crash in
+    /// between never loses a mutation.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PersistenceError::Backend`] if no snapshot exists yet.
+    pub fn record(&self, mutation: M) -> Result<(), PersistenceError> {
+        let mut inner = self.lock()?;
+        if inner.snapshot.is_none() {
+            return Err(PersistenceError::Backend(format!(
+                "history {} has no checkpoint",
+                self.key
+            )));
+        }
+
+        let seq = inner.snapshot_seq + inner.entries.len() as u64 + 1;
+        inner.entries.push_back((seq, mutation));
+
+        if inner.entries.len() > self.capacity {
+            let HistoryInner {
+                snapshot,
+                snapshot_seq,
+                entries,
+            } = &mut *inner;
+            if let (Some(state), Some((seq, oldest))) = (snapshot.as_mut(), entries.pop_front()) {
+                (self.apply)(state, &oldest);
+                *snapshot_seq = seq;
+            }
+            self.write_snapshot(&inner)?;
+        }
+        self.write_journal(&inner)
+    }
+
+    /// Load the latest consistent snapshot and the mutations recorded after it.
+    ///
+    /// Journal entries that fail their checksum, were already folded into
+    /// the snapshot, or don't follow on from the previous entry are
+    /// discarded, along with everything after them. The history continues
+    /// from the recovered point.
+    ///
+    /// Returns `Ok(None)` if nothing has been persisted.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PersistenceError::Corrupted`] if the snapshot itself fails
+    /// its integrity check.
+    pub fn recover(&self) -> Result<Option<Recovery<S, M>>, PersistenceError> {
+        let Some(raw) = self.storage.load_raw(&self.key)? else {
+            return Ok(None);
+        };
+        let record: Record = serde_json::from_str(&raw)
+            .map_err(|e| PersistenceError::Corrupted(format!("{}: {e}", self.key)))?;
+        if !record.is_valid() {
+            return Err(PersistenceError::Corrupted(format!(
+                "{}: snapshot checksum mismatch",
+                self.key
+            )));
+        }
+        let snapshot: S = serde_json::from_str(&record.data)
+            .map_err(|e| PersistenceError::Deserialization(e.to_string()))?;
+
+        let journal: Vec<Record> = self
+            .storage
+            .load_raw(&self.journal_key())?
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default();
+
+        let mut entries = VecDeque::new();
+        let mut discarded = 0;
+        let mut expected = record.seq + 1;
+        for (index, entry) in journal.iter().enumerate() {
+            if entry.seq < expected {
+                // Already folded into the snapshot before the crash
+                continue;
+            }
+            let mutation = (entry.seq == expected && entry.is_valid())
+                .then(|| serde_json::from_str::<M>(&entry.data).ok())
+                .flatten();
+            match mutation {
+                Some(mutation) => {
+                    entries.push_back((entry.seq, mutation));
+                    expected += 1;
+                }
+                None => {
+                    discarded = journal.len() - index;
+                    break;
+                }
+            }
+        }
+        if discarded > 0 {
+            leptos::logging::warn!(
+                "Discarded {} inconsistent journal entries for {}",
+                discarded,
+                self.key
+            );
+        }
+
+        let mutations = entries.iter().map(|(_, m)| m.clone()).collect();
+        let mut inner = self.lock()?;
+        inner.snapshot = Some(snapshot.clone());
+        inner.snapshot_seq = record.seq;
+        inner.entries = entries;
+
+        Ok(Some(Recovery {
+            snapshot,
+            mutations,
+            discarded,
+            apply: Arc::clone(&self.apply),
+        }))
+    }
+
+    /// Remove the persisted snapshot and journal.
+    pub fn clear(&self) -> Result<(), PersistenceError> {
+        let mut inner = self.lock()?;
+        inner.snapshot = None;
+        inner.snapshot_seq = 0;
+        inner.entries.clear();
+        self.storage.remove(&self.key)?;
+        self.storage.remove(&self.journal_key())
+    }
+
+    fn journal_key(&self) -> String {
+        format!("{}{JOURNAL_KEY_SUFFIX}", self.key)
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, HistoryInner<S, M>>, PersistenceError> {
+        self.inner
+            .lock()
+            .map_err(|e| PersistenceError::Backend(e.to_string()))
+    }
+
+    fn write_snapshot(&self, inner: &HistoryInner<S, M>) -> Result<(), PersistenceError> {
+        let data = serde_json::to_string(&inner.snapshot)
+            .map_err(|e| PersistenceError::Serialization(e.to_string()))?;
+        let record = serde_json::to_string(&Record::new(inner.snapshot_seq, data))
+            .map_err(|e| PersistenceError::Serialization(e.to_string()))?;
+        self.storage.save_raw(&self.key, &record)
+    }
+
+    fn write_journal(&self, inner: &HistoryInner<S, M>) -> Result<(), PersistenceError> {
+        let records = inner
+            .entries
+            .iter()
+            .map(|(seq, mutation)| {
+                serde_json::to_string(mutation)
+                    .map(|data| Record::new(*seq, data))
+                    .map_err(|e| PersistenceError::Serialization(e.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let journal = serde_json::to_string(&records)
+            .map_err(|e| PersistenceError::Serialization(e.to_string()))?;
+        self.storage.save_raw(&self.journal_key(), &journal)
+    }
+}
+
+impl<S, M> fmt::Debug for PersistedHistory<S, M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PersistedHistory")
+            .field("key", &self.key)
+            .field("capacity", &self.capacity)
+            .field(
+                "len",
+                &self.inner.lock().map(|i| i.entries.len()).unwrap_or(0),
+            )
+            .finish()
+    }
+}
+
+/// State recovered by [`PersistedHistory::recover`].
+pub struct Recovery<S, M> {
+    /// The latest consistent snapshot.
+    pub snapshot: S,
+    /// Valid mutations recorded after the snapshot, oldest first.
+    pub mutations: Vec<M>,
+    /// Number of journal entries dropped by integrity checks.
+    pub discarded: usize,
+    apply: ApplyFn<S, M>,
+}
+
+impl<S, M> Recovery<S, M> {
+    /// Returns the snapshot with all recovered mutations re-applied.
+    pub fn replay(self) -> S {
+        let mut state = self.snapshot;
+        for mutation in &self.mutations {
+            (self.apply)(&mut state, mutation);
+        }
+        state
+    }
+}
+
+impl<S: fmt::Debug, M: fmt::Debug> fmt::Debug for Recovery<S, M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Recovery")
+            .field("snapshot", &self.snapshot)
+            .field("mutations", &self.mutations)
+            .field("discarded", &self.discarded)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Backend that always reports storage as blocked.
+    struct BlockedStorage;
+
+    impl StorageBackend for BlockedStorage {
+        fn get(&self, _key: &str) -> Result<Option<String>, PersistenceError> {
+            Err(PersistenceError::classify("SecurityError", "blocked"))
+        }
+
+        fn set(&self, _key: &str, _value: &str) -> Result<(), PersistenceError> {
+            Err(PersistenceError::classify("SecurityError", "blocked"))
+        }
+
+        fn remove(&self, _key: &str) -> Result<(), PersistenceError> {
+            Err(PersistenceError::classify("SecurityError", "blocked"))
+        }
+    }
+
+    #[test]
+    fn test_error_classification() {
+        assert!(PersistenceError::classify("QuotaExceededError", "full").is_quota_exceeded());
+        assert!(
+            PersistenceError::classify("NS_ERROR_DOM_QUOTA_REACHED", "full").is_quota_exceeded()
+        );
+        assert!(PersistenceError::classify("SecurityError", "blocked").is_unavailable());
+        assert_eq!(
+            PersistenceError::classify("NotFoundError", "huh"),
+            PersistenceError::Backend("huh".to_string())
+        );
+    }
+
+    #[test]
+    fn test_save_and_load_roundtrip() {
+        let storage = PersistentStorage::new(MemoryStorage::new());
+        storage.save("counter", &42).unwrap();
+
+        assert_eq!(storage.load::<i32>("counter").unwrap(), Some(42));
+        assert_eq!(storage.load::<i32>("missing").unwrap(), None);
+        assert!(storage.health().get_untracked().is_healthy());
+    }
+
+    #[test]
+    fn test_quota_exceeded_evicts_lru() {
+        let backend = MemoryStorage::with_quota(96);
+        let storage = PersistentStorage::new(backend.clone());
+
+        storage.save("a", &"x".repeat(20)).unwrap();
+        storage.save("b", &"y".repeat(20)).unwrap();
+        // Reading "a" makes "b" the least recently used entry
+        storage.load::<String>("a").unwrap();
+        storage.save("c", &"z".repeat(20)).unwrap();
+
+        assert!(storage.load::<String>("a").unwrap().is_some());
+        assert_eq!(storage.load::<String>("b").unwrap(), None);
+        assert_eq!(
+            storage.health().get_untracked(),
+            StorageHealth::QuotaPressure { evicted: 1 }
+        );
+    }
+
+    #[test]
+    fn test_oversized_write_falls_back_to_memory() {
+        let storage = PersistentStorage::new(MemoryStorage::with_quota(16));
+        storage.save("big", &"x".repeat(100)).unwrap();
+
+        assert!(!storage.is_persistent());
+        assert_eq!(
+            storage.load::<String>("big").unwrap(),
+            Some("x".repeat(100))
+        );
+    }
+
+    #[test]
+    fn test_oversized_write_keeps_other_entries() {
+        let storage = PersistentStorage::new(MemoryStorage::with_quota(96));
+        storage.save("a", &"x".repeat(10)).unwrap();
+        storage.save("b", &"y".repeat(10)).unwrap();
+
+        storage.save("big", &"z".repeat(200)).unwrap();
+
+        assert_eq!(storage.load::<String>("a").unwrap(), Some("x".repeat(10)));
+        assert_eq!(storage.load::<String>("b").unwrap(), Some("y".repeat(10)));
+        assert_eq!(
+            storage.load::<String>("big").unwrap(),
+            Some("z".repeat(200))
+        );
+        assert!(!storage.is_persistent());
+    }
+
+    #[test]
+    fn test_fallback_is_per_key_and_recovers() {
+        let backend = MemoryStorage::with_quota(96);
+        let storage = PersistentStorage::new(backend.clone());
+        storage.save("a", &1).unwrap();
+        storage.save("big", &"z".repeat(200)).unwrap();
+        assert!(!storage.is_persistent());
+
+        // Keys that never failed are still read from and written to the backend
+        storage.save("b", &2).unwrap();
+        assert_eq!(backend.get("b").unwrap(), Some("2".to_string()));
+        assert_eq!(storage.load::<i32>("a").unwrap(), Some(1));
+
+        // Once the last in-memory key reaches the backend, storage is healthy again
+        storage.save("big", &"small").unwrap();
+        assert_eq!(backend.get("big").unwrap(), Some("\"small\"".to_string()));
+        assert!(storage.health().get_untracked().is_healthy());
+    }
+
+    #[test]
+    fn test_reconcile_strategies() {
+        let persister = StatePersister::new(PersistentStorage::new(MemoryStorage::new()), "n");
+
+        // Nothing persisted: every strategy keeps the server's state
+        assert_eq!(Reconcile::ClientWins.reconcile(1, &persister), None);
+        persister.save(&2).unwrap();
+
+        assert_eq!(Reconcile::ServerWins.reconcile(1, &persister), None);
+        assert_eq!(Reconcile::ClientWins.reconcile(1, &persister), Some(2));
+        let sum = Reconcile::Merge(|server: i32, client| server + client);
+        assert_eq!(sum.reconcile(1, &persister), Some(3));
+        // The reconciled state is saved
+        assert_eq!(persister.load::<i32>().unwrap(), Some(3));
+    }
+
+    #[test]
+    fn test_timestamped_newest() {
+        let old = Timestamped::at("old", 1);
+        let new = Timestamped::at("new", 2);
+        assert_eq!(old.clone().newest(new.clone()).value, "new");
+        assert_eq!(new.clone().newest(old).value, "new");
+        assert_eq!(new.clone().newest(Timestamped::at("tie", 2)).value, "new");
+
+        let mut value = Timestamped::at("a", 0);
+        value.set("b");
+        assert!(value.updated_at > 0);
+    }
+
+    #[test]
+    fn test_blocked_storage_falls_back_to_memory() {
+        let storage = PersistentStorage::new(BlockedStorage);
+        assert!(matches!(
+            storage.health().get_untracked(),
+            StorageHealth::Fallback { .. }
+        ));
+
+        storage.save("key", &"value").unwrap();
+        assert_eq!(
+            storage.load::<String>("key").unwrap(),
+            Some("value".to_string())
+        );
+    }
+
+    #[test]
+    fn test_lru_index_is_restored() {
+        let backend = MemoryStorage::new();
+        {
+            let storage = PersistentStorage::new(backend.clone());
+            storage.save("first", &1).unwrap();
+            storage.save("second", &2).unwrap();
+        }
+
+        let storage = PersistentStorage::new(backend);
+        assert_eq!(storage.keys(), vec!["first", "second"]);
+    }
+
+    #[test]
+    fn test_remove() {
+        let storage = PersistentStorage::new(MemoryStorage::new());
+        storage.save("key", &1).unwrap();
+        storage.remove("key").unwrap();
+
+        assert_eq!(storage.load::<i32>("key").unwrap(), None);
+        assert!(storage.keys().is_empty());
+    }
+
+    fn counter_history(storage: PersistentStorage, capacity: usize) -> PersistedHistory<i32, i32> {
+        PersistedHistory::new(storage, "counter", capacity, |count, delta| *count += delta)
+    }
+
+    #[test]
+    fn test_state_persister_autosave() {
+        let owner = Owner::new();
+        owner.set();
+
+        let backend = MemoryStorage::new();
+        let persister = StatePersister::new(PersistentStorage::new(backend.clone()), "count")
+            .with_debounce(Duration::from_millis(100));
+        let count = RwSignal::new(1);
+        persister.autosave(count.read_only());
+        assert_eq!(backend.get("count").unwrap(), None);
+
+        // No timers outside the browser, so the debounced save happens now
+        count.set(2);
+        assert_eq!(persister.load::<i32>().unwrap(), Some(2));
+
+        persister.clear().unwrap();
+        assert_eq!(backend.get("count").unwrap(), None);
+    }
+
+    #[test]
+    fn test_history_compacts_into_snapshot() {
+        let backend = MemoryStorage::new();
+        let history = counter_history(PersistentStorage::new(backend.clone()), 2);
+        assert!(history.record(1).is_err());
+
+        history.checkpoint(&10).unwrap();
+        for delta in [1, 2, 3, 4] {
+            history.record(delta).unwrap();
+        }
+        assert_eq!(history.len(), 2);
+
+        let recovered = counter_history(PersistentStorage::new(backend), 2)
+            .recover()
+            .unwrap()
+            .unwrap();
+        assert_eq!(recovered.snapshot, 13);
+        assert_eq!(recovered.mutations, vec![3, 4]);
+        assert_eq!(recovered.discarded, 0);
+        assert_eq!(recovered.replay(), 20);
+    }
+
+    #[test]
+    fn test_history_discards_corrupted_entries() {
+        let backend = MemoryStorage::new();
+        let history = counter_history(PersistentStorage::new(backend.clone()), 5);
+        history.checkpoint(&0).unwrap();
+        for delta in [1, 2, 3] {
+            history.record(delta).unwrap();
+        }
+
+        // Corrupt the second journal entry, as a torn write would
+        let journal = backend.get("counter.journal").unwrap().unwrap();
+        let corrupted = journal.replacen(r#""data":"2""#, r#""data":"9""#, 1);
+        assert_ne!(journal, corrupted);
+        backend.set("counter.journal", &corrupted).unwrap();
+
+        let history = counter_history(PersistentStorage::new(backend.clone()), 5);
+        let recovered = history.recover().unwrap().unwrap();
+        assert_eq!(recovered.mutations, vec![1]);
+        assert_eq!(recovered.discarded, 2);
+        assert_eq!(recovered.replay(), 1);
+
+        // A corrupted snapshot is reported rather than silently restored
+        let snapshot = backend.get("counter").unwrap().unwrap();
+        backend
+            .set(
+                "counter",
+                &snapshot.replacen(r#""data":"0""#, r#""data":"7""#, 1),
+            )
+            .unwrap();
+        assert!(matches!(
+            history.recover(),
+            Err(PersistenceError::Corrupted(_))
+        ));
+    }
+
+    #[test]
+    fn test_history_skips_entries_already_in_snapshot() {
+        let backend = MemoryStorage::new();
+        let history = counter_history(PersistentStorage::new(backend.clone()), 1);
+        history.checkpoint(&0).unwrap();
+        history.record(1).unwrap();
+        let stale_journal = backend.get("counter.journal").unwrap().unwrap();
+        history.record(2).unwrap();
+
+        // Simulate a crash after the snapshot write but before the journal write
+        backend.set("counter.journal", &stale_journal).unwrap();
+
+        let recovered = counter_history(PersistentStorage::new(backend), 1)
+            .recover()
+            .unwrap()
+            .unwrap();
+        assert_eq!(recovered.snapshot, 1);
+        assert!(recovered.mutations.is_empty());
+        assert_eq!(recovered.discarded, 0);
+    }
+}
@@ -0,0 +1,630 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2026 web-mech
+
+//! Prefetch hints for store-driven navigation.
+//!
+//! This module lets an app warm a store's data before the user navigates,
+//! e.g. when a link is hovered or scrolled into view. A prefetched action
+//! starts running immediately; when the target page later dispatches the
+//! same action, it reuses the in-flight (or completed) result instead of
+//! fetching twice.
+//!
+//! # Overview
+//!
+//! - [`PrefetchAction`] - An async action with a key used for deduplication
+//! - [`PrefetchCache`] - Holds prefetched results, provided via context
+//! - [`StorePrefetchExt`] - `store.prefetch(action)` and
+//!   `store.dispatch_prefetched(action)`
+//! - [`PrefetchRoutes`] - Maps route patterns to prefetch functions so links
+//!   can be warmed by their `href`
+//!
+//! With the `hydrate` feature, the [`prefetch_link`] directive wires an
+//! `<a>` element to [`PrefetchRoutes`] on hover/focus or when it enters the
+//! viewport.
+//!
+//! Prefetching is a client-side optimization: when no [`PrefetchCache`] is
+//! provided (e.g. during SSR), `prefetch` does nothing and
+//! `dispatch_prefetched` simply executes the action.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! #[component]
+//! pub fn App() -> impl IntoView {
+//!     provide_prefetch_cache(PrefetchCache::new());
+//!
+//!     let store = TokenStore::new();
+//!     provide_store(store.clone());
+//!     provide_prefetch_routes(PrefetchRoutes::new().route("/tokens/:id", move |params| {
+//!         store.prefetch(FetchToken::new(params["id"].clone()));
+//!     }));
+//!
+//!     view! { <a href="/tokens/42" use:prefetch_link=PrefetchTrigger::Intent>"Token 42"</a> }
+//! }
+//!
+//! // On the token page, reuse the prefetched result
+//! let token = store.dispatch_prefetched(FetchToken::new(id)).await?;
+//! ```
+
+use crate::r#async::{ActionResult, AsyncAction};
+use crate::deps::ActionDeps;
+use crate::middleware;
+use crate::store::{Store, StoreId};
+use futures::FutureExt;
+use futures::future::{BoxFuture, Shared};
+use leptos::prelude::*;
+use std::any::Any;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::future::Future;
+use std::sync::{Arc, Mutex, RwLock};
+
+/// Default number of prefetched results kept by a [`PrefetchCache`].
+pub const DEFAULT_PREFETCH_CAPACITY: usize = 32;
+
+/// An async action that can be prefetched.
+///
+/// The prefetch key identifies the data being fetched; a later dispatch with
+/// the same key on the same store type reuses the prefetched result. The
+/// output and error must be `Clone` so the result can be shared.
+pub trait PrefetchAction<S: Store>:
+    AsyncAction<S, Output: Clone + Sync, Error: Clone + Sync> + 'static
+{
+    /// Returns the key identifying the data this action fetches.
+    fn prefetch_key(&self) -> String;
+}
+
+type SharedResult<O, E> = Shared<BoxFuture<'static, ActionResult<O, E>>>;
+type EntryKey = (StoreId, String);
+
+#[derive(Default)]
+struct PrefetchEntries {
+    results: HashMap<EntryKey, Box<dyn Any + Send>>,
+    order: VecDeque<EntryKey>,
+}
+
+/// Cache of prefetched action results.
+///
+/// Entries are consumed by the first matching dispatch. When the cache is
+/// full, the oldest entry is dropped. Cloning is cheap; all clones share
+/// the same entries.
+#[derive(Clone)]
+pub struct PrefetchCache {
+    entries: Arc<Mutex<PrefetchEntries>>,
+    capacity: usize,
+}
+
+impl Default for PrefetchCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PrefetchCache {
+    /// Create a cache holding up to [`DEFAULT_PREFETCH_CAPACITY`] entries.
+    pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_PREFETCH_CAPACITY)
+    }
+
+    /// Create a cache holding up to `capacity` entries.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: Arc::default(),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Returns the number of prefetched entries.
+    pub fn len(&self) -> usize {
+        self.lock().results.len()
+    }
+
+    /// Check if the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Check if a result is prefetched for the given store and key.
+    pub fn contains<S: Store>(&self, store: &S, key: &str) -> bool {
+        self.lock()
+            .results
+            .contains_key(&(store.id(), key.to_string()))
+    }
+
+    /// Drop the prefetched result for the given store and key.
+    pub fn invalidate<S: Store>(&self, store: &S, key: &str) {
+        let entry_key = (store.id(), key.to_string());
+        let mut entries = self.lock();
+        entries.results.remove(&entry_key);
+        entries.order.retain(|k| k != &entry_key);
+    }
+
+    /// Drop all prefetched results.
+    pub fn clear(&self) {
+        let mut entries = self.lock();
+        entries.results.clear();
+        entries.order.clear();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, PrefetchEntries> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Insert an entry unless one already exists. Returns `true` if inserted.
+    fn insert(&self, key: EntryKey, value: Box<dyn Any + Send>) -> bool {
+        let mut entries = self.lock();
+        if entries.results.contains_key(&key) {
+            return false;
+        }
+        while entries.order.len() >= self.capacity {
+            if let Some(oldest) = entries.order.pop_front() {
+                entries.results.remove(&oldest);
+            }
+        }
+        entries.order.push_back(key.clone());
+        entries.results.insert(key, value);
+        true
+    }
+
+    fn take(&self, key: &EntryKey) -> Option<Box<dyn Any + Send>> {
+        let mut entries = self.lock();
+        entries.order.retain(|k| k != key);
+        entries.results.remove(key)
+    }
+}
+
+impl fmt::Debug for PrefetchCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PrefetchCache")
+            .field("len", &self.len())
+            .field("capacity", &self.capacity)
+            .finish()
+    }
+}
+
+/// Provide a prefetch cache to the component tree.
+pub fn provide_prefetch_cache(cache: PrefetchCache) {
+    provide_context(cache);
+}
+
+/// Access the prefetch cache from context, if one was provided.
+pub fn use_prefetch_cache() -> Option<PrefetchCache> {
+    use_context::<PrefetchCache>()
+}
+
+/// Extension trait adding prefetch support to stores.
+pub trait StorePrefetchExt: Store + Sized {
+    /// Start running an action ahead of time.
+    ///
+    /// Returns `true` if a prefetch was started, or `false` if no
+    /// [`PrefetchCache`] is in context or the key is already prefetched.
+    fn prefetch<A>(&self, action: A) -> bool
+    where
+        A: PrefetchAction<Self>,
+    {
+        let Some(cache) = use_prefetch_cache() else {
+            return false;
+        };
+        let key = (self.id(), action.prefetch_key());
+        if cache.contains(self, &key.1) {
+            return false;
+        }
+
+        let store = self.clone();
+        let deps = ActionDeps::current();
+        let shared: SharedResult<A::Output, A::Error> = async move {
+            middleware::run_async_action::<Self, A, _, _, _>(&store, || {
+                deps.scope(action.execute(&store))
+            })
+            .await
+        }
+        .boxed()
+        .shared();
+
+        let inserted = cache.insert(key, Box::new(shared.clone()));
+        if inserted {
+            leptos::task::spawn(async move {
+                let _ = shared.await;
+            });
+        }
+        inserted
+    }
+
+    /// Dispatch an action, reusing a prefetched result if available.
+    ///
+    /// The prefetched entry is consumed, so subsequent dispatches fetch
+    /// fresh data.
+    fn dispatch_prefetched<A>(
+        &self,
+        action: A,
+    ) -> impl Future<Output = ActionResult<A::Output, A::Error>> + Send + '_
+    where
+        A: PrefetchAction<Self>,
+    {
+        let prefetched = use_prefetch_cache()
+            .and_then(|cache| cache.take(&(self.id(), action.prefetch_key())))
+            .and_then(|entry| entry.downcast::<SharedResult<A::Output, A::Error>>().ok());
+        let deps = ActionDeps::current();
+
+        async move {
+            match prefetched {
+                Some(shared) => (*shared).await,
+                None => {
+                    middleware::run_async_action::<Self, A, _, _, _>(self, || {
+                        deps.scope(action.execute(self))
+                    })
+                    .await
+                }
+            }
+        }
+    }
+}
+
+impl<S: Store> StorePrefetchExt for S {}
+
+// ============================================================================
+// Route-based prefetching
+// ============================================================================
+
+/// Parameters captured from a matched route pattern.
+pub type RouteParams = HashMap<String, String>;
+
+type RoutePrefetcher = Arc<dyn Fn(&RouteParams) + Send + Sync>;
+
+/// A registry mapping route patterns to prefetch functions.
+///
+/// Patterns are matched segment by segment. A `:name` segment captures one
+/// path segment, and a trailing `*name` segment captures the rest of the
+/// path.
+///
+/// # Example
+///
+/// ```rust
+/// use leptos_store::prefetch::PrefetchRoutes;
+/// use std::sync::{Arc, Mutex};
+///
+/// let seen = Arc::new(Mutex::new(Vec::new()));
+/// let recorder = seen.clone();
+/// let routes = PrefetchRoutes::new().route("/tokens/:id", move |params| {
+///     recorder.lock().unwrap().push(params["id"].clone());
+/// });
+///
+/// assert!(routes.prefetch("/tokens/42?tab=chart"));
+/// assert!(!routes.prefetch("/settings"));
+/// assert_eq!(*seen.lock().unwrap(), vec!["42".to_string()]);
+/// ```
+#[derive(Clone, Default)]
+pub struct PrefetchRoutes {
+    routes: Arc<RwLock<Vec<(String, RoutePrefetcher)>>>,
+}
+
+impl PrefetchRoutes {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a prefetch function for a route pattern.
+    pub fn route(
+        self,
+        pattern: impl Into<String>,
+        prefetch: impl Fn(&RouteParams) + Send + Sync + 'static,
+    ) -> Self {
+        self.routes
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .push((pattern.into(), Arc::new(prefetch)));
+        self
+    }
+
+    /// Run the prefetch function for the first pattern matching `href`.
+    ///
+    /// The origin, query string, and fragment are ignored. Returns `true`
+    /// if a route matched.
+    pub fn prefetch(&self, href: &str) -> bool {
+        let path = route_path(href);
+        let matched = self
+            .routes
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .find_map(|(pattern, prefetch)| {
+                match_route(pattern, path).map(|params| (prefetch.clone(), params))
+            });
+
+        match matched {
+            Some((prefetch, params)) => {
+                prefetch(&params);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl fmt::Debug for PrefetchRoutes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let routes = self.routes.read().unwrap_or_else(|e| e.into_inner());
+        f.debug_struct("PrefetchRoutes")
+            .field(
+                "patterns",
+                &routes.iter().map(|(p, _)| p.as_str()).collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+/// Provide prefetch routes to the component tree.
+pub fn provide_prefetch_routes(routes: PrefetchRoutes) {
+    provide_context(routes);
+}
+
+/// Access prefetch routes from context, if provided.
+pub fn use_prefetch_routes() -> Option<PrefetchRoutes> {
+    use_context::<PrefetchRoutes>()
+}
+
+/// Strip the origin, query string, and fragment from an href.
+fn route_path(href: &str) -> &str {
+    let without_origin = match href.find("://") {
+        Some(scheme_end) => {
+            let rest = &href[scheme_end + 3..];
+            rest.find('/').map(|i| &rest[i..]).unwrap_or("/")
+        }
+        None => href,
+    };
+    let end = without_origin
+        .find(['?', '#'])
+        .unwrap_or(without_origin.len());
+    &without_origin[..end]
+}
+
+/// Match a path against a route pattern, returning captured parameters.
+fn match_route(pattern: &str, path: &str) -> Option<RouteParams> {
+    let mut params = RouteParams::new();
+    let mut path_segments = path.split('/').filter(|s| !s.is_empty());
+
+    for segment in pattern.split('/').filter(|s| !s.is_empty()) {
+        if let Some(name) = segment.strip_prefix('*') {
+            let rest: Vec<&str> = path_segments.by_ref().collect();
+            params.insert(name.to_string(), rest.join("/"));
+            return Some(params);
+        }
+        let value = path_segments.next()?;
+        match segment.strip_prefix(':') {
+            Some(name) => {
+                params.insert(name.to_string(), value.to_string());
+            }
+            None if segment == value => {}
+            None => return None,
+        }
+    }
+
+    path_segments.next().is_none().then_some(params)
+}
+
+// ============================================================================
+// Link directive
+// ============================================================================
+
+/// When a [`prefetch_link`] should trigger.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PrefetchTrigger {
+    /// On hover, focus, or touch start.
+    #[default]
+    Intent,
+    /// When the link enters the viewport.
+    Viewport,
+}
+
+/// Directive that prefetches a link's `href` through [`PrefetchRoutes`].
+///
+/// Use it as `use:prefetch_link=PrefetchTrigger::Intent` on an `<a>`
+/// element. The [`PrefetchRoutes`] must be provided in context. Each link
+/// prefetches at most once, and its listeners or observer are removed when
+/// the link is unmounted.
+#[cfg(feature = "hydrate")]
+pub fn prefetch_link(el: leptos::web_sys::Element, trigger: PrefetchTrigger) {
+    let Some(routes) = use_prefetch_routes() else {
+        return;
+    };
+    let Some(href) = el.get_attribute("href") else {
+        return;
+    };
+
+    #[cfg(target_arch = "wasm32")]
+    web::attach(el, trigger, routes, href);
+
+    #[cfg(not(target_arch = "wasm32"))]
+    let _ = (el, trigger, routes, href);
+}
+
+#[cfg(all(feature = "hydrate", target_arch = "wasm32"))]
+mod web {
+    use super::{PrefetchRoutes, PrefetchTrigger};
+    use leptos::prelude::on_cleanup;
+    use send_wrapper::SendWrapper;
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen::closure::Closure;
+    use web_sys::{Element, IntersectionObserver, IntersectionObserverEntry};
+
+    const INTENT_EVENTS: [&str; 3] = ["mouseenter", "focus", "touchstart"];
+
+    pub(super) fn attach(
+        el: Element,
+        trigger: PrefetchTrigger,
+        routes: PrefetchRoutes,
+        href: String,
+    ) {
+        let done = Rc::new(Cell::new(false));
+        let run = move || {
+            if !done.replace(true) {
+                routes.prefetch(&href);
+            }
+        };
+
+        match trigger {
+            PrefetchTrigger::Intent => {
+                let handler = Closure::<dyn Fn()>::new(run);
+                for event in INTENT_EVENTS {
+                    let _ = el
+                        .add_event_listener_with_callback(event, handler.as_ref().unchecked_ref());
+                }
+                let listener = SendWrapper::new((el, handler));
+                on_cleanup(move || {
+                    let (el, handler) = listener.take();
+                    for event in INTENT_EVENTS {
+                        let _ = el.remove_event_listener_with_callback(
+                            event,
+                            handler.as_ref().unchecked_ref(),
+                        );
+                    }
+                });
+            }
+            PrefetchTrigger::Viewport => {
+                let callback = Closure::<dyn Fn(js_sys::Array, IntersectionObserver)>::new(
+                    move |entries: js_sys::Array, observer: IntersectionObserver| {
+                        let visible = entries.iter().any(|entry| {
+                            entry
+                                .unchecked_into::<IntersectionObserverEntry>()
+                                .is_intersecting()
+                        });
+                        if visible {
+                            run();
+                            observer.disconnect();
+                        }
+                    },
+                );
+                let Ok(observer) = IntersectionObserver::new(callback.as_ref().unchecked_ref())
+                else {
+                    return;
+                };
+                observer.observe(&el);
+                let observer = SendWrapper::new((observer, callback));
+                on_cleanup(move || {
+                    let (observer, _callback) = observer.take();
+                    observer.disconnect();
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(Clone, Debug, Default)]
+    struct TokenState;
+
+    #[derive(Clone)]
+    struct TokenStore {
+        state: RwSignal<TokenState>,
+        fetches: Arc<AtomicUsize>,
+    }
+
+    impl Store for TokenStore {
+        type State = TokenState;
+
+        fn state(&self) -> ReadSignal<Self::State> {
+            self.state.read_only()
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq, thiserror::Error)]
+    #[error("fetch failed")]
+    struct FetchError;
+
+    struct FetchToken(u32);
+
+    impl AsyncAction<TokenStore> for FetchToken {
+        type Output = String;
+        type Error = FetchError;
+
+        async fn execute(&self, store: &TokenStore) -> ActionResult<String, FetchError> {
+            store.fetches.fetch_add(1, Ordering::SeqCst);
+            Ok(format!("token-{}", self.0))
+        }
+    }
+
+    impl PrefetchAction<TokenStore> for FetchToken {
+        fn prefetch_key(&self) -> String {
+            self.0.to_string()
+        }
+    }
+
+    fn store() -> TokenStore {
+        TokenStore {
+            state: RwSignal::new(TokenState),
+            fetches: Arc::default(),
+        }
+    }
+
+    #[test]
+    fn test_prefetch_dedupes_dispatch() {
+        let _ = any_spawner::Executor::init_futures_executor();
+        let owner = Owner::new();
+        owner.with(|| {
+            let store = store();
+            provide_prefetch_cache(PrefetchCache::new());
+
+            assert!(store.prefetch(FetchToken(1)));
+            // Already prefetched
+            assert!(!store.prefetch(FetchToken(1)));
+
+            let result = futures::executor::block_on(store.dispatch_prefetched(FetchToken(1)));
+            assert_eq!(result, Ok("token-1".to_string()));
+            assert_eq!(store.fetches.load(Ordering::SeqCst), 1);
+
+            // The prefetched entry was consumed
+            assert!(use_prefetch_cache().unwrap().is_empty());
+            let _ = futures::executor::block_on(store.dispatch_prefetched(FetchToken(1)));
+            assert_eq!(store.fetches.load(Ordering::SeqCst), 2);
+        });
+    }
+
+    #[test]
+    fn test_prefetch_without_cache() {
+        let owner = Owner::new();
+        owner.with(|| {
+            let store = store();
+            assert!(!store.prefetch(FetchToken(1)));
+
+            let result = futures::executor::block_on(store.dispatch_prefetched(FetchToken(2)));
+            assert_eq!(result, Ok("token-2".to_string()));
+        });
+    }
+
+    #[test]
+    fn test_prefetch_cache_capacity() {
+        let cache = PrefetchCache::with_capacity(2);
+        let store = store();
+        for key in ["a", "b", "c"] {
+            assert!(cache.insert((store.id(), key.to_string()), Box::new(())));
+        }
+        assert_eq!(cache.len(), 2);
+        assert!(!cache.contains(&store, "a"));
+        assert!(cache.contains(&store, "c"));
+
+        cache.invalidate(&store, "c");
+        assert!(!cache.contains(&store, "c"));
+    }
+
+    #[test]
+    fn test_route_matching() {
+        assert_eq!(route_path("https://example.com/a/b?x=1#top"), "/a/b");
+        assert_eq!(route_path("/a#top"), "/a");
+
+        let params = match_route("/tokens/:id", "/tokens/42").unwrap();
+        assert_eq!(params["id"], "42");
+        assert!(match_route("/tokens/:id", "/tokens/42/chart").is_none());
+        assert!(match_route("/tokens/:id", "/users/42").is_none());
+
+        let params = match_route("/docs/*path", "/docs/guide/intro").unwrap();
+        assert_eq!(params["path"], "guide/intro");
+        assert!(match_route("/", "/").is_some());
+    }
+}
@@ -26,29 +26,87 @@
 
 // Core store traits and types
 pub use crate::store::{
-    Getter, Mutator, MutatorContext, ReadonlyStore, Store, StoreBuilder, StoreError, StoreId,
+    DescribeStore, DryRun, DryRunStore, Getter, KeyedStore, MutationPlan, Mutator, MutatorContext,
+    PlannedMutation, ReadonlyStore, Store, StoreBuilder, StoreError, StoreId, StoreMethods,
     StoreRegistry,
 };
 
 // Context management
-pub use crate::context::{StoreProvider, provide_store, use_store};
+pub use crate::context::{
+    StoreFamily, StoreFamilyHandle, StoreProvider, provide_owned_store, provide_store,
+    provide_store_as, provide_store_family, use_local_store, use_store, use_store_keyed,
+    use_store_or_provide, use_store_trait,
+};
+
+// Stores built from the request being rendered
+pub use crate::request::{RequestData, StoreFromRequest};
+
+// Action dependencies
+pub use crate::deps::{ActionDeps, action_dep, provide_action_deps, require_action_dep};
+
+// Entity collections
+pub use crate::entity::EntityState;
+
+// Environment configuration
+pub use crate::env::{Profile, StoreEnv, provide_store_env, use_store_env};
+
+// Action middleware
+pub use crate::middleware::{
+    ActionContext, ActionEvent, ActionFailure, ActionSubscription, Middleware, on_action_event,
+    on_store_action_event, register_middleware, register_store_middleware,
+};
+
+pub use crate::mutation::{MutationEvent, MutationPayload, PayloadSummary, on_mutation};
+
+// Prefetching
+pub use crate::prefetch::{
+    PrefetchAction, PrefetchCache, PrefetchRoutes, StorePrefetchExt, provide_prefetch_cache,
+    provide_prefetch_routes,
+};
 
 // Async actions
 pub use crate::r#async::{
-    Action, ActionError, ActionFuture, ActionResult, ActionState, AsyncAction, AsyncActionBuilder,
+    Action, ActionError, ActionFuture, ActionResult, ActionSet, ActionState, AsyncAction,
+    AsyncActionBuilder, AsyncDispatcher, Backoff, CachedAction, CancellationToken, Concurrency,
+    Debounced, KeyedAction, Overflow, Poller, RateLimiter, ReactiveAction, RetryPolicy,
+    ServerFnAction, ServerTasks, StoreActionExt, StreamAction, Throttled, debounced_action,
+    on_page_restore, store_resource, throttled_action,
 };
 
+// Background tasks
+pub use crate::tasks::{StoreTasks, TaskHandle, TaskInfo};
+
 // Hydration support (when feature is enabled)
 #[cfg(feature = "hydrate")]
 pub use crate::hydration::{
-    HYDRATION_SCRIPT_PREFIX, HydratableStore, HydrationBuilder, StoreHydrationError,
-    has_hydration_data, hydrate_store, hydration_script_html, hydration_script_id,
-    serialize_store_state,
+    HYDRATION_BUNDLE_ID, HYDRATION_GLOBAL, HYDRATION_SCRIPT_PREFIX, HydratableStore,
+    HydrationBuilder, HydrationEvent, HydrationMask, HydrationReport, HydrationSchema,
+    HydrationSubscription, JsonCodec, StateCodec, StoreHydrationError, StoreHydrationScripts,
+    has_hydration_data, hydrate_all, hydrate_store, hydrate_store_into, hydrate_streamed,
+    hydration_script_html, hydration_script_id, on_hydration_event, register_hydratable,
+    register_rehydration_source, rehydrate, rehydrate_on_restore, serialize_store_state,
+    streamed_hydration_script,
 };
 
+#[cfg(feature = "hydrate-compress")]
+pub use crate::hydration::HydrationCompression;
+
+#[cfg(feature = "hydrate-encrypt")]
+pub use crate::hydration::{EncryptedCodec, PayloadCipher, provide_hydration_key};
+
 #[cfg(feature = "hydrate")]
 pub use crate::context::{
-    HydratableStoreContextExt, provide_hydrated_store, try_use_hydrated_store, use_hydrated_store,
+    HydratableStoreContextExt, HydratedStoreProvider, RequestStore, provide_hydrated_store,
+    provide_island_store, provide_island_store_attrs, provide_request_store, provide_shared_store,
+    register_hydrated_store, register_lazy_hydrated_store, try_use_hydrated_store,
+    use_hydrated_store,
+};
+
+// Persistence support (when feature is enabled)
+#[cfg(feature = "persist")]
+pub use crate::persistence::{
+    LocalStorage, MemoryStorage, PersistedHistory, PersistenceError, PersistentStorage, Reconcile,
+    Recovery, SessionStorage, StatePersister, StorageBackend, StorageHealth, Timestamped,
 };
 
 // Re-export commonly used Leptos types for convenience
@@ -0,0 +1,177 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2026 web-mech
+
+//! Building stores from the request being rendered.
+//!
+//! During SSR, many stores shouldn't start empty: an auth store should
+//! already hold the user logged in by the session, a settings store the
+//! theme from a cookie. [`StoreFromRequest`] builds a store from any
+//! [`RequestData`], a read-only view of the request's headers, cookies and
+//! extensions (where session middleware keeps the session), so the same
+//! store works with every server integration.
+//!
+//! The `axum` feature implements [`RequestData`] for `http::request::Parts`,
+//! and the `actix` feature for Actix's `HttpRequest`; both `AxumStores`
+//! and `ActixStores` take such stores with `from_request`.
+//!
+//! # Example
+//!
+//! ```rust
+//! use leptos::prelude::*;
+//! use leptos_store::prelude::*;
+//!
+//! #[derive(Clone, Debug, Default)]
+//! struct AuthState {
+//!     user: Option<String>,
+//! }
+//!
+//! #[derive(Clone)]
+//! struct AuthStore {
+//!     state: RwSignal<AuthState>,
+//! }
+//!
+//! impl Store for AuthStore {
+//!     type State = AuthState;
+//!
+//!     fn state(&self) -> ReadSignal<Self::State> {
+//!         self.state.read_only()
+//!     }
+//! }
+//!
+//! impl StoreFromRequest for AuthStore {
+//!     fn from_request(request: &impl RequestData) -> Self {
+//!         let user = request.cookie("user");
+//!         Self {
+//!             state: RwSignal::new(AuthState { user }),
+//!         }
+//!     }
+//! }
+//! ```
+
+use crate::store::Store;
+
+/// Read access to the request being rendered, independent of the server
+/// framework.
+pub trait RequestData {
+    /// Returns the value of the header `name`, if present and valid UTF-8.
+    fn header(&self, name: &str) -> Option<&str>;
+
+    /// Returns the value of the cookie `name`.
+    fn cookie(&self, name: &str) -> Option<String>;
+
+    /// Returns a clone of the request extension of type `T`, such as a
+    /// session or user inserted by middleware.
+    fn extension<T: Clone + Send + Sync + 'static>(&self) -> Option<T>;
+}
+
+/// A store whose initial state is built from the request being rendered.
+pub trait StoreFromRequest: Store + Sized {
+    /// Build the store for `request`.
+    fn from_request(request: &impl RequestData) -> Self;
+}
+
+/// Find the cookie `name` in the values of `Cookie` headers.
+#[cfg(any(feature = "axum", test))]
+pub(crate) fn find_cookie<'a>(
+    headers: impl IntoIterator<Item = &'a str>,
+    name: &str,
+) -> Option<String> {
+    headers
+        .into_iter()
+        .flat_map(|header| header.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value.trim_matches('"').to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use leptos::prelude::*;
+    use std::collections::HashMap;
+
+    /// Request data kept in maps.
+    #[derive(Default)]
+    struct TestRequest {
+        headers: HashMap<String, String>,
+        user: Option<String>,
+    }
+
+    impl RequestData for TestRequest {
+        fn header(&self, name: &str) -> Option<&str> {
+            self.headers.get(name).map(String::as_str)
+        }
+
+        fn cookie(&self, name: &str) -> Option<String> {
+            find_cookie(self.header("cookie"), name)
+        }
+
+        fn extension<T: Clone + Send + Sync + 'static>(&self) -> Option<T> {
+            (&self.user as &dyn std::any::Any)
+                .downcast_ref::<Option<T>>()
+                .cloned()
+                .flatten()
+        }
+    }
+
+    #[derive(Clone, Debug, Default, PartialEq)]
+    struct AuthState {
+        user: Option<String>,
+        theme: String,
+    }
+
+    #[derive(Clone)]
+    struct AuthStore {
+        state: RwSignal<AuthState>,
+    }
+
+    impl Store for AuthStore {
+        type State = AuthState;
+
+        fn state(&self) -> ReadSignal<Self::State> {
+            self.state.read_only()
+        }
+    }
+
+    impl StoreFromRequest for AuthStore {
+        fn from_request(request: &impl RequestData) -> Self {
+            Self {
+                state: RwSignal::new(AuthState {
+                    user: request.extension::<String>(),
+                    theme: request.cookie("theme").unwrap_or_default(),
+                }),
+            }
+        }
+    }
+
+    #[test]
+    fn test_find_cookie() {
+        let headers = ["a=1; theme=dark", "b=\"quoted\""];
+        assert_eq!(find_cookie(headers, "theme").as_deref(), Some("dark"));
+        assert_eq!(find_cookie(headers, "b").as_deref(), Some("quoted"));
+        assert_eq!(find_cookie(headers, "them"), None);
+        assert_eq!(find_cookie([], "theme"), None);
+    }
+
+    #[test]
+    fn test_store_from_request() {
+        let owner = Owner::new();
+        owner.with(|| {
+            let request = TestRequest {
+                headers: HashMap::from([("cookie".to_string(), "theme=dark".to_string())]),
+                user: Some("alice".to_string()),
+            };
+            let store = AuthStore::from_request(&request);
+            assert_eq!(
+                store.state.get_untracked(),
+                AuthState {
+                    user: Some("alice".to_string()),
+                    theme: "dark".to_string(),
+                }
+            );
+
+            let store = AuthStore::from_request(&TestRequest::default());
+            assert_eq!(store.state.get_untracked(), AuthState::default());
+        });
+    }
+}
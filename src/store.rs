@@ -9,16 +9,82 @@
 //! - [`StoreBuilder`] - Builder pattern for constructing stores
 //! - [`Getter`] - Trait for derived, read-only computed values
 //! - [`Mutator`] - Trait for pure, synchronous state mutations
+//! - [`DryRun`] - Record mutations against a shadow copy without committing
+//! - [`DryRunStore`] - Stores that actions can be dry-run against
 //! - [`StoreRegistry`] - Registry for managing multiple stores
+//! - [`KeyedStore`] - Stores with a stable key, recorded by [`register_store_key`]
 
 use leptos::prelude::*;
 use std::any::{Any, TypeId};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
 use std::marker::PhantomData;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use thiserror::Error;
 
+/// Derive macro for the [`Store`] trait.
+///
+/// See the [`Store`] trait documentation for the supported attributes.
+pub use leptos_store_macros::Store;
+
+/// Attribute macro classifying the methods of a store impl block.
+///
+/// Methods are marked `#[getter]`, `#[mutator]`, or `#[action]`, and the
+/// macro enforces the layer rules at compile time:
+///
+/// - getters and mutators take `&self` and are synchronous
+/// - mutators must be private, so state changes go through actions
+/// - actions are made `pub` unless a visibility is given
+///
+/// It also generates the `read`/`mutate` helpers used by the `store!` macro
+/// and implements [`DescribeStore`] with the classified method names. The
+/// state signal field defaults to `state`; use `#[store(state = field)]`
+/// otherwise. The type must implement [`Store`] (e.g. via
+/// `#[derive(Store)]`).
+///
+/// # Example
+///
+/// ```rust
+/// use leptos::prelude::*;
+/// use leptos_store::prelude::*;
+/// use leptos_store::store::{DescribeStore, store};
+///
+/// #[derive(Clone, Debug, Default)]
+/// struct AuthState {
+///     user: Option<String>,
+/// }
+///
+/// #[derive(Clone, Store)]
+/// #[store(key = "auth")]
+/// struct AuthStore {
+///     state: RwSignal<AuthState>,
+/// }
+///
+/// #[store]
+/// impl AuthStore {
+///     #[getter]
+///     pub fn is_authenticated(&self) -> bool {
+///         self.read(|s| s.user.is_some())
+///     }
+///
+///     #[mutator]
+///     fn set_user(&self, user: Option<String>) {
+///         self.mutate(|s| s.user = user);
+///     }
+///
+///     #[action]
+///     fn login(&self, name: &str) {
+///         self.set_user(Some(name.to_string()));
+///     }
+/// }
+///
+/// let store = AuthStore { state: RwSignal::new(AuthState::default()) };
+/// store.login("ada");
+/// assert!(store.is_authenticated());
+/// assert_eq!(AuthStore::methods().mutators, &["set_user"]);
+/// ```
+pub use leptos_store_macros::store;
+
 /// Unique identifier for a store instance.
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub struct StoreId {
@@ -88,6 +154,41 @@ pub enum StoreError {
 /// - `State`: The type of state this store manages. Must be `Clone` for
 ///   reactive updates and `'static` for Leptos compatibility.
 ///
+/// # Deriving
+///
+/// `#[derive(Store)]` implements this trait for a struct holding an
+/// `RwSignal<State>` field. The state field is the one marked
+/// `#[store(state)]`, the field named `state`, or the only `RwSignal`
+/// field. Struct-level options:
+///
+/// - `#[store(key = "...")]` - override [`Store::name`]
+/// - `#[store(default)]` - implement `Default`
+/// - `#[store(constructors)]` - generate `new()` and `with_state(state)`
+///
+/// Non-state fields are initialized with `Default::default()` by the
+/// generated constructors.
+///
+/// ```rust
+/// use leptos::prelude::*;
+/// use leptos_store::prelude::*;
+///
+/// #[derive(Clone, Debug, Default)]
+/// struct UserState {
+///     name: String,
+/// }
+///
+/// #[derive(Clone, Store)]
+/// #[store(key = "user", default, constructors)]
+/// struct UserStore {
+///     #[store(state)]
+///     user: RwSignal<UserState>,
+/// }
+///
+/// let store = UserStore::with_state(UserState { name: "Ada".to_string() });
+/// assert_eq!(store.state().get().name, "Ada");
+/// assert_eq!(store.name(), "user");
+/// ```
+///
 /// # Hydration Support
 ///
 /// For SSR hydration support, implement the `HydratableStore` trait
@@ -150,6 +251,104 @@ pub trait Store: Clone + Send + Sync + 'static {
     }
 }
 
+/// Mirror a memo into a `ReadSignal`, so a store can be backed by it.
+///
+/// [`Store::state`] returns a `ReadSignal`, which a [`Memo`] can't be
+/// turned into. Call this once when the store is built, keep the result in
+/// a field, and name it with `impl_store!(S, State, field: Memo => reader)`.
+///
+/// The signal is updated by an effect, on the server too, so it catches up
+/// with the memo once effects have run rather than synchronously; an
+/// immediate effect would deadlock reading the memo while it notifies.
+/// The effect stops when the current owner is cleaned up.
+pub fn memo_reader<T: Clone + Send + Sync + 'static>(memo: Memo<T>) -> ReadSignal<T> {
+    let (reader, writer) = signal(memo.get_untracked());
+    Effect::new_isomorphic(move |_| writer.set(memo.get()));
+    reader
+}
+
+/// A store with a stable, unique key.
+///
+/// The key identifies the store in hydration scripts, devtools, and the
+/// [`StoreRegistry`]. Declaring it once, usually with
+/// `store! { pub TokenStore(key = "token_store") { ... } }`, keeps those
+/// places from drifting apart.
+pub trait KeyedStore: Store {
+    /// The store key. Must be unique across all stores in the application.
+    const KEY: &'static str;
+}
+
+/// Store keys recorded so far, mapped to the store type name.
+static STORE_KEYS: Mutex<BTreeMap<&'static str, &'static str>> = Mutex::new(BTreeMap::new());
+
+/// Record the key of `S` in the global key directory.
+///
+/// Stores generated by [`store!`](crate::store!) with a key call this when
+/// they are created, so devtools can list them with
+/// [`registered_store_keys`]. Registering the same store again is a no-op.
+///
+/// # Errors
+///
+/// Returns [`StoreError::AlreadyExists`] if the key is already used by a
+/// different store type.
+pub fn register_store_key<S: KeyedStore>() -> Result<(), StoreError> {
+    let name = std::any::type_name::<S>();
+    let mut keys = STORE_KEYS.lock().unwrap_or_else(|e| e.into_inner());
+    match keys.get(S::KEY) {
+        Some(existing) if *existing != name => Err(StoreError::AlreadyExists(format!(
+            "key `{}` is used by both {existing} and {name}",
+            S::KEY
+        ))),
+        Some(_) => Ok(()),
+        None => {
+            keys.insert(S::KEY, name);
+            Ok(())
+        }
+    }
+}
+
+/// Returns the registered store keys and store type names, sorted by key.
+pub fn registered_store_keys() -> Vec<(&'static str, &'static str)> {
+    STORE_KEYS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .map(|(key, name)| (*key, *name))
+        .collect()
+}
+
+/// Names of the getters, mutators, and actions declared on a store.
+///
+/// Used by devtools and diagnostics to list a store's API.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StoreMethods {
+    /// Getter method names.
+    pub getters: &'static [&'static str],
+    /// Mutator method names.
+    pub mutators: &'static [&'static str],
+    /// Action method names.
+    pub actions: &'static [&'static str],
+}
+
+impl StoreMethods {
+    /// Check if a method with the given name is declared.
+    pub fn contains(&self, name: &str) -> bool {
+        self.getters
+            .iter()
+            .chain(self.mutators)
+            .chain(self.actions)
+            .any(|m| *m == name)
+    }
+}
+
+/// Trait for stores that can describe their methods.
+///
+/// Implemented by the [`store`](macro@store) attribute macro.
+pub trait DescribeStore: Store {
+    /// Returns the names of the store's getters, mutators, and actions.
+    fn methods() -> StoreMethods;
+}
+
 /// A read-only view into a store.
 ///
 /// This wrapper ensures that consumers can only read state,
@@ -315,6 +514,365 @@ where
     }
 }
 
+/// A single mutation recorded during a dry run.
+///
+/// Holds the state before and after the mutation so callers can inspect
+/// exactly what would change.
+#[derive(Clone, Debug)]
+pub struct PlannedMutation<State> {
+    name: String,
+    before: State,
+    after: State,
+}
+
+impl<State> PlannedMutation<State> {
+    /// Get the mutation name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the state before the mutation.
+    pub fn before(&self) -> &State {
+        &self.before
+    }
+
+    /// Get the state after the mutation.
+    pub fn after(&self) -> &State {
+        &self.after
+    }
+
+    /// Check if the mutation would leave the state unchanged.
+    pub fn is_noop(&self) -> bool
+    where
+        State: PartialEq,
+    {
+        self.before == self.after
+    }
+
+    /// Compute a line diff of the pretty-printed state.
+    ///
+    /// Only changed lines are returned, prefixed with `-` or `+`.
+    pub fn diff(&self) -> Vec<String>
+    where
+        State: fmt::Debug,
+    {
+        let before = format!("{:#?}", self.before);
+        let after = format!("{:#?}", self.after);
+        line_diff(&before, &after)
+    }
+}
+
+/// Diff two multi-line strings using a longest-common-subsequence table.
+fn line_diff(before: &str, after: &str) -> Vec<String> {
+    let a: Vec<&str> = before.lines().collect();
+    let b: Vec<&str> = after.lines().collect();
+
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            diff.push(format!("-{}", a[i]));
+            i += 1;
+        } else {
+            diff.push(format!("+{}", b[j]));
+            j += 1;
+        }
+    }
+    diff.extend(a[i..].iter().map(|line| format!("-{line}")));
+    diff.extend(b[j..].iter().map(|line| format!("+{line}")));
+    diff
+}
+
+/// The result of a dry run: every mutation that would be performed.
+///
+/// A plan can be inspected (e.g. for a "preview changes" dialog) and
+/// later committed in a single update, as long as the state hasn't changed
+/// since the dry run.
+#[derive(Clone, Debug)]
+pub struct MutationPlan<State> {
+    initial: State,
+    mutations: Vec<PlannedMutation<State>>,
+    final_state: State,
+}
+
+impl<State: Clone> MutationPlan<State> {
+    /// Get the recorded mutations in order.
+    pub fn mutations(&self) -> &[PlannedMutation<State>] {
+        &self.mutations
+    }
+
+    /// Get the names of the recorded mutations in order.
+    pub fn names(&self) -> Vec<&str> {
+        self.mutations.iter().map(|m| m.name()).collect()
+    }
+
+    /// Get the state the dry run started from.
+    pub fn initial_state(&self) -> &State {
+        &self.initial
+    }
+
+    /// Get the state that committing the plan would produce.
+    pub fn final_state(&self) -> &State {
+        &self.final_state
+    }
+
+    /// Check if no mutations were recorded.
+    pub fn is_empty(&self) -> bool {
+        self.mutations.is_empty()
+    }
+
+    /// Get the number of recorded mutations.
+    pub fn len(&self) -> usize {
+        self.mutations.len()
+    }
+
+    /// Consume the plan, returning the final state.
+    pub fn into_state(self) -> State {
+        self.final_state
+    }
+
+    /// Commit the plan to a state signal in a single update.
+    ///
+    /// Returns `false` and leaves the signal alone if its state no longer
+    /// equals the state the dry run started from, since committing would
+    /// silently undo the changes made since; dry-run the action again.
+    pub fn commit(self, signal: RwSignal<State>) -> bool
+    where
+        State: PartialEq + Send + Sync + 'static,
+    {
+        if !signal.with_untracked(|state| *state == self.initial) {
+            return false;
+        }
+        if self.final_state != self.initial {
+            signal.set(self.final_state);
+        }
+        true
+    }
+
+    /// Commit the plan to `store`, like [`commit`](Self::commit).
+    pub fn commit_to<S>(self, store: &S) -> bool
+    where
+        S: DryRunStore<State = State>,
+        State: PartialEq + Send + Sync + 'static,
+    {
+        if !store.state().with_untracked(|state| *state == self.initial) {
+            return false;
+        }
+        if self.final_state != self.initial {
+            store.replace_state(self.final_state);
+        }
+        true
+    }
+}
+
+/// A shadow copy of state that records mutations instead of committing them.
+///
+/// [`StoreActionExt::dry_run`](crate::r#async::StoreActionExt::dry_run)
+/// fills one in from the mutators an action calls; it can also be driven
+/// by hand.
+///
+/// # Example
+///
+/// ```rust
+/// use leptos_store::prelude::*;
+///
+/// #[derive(Clone, Debug, Default, PartialEq)]
+/// struct CartState {
+///     items: Vec<String>,
+/// }
+///
+/// let mut dry_run = DryRun::new(CartState {
+///     items: vec!["apple".to_string(), "pear".to_string()],
+/// });
+/// dry_run.apply("clear_cart", |ctx: &mut MutatorContext<CartState>| {
+///     ctx.state_mut().items.clear();
+/// });
+///
+/// let plan = dry_run.into_plan();
+/// assert_eq!(plan.names(), vec!["clear_cart"]);
+/// assert!(plan.final_state().items.is_empty());
+/// assert!(plan.mutations()[0].diff().iter().any(|l| l.contains("apple")));
+/// ```
+#[derive(Clone, Debug)]
+pub struct DryRun<State> {
+    initial: State,
+    current: State,
+    mutations: Vec<PlannedMutation<State>>,
+}
+
+impl<State: Clone> DryRun<State> {
+    /// Start a dry run from a snapshot of the state.
+    pub fn new(state: State) -> Self {
+        Self {
+            initial: state.clone(),
+            current: state,
+            mutations: Vec::new(),
+        }
+    }
+
+    /// Apply a named mutator to the shadow state and record the change.
+    pub fn apply(&mut self, name: impl Into<String>, mutator: impl Mutator<State>) -> &mut Self {
+        let before = self.current.clone();
+        {
+            let mut ctx = MutatorContext::new(&mut self.current);
+            mutator.mutate(&mut ctx);
+        }
+        let after = self.current.clone();
+        self.record(name, before, after);
+        self
+    }
+
+    /// Get the current shadow state.
+    pub fn state(&self) -> &State {
+        &self.current
+    }
+
+    /// Finish the dry run, returning the recorded plan.
+    pub fn into_plan(self) -> MutationPlan<State> {
+        MutationPlan {
+            initial: self.initial,
+            mutations: self.mutations,
+            final_state: self.current,
+        }
+    }
+
+    /// Record a mutation that moved the shadow state from `before` to `after`.
+    fn record(&mut self, name: impl Into<String>, before: State, after: State) {
+        self.current = after.clone();
+        self.mutations.push(PlannedMutation {
+            name: name.into(),
+            before,
+            after,
+        });
+    }
+}
+
+/// A store that actions can be dry-run against.
+///
+/// [`shadow`](Self::shadow) builds a copy of the store around a new signal
+/// holding `state`, sharing its dependencies but none of its reactive
+/// state, so an action can run against it unchanged. The
+/// [`store!`](crate::store!) macro implements it, and records each of its
+/// mutators into the active dry run.
+pub trait DryRunStore: Store {
+    /// Create a detached copy of the store holding `state`.
+    ///
+    /// The copy doesn't run the store's effects or persistence.
+    fn shadow(&self, state: Self::State) -> Self;
+
+    /// Replace the store's state, as when committing a [`MutationPlan`].
+    fn replace_state(&self, state: Self::State);
+}
+
+/// The dry run in progress on this thread.
+struct ActiveDryRun {
+    store: TypeId,
+    depth: usize,
+    run: Box<dyn Any>,
+}
+
+thread_local! {
+    static DRY_RUN: std::cell::RefCell<Option<ActiveDryRun>> = const { std::cell::RefCell::new(None) };
+}
+
+/// Check if mutators of `S` are currently recorded by a dry run.
+#[doc(hidden)]
+pub fn in_dry_run<S: Store>() -> bool {
+    DRY_RUN.with(|active| {
+        active
+            .borrow()
+            .as_ref()
+            .is_some_and(|active| active.store == TypeId::of::<S>())
+    })
+}
+
+/// Run the body of the mutator `name` of `S`, recording it into the active
+/// dry run of `S`, if any.
+///
+/// Only the outermost mutator is recorded when mutators call each other.
+#[doc(hidden)]
+pub fn record_mutation<S: Store>(
+    name: &'static str,
+    state: impl Fn() -> S::State,
+    body: impl FnOnce(),
+) {
+    let depth = DRY_RUN.with(|active| {
+        let mut active = active.borrow_mut();
+        let active = active
+            .as_mut()
+            .filter(|active| active.store == TypeId::of::<S>())?;
+        active.depth += 1;
+        Some(active.depth)
+    });
+    let Some(depth) = depth else {
+        return body();
+    };
+
+    let before = (depth == 1).then(&state);
+    body();
+    let after = before.is_some().then(&state);
+    DRY_RUN.with(|active| {
+        if let Some(active) = active.borrow_mut().as_mut() {
+            active.depth -= 1;
+            if let (Some(before), Some(after), Some(run)) =
+                (before, after, active.run.downcast_mut::<DryRun<S::State>>())
+            {
+                run.record(name, before, after);
+            }
+        }
+    });
+}
+
+/// Restores the enclosing dry run, even if the action panics.
+struct DryRunGuard(Option<ActiveDryRun>);
+
+impl Drop for DryRunGuard {
+    fn drop(&mut self) {
+        let previous = self.0.take();
+        DRY_RUN.with(|active| *active.borrow_mut() = previous);
+    }
+}
+
+/// Record the mutators of `S` called by `run` into a dry run starting from
+/// `initial`. `run` returns the final shadow state.
+pub(crate) fn record_dry_run<S: Store>(
+    initial: S::State,
+    run: impl FnOnce() -> S::State,
+) -> MutationPlan<S::State> {
+    let previous = DRY_RUN.with(|active| {
+        active.borrow_mut().replace(ActiveDryRun {
+            store: TypeId::of::<S>(),
+            depth: 0,
+            run: Box::new(DryRun::new(initial.clone())),
+        })
+    });
+    let guard = DryRunGuard(previous);
+    let final_state = run();
+
+    let recorded = DRY_RUN.with(|active| active.borrow_mut().take());
+    drop(guard);
+    let mut dry_run = recorded
+        .and_then(|active| active.run.downcast::<DryRun<S::State>>().ok())
+        .map(|run| *run)
+        .unwrap_or_else(|| DryRun::new(initial));
+    dry_run.current = final_state;
+    dry_run.into_plan()
+}
+
 /// Builder for constructing stores with fluent API.
 ///
 /// # Example
@@ -393,6 +951,7 @@ impl<State: Clone + Send + Sync + 'static> StoreBuilder<State> {
 #[derive(Default)]
 pub struct StoreRegistry {
     stores: HashMap<StoreId, Arc<dyn Any + Send + Sync>>,
+    keys: HashMap<&'static str, StoreId>,
 }
 
 impl StoreRegistry {
@@ -411,6 +970,26 @@ impl StoreRegistry {
         Ok(id)
     }
 
+    /// Register a keyed store, so it can also be looked up by its key.
+    pub fn register_keyed<S: KeyedStore>(&mut self, store: S) -> Result<StoreId, StoreError> {
+        if self.keys.contains_key(S::KEY) {
+            return Err(StoreError::AlreadyExists(format!("key `{}`", S::KEY)));
+        }
+        let id = self.register(store)?;
+        self.keys.insert(S::KEY, id);
+        Ok(id)
+    }
+
+    /// Returns the ID of the store registered under `key`.
+    pub fn id_for_key(&self, key: &str) -> Option<StoreId> {
+        self.keys.get(key).copied()
+    }
+
+    /// Returns the keys of the registered keyed stores.
+    pub fn keys(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.keys.keys().copied()
+    }
+
     /// Get a store from the registry.
     pub fn get<S: Store + Send + Sync>(&self) -> Option<Arc<S>> {
         let id = StoreId::new::<S>();
@@ -422,6 +1001,7 @@ impl StoreRegistry {
     /// Remove a store from the registry.
     pub fn unregister<S: Store>(&mut self) -> bool {
         let id = StoreId::new::<S>();
+        self.keys.retain(|_, registered| *registered != id);
         self.stores.remove(&id).is_some()
     }
 
@@ -473,6 +1053,60 @@ mod tests {
         }
     }
 
+    #[derive(Clone)]
+    struct OtherStore {
+        state: RwSignal<TestState>,
+    }
+
+    impl Store for OtherStore {
+        type State = TestState;
+
+        fn state(&self) -> ReadSignal<Self::State> {
+            self.state.read_only()
+        }
+    }
+
+    impl KeyedStore for TestStore {
+        const KEY: &'static str = "store_test";
+    }
+
+    impl KeyedStore for OtherStore {
+        const KEY: &'static str = "store_test";
+    }
+
+    #[test]
+    fn test_store_keys() {
+        assert!(register_store_key::<TestStore>().is_ok());
+        assert!(register_store_key::<TestStore>().is_ok());
+        assert!(matches!(
+            register_store_key::<OtherStore>(),
+            Err(StoreError::AlreadyExists(_))
+        ));
+        assert!(
+            registered_store_keys().contains(&("store_test", std::any::type_name::<TestStore>()))
+        );
+
+        let mut registry = StoreRegistry::new();
+        let id = registry
+            .register_keyed(TestStore {
+                state: RwSignal::new(TestState::default()),
+            })
+            .unwrap();
+        assert_eq!(registry.id_for_key("store_test"), Some(id));
+        assert!(
+            registry
+                .register_keyed(OtherStore {
+                    state: RwSignal::new(TestState::default()),
+                })
+                .is_err()
+        );
+        assert!(!registry.contains::<OtherStore>());
+
+        assert!(registry.unregister::<TestStore>());
+        assert_eq!(registry.id_for_key("store_test"), None);
+        assert_eq!(registry.keys().count(), 0);
+    }
+
     #[test]
     fn test_store_id_creation() {
         let id1 = StoreId::new::<TestStore>();
@@ -545,6 +1179,119 @@ mod tests {
         assert_eq!(state.name, "Charlie");
     }
 
+    #[derive(Clone, Store)]
+    #[store(key = "derived", default, constructors)]
+    struct DerivedStore {
+        #[store(state)]
+        data: RwSignal<TestState>,
+        other: RwSignal<u32>,
+        label: String,
+    }
+
+    #[test]
+    fn test_derive_store() {
+        let store = DerivedStore::with_state(TestState {
+            count: 3,
+            name: "derived".to_string(),
+        });
+        assert_eq!(store.state().get().count, 3);
+        assert_eq!(store.name(), "derived");
+        assert_eq!(store.other.get(), 0);
+        assert!(store.label.is_empty());
+
+        let store = DerivedStore::default();
+        assert_eq!(store.state().get(), TestState::default());
+        assert_eq!(DerivedStore::new().data.get(), TestState::default());
+    }
+
+    #[store(state = data)]
+    impl DerivedStore {
+        #[getter]
+        fn count(&self) -> i32 {
+            self.read(|s| s.count)
+        }
+
+        #[mutator]
+        fn set_count(&self, count: i32) {
+            self.mutate(|s| s.count = count);
+        }
+
+        #[action]
+        fn reset(&self) {
+            self.set_count(0);
+        }
+
+        fn helper(&self) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn test_store_attribute() {
+        let store = DerivedStore::with_state(TestState {
+            count: 5,
+            name: String::new(),
+        });
+        assert_eq!(store.count(), 5);
+        store.reset();
+        assert_eq!(store.count(), 0);
+        assert!(store.helper());
+
+        let methods = DerivedStore::methods();
+        assert_eq!(methods.getters, &["count"]);
+        assert_eq!(methods.mutators, &["set_count"]);
+        assert_eq!(methods.actions, &["reset"]);
+        assert!(methods.contains("reset"));
+        assert!(!methods.contains("helper"));
+    }
+
+    #[test]
+    fn test_dry_run_records_without_committing() {
+        let signal = RwSignal::new(TestState {
+            count: 1,
+            name: "Alice".to_string(),
+        });
+
+        let mut dry_run = DryRun::new(signal.get());
+        dry_run
+            .apply("increment", |ctx: &mut MutatorContext<TestState>| {
+                ctx.state_mut().count += 1;
+            })
+            .apply("rename", |ctx: &mut MutatorContext<TestState>| {
+                ctx.state_mut().name = "Bob".to_string();
+            })
+            .apply("noop", |_: &mut MutatorContext<TestState>| {});
+        let plan = dry_run.into_plan();
+
+        // Nothing committed yet
+        assert_eq!(signal.get().count, 1);
+
+        assert_eq!(plan.names(), vec!["increment", "rename", "noop"]);
+        assert_eq!(plan.mutations()[0].before().count, 1);
+        assert_eq!(plan.mutations()[0].after().count, 2);
+        assert!(plan.mutations()[2].is_noop());
+
+        let diff = plan.mutations()[1].diff();
+        assert_eq!(diff.len(), 2);
+        assert!(diff[0].starts_with('-') && diff[0].contains("Alice"));
+        assert!(diff[1].starts_with('+') && diff[1].contains("Bob"));
+
+        let stale = plan.clone();
+        assert!(plan.commit(signal));
+        assert_eq!(
+            signal.get(),
+            TestState {
+                count: 2,
+                name: "Bob".to_string(),
+            }
+        );
+
+        // The state moved on since the dry run, so the plan is refused
+        signal.update(|s| s.count = 10);
+        assert!(!stale.commit(signal));
+        assert_eq!(signal.get().count, 10);
+    }
+
     #[test]
     fn test_store_error_display() {
         let err = StoreError::NotFound("TestStore".to_string());
@@ -0,0 +1,376 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2026 web-mech
+
+//! Background tasks supervised per store instance.
+//!
+//! [`StoreActionExt::tasks`] returns the [`StoreTasks`] of a store, which
+//! spawns named background tasks, lists the ones still running, and aborts
+//! them all when the store goes away. Use it for intervals, syncs, and
+//! fetches that should live as long as the store rather than a single
+//! component, so they can't outlive it and leak.
+//!
+//! The supervisor is created by the first call to `tasks()` on a store
+//! instance and is bound to the reactive owner current at that point, so
+//! call it where the store is created (next to `provide_store`). When that
+//! owner is disposed, every task is aborted at its next await point.
+//! Outside an owner, tasks run until they finish or are aborted.
+//!
+//! Like [`StoreActionExt::spawn`], tasks run on the local executor in the
+//! browser, and during SSR only if the store environment sets
+//! [`ServerTasks::Spawn`].
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let store = TokenStore::new();
+//! provide_store(store.clone());
+//!
+//! let this = store.clone();
+//! store.tasks().spawn("price-feed", async move {
+//!     while let Some(update) = feed.next().await {
+//!         this.apply_price(update);
+//!     }
+//! });
+//!
+//! // In a devtools panel
+//! let names = move || store.tasks().running().iter().map(|t| t.name().to_string()).collect::<Vec<_>>();
+//! ```
+//!
+//! [`StoreActionExt::tasks`]: crate::r#async::StoreActionExt::tasks
+//! [`StoreActionExt::spawn`]: crate::r#async::StoreActionExt::spawn
+//! [`ServerTasks::Spawn`]: crate::r#async::ServerTasks::Spawn
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::r#async::ServerTasks;
+use crate::r#async::{CancellationToken, now_ms};
+use crate::store::Store;
+use leptos::prelude::*;
+use std::any::{Any, TypeId};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::future::Future;
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// A task registered with a [`StoreTasks`].
+struct RunningTask {
+    info: TaskInfo,
+    token: CancellationToken,
+}
+
+#[derive(Default)]
+struct TaskList {
+    running: Vec<RunningTask>,
+    next_id: u64,
+}
+
+/// Supervisors of every store instance that has one, by store type.
+type Supervisors<S> = Vec<(ArcReadSignal<<S as Store>::State>, StoreTasks)>;
+
+static SUPERVISORS: Mutex<BTreeMap<TypeId, Box<dyn Any + Send>>> = Mutex::new(BTreeMap::new());
+
+/// Runs the background tasks of one store instance.
+///
+/// See the [module documentation](self). Clones share the same tasks.
+#[derive(Clone, Default)]
+pub struct StoreTasks {
+    tasks: Arc<Mutex<TaskList>>,
+}
+
+impl StoreTasks {
+    /// Returns the supervisor of `store`, creating it if needed.
+    pub(crate) fn of<S: Store>(store: &S) -> Self {
+        let state = ArcReadSignal::from(store.state());
+        let mut registry = SUPERVISORS.lock().unwrap_or_else(PoisonError::into_inner);
+        let supervisors = registry
+            .entry(TypeId::of::<S>())
+            .or_insert_with(|| Box::new(Supervisors::<S>::new()))
+            .downcast_mut::<Supervisors<S>>()
+            .expect("supervisors are keyed by store type");
+        if let Some((_, tasks)) = supervisors.iter().find(|(signal, _)| *signal == state) {
+            return tasks.clone();
+        }
+        let tasks = Self::default();
+        supervisors.push((state.clone(), tasks.clone()));
+        on_cleanup({
+            let tasks = tasks.clone();
+            move || {
+                Self::forget::<S>(&state);
+                tasks.abort_all();
+            }
+        });
+        tasks
+    }
+
+    /// Remove the supervisor of the store whose state is `state`.
+    fn forget<S: Store>(state: &ArcReadSignal<S::State>) {
+        let mut registry = SUPERVISORS.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(supervisors) = registry
+            .get_mut(&TypeId::of::<S>())
+            .and_then(|supervisors| supervisors.downcast_mut::<Supervisors<S>>())
+        {
+            supervisors.retain(|(signal, _)| signal != state);
+        }
+    }
+
+    /// Spawn a background task named `name`.
+    ///
+    /// The task is listed by [`running`](Self::running) until it finishes
+    /// or is aborted. During SSR without
+    /// [`ServerTasks::Spawn`](crate::r#async::ServerTasks::Spawn) it is
+    /// skipped, and the returned handle is already aborted.
+    pub fn spawn<F>(&self, name: impl Into<String>, task: F) -> TaskHandle
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let token = CancellationToken::new();
+        let mut list = self.tasks.lock().unwrap_or_else(PoisonError::into_inner);
+        let info = TaskInfo {
+            id: list.next_id,
+            name: name.into(),
+            started_at_ms: now_ms(),
+        };
+        list.next_id += 1;
+        let handle = TaskHandle {
+            info: info.clone(),
+            token: token.clone(),
+        };
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if ServerTasks::current() == ServerTasks::Skip {
+            token.cancel();
+            return handle;
+        }
+
+        list.running.push(RunningTask {
+            info,
+            token: token.clone(),
+        });
+        drop(list);
+
+        let tasks = self.clone();
+        let id = handle.info.id;
+        let task = token.run(task);
+        leptos::task::spawn(async move {
+            let _ = task.await;
+            tasks.remove(id);
+        });
+        handle
+    }
+
+    /// Returns the tasks still running, oldest first.
+    pub fn running(&self) -> Vec<TaskInfo> {
+        self.tasks
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .running
+            .iter()
+            .map(|task| task.info.clone())
+            .collect()
+    }
+
+    /// Returns the number of tasks still running.
+    pub fn len(&self) -> usize {
+        self.tasks
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .running
+            .len()
+    }
+
+    /// Check if no task is running.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Abort the running tasks named `name`, returning how many there were.
+    pub fn abort(&self, name: &str) -> usize {
+        let aborted: Vec<_> = {
+            let mut list = self.tasks.lock().unwrap_or_else(PoisonError::into_inner);
+            let (aborted, kept) = list
+                .running
+                .drain(..)
+                .partition::<Vec<_>, _>(|task| task.info.name == name);
+            list.running = kept;
+            aborted
+        };
+        for task in &aborted {
+            task.token.cancel();
+        }
+        aborted.len()
+    }
+
+    /// Abort every running task.
+    pub fn abort_all(&self) {
+        let aborted = std::mem::take(
+            &mut self
+                .tasks
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .running,
+        );
+        for task in aborted {
+            task.token.cancel();
+        }
+    }
+
+    /// Forget task `id` once it has finished.
+    fn remove(&self, id: u64) {
+        self.tasks
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .running
+            .retain(|task| task.info.id != id);
+    }
+}
+
+impl fmt::Debug for StoreTasks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StoreTasks")
+            .field("running", &self.running())
+            .finish()
+    }
+}
+
+/// Describes a task spawned with [`StoreTasks::spawn`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TaskInfo {
+    id: u64,
+    name: String,
+    started_at_ms: u64,
+}
+
+impl TaskInfo {
+    /// Returns the task's id, unique within its store.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Returns the task's name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns when the task was spawned, in milliseconds since the Unix
+    /// epoch.
+    pub fn started_at_ms(&self) -> u64 {
+        self.started_at_ms
+    }
+}
+
+/// Controls a task spawned with [`StoreTasks::spawn`].
+#[derive(Clone, Debug)]
+pub struct TaskHandle {
+    info: TaskInfo,
+    token: CancellationToken,
+}
+
+impl TaskHandle {
+    /// Returns the task's description.
+    pub fn info(&self) -> &TaskInfo {
+        &self.info
+    }
+
+    /// Abort the task at its next await point.
+    pub fn abort(&self) {
+        self.token.cancel();
+    }
+
+    /// Check if the task was aborted, or skipped during SSR.
+    pub fn is_aborted(&self) -> bool {
+        self.token.is_cancelled()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::r#async::{ServerTasks, StoreActionExt};
+    use crate::env::{Profile, StoreEnv, provide_store_env};
+    use std::time::{Duration, Instant};
+
+    #[derive(Clone)]
+    struct TestStore {
+        state: RwSignal<i32>,
+    }
+
+    impl Store for TestStore {
+        type State = i32;
+
+        fn state(&self) -> ReadSignal<Self::State> {
+            self.state.read_only()
+        }
+    }
+
+    fn wait_until(condition: impl Fn() -> bool) {
+        let start = Instant::now();
+        while !condition() {
+            assert!(start.elapsed() < Duration::from_secs(5), "timed out");
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn test_store_tasks() {
+        let _ = any_spawner::Executor::init_futures_executor();
+        let owner = Owner::new();
+        let (forever, other) = owner.with(|| {
+            provide_store_env(
+                StoreEnv::builder()
+                    .config(ServerTasks::Spawn)
+                    .build(Profile::Test),
+            );
+            let store = TestStore {
+                state: RwSignal::new(0),
+            };
+            let other = TestStore {
+                state: RwSignal::new(0),
+            };
+
+            let forever = store
+                .tasks()
+                .spawn("feed", futures::future::pending::<()>());
+            let done = store.tasks().spawn("fetch", async {});
+            let stopped = store
+                .tasks()
+                .spawn("sync", futures::future::pending::<()>());
+            let other_task = other
+                .tasks()
+                .spawn("feed", futures::future::pending::<()>());
+            assert_eq!(done.info().name(), "fetch");
+            assert!(forever.info().started_at_ms() > 0);
+
+            // Finished tasks drop out of the list.
+            wait_until(|| store.tasks().len() == 2);
+            let names: Vec<_> = store
+                .tasks()
+                .running()
+                .iter()
+                .map(|task| task.name().to_string())
+                .collect();
+            assert_eq!(names, ["feed", "sync"]);
+
+            assert_eq!(store.tasks().abort("sync"), 1);
+            assert!(stopped.is_aborted());
+            assert_eq!(other.tasks().len(), 1);
+            (forever, other_task)
+        });
+
+        // Disposing the owner aborts the tasks of every store.
+        owner.cleanup();
+        assert!(forever.is_aborted());
+        assert!(other.is_aborted());
+    }
+
+    #[test]
+    fn test_server_tasks_skipped() {
+        let owner = Owner::new();
+        owner.with(|| {
+            let store = TestStore {
+                state: RwSignal::new(0),
+            };
+            let task = store.tasks().spawn("feed", async {});
+            assert!(task.is_aborted());
+            assert!(store.tasks().is_empty());
+        });
+    }
+}
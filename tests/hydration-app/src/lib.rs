@@ -0,0 +1,119 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2026 web-mech
+
+//! Hydration Test App
+//!
+//! A minimal app exercising the `leptos-store` hydration context API.
+//! The server half renders [`ServerApp`], which embeds the store state with
+//! `provide_hydrated_store`; the client half renders [`ClientApp`], which
+//! restores it with `HydrationBuilder`. The browser tests in `tests/web.rs`
+//! drive both halves against a real DOM.
+
+use leptos::prelude::*;
+use leptos_store::hydration::HydrationBuilder;
+use leptos_store::prelude::*;
+use leptos_store::{define_hydratable_state, impl_hydratable_store, impl_store};
+
+define_hydratable_state! {
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct SessionState {
+        pub user: String = "guest".to_string(),
+        pub visits: u32,
+        pub note: String,
+    }
+}
+
+/// Store holding the current session.
+#[derive(Clone)]
+pub struct SessionStore {
+    state: RwSignal<SessionState>,
+}
+
+impl SessionStore {
+    /// Create a store with the default session.
+    pub fn new() -> Self {
+        Self::with_state(SessionState::default())
+    }
+
+    /// Create a store with an existing session.
+    pub fn with_state(state: SessionState) -> Self {
+        Self {
+            state: RwSignal::new(state),
+        }
+    }
+
+    /// Create a store for a signed-in user.
+    pub fn signed_in(user: &str, visits: u32, note: &str) -> Self {
+        Self::with_state(SessionState {
+            user: user.to_string(),
+            visits,
+            note: note.to_string(),
+        })
+    }
+
+    /// The current user name.
+    pub fn user(&self) -> String {
+        self.state.with(|s| s.user.clone())
+    }
+
+    /// The number of visits.
+    pub fn visits(&self) -> u32 {
+        self.state.with(|s| s.visits)
+    }
+
+    /// The free-form session note.
+    pub fn note(&self) -> String {
+        self.state.with(|s| s.note.clone())
+    }
+
+    /// Record a visit.
+    pub fn visit(&self) {
+        self.state.update(|s| s.visits += 1);
+    }
+}
+
+impl Default for SessionStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl_store!(SessionStore, SessionState, state);
+impl_hydratable_store!(SessionStore, "session");
+
+/// Server half: provides `store` and embeds its state in the page.
+#[component]
+pub fn ServerApp(store: SessionStore) -> impl IntoView {
+    let hydration_script = provide_hydrated_store(store);
+
+    view! {
+        {hydration_script}
+        <Greeting/>
+    }
+}
+
+/// Client half: restores the store from the page, or starts a new session.
+#[component]
+pub fn ClientApp() -> impl IntoView {
+    let store = HydrationBuilder::new()
+        .with_fallback(SessionStore::new())
+        .build();
+    let hydration_script = provide_hydrated_store(store);
+
+    view! {
+        {hydration_script}
+        <Greeting/>
+    }
+}
+
+/// Renders the session from context.
+#[component]
+pub fn Greeting() -> impl IntoView {
+    let store = use_store::<SessionStore>();
+
+    view! {
+        <p class="greeting">
+            {move || format!("{} ({})", store.user(), store.visits())}
+        </p>
+    }
+}
@@ -0,0 +1,174 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2026 web-mech
+
+//! Browser tests for the hydration context API.
+//!
+//! Each test renders or injects hydration scripts into the test page and
+//! removes them afterwards, so tests don't observe each other's state.
+
+#![cfg(target_arch = "wasm32")]
+
+use hydration_test_app::{ClientApp, ServerApp, SessionState, SessionStore};
+use leptos::prelude::*;
+use leptos_store::hydration::{
+    HydratableStore, HydrationBuilder, StoreHydrationError, has_hydration_data,
+    hydration_script_html, hydration_script_id, read_hydration_data,
+};
+use leptos_store::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+fn document() -> web_sys::Document {
+    web_sys::window().unwrap().document().unwrap()
+}
+
+/// Create an empty container appended to the body.
+fn container() -> web_sys::HtmlElement {
+    let element = document()
+        .create_element("div")
+        .unwrap()
+        .unchecked_into::<web_sys::HtmlElement>();
+    document().body().unwrap().append_child(&element).unwrap();
+    element
+}
+
+/// Embed hydration data for the session store, as the server would.
+fn inject(data: &str) -> web_sys::HtmlElement {
+    let element = container();
+    element.set_inner_html(&hydration_script_html(SessionStore::store_key(), data));
+    element
+}
+
+fn text_of(root: &web_sys::HtmlElement, selector: &str) -> String {
+    root.query_selector(selector)
+        .unwrap()
+        .and_then(|el| el.text_content())
+        .unwrap_or_default()
+}
+
+#[wasm_bindgen_test]
+fn server_render_embeds_state_script() {
+    let root = container();
+    let store = SessionStore::signed_in("ada", 3, "</script><b>not markup</b>");
+    let handle = leptos::mount::mount_to(root.clone(), move || {
+        view! { <ServerApp store=store.clone()/> }
+    });
+
+    assert!(has_hydration_data(SessionStore::store_key()));
+    let script = document()
+        .get_element_by_id(&hydration_script_id(SessionStore::store_key()))
+        .unwrap();
+    assert_eq!(
+        script.get_attribute("type").as_deref(),
+        Some("application/json")
+    );
+
+    // The closing tag inside the state must not terminate the script early
+    let data = read_hydration_data(SessionStore::store_key()).unwrap();
+    let state: SessionState = serde_json::from_str(&data).unwrap();
+    assert_eq!(state.note, "</script><b>not markup</b>");
+    assert!(root.query_selector("b").unwrap().is_none());
+    assert_eq!(text_of(&root, ".greeting"), "ada (3)");
+
+    drop(handle);
+    root.remove();
+}
+
+#[wasm_bindgen_test]
+fn client_restores_server_state() {
+    // Server half
+    let server_root = container();
+    let server_store = SessionStore::signed_in("grace", 7, "hello");
+    let server = leptos::mount::mount_to(server_root.clone(), move || {
+        view! { <ServerApp store=server_store.clone()/> }
+    });
+    let html = server_root.inner_html();
+    drop(server);
+    server_root.remove();
+
+    // Client half sees only the HTML produced by the server
+    let page = container();
+    page.set_inner_html(&html);
+    let client_root = container();
+    let client = leptos::mount::mount_to(client_root.clone(), || view! { <ClientApp/> });
+
+    assert_eq!(text_of(&client_root, ".greeting"), "grace (7)");
+
+    drop(client);
+    client_root.remove();
+    page.remove();
+}
+
+#[wasm_bindgen_test]
+fn use_hydrated_store_reads_dom_and_provides_context() {
+    let page = inject(r#"{"user":"linus","visits":2,"note":""}"#);
+
+    Owner::new().with(|| {
+        let store = use_hydrated_store::<SessionStore>();
+        assert_eq!(store.user(), "linus");
+        assert_eq!(store.visits(), 2);
+
+        // Later lookups share the hydrated instance
+        store.visit();
+        assert_eq!(use_store::<SessionStore>().visits(), 3);
+    });
+
+    page.remove();
+}
+
+#[wasm_bindgen_test]
+fn try_use_hydrated_store_without_data_or_context() {
+    assert!(!has_hydration_data(SessionStore::store_key()));
+
+    Owner::new().with(|| {
+        assert!(matches!(
+            try_use_hydrated_store::<SessionStore>(),
+            Err(StoreHydrationError::NotFound(_))
+        ));
+
+        // Falls back to a store provided the regular way
+        provide_store(SessionStore::signed_in("barbara", 1, ""));
+        assert_eq!(
+            try_use_hydrated_store::<SessionStore>().unwrap().user(),
+            "barbara"
+        );
+    });
+}
+
+#[wasm_bindgen_test]
+fn invalid_data_falls_back() {
+    let page = inject("{not json");
+
+    assert!(matches!(
+        HydrationBuilder::<SessionStore>::new().try_build(),
+        Err(StoreHydrationError::Deserialization(_))
+    ));
+    let store = HydrationBuilder::new()
+        .with_fallback(SessionStore::signed_in("fallback", 0, ""))
+        .build();
+    assert_eq!(store.user(), "fallback");
+
+    Owner::new().with(|| {
+        provide_store(SessionStore::signed_in("context", 0, ""));
+        assert_eq!(use_hydrated_store::<SessionStore>().user(), "context");
+    });
+
+    page.remove();
+}
+
+#[wasm_bindgen_test]
+fn missing_data_uses_builder_fallback() {
+    assert!(matches!(
+        HydrationBuilder::<SessionStore>::new().try_build(),
+        Err(StoreHydrationError::NotFound(_))
+    ));
+
+    let root = container();
+    let handle = leptos::mount::mount_to(root.clone(), || view! { <ClientApp/> });
+    assert_eq!(text_of(&root, ".greeting"), "guest (0)");
+
+    drop(handle);
+    root.remove();
+}